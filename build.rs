@@ -0,0 +1,33 @@
+use std::process::Command;
+
+fn main() {
+    // Build info for RunInfo: the commit (with -dirty for uncommitted
+    // changes) and profile. Outside a git checkout the commit is "unknown".
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    };
+    let commit = match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(commit) if git(&["status", "--porcelain"]).is_some_and(|s| !s.is_empty()) => format!("{}-dirty", commit),
+        Some(commit) => commit,
+        None => "unknown".to_string(),
+    };
+    println!("cargo:rustc-env=GIT_COMMIT={}", commit);
+    println!("cargo:rustc-env=BUILD_PROFILE={}", std::env::var("PROFILE").unwrap_or_default());
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/index");
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // gRPC stubs are only generated with the `grpc` feature. protoc comes
+    // from protoc-bin-vendored so the build doesn't need it installed.
+    #[cfg(feature = "grpc")]
+    {
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc");
+        std::env::set_var("PROTOC", protoc);
+        tonic_build::compile_protos("proto/polybot.proto").expect("compiling proto/polybot.proto");
+    }
+}
@@ -0,0 +1,369 @@
+//! Python bindings for strategy research.
+//!
+//! Thin wrappers over the engine's own types so notebook prototypes run the
+//! exact Rust logic against recorded data. Conventions match the engine:
+//! prices are ticks (1000 = $1.00), sides are "YES"/"NO", sizes are floats
+//! (converted to Decimal at the boundary).
+//!
+//! Build with `maturin develop --release` from this directory.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use polybot_rs::events::Side;
+use polybot_rs::state;
+use polybot_rs::strategy::{self, MarketDuration};
+
+fn parse_side(s: &str) -> PyResult<Side> {
+    match s.to_ascii_uppercase().as_str() {
+        "YES" => Ok(Side::Yes),
+        "NO" => Ok(Side::No),
+        _ => Err(PyValueError::new_err(format!("side must be YES or NO, got {:?}", s))),
+    }
+}
+
+fn parse_duration(s: &str) -> PyResult<MarketDuration> {
+    match s {
+        "5m" => Ok(MarketDuration::FiveMin),
+        "15m" => Ok(MarketDuration::FifteenMin),
+        _ => Err(PyValueError::new_err(format!("duration must be 5m or 15m, got {:?}", s))),
+    }
+}
+
+fn dec(x: f64) -> PyResult<Decimal> {
+    Decimal::try_from(x).map_err(|e| PyValueError::new_err(format!("bad size {}: {}", x, e)))
+}
+
+fn float(d: Decimal) -> f64 {
+    d.to_f64().unwrap_or(0.0)
+}
+
+/// Best bid/ask for YES and NO.
+#[pyclass]
+#[derive(Clone, Default)]
+struct Book(state::Book);
+
+#[pymethods]
+impl Book {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn update(&mut self, side: &str, bid: u16, ask: u16, timestamp_ms: i64) -> PyResult<()> {
+        self.0.update(parse_side(side)?, bid, ask, timestamp_ms);
+        Ok(())
+    }
+
+    fn best_bid(&self, side: &str) -> PyResult<Option<u16>> {
+        Ok(self.0.best_bid(parse_side(side)?))
+    }
+
+    fn best_ask(&self, side: &str) -> PyResult<Option<u16>> {
+        Ok(self.0.best_ask(parse_side(side)?))
+    }
+
+    fn spread(&self, side: &str) -> PyResult<Option<i32>> {
+        Ok(self.0.spread(parse_side(side)?))
+    }
+
+    fn is_synced(&self) -> bool {
+        self.0.is_synced()
+    }
+
+    fn is_locked_or_crossed(&self) -> bool {
+        self.0.is_locked_or_crossed()
+    }
+
+    #[getter]
+    fn last_update_ms(&self) -> i64 {
+        self.0.last_update_ms
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// Inventory and cost basis.
+#[pyclass]
+#[derive(Clone, Default)]
+struct Position(state::Position);
+
+#[pymethods]
+impl Position {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn apply_fill(&mut self, side: &str, price: u16, size: f64) -> PyResult<()> {
+        self.0.apply_fill(parse_side(side)?, price, dec(size)?);
+        Ok(())
+    }
+
+    fn qty(&self, side: &str) -> PyResult<f64> {
+        Ok(float(self.0.qty(parse_side(side)?)))
+    }
+
+    /// Average price in ticks, None with no shares on that side.
+    fn avg_price(&self, side: &str) -> PyResult<Option<f64>> {
+        let avg = match parse_side(side)? {
+            Side::Yes => self.0.avg_price_yes(),
+            Side::No => self.0.avg_price_no(),
+        };
+        Ok(avg.map(float))
+    }
+
+    fn net_position(&self) -> f64 {
+        float(self.0.net_position())
+    }
+
+    fn imbalance(&self) -> f64 {
+        float(self.0.imbalance())
+    }
+
+    /// avg YES + avg NO in ticks, None unless holding both.
+    fn pair_cost(&self) -> Option<f64> {
+        self.0.pair_cost().map(float)
+    }
+
+    fn min_pnl_usd(&self) -> f64 {
+        float(self.0.min_pnl_usd())
+    }
+
+    fn nav_usd(&self, yes_mid: u16) -> f64 {
+        float(self.0.nav_usd(yes_mid))
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+
+    fn __repr__(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// Resting orders per side and price.
+#[pyclass]
+#[derive(Default)]
+struct OrderTracker(state::OrderTracker);
+
+#[pymethods]
+impl OrderTracker {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn add(&mut self, side: &str, order_id: String, price: u16, size: f64) -> PyResult<()> {
+        self.0.add(parse_side(side)?, order_id, price, dec(size)?);
+        Ok(())
+    }
+
+    /// Remove by id; True if it was tracked.
+    fn remove(&mut self, side: &str, order_id: &str) -> PyResult<bool> {
+        Ok(self.0.remove_by_id(parse_side(side)?, order_id).is_some())
+    }
+
+    /// Apply a fill: "live", "pending_cancel", "after_cancel" or "unknown".
+    fn update_fill(&mut self, side: &str, order_id: &str, size: f64) -> PyResult<&'static str> {
+        Ok(match self.0.update_fill(parse_side(side)?, order_id, dec(size)?) {
+            state::FillMatch::Live => "live",
+            state::FillMatch::PendingCancel => "pending_cancel",
+            state::FillMatch::AfterCancel => "after_cancel",
+            state::FillMatch::Unknown => "unknown",
+        })
+    }
+
+    fn prices(&self, side: &str) -> PyResult<Vec<u16>> {
+        Ok(self.0.prices(parse_side(side)?))
+    }
+
+    fn top_price(&self, side: &str) -> PyResult<Option<u16>> {
+        Ok(self.0.top_price(parse_side(side)?))
+    }
+
+    fn count(&self, side: &str) -> PyResult<usize> {
+        Ok(self.0.count(parse_side(side)?))
+    }
+
+    fn total_count(&self) -> usize {
+        self.0.total_count()
+    }
+
+    fn total_exposure(&self, side: &str) -> PyResult<f64> {
+        Ok(float(self.0.total_exposure(parse_side(side)?)))
+    }
+
+    /// Resting notional in ticks x shares.
+    fn locked_notional(&self) -> f64 {
+        float(self.0.locked_notional())
+    }
+
+    fn clear_all(&mut self) {
+        self.0.clear_all();
+    }
+}
+
+/// Avellaneda-Stoikov pricer in logit space.
+#[pyclass]
+#[derive(Clone)]
+struct AvellanedaStoikov(strategy::AvellanedaStoikov);
+
+#[pymethods]
+impl AvellanedaStoikov {
+    #[new]
+    fn new(gamma: f64) -> Self {
+        Self(strategy::AvellanedaStoikov::new(gamma))
+    }
+
+    #[getter]
+    fn gamma(&self) -> f64 {
+        self.0.gamma
+    }
+
+    #[setter]
+    fn set_gamma(&mut self, gamma: f64) {
+        self.0.gamma = gamma;
+    }
+
+    #[getter]
+    fn expiry_base_penalty(&self) -> f64 {
+        self.0.expiry_base_penalty
+    }
+
+    #[setter]
+    fn set_expiry_base_penalty(&mut self, penalty: f64) {
+        self.0.expiry_base_penalty = penalty;
+    }
+
+    /// (yes_bid, no_bid) as probabilities.
+    fn compute_quotes(&self, mid: f64, inventory: f64, var: f64, k: f64, time_left: f64) -> (f64, f64) {
+        let q = self.0.compute_quotes(mid, inventory, var, k, time_left);
+        (q.yes_bid, q.no_bid)
+    }
+}
+
+/// Rolling variance of logit mid increments.
+#[pyclass]
+struct VarianceEstimator(strategy::VarianceEstimator);
+
+#[pymethods]
+impl VarianceEstimator {
+    #[new]
+    fn new(window_size: usize, var_floor: f64) -> Self {
+        Self(strategy::VarianceEstimator::new(window_size, var_floor))
+    }
+
+    fn update(&mut self, mid: f64, timestamp_secs: f64) {
+        self.0.update_poly(mid, timestamp_secs);
+    }
+
+    fn current_var(&self) -> f64 {
+        self.0.current_var()
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+/// Trade arrival rate (k).
+#[pyclass]
+struct FlowEstimator(strategy::FlowEstimator);
+
+#[pymethods]
+impl FlowEstimator {
+    #[new]
+    fn new(window_secs: f64, k_floor: f64) -> Self {
+        Self(strategy::FlowEstimator::new(window_secs, k_floor))
+    }
+
+    fn record_trade(&mut self, timestamp_secs: f64) {
+        self.0.record_trade(timestamp_secs);
+    }
+
+    fn current_k(&self) -> f64 {
+        self.0.current_k()
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+    }
+}
+
+/// Highest bid on `side` that keeps the pair under $1 minus `margin_ticks`.
+#[pyfunction]
+fn calc_max_bid(side: &str, book: &Book, margin_ticks: u16) -> PyResult<u16> {
+    Ok(strategy::calc_max_bid(parse_side(side)?, &book.0, margin_ticks))
+}
+
+/// Probability to ticks.
+#[pyfunction]
+fn to_ticks(p: f64) -> u16 {
+    strategy::AvellanedaStoikov::to_ticks(p)
+}
+
+/// Whether the mid is inside the quoting range.
+#[pyfunction]
+fn should_quote(mid: f64) -> bool {
+    strategy::Quotes::should_quote(mid)
+}
+
+/// Order size for the time remaining ("5m" or "15m" market).
+#[pyfunction]
+fn calc_size(time_remaining_secs: i64, duration: &str) -> PyResult<f64> {
+    Ok(float(strategy::calc_size(time_remaining_secs, parse_duration(duration)?)))
+}
+
+/// Order size, or 0 if `side` is at `max_position`.
+#[pyfunction]
+fn calc_size_with_limit(
+    side: &str,
+    position: &Position,
+    time_remaining_secs: i64,
+    duration: &str,
+    max_position: f64,
+) -> PyResult<f64> {
+    let size = strategy::calc_size_with_limit(
+        parse_side(side)?,
+        &position.0,
+        time_remaining_secs,
+        parse_duration(duration)?,
+        dec(max_position)?,
+    );
+    Ok(float(size))
+}
+
+/// Whether `side` is below `max_position`.
+#[pyfunction]
+fn can_place(side: &str, position: &Position, max_position: f64) -> PyResult<bool> {
+    Ok(strategy::can_place(parse_side(side)?, &position.0, dec(max_position)?))
+}
+
+#[pymodule]
+fn polybot(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Book>()?;
+    m.add_class::<Position>()?;
+    m.add_class::<OrderTracker>()?;
+    m.add_class::<AvellanedaStoikov>()?;
+    m.add_class::<VarianceEstimator>()?;
+    m.add_class::<FlowEstimator>()?;
+    m.add_function(wrap_pyfunction!(calc_max_bid, m)?)?;
+    m.add_function(wrap_pyfunction!(to_ticks, m)?)?;
+    m.add_function(wrap_pyfunction!(should_quote, m)?)?;
+    m.add_function(wrap_pyfunction!(calc_size, m)?)?;
+    m.add_function(wrap_pyfunction!(calc_size_with_limit, m)?)?;
+    m.add_function(wrap_pyfunction!(can_place, m)?)?;
+    m.add("P_MIN", strategy::P_MIN)?;
+    m.add("P_MAX", strategy::P_MAX)?;
+    Ok(())
+}
@@ -0,0 +1,260 @@
+//! Alert dedupe, rate limiting, and escalation.
+//!
+//! A flapping connection can fire the same alert hundreds of times. Alerts
+//! are keyed (e.g. "feed:binance"): the first raise is sent, repeats within
+//! `repeat_secs` are counted but suppressed, and if the condition is still
+//! active after `escalate_secs` a single critical alert goes out.
+//!
+//! A resolve followed by a new raise within `repeat_secs` is treated as the
+//! same incident, so reconnect/drop cycles don't reset the clock.
+//!
+//! The process-wide `raise`/`resolve` are no-ops when built without the
+//! `alerts` feature, or after `set_enabled(false)`.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Configuration for alert rate limiting.
+#[derive(Debug, Clone)]
+pub struct AlertConfig {
+    /// Minimum time between repeats of the same alert (seconds)
+    pub repeat_secs: f64,
+    /// Escalate if still active this long after first raise (seconds)
+    pub escalate_secs: f64,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            repeat_secs: 300.0,   // One alert per key per 5 minutes
+            escalate_secs: 900.0, // Escalate if still down after 15 minutes
+        }
+    }
+}
+
+/// Severity of an outgoing alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertLevel {
+    Warning,
+    Critical,
+}
+
+/// An alert that passed the limiter and should be sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertNotice {
+    pub level: AlertLevel,
+    /// Raises suppressed since the last notice for this key
+    pub suppressed: u32,
+    /// Seconds since the incident started
+    pub active_secs: f64,
+}
+
+#[derive(Debug)]
+struct AlertState {
+    first_seen: f64,
+    last_sent: f64,
+    suppressed: u32,
+    escalated: bool,
+    /// When the condition last cleared (None while active)
+    resolved_at: Option<f64>,
+}
+
+/// Per-key dedupe and rate limiting.
+#[derive(Debug, Default)]
+pub struct AlertLimiter {
+    config: AlertConfig,
+    states: HashMap<String, AlertState>,
+}
+
+impl AlertLimiter {
+    pub fn new(config: AlertConfig) -> Self {
+        Self {
+            config,
+            states: HashMap::new(),
+        }
+    }
+
+    /// Raise an alert for `key`.
+    ///
+    /// # Returns
+    /// `Some(notice)` if it should be sent, `None` if suppressed.
+    pub fn raise(&mut self, key: &str, now: f64) -> Option<AlertNotice> {
+        let config = &self.config;
+        let state = self.states.entry(key.to_string()).or_insert(AlertState {
+            first_seen: now,
+            last_sent: f64::NEG_INFINITY,
+            suppressed: 0,
+            escalated: false,
+            resolved_at: None,
+        });
+
+        // Cleared long enough ago: this is a new incident
+        if let Some(resolved_at) = state.resolved_at.take() {
+            if now - resolved_at >= config.repeat_secs {
+                state.first_seen = now;
+                state.escalated = false;
+            }
+        }
+
+        let active_secs = now - state.first_seen;
+        let level = if !state.escalated && active_secs >= config.escalate_secs {
+            state.escalated = true;
+            AlertLevel::Critical
+        } else if now - state.last_sent >= config.repeat_secs {
+            AlertLevel::Warning
+        } else {
+            state.suppressed += 1;
+            return None;
+        };
+
+        let notice = AlertNotice {
+            level,
+            suppressed: state.suppressed,
+            active_secs,
+        };
+        state.last_sent = now;
+        state.suppressed = 0;
+        Some(notice)
+    }
+
+    /// Mark the condition for `key` as cleared.
+    ///
+    /// # Returns
+    /// Seconds the incident was active, if it was.
+    pub fn resolve(&mut self, key: &str, now: f64) -> Option<f64> {
+        let state = self.states.get_mut(key)?;
+        if state.resolved_at.is_some() {
+            return None;
+        }
+        state.resolved_at = Some(now);
+        Some(now - state.first_seen)
+    }
+}
+
+static ALERTS: LazyLock<Mutex<AlertLimiter>> =
+    LazyLock::new(|| Mutex::new(AlertLimiter::new(AlertConfig::default())));
+
+static ENABLED: AtomicBool = AtomicBool::new(cfg!(feature = "alerts"));
+
+/// Turn process-wide alerts on or off (`[subsystems] alerts`). Can't turn
+/// them on in a build without the `alerts` feature.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled && cfg!(feature = "alerts"), Ordering::Relaxed);
+}
+
+/// Raise a process-wide alert, printing it unless rate limited.
+pub fn raise(key: &str, message: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let notice = ALERTS.lock().unwrap().raise(key, now_secs());
+    if let Some(notice) = notice {
+        let tag = match notice.level {
+            AlertLevel::Warning => "ALERT",
+            AlertLevel::Critical => "ALERT CRITICAL",
+        };
+        if notice.suppressed > 0 {
+            println!(
+                "[{}] {} (active {:.0}s, {} repeats suppressed)",
+                tag, message, notice.active_secs, notice.suppressed
+            );
+        } else {
+            println!("[{}] {}", tag, message);
+        }
+    }
+}
+
+/// Clear a process-wide alert.
+pub fn resolve(key: &str) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let _ = ALERTS.lock().unwrap().resolve(key, now_secs());
+}
+
+fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs_f64()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter() -> AlertLimiter {
+        AlertLimiter::new(AlertConfig {
+            repeat_secs: 300.0,
+            escalate_secs: 900.0,
+        })
+    }
+
+    #[test]
+    fn test_first_raise_sent_repeats_suppressed() {
+        let mut a = limiter();
+        let first = a.raise("feed", 0.0).unwrap();
+        assert_eq!(first.level, AlertLevel::Warning);
+
+        for t in 1..100 {
+            assert!(a.raise("feed", t as f64).is_none());
+        }
+
+        // After the repeat window, one more with the suppressed count
+        let next = a.raise("feed", 300.0).unwrap();
+        assert_eq!(next.level, AlertLevel::Warning);
+        assert_eq!(next.suppressed, 99);
+    }
+
+    #[test]
+    fn test_keys_are_independent() {
+        let mut a = limiter();
+        assert!(a.raise("binance", 0.0).is_some());
+        assert!(a.raise("polymarket", 1.0).is_some());
+        assert!(a.raise("binance", 2.0).is_none());
+    }
+
+    #[test]
+    fn test_escalates_once() {
+        let mut a = limiter();
+        a.raise("feed", 0.0);
+        a.raise("feed", 300.0);
+        a.raise("feed", 600.0);
+
+        let esc = a.raise("feed", 900.0).unwrap();
+        assert_eq!(esc.level, AlertLevel::Critical);
+        assert_eq!(esc.active_secs, 900.0);
+
+        // Back to normal repeats afterwards
+        assert!(a.raise("feed", 901.0).is_none());
+        assert_eq!(a.raise("feed", 1200.0).unwrap().level, AlertLevel::Warning);
+    }
+
+    #[test]
+    fn test_flapping_is_one_incident() {
+        let mut a = limiter();
+        a.raise("feed", 0.0);
+        // Reconnects and drops every 10s
+        for t in (10..900).step_by(10) {
+            a.resolve("feed", t as f64);
+            a.raise("feed", t as f64 + 1.0);
+        }
+        let esc = a.raise("feed", 905.0);
+        assert_eq!(esc.unwrap().level, AlertLevel::Critical);
+    }
+
+    #[test]
+    fn test_resolved_long_ago_is_new_incident() {
+        let mut a = limiter();
+        a.raise("feed", 0.0);
+        assert_eq!(a.resolve("feed", 100.0), Some(100.0));
+        assert_eq!(a.resolve("feed", 101.0), None);
+
+        // Down again well after the repeat window: fresh warning, fresh clock
+        let next = a.raise("feed", 1000.0).unwrap();
+        assert_eq!(next.level, AlertLevel::Warning);
+        assert_eq!(next.active_secs, 0.0);
+    }
+}
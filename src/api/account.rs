@@ -0,0 +1,137 @@
+//! Proxy wallet collateral: USDC balance and exchange allowances.
+//!
+//! Plain ERC-20 reads over Polygon JSON-RPC, so they need the wallet's
+//! address but no signer. `preflight` checks them once before going live;
+//! `feeds::balance` polls them while trading, and the main loop holds bids
+//! to what's both held and approved (`collateral`).
+
+use anyhow::{anyhow, bail, Result};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::json;
+
+/// USDC.e on Polygon, which bids are funded in
+pub const USDC: &str = "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174";
+const USDC_DECIMALS: i32 = 6;
+
+/// Contracts that settle our orders, and so need a USDC allowance: the CTF
+/// exchange, then the neg-risk exchange (for `MarketInfo::neg_risk` markets)
+pub const EXCHANGES: [(&str, &str); 2] = [
+    ("CTF exchange", "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E"),
+    ("neg-risk exchange", "0xC5d563A36AE78145C45a50134d48A1215220f80a"),
+];
+
+/// Polygon JSON-RPC endpoint.
+pub fn polygon_rpc() -> String {
+    std::env::var("POLYGON_RPC_URL").unwrap_or_else(|_| "https://polygon-rpc.com".to_string())
+}
+
+/// An address as a 32-byte ABI word (hex, no prefix).
+fn abi_address(address: &str) -> Result<String> {
+    let hex = address.trim_start_matches("0x");
+    if hex.len() != 40 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("{} is not an address", address);
+    }
+    Ok(format!("{:0>64}", hex.to_lowercase()))
+}
+
+/// A uint256 return value in USDC. Unlimited approvals overflow any integer
+/// type, so it's accumulated as a float.
+fn usdc_amount(word: &str) -> Result<f64> {
+    let hex = word.trim_start_matches("0x");
+    let units = hex.chars().try_fold(0.0_f64, |acc, c| {
+        c.to_digit(16).map(|d| acc * 16.0 + d as f64).ok_or_else(|| anyhow!("bad hex in {}", word))
+    })?;
+    Ok(units / 10f64.powi(USDC_DECIMALS))
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse {
+    result: Option<String>,
+    error: Option<serde_json::Value>,
+}
+
+async fn eth_call(http: &reqwest::Client, rpc: &str, to: &str, data: String) -> Result<String> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_call",
+        "params": [{ "to": to, "data": data }, "latest"],
+    });
+    let response: RpcResponse = http.post(rpc).json(&request).send().await?.error_for_status()?.json().await?;
+    match (response.result, response.error) {
+        (Some(result), _) => Ok(result),
+        (None, Some(error)) => bail!("eth_call failed: {}", error),
+        (None, None) => bail!("eth_call returned nothing"),
+    }
+}
+
+/// USDC held by `owner`.
+pub async fn usdc_balance(http: &reqwest::Client, rpc: &str, owner: &str) -> Result<f64> {
+    // balanceOf(address)
+    let data = format!("0x70a08231{}", abi_address(owner)?);
+    usdc_amount(&eth_call(http, rpc, USDC, data).await?)
+}
+
+/// USDC `spender` may move for `owner`.
+pub async fn usdc_allowance(http: &reqwest::Client, rpc: &str, owner: &str, spender: &str) -> Result<f64> {
+    // allowance(address,address)
+    let data = format!("0xdd62ed3e{}{}", abi_address(owner)?, abi_address(spender)?);
+    usdc_amount(&eth_call(http, rpc, USDC, data).await?)
+}
+
+/// USDC that bids on a market can actually use: held, and approved for the
+/// exchange that settles it. `allowances_usd` is in `EXCHANGES` order; a
+/// missing allowance doesn't limit anything.
+pub fn collateral(balance_usd: f64, allowances_usd: &[f64], neg_risk: bool) -> f64 {
+    let exchange = usize::from(neg_risk);
+    allowances_usd.get(exchange).map_or(balance_usd, |a| balance_usd.min(*a)).max(0.0)
+}
+
+/// Balance and the allowance of each of `EXCHANGES`, in that order.
+pub async fn fetch(http: &reqwest::Client, rpc: &str, owner: &str) -> Result<(f64, Vec<f64>)> {
+    let balance = usdc_balance(http, rpc, owner).await?;
+    let mut allowances = Vec::with_capacity(EXCHANGES.len());
+    for (_, spender) in EXCHANGES {
+        allowances.push(usdc_allowance(http, rpc, owner, spender).await?);
+    }
+    Ok((balance, allowances))
+}
+
+/// How far `collateral` falls short of what the full ladder would lock
+/// (both in USDC), if it does.
+pub fn shortfall(collateral: Decimal, ladder: Decimal) -> Option<Decimal> {
+    (ladder > collateral).then(|| ladder - collateral)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_abi_encoding() {
+        let word = abi_address("0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E").unwrap();
+        assert_eq!(word.len(), 64);
+        assert!(word.ends_with("4bfb41d5b3570defd03c39a9a4d8de6bd8b8982e"));
+        assert!(abi_address("0x1234").is_err());
+
+        assert_eq!(usdc_amount("0x0000000000000000000000000000000000000000000000000000000005f5e100").unwrap(), 100.0);
+        // An unlimited approval
+        assert!(usdc_amount(&format!("0x{}", "f".repeat(64))).unwrap() > 1e60);
+    }
+
+    #[test]
+    fn test_collateral() {
+        assert_eq!(collateral(100.0, &[1e70, 1e70], false), 100.0);
+        assert_eq!(collateral(100.0, &[40.0, 1e70], false), 40.0);
+        assert_eq!(collateral(100.0, &[40.0, 1e70], true), 100.0);
+        assert_eq!(collateral(100.0, &[], false), 100.0);
+
+        // No neg-risk approval: only neg-risk markets are blocked
+        assert_eq!(collateral(100.0, &[1e70, 0.0], false), 100.0);
+        assert_eq!(collateral(100.0, &[1e70, 0.0], true), 0.0);
+        assert_eq!(shortfall(dec!(10), dec!(24.5)), Some(dec!(14.5)));
+        assert_eq!(shortfall(dec!(30), dec!(24.5)), None);
+    }
+}
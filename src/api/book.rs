@@ -0,0 +1,85 @@
+//! CLOB order book snapshots over REST.
+//!
+//! The WebSocket feed only gives us top of book. Takes that walk the ask
+//! side need every visible level, so they fetch a snapshot here. The
+//! depth feed resyncs a token from one when its checksum stops matching.
+
+use anyhow::Result;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use super::CLOB_BASE;
+
+/// A price level as the CLOB returns it (dollar strings).
+#[derive(Debug, Clone, Deserialize)]
+pub struct Level {
+    pub price: String,
+    pub size: String,
+}
+
+/// A token's full book, fields as the CLOB sent them.
+#[derive(Debug, Default, Deserialize)]
+pub struct BookResponse {
+    #[serde(default)]
+    pub market: String,
+    #[serde(default)]
+    pub asset_id: String,
+    #[serde(default)]
+    pub timestamp: String,
+    /// SHA-1 of the book (see `feeds::checksum`)
+    #[serde(default)]
+    pub hash: Option<String>,
+    #[serde(default)]
+    pub bids: Vec<Level>,
+    #[serde(default)]
+    pub asks: Vec<Level>,
+}
+
+/// Parse ask levels into (price in ticks, size), best (lowest) first.
+/// Levels that don't parse are skipped.
+fn parse_asks(levels: &[Level]) -> Vec<(u16, Decimal)> {
+    let mut asks: Vec<(u16, Decimal)> = levels
+        .iter()
+        .filter_map(|l| {
+            let price: Decimal = l.price.parse().ok()?;
+            let size: Decimal = l.size.parse().ok()?;
+            let ticks = (price * Decimal::from(1000)).round().to_u16()?;
+            (size > Decimal::ZERO).then_some((ticks, size))
+        })
+        .collect();
+    asks.sort_by_key(|&(price, _)| price);
+    asks
+}
+
+/// Fetch the visible ask levels for a token, best first.
+pub async fn fetch_asks(token_id: &str) -> Result<Vec<(u16, Decimal)>> {
+    let url = format!("{}/book?token_id={}", CLOB_BASE, token_id);
+    let book: BookResponse = reqwest::get(&url).await?.error_for_status()?.json().await?;
+    Ok(parse_asks(&book.asks))
+}
+
+/// Fetch a token's full book.
+pub async fn fetch_book(token_id: &str) -> Result<BookResponse> {
+    let url = format!("{}/book?token_id={}", CLOB_BASE, token_id);
+    Ok(reqwest::get(&url).await?.error_for_status()?.json().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_asks_sorted_best_first() {
+        // The CLOB lists asks worst-first
+        let json = r#"{"asks":[
+            {"price":"0.55","size":"40"},
+            {"price":"0.53","size":"12.5"},
+            {"price":"bad","size":"1"},
+            {"price":"0.54","size":"0"}
+        ]}"#;
+        let book: BookResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(parse_asks(&book.asks), vec![(530, dec!(12.5)), (550, dec!(40))]);
+    }
+}
@@ -0,0 +1,261 @@
+//! Polymarket Data API: public trades, holders, wallet positions and
+//! reward payouts.
+//!
+//! Gives context the CLOB feed doesn't: who was on the other side. Used for
+//! the "share of volume we captured" and "who was hitting us" lines in the
+//! window report, by the whale feed as an optional toxicity input, as the
+//! exchange's side of the shadow position check, and for the reward totals
+//! in `report`.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::events::Side;
+
+const DATA_API_BASE: &str = "https://data-api.polymarket.com";
+
+/// One public trade. The API reports the taker's side by default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicTrade {
+    pub proxy_wallet: String,
+    /// "BUY" or "SELL"
+    pub side: String,
+    pub size: f64,
+    pub price: f64,
+    /// Unix seconds
+    pub timestamp: i64,
+    /// 0 = YES (Up), 1 = NO (Down)
+    pub outcome_index: usize,
+    #[serde(default)]
+    pub pseudonym: Option<String>,
+}
+
+impl PublicTrade {
+    /// +1 if this trade pushed YES up (bought YES / sold NO), -1 if down.
+    fn direction(&self) -> f64 {
+        let buy = self.side.eq_ignore_ascii_case("BUY");
+        match (self.outcome_index, buy) {
+            (0, true) | (1, false) => 1.0,
+            _ => -1.0,
+        }
+    }
+
+    /// Name to show in reports: pseudonym if set, else a short wallet.
+    pub fn who(&self) -> String {
+        match &self.pseudonym {
+            Some(p) if !p.is_empty() => p.clone(),
+            _ => self.proxy_wallet[..self.proxy_wallet.len().min(10)].to_string(),
+        }
+    }
+}
+
+/// One holder of an outcome token.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Holder {
+    pub proxy_wallet: String,
+    pub amount: f64,
+    pub outcome_index: usize,
+    #[serde(default)]
+    pub pseudonym: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HoldersResponse {
+    holders: Vec<Holder>,
+}
+
+/// A wallet's holding of one outcome token.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeldPosition {
+    pub size: f64,
+    /// Average price paid per share (dollars)
+    #[serde(default)]
+    pub avg_price: f64,
+    /// 0 = YES (Up), 1 = NO (Down)
+    pub outcome_index: usize,
+}
+
+/// One liquidity-reward payout to a wallet.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RewardPayout {
+    /// Unix seconds
+    pub timestamp: i64,
+    /// Paid in USDC
+    pub usdc_size: f64,
+}
+
+/// A wallet's taker activity over the summarized trades.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Taker {
+    pub who: String,
+    /// Shares taken
+    pub volume: f64,
+    /// Net direction in YES terms, -1 to +1
+    pub pressure: f64,
+}
+
+impl Taker {
+    /// The side this taker was running over: pushing YES up hits NO bids.
+    pub fn pressured_side(&self) -> Side {
+        if self.pressure > 0.0 { Side::No } else { Side::Yes }
+    }
+}
+
+/// Volume and counterparty summary for a set of trades.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlowSummary {
+    /// Total shares traded
+    pub total_volume: f64,
+    /// Largest takers by volume, biggest first
+    pub top_takers: Vec<Taker>,
+}
+
+impl FlowSummary {
+    /// Summarize trades. `our_wallet` (case-insensitive) is left out of
+    /// the taker ranking: our own Takes aren't a counterparty.
+    pub fn from_trades(trades: &[PublicTrade], our_wallet: Option<&str>, top_n: usize) -> Self {
+        let is_us = |w: &str| our_wallet.is_some_and(|o| o.eq_ignore_ascii_case(w));
+        let mut by_wallet: HashMap<&str, (String, f64, f64)> = HashMap::new();
+        let mut summary = FlowSummary::default();
+
+        for t in trades {
+            summary.total_volume += t.size;
+            if is_us(&t.proxy_wallet) {
+                continue;
+            }
+            let entry = by_wallet.entry(&t.proxy_wallet).or_insert_with(|| (t.who(), 0.0, 0.0));
+            entry.1 += t.size;
+            entry.2 += t.size * t.direction();
+        }
+
+        let mut takers: Vec<Taker> = by_wallet
+            .into_values()
+            .map(|(who, volume, net)| Taker { who, volume, pressure: net / volume })
+            .collect();
+        takers.sort_by(|a, b| b.volume.total_cmp(&a.volume));
+        takers.truncate(top_n);
+        summary.top_takers = takers;
+        summary
+    }
+
+    /// Fraction of volume we were filled on (0 if nothing traded).
+    /// The trades are taker-side, so our maker fills are counted by the
+    /// caller from its own fill log.
+    pub fn our_share(&self, our_volume: f64) -> f64 {
+        if self.total_volume > 0.0 {
+            (our_volume / self.total_volume).min(1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Fetch recent public trades for a market (newest first).
+pub async fn fetch_trades(http: &reqwest::Client, condition_id: &str, limit: usize) -> Result<Vec<PublicTrade>> {
+    let url = format!("{}/trades?market={}&limit={}", DATA_API_BASE, condition_id, limit);
+    Ok(http.get(&url).send().await?.error_for_status()?.json().await?)
+}
+
+/// Fetch the largest holders of each outcome token.
+pub async fn fetch_holders(http: &reqwest::Client, condition_id: &str, limit: usize) -> Result<Vec<Holder>> {
+    let url = format!("{}/holders?market={}&limit={}", DATA_API_BASE, condition_id, limit);
+    let tokens: Vec<HoldersResponse> = http.get(&url).send().await?.error_for_status()?.json().await?;
+    Ok(tokens.into_iter().flat_map(|t| t.holders).collect())
+}
+
+/// Fetch a wallet's holdings in one market.
+pub async fn fetch_holdings(http: &reqwest::Client, wallet: &str, condition_id: &str) -> Result<Vec<HeldPosition>> {
+    let url = format!("{}/positions?user={}&market={}&sizeThreshold=0", DATA_API_BASE, wallet, condition_id);
+    Ok(http.get(&url).send().await?.error_for_status()?.json().await?)
+}
+
+/// Fetch a wallet's positions in one market, as (YES, NO) shares.
+pub async fn fetch_positions(http: &reqwest::Client, wallet: &str, condition_id: &str) -> Result<(f64, f64)> {
+    let held = fetch_holdings(http, wallet, condition_id).await?;
+    Ok(held.iter().fold((0.0, 0.0), |(yes, no), p| match p.outcome_index {
+        0 => (yes + p.size, no),
+        1 => (yes, no + p.size),
+        _ => (yes, no),
+    }))
+}
+
+/// Page size for the activity endpoint (its maximum)
+const ACTIVITY_PAGE: usize = 500;
+
+/// Fetch a wallet's liquidity-reward payouts since `since` (unix seconds),
+/// oldest first.
+pub async fn fetch_rewards(http: &reqwest::Client, wallet: &str, since: i64) -> Result<Vec<RewardPayout>> {
+    let mut payouts = Vec::new();
+    loop {
+        let url = format!(
+            "{}/activity?user={}&type=REWARD&start={}&limit={}&offset={}&sortBy=TIMESTAMP&sortDirection=ASC",
+            DATA_API_BASE, wallet, since, ACTIVITY_PAGE, payouts.len()
+        );
+        let page: Vec<RewardPayout> = http.get(&url).send().await?.error_for_status()?.json().await?;
+        let done = page.len() < ACTIVITY_PAGE;
+        payouts.extend(page);
+        if done {
+            return Ok(payouts);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(wallet: &str, side: &str, outcome_index: usize, size: f64) -> PublicTrade {
+        PublicTrade {
+            proxy_wallet: wallet.to_string(),
+            side: side.to_string(),
+            size,
+            price: 0.5,
+            timestamp: 0,
+            outcome_index,
+            pseudonym: None,
+        }
+    }
+
+    #[test]
+    fn test_summary_share_and_ranking() {
+        let trades = vec![
+            trade("0xwhale", "BUY", 0, 100.0),
+            trade("0xwhale", "SELL", 1, 50.0),
+            trade("0xsmall", "SELL", 0, 10.0),
+            trade("0xUS", "BUY", 1, 40.0),
+        ];
+        let summary = FlowSummary::from_trades(&trades, Some("0xus"), 5);
+
+        assert_eq!(summary.total_volume, 200.0);
+        assert!((summary.our_share(40.0) - 0.2).abs() < 1e-9);
+        assert_eq!(FlowSummary::default().our_share(10.0), 0.0);
+        assert_eq!(summary.top_takers.len(), 2);
+
+        let whale = &summary.top_takers[0];
+        assert_eq!(whale.who, "0xwhale");
+        assert_eq!(whale.volume, 150.0);
+        assert_eq!(whale.pressure, 1.0);
+        assert_eq!(whale.pressured_side(), Side::No);
+        assert_eq!(summary.top_takers[1].pressure, -1.0);
+    }
+
+    #[test]
+    fn test_parse_trades_and_holders() {
+        let json = r#"[{"proxyWallet":"0xabc","side":"BUY","asset":"1","conditionId":"0x1",
+            "size":12.5,"price":0.48,"timestamp":1700000000,"outcome":"Up","outcomeIndex":0,
+            "name":"x","pseudonym":"Quiet-Whale"}]"#;
+        let trades: Vec<PublicTrade> = serde_json::from_str(json).unwrap();
+        assert_eq!(trades[0].who(), "Quiet-Whale");
+        assert_eq!(trades[0].size, 12.5);
+
+        let json = r#"[{"token":"1","holders":[{"proxyWallet":"0xabc","amount":900.0,"outcomeIndex":0}]}]"#;
+        let tokens: Vec<HoldersResponse> = serde_json::from_str(json).unwrap();
+        assert_eq!(tokens[0].holders[0].amount, 900.0);
+    }
+}
@@ -0,0 +1,55 @@
+//! CLOB fee rate per token.
+//!
+//! Signed orders carry the fee rate they accept, and the CLOB rejects
+//! orders whose rate doesn't match the market's. Fetch it rather than
+//! hardcode it, and refuse to trade if it's far above what we expect.
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+use super::CLOB_BASE;
+
+#[derive(Debug, Deserialize)]
+struct FeeRateResponse {
+    #[serde(alias = "fee_rate_bps")]
+    base_fee: u32,
+}
+
+/// Fetch the fee rate (bps) orders on `token_id` must carry.
+pub async fn fetch_fee_rate(token_id: &str) -> Result<u32> {
+    let url = format!("{}/fee-rate?token_id={}", CLOB_BASE, token_id);
+    let response: FeeRateResponse = reqwest::get(&url).await?.error_for_status()?.json().await?;
+    Ok(response.base_fee)
+}
+
+/// The fetched rate, or an error if it's above `max_bps`.
+pub fn check_fee_rate(token_id: &str, bps: u32, max_bps: u32) -> Result<u32> {
+    if bps > max_bps {
+        bail!(
+            "Fee rate for token {}... is {}bps, above the {}bps we accept; refusing to trade",
+            &token_id[..token_id.len().min(20)], bps, max_bps
+        );
+    }
+    Ok(bps)
+}
+
+/// Fetch and check the fee rate for `token_id`.
+pub async fn fee_rate(token_id: &str, max_bps: u32) -> Result<u32> {
+    check_fee_rate(token_id, fetch_fee_rate(token_id).await?, max_bps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_check_fee_rate() {
+        let parsed: FeeRateResponse = serde_json::from_str(r#"{"base_fee":1000}"#).unwrap();
+        assert_eq!(parsed.base_fee, 1000);
+        let parsed: FeeRateResponse = serde_json::from_str(r#"{"fee_rate_bps":0}"#).unwrap();
+        assert_eq!(parsed.base_fee, 0);
+
+        assert_eq!(check_fee_rate("123", 1000, 1000).unwrap(), 1000);
+        assert!(check_fee_rate("123", 2500, 1000).is_err());
+    }
+}
@@ -1,8 +1,32 @@
 use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
 use serde::Deserialize;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
 
-const GAMMA_BASE: &str = "https://gamma-api.polymarket.com";
+use crate::clock::{Clock, SystemClock};
+use crate::strategy::MarketDuration;
+
+pub(crate) const GAMMA_BASE: &str = "https://gamma-api.polymarket.com";
+
+/// Right at a 5m boundary the new slug often 404s for a few seconds.
+/// Retry "not found" this many times before giving up.
+const NOT_FOUND_RETRIES: u32 = 8;
+/// First retry delay; doubles each attempt up to NOT_FOUND_MAX_BACKOFF_MS.
+const NOT_FOUND_BACKOFF_MS: u64 = 250;
+const NOT_FOUND_MAX_BACKOFF_MS: u64 = 2000;
+
+/// Resolved markets kept in the cache (a market's info never changes).
+const CACHE_CAPACITY: usize = 64;
+
+static MARKET_CACHE: LazyLock<Mutex<HashMap<String, MarketInfo>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Gamma returned 404 for a slug.
+#[derive(Debug, thiserror::Error)]
+#[error("Market not found: {0}")]
+pub struct MarketNotFound(pub String);
 
 /// Market data from Gamma API
 #[derive(Debug, Deserialize)]
@@ -13,59 +37,176 @@ pub struct Market {
     #[serde(rename = "clobTokenIds")]
     pub clob_token_ids: String, // JSON string like "[\"abc\", \"def\"]"
 
+    /// Outcome labels, same order as clob_token_ids: "[\"Up\", \"Down\"]"
+    pub outcomes: Option<String>,
+
     #[serde(rename = "endDate")]
     pub end_date: Option<String>,
 
     pub slug: Option<String>,
+
+    /// Minimum price increment in dollars (e.g., 0.01)
+    #[serde(rename = "orderPriceMinTickSize")]
+    pub order_price_min_tick_size: Option<f64>,
+
+    /// Max distance from midpoint that earns liquidity rewards, in cents
+    #[serde(rename = "rewardsMaxSpread")]
+    pub rewards_max_spread: Option<f64>,
+
+    /// Settles through the neg-risk exchange
+    #[serde(rename = "negRisk")]
+    pub neg_risk: Option<bool>,
+}
+
+/// Trading status of a market, polled while it's live (never cached).
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub struct MarketStatus {
+    pub active: Option<bool>,
+    pub closed: Option<bool>,
+    #[serde(rename = "acceptingOrders")]
+    pub accepting_orders: Option<bool>,
+}
+
+impl MarketStatus {
+    /// Why the market can't be traded, or None if it can. A missing
+    /// field counts as tradable.
+    pub fn halt_reason(&self) -> Option<String> {
+        if self.closed == Some(true) {
+            Some("closed".to_string())
+        } else if self.active == Some(false) {
+            Some("inactive".to_string())
+        } else if self.accepting_orders == Some(false) {
+            Some("not accepting orders (paused)".to_string())
+        } else {
+            None
+        }
+    }
 }
 
 /// Parsed market info with extracted token IDs
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct MarketInfo {
     pub condition_id: String,
     pub yes_token: String,
     pub no_token: String,
+    /// Outcome label for the YES token (e.g. "Up")
+    pub yes_label: String,
+    /// Outcome label for the NO token (e.g. "Down")
+    pub no_label: String,
     pub end_date: Option<String>,
     pub slug: String,
+    /// Minimum price increment in dollars (defaults to $0.01 if Gamma omits it)
+    pub tick_size: Decimal,
+    /// Liquidity-reward max spread in cents (None if the market has no rewards)
+    pub rewards_max_spread: Option<Decimal>,
+    /// Settles through the neg-risk exchange rather than the CTF exchange
+    pub neg_risk: bool,
+}
+
+impl MarketInfo {
+    /// Market length, from the slug or failing that from its start epoch
+    /// and Gamma's end date.
+    pub fn duration(&self) -> Option<MarketDuration> {
+        MarketDuration::from_slug(&self.slug).or_else(|| {
+            let start = parse_start_epoch(&self.slug)? as i64;
+            let end = chrono::DateTime::parse_from_rfc3339(self.end_date.as_deref()?).ok()?;
+            MarketDuration::from_secs(end.timestamp() - start)
+        })
+    }
 }
 
 /// Get current unix timestamp
-fn now() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
+fn now(clock: &dyn Clock) -> u64 {
+    (clock.now_ms() / 1000) as u64
 }
 
-/// Floor timestamp to 15-minute boundary
-fn floor_15m(ts: u64) -> u64 {
-    ts - (ts % 900)
+/// Which up/down market to trade: an asset and a window length. Slugs are
+/// `<asset>-updown-<length>-<start epoch>`, e.g. "eth-updown-15m-1772242200".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MarketSelector {
+    /// Lowercase symbol: "btc", "eth", "sol"
+    pub asset: String,
+    pub duration: MarketDuration,
 }
 
-/// Floor timestamp to 5-minute boundary
-fn floor_5m(ts: u64) -> u64 {
-    ts - (ts % 300)
+impl MarketSelector {
+    pub fn new(asset: &str, duration: MarketDuration) -> Result<Self> {
+        let asset = asset.trim().to_ascii_lowercase();
+        if asset.is_empty() || !asset.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(anyhow!("Bad asset symbol {:?}, expected e.g. \"btc\" or \"eth\"", asset));
+        }
+        Ok(Self { asset, duration })
+    }
+
+    pub fn btc(duration: MarketDuration) -> Self {
+        Self { asset: "btc".to_string(), duration }
+    }
+
+    /// "eth-15m"
+    pub fn label(&self) -> String {
+        format!("{}-{}", self.asset, self.duration.label())
+    }
+
+    /// Binance symbol of the asset's spot and perp feeds, e.g. "ethusdt".
+    pub fn binance_symbol(&self) -> String {
+        format!("{}usdt", self.asset)
+    }
+
+    /// Slug of the window starting at `start` (epoch seconds).
+    pub fn slug(&self, start: u64) -> String {
+        format!("{}-updown-{}-{}", self.asset, self.duration.label(), start)
+    }
+
+    /// Start of the window live at `clock`'s time.
+    fn current_start(&self, clock: &dyn Clock) -> u64 {
+        let ts = now(clock);
+        ts - (ts % self.duration.total_secs() as u64)
+    }
+
+    /// Slug of the market live at `clock`'s time.
+    pub fn current_slug(&self, clock: &dyn Clock) -> String {
+        self.slug(self.current_start(clock))
+    }
+
+    /// Slug of the market after the live one.
+    pub fn next_slug(&self, clock: &dyn Clock) -> String {
+        self.slug(self.current_start(clock) + self.duration.total_secs() as u64)
+    }
+
+    /// Fetch the market live at `clock`'s time.
+    pub async fn fetch_at(&self, clock: &dyn Clock) -> Result<MarketInfo> {
+        fetch_market_by_slug(&self.current_slug(clock)).await
+    }
+}
+
+/// Slug of the BTC 5-minute market live at `clock`'s time.
+pub fn current_5m_slug(clock: &dyn Clock) -> String {
+    MarketSelector::btc(MarketDuration::FiveMin).current_slug(clock)
+}
+
+/// Slug of the BTC 5-minute market after the live one.
+pub fn next_5m_slug(clock: &dyn Clock) -> String {
+    MarketSelector::btc(MarketDuration::FiveMin).next_slug(clock)
 }
 
 /// Fetch current BTC 15-minute market
 pub async fn get_current_15m_market() -> Result<MarketInfo> {
-    let epoch = floor_15m(now());
-    let slug = format!("btc-updown-15m-{}", epoch);
-    fetch_market_by_slug(&slug).await
+    MarketSelector::btc(MarketDuration::FifteenMin).fetch_at(&SystemClock).await
 }
 
 /// Fetch current BTC 5-minute market
 pub async fn get_current_5m_market() -> Result<MarketInfo> {
-    let epoch = floor_5m(now());
-    let slug = format!("btc-updown-5m-{}", epoch);
-    fetch_market_by_slug(&slug).await
+    get_5m_market_at(&SystemClock).await
+}
+
+/// Fetch the BTC 5-minute market live at `clock`'s time
+pub async fn get_5m_market_at(clock: &dyn Clock) -> Result<MarketInfo> {
+    MarketSelector::btc(MarketDuration::FiveMin).fetch_at(clock).await
 }
 
 /// Fetch next BTC 5-minute market
 pub async fn get_next_5m_market() -> Result<MarketInfo> {
-    let epoch = floor_5m(now()) + 300;
-    let slug = format!("btc-updown-5m-{}", epoch);
-    fetch_market_by_slug(&slug).await
+    fetch_market_by_slug(&next_5m_slug(&SystemClock)).await
 }
 
 /// Parse start epoch from slug (e.g., "btc-updown-5m-1772242500" -> 1772242500)
@@ -73,15 +214,69 @@ pub fn parse_start_epoch(slug: &str) -> Option<u64> {
     slug.split('-').last()?.parse().ok()
 }
 
-/// Fetch market by slug from Gamma API
+/// Fetch market by slug, using the cache and retrying while the slug 404s.
+/// Other errors are returned immediately.
 async fn fetch_market_by_slug(slug: &str) -> Result<MarketInfo> {
+    if let Some(info) = cached(slug) {
+        return Ok(info);
+    }
+
+    let mut attempt = 0;
+    loop {
+        match fetch_market_uncached(slug).await {
+            Ok(info) => {
+                cache_insert(&info);
+                return Ok(info);
+            }
+            Err(e) if e.is::<MarketNotFound>() && attempt < NOT_FOUND_RETRIES => {
+                tokio::time::sleep(not_found_backoff(attempt)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Delay before retry number `attempt` (0-based).
+fn not_found_backoff(attempt: u32) -> Duration {
+    let ms = NOT_FOUND_BACKOFF_MS.saturating_mul(1 << attempt.min(16));
+    Duration::from_millis(ms.min(NOT_FOUND_MAX_BACKOFF_MS))
+}
+
+fn cached(slug: &str) -> Option<MarketInfo> {
+    MARKET_CACHE.lock().unwrap().get(slug).cloned()
+}
+
+fn cache_insert(info: &MarketInfo) {
+    let mut cache = MARKET_CACHE.lock().unwrap();
+    if cache.len() >= CACHE_CAPACITY {
+        // Markets are fetched in time order; the oldest slug sorts first
+        if let Some(oldest) = cache.keys().min_by_key(|s| parse_start_epoch(s)).cloned() {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(info.slug.clone(), info.clone());
+}
+
+/// Fetch a market's current trading status, bypassing the cache.
+pub async fn fetch_market_status(http: &reqwest::Client, slug: &str) -> Result<MarketStatus> {
+    let url = format!("{}/markets/slug/{}", GAMMA_BASE, slug);
+    let response = http.get(&url).send().await?;
+    if response.status() == 404 {
+        return Err(MarketNotFound(slug.to_string()).into());
+    }
+    Ok(response.json().await?)
+}
+
+/// Fetch market by slug from Gamma API
+async fn fetch_market_uncached(slug: &str) -> Result<MarketInfo> {
     let url = format!("{}/markets/slug/{}", GAMMA_BASE, slug);
 
     let client = reqwest::Client::new();
     let response = client.get(&url).send().await?;
 
     if response.status() == 404 {
-        return Err(anyhow!("Market not found: {}", slug));
+        return Err(MarketNotFound(slug.to_string()).into());
     }
 
     let market: Market = response.json().await?;
@@ -93,11 +288,190 @@ async fn fetch_market_by_slug(slug: &str) -> Result<MarketInfo> {
         return Err(anyhow!("Market {} has less than 2 tokens", slug));
     }
 
+    let labels: Option<Vec<String>> = match &market.outcomes {
+        Some(raw) => Some(serde_json::from_str(raw)?),
+        None => None,
+    };
+    let (yes_idx, no_idx) = map_outcomes(slug, token_ids.len(), labels.as_deref())?;
+    let label = |i: usize| {
+        labels
+            .as_ref()
+            .map(|l| l[i].clone())
+            .unwrap_or_else(|| if i == yes_idx { "Yes" } else { "No" }.to_string())
+    };
+
+    // f64 -> string -> Decimal keeps 0.01 exact
+    let tick_size = market
+        .order_price_min_tick_size
+        .and_then(|t| t.to_string().parse::<Decimal>().ok())
+        .unwrap_or(Decimal::new(1, 2));
+    let rewards_max_spread = market
+        .rewards_max_spread
+        .and_then(|c| c.to_string().parse::<Decimal>().ok());
+
     Ok(MarketInfo {
         condition_id: market.condition_id,
-        yes_token: token_ids[0].clone(),
-        no_token: token_ids[1].clone(),
+        yes_token: token_ids[yes_idx].clone(),
+        no_token: token_ids[no_idx].clone(),
+        yes_label: label(yes_idx),
+        no_label: label(no_idx),
         end_date: market.end_date,
         slug: slug.to_string(),
+        tick_size,
+        rewards_max_spread,
+        neg_risk: market.neg_risk.unwrap_or(false),
     })
 }
+
+/// Labels that mean "the YES side" of a binary market.
+const YES_LABELS: [&str; 2] = ["yes", "up"];
+/// Labels that mean "the NO side" of a binary market.
+const NO_LABELS: [&str; 2] = ["no", "down"];
+
+/// Work out which token index is YES and which is NO from the outcome
+/// labels, instead of assuming index 0 is YES. A swapped mapping would
+/// silently invert the strategy, so anything ambiguous is an error.
+///
+/// # Returns
+/// `(yes_index, no_index)` into clob_token_ids
+fn map_outcomes(slug: &str, n_tokens: usize, labels: Option<&[String]>) -> Result<(usize, usize)> {
+    let Some(labels) = labels else {
+        println!("[gamma] {} has no outcome labels, assuming [YES, NO] order", slug);
+        return Ok((0, 1));
+    };
+    if labels.len() != n_tokens || labels.len() != 2 {
+        return Err(anyhow!(
+            "Market {} has {} labels for {} tokens: {:?}",
+            slug, labels.len(), n_tokens, labels
+        ));
+    }
+
+    let find = |accepted: &[&str]| {
+        labels
+            .iter()
+            .position(|l| accepted.contains(&l.trim().to_lowercase().as_str()))
+    };
+    match (find(&YES_LABELS), find(&NO_LABELS)) {
+        (Some(yes), Some(no)) if yes != no => Ok((yes, no)),
+        _ => Err(anyhow!("Market {} has unrecognized outcome labels {:?}", slug, labels)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(slug: &str) -> MarketInfo {
+        MarketInfo {
+            condition_id: "0xabc".to_string(),
+            yes_token: "yes".to_string(),
+            no_token: "no".to_string(),
+            yes_label: "Up".to_string(),
+            no_label: "Down".to_string(),
+            end_date: None,
+            slug: slug.to_string(),
+            tick_size: Decimal::new(1, 2),
+            rewards_max_spread: None,
+            neg_risk: false,
+        }
+    }
+
+    fn labels(l: &[&str]) -> Vec<String> {
+        l.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_map_outcomes() {
+        assert_eq!(map_outcomes("m", 2, Some(&labels(&["Up", "Down"]))).unwrap(), (0, 1));
+        assert_eq!(map_outcomes("m", 2, Some(&labels(&["Yes", "No"]))).unwrap(), (0, 1));
+        // Swapped order is mapped, not assumed
+        assert_eq!(map_outcomes("m", 2, Some(&labels(&["Down", "Up"]))).unwrap(), (1, 0));
+        // No labels: legacy assumption
+        assert_eq!(map_outcomes("m", 2, None).unwrap(), (0, 1));
+    }
+
+    #[test]
+    fn test_map_outcomes_rejects_ambiguous() {
+        assert!(map_outcomes("m", 2, Some(&labels(&["Trump", "Harris"]))).is_err());
+        assert!(map_outcomes("m", 2, Some(&labels(&["Up", "Up"]))).is_err());
+        assert!(map_outcomes("m", 3, Some(&labels(&["Up", "Down"]))).is_err());
+        assert!(map_outcomes("m", 3, Some(&labels(&["Up", "Down", "Flat"]))).is_err());
+    }
+
+    #[test]
+    fn test_rollover_slugs_follow_clock() {
+        let clock = crate::clock::ManualClock::new(1_772_242_650_000);
+        assert_eq!(current_5m_slug(&clock), "btc-updown-5m-1772242500");
+        assert_eq!(next_5m_slug(&clock), "btc-updown-5m-1772242800");
+
+        // Exactly on the boundary belongs to the new market
+        clock.set_ms(1_772_242_800_000);
+        assert_eq!(current_5m_slug(&clock), "btc-updown-5m-1772242800");
+    }
+
+    #[test]
+    fn test_selector_slugs() {
+        let clock = crate::clock::ManualClock::new(1_772_242_650_000);
+        let eth = MarketSelector::new(" ETH ", MarketDuration::FifteenMin).unwrap();
+        assert_eq!(eth.current_slug(&clock), "eth-updown-15m-1772242200");
+        assert_eq!(eth.next_slug(&clock), "eth-updown-15m-1772243100");
+        assert_eq!((eth.label(), eth.binance_symbol()), ("eth-15m".to_string(), "ethusdt".to_string()));
+        assert_eq!(MarketDuration::from_slug(&eth.current_slug(&clock)), Some(MarketDuration::FifteenMin));
+
+        assert!(MarketSelector::new("", MarketDuration::FiveMin).is_err());
+        assert!(MarketSelector::new("btc-updown", MarketDuration::FiveMin).is_err());
+    }
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(info("btc-updown-5m-1772242500").duration(), Some(MarketDuration::FiveMin));
+        assert_eq!(info("btc-updown-15m-1772242200").duration(), Some(MarketDuration::FifteenMin));
+
+        // Slug without a length: fall back to the end date
+        let mut m = info("btc-updown-1772242200");
+        assert_eq!(m.duration(), None);
+        m.end_date = Some("2026-02-28T01:45:00Z".to_string());
+        assert_eq!(m.duration(), Some(MarketDuration::FifteenMin));
+    }
+
+    #[test]
+    fn test_not_found_backoff() {
+        assert_eq!(not_found_backoff(0), Duration::from_millis(250));
+        assert_eq!(not_found_backoff(1), Duration::from_millis(500));
+        assert_eq!(not_found_backoff(2), Duration::from_millis(1000));
+        assert_eq!(not_found_backoff(3), Duration::from_millis(2000));
+        assert_eq!(not_found_backoff(30), Duration::from_millis(2000));
+    }
+
+    #[test]
+    fn test_not_found_is_distinguishable() {
+        let e: anyhow::Error = MarketNotFound("btc-updown-5m-1".to_string()).into();
+        assert!(e.is::<MarketNotFound>());
+        assert_eq!(e.to_string(), "Market not found: btc-updown-5m-1");
+        assert!(!anyhow!("timeout").is::<MarketNotFound>());
+    }
+
+    #[test]
+    fn test_cache_evicts_oldest() {
+        // Slugs far from real epochs so other tests can't collide
+        for i in 0..CACHE_CAPACITY as u64 + 1 {
+            cache_insert(&info(&format!("test-cache-{}", 10 + i)));
+        }
+        assert!(cached("test-cache-10").is_none());
+        assert!(cached("test-cache-11").is_some());
+        assert_eq!(
+            cached(&format!("test-cache-{}", 10 + CACHE_CAPACITY)).unwrap().condition_id,
+            "0xabc"
+        );
+    }
+
+    #[test]
+    fn test_market_status() {
+        let parse = |json: &str| serde_json::from_str::<MarketStatus>(json).unwrap().halt_reason();
+        assert_eq!(parse(r#"{"active":true,"closed":false,"acceptingOrders":true,"slug":"m"}"#), None);
+        assert_eq!(parse(r#"{"slug":"m"}"#), None);
+        assert_eq!(parse(r#"{"active":true,"closed":false,"acceptingOrders":false}"#).unwrap(), "not accepting orders (paused)");
+        assert_eq!(parse(r#"{"active":true,"closed":true,"acceptingOrders":false}"#).unwrap(), "closed");
+        assert_eq!(parse(r#"{"active":false}"#).unwrap(), "inactive");
+    }
+}
@@ -1 +1,9 @@
+pub mod account;
+pub mod book;
+pub mod data;
+pub mod fees;
 pub mod gamma;
+pub mod settlement;
+
+/// Polymarket CLOB REST API.
+pub(crate) const CLOB_BASE: &str = "https://clob.polymarket.com";
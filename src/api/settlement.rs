@@ -0,0 +1,210 @@
+//! Settlement: redeem a closed window's shares once its market resolves.
+//!
+//! Paired shares are merged at rollover, but the unpaired rest only pays
+//! once the market resolves, usually minutes after it closes. Each window
+//! that closes with unpaired shares is queued here. Every `POLL_SECS` Gamma
+//! is asked whether it has resolved. Once it has, the main loop redeems
+//! through the CTF contract, off the tick, and records the payout with
+//! `Pnl::redeem` when the result comes back.
+//! A window still unresolved after `MAX_WAIT_SECS` is dropped, to be
+//! swept up by the `redeem` binary.
+
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use super::gamma::{MarketInfo, MarketNotFound, GAMMA_BASE};
+use crate::events::Side;
+
+/// Between resolution checks for one window (seconds)
+pub const POLL_SECS: f64 = 30.0;
+/// Give up on a window after this long (seconds)
+pub const MAX_WAIT_SECS: f64 = 3600.0;
+
+/// Resolution fields of a Gamma market.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub struct Resolution {
+    pub closed: Option<bool>,
+    /// Outcome labels, as a JSON string: "[\"Up\", \"Down\"]"
+    pub outcomes: Option<String>,
+    /// Final prices per outcome, as a JSON string: "[\"1\", \"0\"]"
+    #[serde(rename = "outcomePrices")]
+    pub outcome_prices: Option<String>,
+}
+
+impl Resolution {
+    /// The winning side, once the market is closed and one outcome is
+    /// priced at $1.
+    pub fn winner(&self, yes_label: &str) -> Option<Side> {
+        if self.closed != Some(true) {
+            return None;
+        }
+        let labels: Vec<String> = serde_json::from_str(self.outcomes.as_deref()?).ok()?;
+        let prices: Vec<String> = serde_json::from_str(self.outcome_prices.as_deref()?).ok()?;
+        let won = prices.iter().position(|p| p.parse::<f64>().is_ok_and(|p| p >= 1.0))?;
+        let label = labels.get(won)?;
+        Some(if label == yes_label { Side::Yes } else { Side::No })
+    }
+}
+
+/// A window that resolved, with the redeem transaction if we sent one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Resolved {
+    pub winner: Side,
+    pub tx: Option<String>,
+}
+
+/// Fetch a market's resolution, bypassing the market cache.
+pub async fn fetch_resolution(http: &reqwest::Client, slug: &str) -> Result<Resolution> {
+    let url = format!("{}/markets/slug/{}", GAMMA_BASE, slug);
+    let response = http.get(&url).send().await?;
+    if response.status() == 404 {
+        return Err(MarketNotFound(slug.to_string()).into());
+    }
+    Ok(response.json().await?)
+}
+
+/// A closed window waiting to resolve.
+#[derive(Debug, Clone)]
+pub struct Pending {
+    pub slug: String,
+    pub condition_id: String,
+    pub yes_label: String,
+    pub qty_yes: Decimal,
+    pub qty_no: Decimal,
+    queued_at: f64,
+    last_poll: f64,
+}
+
+impl Pending {
+    /// USDC the winning shares pay out ($1 each).
+    pub fn payout(&self, winner: Side) -> Decimal {
+        match winner {
+            Side::Yes => self.qty_yes,
+            Side::No => self.qty_no,
+        }
+    }
+}
+
+/// Closed windows waiting to resolve, oldest first.
+#[derive(Debug, Default)]
+pub struct Settlement {
+    pending: Vec<Pending>,
+}
+
+impl Settlement {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a window that closed holding `qty_yes` / `qty_no`.
+    pub fn queue(&mut self, market: &MarketInfo, qty_yes: Decimal, qty_no: Decimal, now: f64) {
+        self.pending.push(Pending {
+            slug: market.slug.clone(),
+            condition_id: market.condition_id.clone(),
+            yes_label: market.yes_label.clone(),
+            qty_yes,
+            qty_no,
+            queued_at: now,
+            last_poll: now,
+        });
+    }
+
+    /// Windows to check now (each at most every `POLL_SECS`).
+    pub fn due(&mut self, now: f64) -> Vec<Pending> {
+        self.pending
+            .iter_mut()
+            .filter(|p| now - p.last_poll >= POLL_SECS)
+            .map(|p| {
+                p.last_poll = now;
+                p.clone()
+            })
+            .collect()
+    }
+
+    /// A window still waiting, by slug.
+    pub fn get(&self, slug: &str) -> Option<&Pending> {
+        self.pending.iter().find(|p| p.slug == slug)
+    }
+
+    /// Done with a window: redeemed, or nothing to redeem.
+    pub fn settled(&mut self, slug: &str) {
+        self.pending.retain(|p| p.slug != slug);
+    }
+
+    /// Drop windows waiting longer than `MAX_WAIT_SECS`.
+    ///
+    /// # Returns
+    /// Their slugs.
+    pub fn expire(&mut self, now: f64) -> Vec<String> {
+        let (expired, kept): (Vec<_>, Vec<_>) = self.pending.drain(..).partition(|p| now - p.queued_at >= MAX_WAIT_SECS);
+        self.pending = kept;
+        expired.into_iter().map(|p| p.slug).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn resolution(closed: bool, prices: &str) -> Resolution {
+        Resolution {
+            closed: Some(closed),
+            outcomes: Some("[\"Up\", \"Down\"]".to_string()),
+            outcome_prices: Some(prices.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_winner() {
+        assert_eq!(resolution(true, "[\"1\", \"0\"]").winner("Up"), Some(Side::Yes));
+        assert_eq!(resolution(true, "[\"0\", \"1\"]").winner("Up"), Some(Side::No));
+        // YES is whichever outcome the market's YES token is
+        assert_eq!(resolution(true, "[\"1\", \"0\"]").winner("Down"), Some(Side::No));
+        // Closed but not yet resolved, or still trading
+        assert_eq!(resolution(true, "[\"0.52\", \"0.48\"]").winner("Up"), None);
+        assert_eq!(resolution(false, "[\"1\", \"0\"]").winner("Up"), None);
+        assert_eq!(Resolution::default().winner("Up"), None);
+    }
+
+    #[test]
+    fn test_queue_poll_and_expire() {
+        let market = MarketInfo {
+            condition_id: "0xabc".to_string(),
+            yes_token: "y".to_string(),
+            no_token: "n".to_string(),
+            yes_label: "Up".to_string(),
+            no_label: "Down".to_string(),
+            end_date: None,
+            slug: "btc-updown-5m-0".to_string(),
+            tick_size: dec!(0.01),
+            rewards_max_spread: None,
+            neg_risk: false,
+        };
+        let mut settlement = Settlement::new();
+        settlement.queue(&market, dec!(12), dec!(5), 100.0);
+
+        assert!(settlement.due(110.0).is_empty());
+        let due = settlement.due(130.0);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].payout(Side::Yes), dec!(12));
+        assert!(settlement.due(140.0).is_empty()); // Polled at 130
+
+        assert!(settlement.expire(200.0).is_empty());
+        assert_eq!(settlement.expire(100.0 + MAX_WAIT_SECS), ["btc-updown-5m-0"]);
+        assert!(settlement.is_empty());
+
+        settlement.queue(&market, dec!(1), dec!(0), 0.0);
+        settlement.settled("btc-updown-5m-0");
+        assert_eq!(settlement.len(), 0);
+    }
+}
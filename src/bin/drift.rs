@@ -0,0 +1,114 @@
+//! Find where live state drifted from what its inputs imply.
+//!
+//! Replays the state ledger (and, with `--feed`, the recorded market feed)
+//! from a journal's first frame, and compares the rebuilt position, ladder
+//! and book with each frame the journal recorded. Prints the first lasting
+//! difference and the events since the last frame that agreed, and exits
+//! nonzero if there is one.
+//!
+//! Usage:
+//!     cargo run --bin drift -- logs/polybot_<ts>.journal.jsonl logs/state.jsonl
+//!     cargo run --bin drift -- <journal> <ledger> --feed logs/feed/<file>.jsonl.zst --tokens <YES> <NO>
+//!
+//! The book is only checked with `--feed`; the recorded messages are mapped
+//! to sides with the market's YES and NO token IDs.
+
+use anyhow::{bail, Result};
+
+use polybot_rs::drift::{find_drift, Step};
+use polybot_rs::journal::read_journal;
+use polybot_rs::ledger::read_ledger;
+
+fn arg_after<'a>(args: &'a [String], flag: &str, offset: usize) -> Option<&'a String> {
+    args.iter().position(|a| a == flag).and_then(|i| args.get(i + offset))
+}
+
+/// Best bid/ask updates from a recorded feed file.
+#[cfg(feature = "recorder")]
+fn book_steps(path: &str, yes_token: &str, no_token: &str) -> Result<Vec<Step>> {
+    use polybot_rs::events::Event;
+    use polybot_rs::feeds::polymarket::PolymarketFeed;
+
+    let feed = PolymarketFeed::new(yes_token.to_string(), no_token.to_string());
+    let mut steps = Vec::new();
+    for message in polybot_rs::feeds::recorder::read(path)? {
+        if message.source != "polymarket" {
+            continue;
+        }
+        for event in feed.parse(&message.raw).unwrap_or_default() {
+            if let Event::BookUpdate { side, bid, ask } = event {
+                steps.push(Step::Book { ts: message.ts_ms as f64 / 1000.0, side, bid, ask });
+            }
+        }
+    }
+    Ok(steps)
+}
+
+#[cfg(not(feature = "recorder"))]
+fn book_steps(_path: &str, _yes_token: &str, _no_token: &str) -> Result<Vec<Step>> {
+    bail!("--feed needs the `recorder` feature")
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let positional: Vec<&String> = args.iter().skip(1).take_while(|a| !a.starts_with("--")).collect();
+    let [journal_path, ledger_path] = positional[..] else {
+        bail!("Usage: drift <journal.jsonl> <state.jsonl> [--feed FILE.jsonl.zst --tokens YES NO]");
+    };
+
+    let snapshots = read_journal(journal_path)?;
+    let (Some(first), Some(last)) = (snapshots.first(), snapshots.last()) else {
+        bail!("No snapshots in {}", journal_path);
+    };
+
+    // The ledger spans runs: keep this journal's stretch of it
+    let mut steps: Vec<Step> = read_ledger(ledger_path)?
+        .into_iter()
+        .filter(|e| e.ts() >= first.ts && e.ts() <= last.ts)
+        .map(Step::Ledger)
+        .collect();
+    let ledger_steps = steps.len();
+
+    let check_book = match arg_after(&args, "--feed", 1) {
+        Some(feed) => {
+            let (Some(yes), Some(no)) = (arg_after(&args, "--tokens", 1), arg_after(&args, "--tokens", 2)) else {
+                bail!("--feed needs --tokens YES NO");
+            };
+            steps.extend(book_steps(feed, yes, no)?.into_iter().filter(|s| s.ts() <= last.ts));
+            true
+        }
+        None => false,
+    };
+    steps.sort_by(|a, b| a.ts().total_cmp(&b.ts()));
+
+    println!(
+        "Replaying {} ledger entries and {} book updates over {} frames ({:.0}s)",
+        ledger_steps,
+        steps.len() - ledger_steps,
+        snapshots.len(),
+        last.ts - first.ts
+    );
+
+    match find_drift(&snapshots, &steps, check_book) {
+        None => {
+            println!("No drift: every frame matches the replayed state");
+            Ok(())
+        }
+        Some(drift) => {
+            println!(
+                "\nDRIFT at frame {} ({:.3}, {:.0}s in)",
+                drift.snapshot,
+                drift.ts,
+                drift.ts - first.ts
+            );
+            for diff in &drift.diffs {
+                println!("  {}", diff);
+            }
+            println!("\nEvents since the last matching frame ({}):", drift.suspects.len());
+            for step in &drift.suspects {
+                println!("  {}", step.describe());
+            }
+            std::process::exit(1);
+        }
+    }
+}
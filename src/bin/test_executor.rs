@@ -13,10 +13,12 @@ use polyfill_rs::ClobClient;
 use polyfill_rs::orders::SigType;
 use rust_decimal_macros::dec;
 use std::str::FromStr;
+use std::time::Duration;
 
-use polybot_rs::api::gamma;
+use polybot_rs::api::{fees, gamma};
 use polybot_rs::events::Side;
 use polybot_rs::executor::{Executor, ExecutorConfig};
+use polybot_rs::risk::{OrderRules, RiskLimits, VariantBudget};
 use polybot_rs::state::OrderTracker;
 use polybot_rs::strategy::Action;
 
@@ -63,6 +65,14 @@ async fn main() -> Result<()> {
         log_only: false,
         yes_token: market.yes_token.clone(),
         no_token: market.no_token.clone(),
+        limits: RiskLimits::default(),
+        take_cooldown: Duration::from_secs(10),
+        variant_budgets: VariantBudget::defaults(),
+        end_timestamp_ms: None,
+        close_buffer: Duration::from_secs(3),
+        order_rules: OrderRules::default(),
+        take_max_depth_fraction: dec!(1),
+        fee_rate_bps: fees::fee_rate(&market.yes_token, 1000).await?,
     };
     let executor = Executor::new(client, config);
 
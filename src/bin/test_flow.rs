@@ -55,7 +55,7 @@ async fn main() -> anyhow::Result<()> {
         tokio::select! {
             Some(event) = rx.recv() => {
                 match event {
-                    Event::Trade { side, price } => {
+                    Event::Trade { side, price, .. } => {
                         let now = now_secs();
                         flow_est.record_trade(now);
 
@@ -7,7 +7,7 @@ use rust_decimal_macros::dec;
 use std::str::FromStr;
 use std::time::Instant;
 
-use polybot_rs::api::gamma;
+use polybot_rs::api::{fees, gamma};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -40,10 +40,14 @@ async fn main() -> Result<()> {
     let market = gamma::get_current_15m_market().await?;
     println!("Market: {} | Token: {}...", market.slug, &market.yes_token[..20]);
 
+    // Fee rate the CLOB expects for this market
+    let fee_rate_bps = fees::fee_rate(&market.yes_token, 1000).await?;
+    println!("Fee rate: {}bps", fee_rate_bps);
+
     // Place order
     let args = OrderArgs::new(&market.yes_token, dec!(0.01), dec!(5.0), Side::BUY);
     let extras = ExtraOrderArgs {
-        fee_rate_bps: 1000,
+        fee_rate_bps,
         nonce: U256::ZERO,
         taker: "0x0000000000000000000000000000000000000000".to_string(),
     };
@@ -13,6 +13,7 @@ use tokio::sync::mpsc;
 use polybot_rs::api::gamma;
 use polybot_rs::events::{Event, Side};
 use polybot_rs::feeds::binance;
+use polybot_rs::feeds::recorder::Recorder;
 use polybot_rs::feeds::polymarket::PolymarketFeed;
 use polybot_rs::strategy::{AvellanedaStoikov, FlowEstimator, Quotes, VarianceEstimator};
 
@@ -47,7 +48,7 @@ async fn main() -> anyhow::Result<()> {
     let (tx, mut rx) = mpsc::channel::<Event>(1000);
 
     // Spawn feeds
-    binance::spawn(tx.clone());
+    binance::spawn("btcusdt", tx.clone(), Recorder::disabled());
     let poly_feed = PolymarketFeed::new(market.yes_token.clone(), market.no_token.clone());
     let mut poly_handle = poly_feed.spawn(tx.clone());
 
@@ -12,6 +12,7 @@ use tokio::sync::mpsc;
 use polybot_rs::api::gamma;
 use polybot_rs::events::{Event, Side};
 use polybot_rs::feeds::binance;
+use polybot_rs::feeds::recorder::Recorder;
 use polybot_rs::feeds::polymarket::PolymarketFeed;
 use polybot_rs::strategy::VarianceEstimator;
 
@@ -98,7 +99,7 @@ async fn main() -> anyhow::Result<()> {
     let (tx, mut rx) = mpsc::channel::<Event>(1000);
 
     // Spawn Binance feed
-    binance::spawn(tx.clone());
+    binance::spawn("btcusdt", tx.clone(), Recorder::disabled());
 
     // Spawn Polymarket feed
     let poly_feed = PolymarketFeed::new(market.yes_token.clone(), market.no_token.clone());
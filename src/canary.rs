@@ -0,0 +1,249 @@
+//! Canary deployment: a new build trades a tiny, hard-capped notional
+//! next to the production instance, then compares its windows with
+//! production's.
+//!
+//! With `--canary-deploy USD` the run never has more than `USD` at risk:
+//! filled cost plus resting bids, both sides and every variant. It trades
+//! `--markets N` windows (`DEFAULT_MARKETS` unless set), and at exit reads
+//! the other session CSVs in `logs/`, matches its windows to production's
+//! by market and writes `logs/canary_<ts>.txt`. Sizes differ by design, so
+//! windows are compared per share: guaranteed P&L per paired share, and
+//! the fraction of shares left unpaired.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rust_decimal::Decimal;
+
+use crate::risk::{Variant, VariantBudget};
+use crate::state::Position;
+
+/// Windows a canary trades when `--markets` isn't given (an hour of 5m markets)
+pub const DEFAULT_MARKETS: u32 = 12;
+
+/// The canary passes if its edge per paired share is no more than this
+/// below production's
+const EDGE_TOLERANCE_CENTS: f64 = 0.5;
+
+/// One window's end state, from a `WINDOW_END` row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowResult {
+    pub market: String,
+    pub yes_shares: f64,
+    pub no_shares: f64,
+    /// Guaranteed P&L ($)
+    pub min_pnl: f64,
+}
+
+impl WindowResult {
+    /// Parse a `WINDOW_END` CSV row.
+    fn parse(line: &str) -> Option<Self> {
+        let cols: Vec<&str> = line.split(',').collect();
+        if cols.first() != Some(&"WINDOW_END") || cols.len() < 7 {
+            return None;
+        }
+        let shares = |col: &str, prefix: &str| col.strip_prefix(prefix)?.split('@').next()?.parse::<f64>().ok();
+        Some(Self {
+            market: cols[3].to_string(),
+            yes_shares: shares(cols[4], "Y=")?,
+            no_shares: shares(cols[5], "N=")?,
+            min_pnl: cols[6].strip_prefix("minpnl=$")?.parse().ok()?,
+        })
+    }
+
+    pub fn paired(&self) -> f64 {
+        self.yes_shares.min(self.no_shares)
+    }
+
+    /// Guaranteed P&L per paired share (cents). None without pairs.
+    pub fn edge_cents(&self) -> Option<f64> {
+        (self.paired() > 0.0).then(|| self.min_pnl / self.paired() * 100.0)
+    }
+
+    /// Share of the window's shares left without a partner.
+    pub fn unpaired_fraction(&self) -> f64 {
+        let total = self.yes_shares + self.no_shares;
+        if total > 0.0 {
+            (self.yes_shares - self.no_shares).abs() / total
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Every window in a session CSV.
+pub fn windows(csv: &str) -> Vec<WindowResult> {
+    csv.lines().filter_map(WindowResult::parse).collect()
+}
+
+/// The canary's windows, each with production's window on the same market.
+#[derive(Debug, Default)]
+pub struct Comparison {
+    pub rows: Vec<(WindowResult, Option<WindowResult>)>,
+}
+
+impl Comparison {
+    pub fn new(canary: Vec<WindowResult>, production: &[WindowResult]) -> Self {
+        let by_market: HashMap<&str, &WindowResult> = production.iter().map(|w| (w.market.as_str(), w)).collect();
+        let rows = canary
+            .into_iter()
+            .map(|w| {
+                let prod = by_market.get(w.market.as_str()).map(|p| (*p).clone());
+                (w, prod)
+            })
+            .collect();
+        Self { rows }
+    }
+
+    /// Mean edge per paired share over windows both sides traded, as
+    /// (canary, production).
+    pub fn mean_edges(&self) -> Option<(f64, f64)> {
+        let pairs: Vec<(f64, f64)> = self
+            .rows
+            .iter()
+            .filter_map(|(c, p)| Some((c.edge_cents()?, p.as_ref()?.edge_cents()?)))
+            .collect();
+        if pairs.is_empty() {
+            return None;
+        }
+        let n = pairs.len() as f64;
+        Some((pairs.iter().map(|p| p.0).sum::<f64>() / n, pairs.iter().map(|p| p.1).sum::<f64>() / n))
+    }
+
+    /// PASS if the canary's edge holds up against production's, REVIEW if
+    /// it falls short or there's nothing to compare.
+    pub fn verdict(&self) -> &'static str {
+        match self.mean_edges() {
+            Some((canary, production)) if canary >= production - EDGE_TOLERANCE_CENTS => "PASS",
+            _ => "REVIEW",
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let cents = |v: Option<f64>| v.map_or("-".to_string(), |c| format!("{:+.2}c", c));
+        let _ = writeln!(
+            out,
+            "{:<28} {:>9} {:>9} {:>10} {:>9} {:>9} {:>10}",
+            "market", "canary $", "edge", "unpaired", "prod $", "edge", "unpaired"
+        );
+        for (canary, prod) in &self.rows {
+            let _ = writeln!(
+                out,
+                "{:<28} {:>9.2} {:>9} {:>9.0}% {:>9} {:>9} {:>10}",
+                canary.market,
+                canary.min_pnl,
+                cents(canary.edge_cents()),
+                canary.unpaired_fraction() * 100.0,
+                prod.as_ref().map_or("-".to_string(), |p| format!("{:.2}", p.min_pnl)),
+                cents(prod.as_ref().and_then(WindowResult::edge_cents)),
+                prod.as_ref().map_or("-".to_string(), |p| format!("{:.0}%", p.unpaired_fraction() * 100.0)),
+            );
+        }
+        let matched = self.rows.iter().filter(|(_, p)| p.is_some()).count();
+        let _ = writeln!(out, "\n{} window(s), {} matched to production", self.rows.len(), matched);
+        if let Some((canary, production)) = self.mean_edges() {
+            let _ = writeln!(out, "Mean edge per paired share: canary {:+.2}c, production {:+.2}c", canary, production);
+        }
+        let _ = writeln!(out, "Verdict: {}", self.verdict());
+        out
+    }
+}
+
+/// Compare the canary's session CSV with the other sessions in `log_dir`
+/// and write the report there.
+///
+/// # Returns
+/// The report's path and text.
+pub fn write_report(own_csv: &Path, log_dir: &Path) -> anyhow::Result<(PathBuf, String)> {
+    let canary = windows(&fs::read_to_string(own_csv)?);
+    let mut production = Vec::new();
+    for entry in fs::read_dir(log_dir)? {
+        let path = entry?.path();
+        let is_session = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("polybot_") && n.ends_with(".csv"));
+        if is_session && path != own_csv {
+            production.extend(windows(&fs::read_to_string(&path)?));
+        }
+    }
+
+    let text = Comparison::new(canary, &production).render();
+    let ts = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    let path = log_dir.join(format!("canary_{}.txt", ts));
+    fs::write(&path, &text)?;
+    Ok((path, text))
+}
+
+/// Cap every variant's notional budget at `cap`, giving the ladder (which
+/// normally has none) one too.
+pub fn cap_budgets(budgets: &mut HashMap<Variant, VariantBudget>, cap: Decimal, max_position: Decimal) {
+    for budget in budgets.values_mut() {
+        budget.max_notional = budget.max_notional.min(cap);
+    }
+    budgets.insert(Variant::Ladder, VariantBudget { max_position, max_notional: cap });
+}
+
+/// USDC new bids may lock: the cap less inventory cost, and no more than
+/// the wallet's balance if that's known.
+pub fn available(cap: Decimal, position: &Position, balance: Option<Decimal>) -> Decimal {
+    let room = (cap - position.capital_employed_usd(Decimal::ZERO)).max(Decimal::ZERO);
+    balance.map_or(room, |b| room.min(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    const CSV: &str = "type,timestamp\n\
+        WINDOW_END,100.000,,btc-updown-5m-1,Y=10.0@48.0c,N=10.0@49.0c,minpnl=$0.30,gamma=0.1000,halt=15,,,,,,,,,,,,\n\
+        TICK,101.000\n\
+        WINDOW_END,400.000,,btc-updown-5m-2,Y=0.0@0.0c,N=5.0@50.0c,minpnl=$-2.50,gamma=0.1000,halt=15,,,,,,,,,,,,\n";
+
+    #[test]
+    fn test_windows_parse() {
+        let parsed = windows(CSV);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].market, "btc-updown-5m-1");
+        assert!((parsed[0].edge_cents().unwrap() - 3.0).abs() < 1e-9);
+        assert_eq!(parsed[1].edge_cents(), None);
+        assert_eq!(parsed[1].unpaired_fraction(), 1.0);
+    }
+
+    #[test]
+    fn test_comparison_verdict() {
+        let canary = windows(CSV);
+        let production = vec![WindowResult {
+            market: "btc-updown-5m-1".to_string(),
+            yes_shares: 100.0,
+            no_shares: 100.0,
+            min_pnl: 3.2,
+        }];
+        let comparison = Comparison::new(canary.clone(), &production);
+        assert_eq!(comparison.rows.iter().filter(|(_, p)| p.is_some()).count(), 1);
+        assert_eq!(comparison.verdict(), "PASS");
+        assert!(comparison.render().contains("Verdict: PASS"));
+
+        let production = vec![WindowResult { min_pnl: 5.0, ..production[0].clone() }];
+        assert_eq!(Comparison::new(canary.clone(), &production).verdict(), "REVIEW");
+        assert_eq!(Comparison::new(canary, &[]).verdict(), "REVIEW");
+    }
+
+    #[test]
+    fn test_cap() {
+        let mut budgets = VariantBudget::defaults();
+        cap_budgets(&mut budgets, dec!(10), dec!(150));
+        assert_eq!(budgets[&Variant::Ladder].max_notional, dec!(10));
+        assert_eq!(budgets[&Variant::EndGame].max_notional, dec!(10));
+
+        let position = Position { qty_yes: dec!(10), cost_yes: dec!(4800), ..Default::default() };
+        assert_eq!(available(dec!(20), &position, None), dec!(15.2));
+        assert_eq!(available(dec!(20), &position, Some(dec!(10))), dec!(10));
+        assert_eq!(available(dec!(4), &position, None), dec!(0));
+    }
+}
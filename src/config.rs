@@ -1,13 +1,51 @@
+use anyhow::Context;
+use rust_decimal::Decimal;
 use serde::Deserialize;
-use std::fs;
+use std::sync::Arc;
+use std::{fs, io};
 
-#[derive(Debug, Deserialize)]
+use crate::journal::read_journal;
+use crate::risk::RiskLimits;
+use crate::strategy::{CalibratedFillModel, StrategyConfig, TimeInForce};
+
+/// Distance bucket for a journal-calibrated fill model (1c)
+const FILL_BUCKET_TICKS: u16 = 10;
+
+#[derive(Debug, Default, Deserialize)]
 pub struct Config {
     pub credentials: Credentials,
     pub general: General,
+    #[serde(default)]
+    pub strategy: Strategy,
+    #[serde(default)]
+    pub metrics: Metrics,
+    #[serde(default)]
+    pub logging: Logging,
+    #[serde(default)]
+    pub tracing: Tracing,
+    #[serde(default)]
+    pub paper: Paper,
+    #[serde(default)]
+    pub signal: Signal,
+    #[serde(default)]
+    pub sink: Sink,
+    #[serde(default)]
+    pub basis: Basis,
+    #[serde(default)]
+    pub subsystems: Subsystems,
+    #[serde(default)]
+    pub instances: Vec<Instance>,
+    #[serde(default)]
+    pub recorder: Recorder,
+    #[serde(default)]
+    pub dataset: Dataset,
+    #[serde(default)]
+    pub risk: Risk,
+    #[serde(default)]
+    pub markets: Markets,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct Credentials {
     pub private_key: String,
     pub proxy_wallet: String,
@@ -18,10 +56,660 @@ pub struct General {
     pub log_level: String,
 }
 
+impl Default for General {
+    fn default() -> Self {
+        Self { log_level: "info".to_string() }
+    }
+}
+
+/// Strategy overrides on top of the built-in `StrategyConfig`. Unset
+/// keys keep the built-in value.
+#[derive(Debug, Default, Deserialize)]
+pub struct Strategy {
+    /// Pair margin in ticks: pair cost is capped at $1 minus this
+    pub margin_ticks: Option<u16>,
+    /// Net shares on one side before that side stops quoting
+    pub max_position: Option<f64>,
+    pub ladder_rungs: Option<u16>,
+    /// Ticks between rungs
+    pub rung_spacing: Option<u16>,
+    /// Pair margin per rung in ticks, top rung first. Replaces
+    /// `margin_ticks`, `ladder_rungs` and `rung_spacing` when set.
+    pub rung_margins: Option<Vec<u16>>,
+    /// Imbalance tolerated at the open (shares)
+    pub rebalance_threshold: Option<f64>,
+    /// Imbalance tolerated from `inventory_flat_secs` before expiry on
+    /// (shares); the tolerance narrows to it from `rebalance_threshold`
+    pub inventory_final_tolerance: Option<f64>,
+    pub inventory_flat_secs: Option<f64>,
+    /// Heavy-side widening per share over the tolerance, and its cap (ticks)
+    pub lean_ticks_per_share: Option<f64>,
+    pub max_lean_ticks: Option<u16>,
+    pub max_take_size: Option<f64>,
+    /// Most a rebalancing Take pays over the light side's fair value (ticks)
+    pub take_premium_ticks: Option<u16>,
+    /// Hysteresis for rebalancing Takes (shares)
+    pub take_band: Option<f64>,
+    /// Post resting orders as GTD, expiring this many seconds before the
+    /// market ends
+    pub expire_before_end_secs: Option<u64>,
+    /// Seconds each rung rests before the exchange expires it, top rung
+    /// first (0 = GTC). Rungs past the list are GTC.
+    pub rung_tif: Option<Vec<u64>>,
+    /// Margin and spacing in cents, converted per market (see
+    /// `StrategyConfig::set_cents`). Override the tick values when set.
+    pub margin_cents: Option<f64>,
+    pub rung_spacing_cents: Option<f64>,
+    /// "5m" or "15m". Normally detected from the market; a mismatch warns.
+    pub duration: Option<String>,
+    /// Drop rungs less likely than this to fill within `fill_horizon_secs`
+    /// (0 keeps every rung)
+    pub min_fill_prob: Option<f64>,
+    pub fill_horizon_secs: Option<f64>,
+    /// Session journal to calibrate the fill model from, instead of the
+    /// heuristic
+    pub fill_model_journal: Option<String>,
+}
+
+impl Strategy {
+    /// Apply the tick and share overrides.
+    ///
+    /// # Errors
+    /// A zero rung count or spacing, rung margins that are empty or don't
+    /// increase, or a share amount that isn't a positive number.
+    pub fn apply(&self, config: &mut StrategyConfig) -> anyhow::Result<()> {
+        let shares = |name: &str, value: Option<f64>, current: Decimal| match value {
+            Some(v) if v > 0.0 => Decimal::try_from(v).map_err(|e| anyhow::anyhow!("[strategy] {}: {}", name, e)),
+            Some(v) => anyhow::bail!("[strategy] {} must be positive, got {}", name, v),
+            None => Ok(current),
+        };
+        if self.ladder_rungs == Some(0) || self.rung_spacing == Some(0) {
+            anyhow::bail!("[strategy] ladder_rungs and rung_spacing must be positive");
+        }
+        config.max_position = shares("max_position", self.max_position, config.max_position)?;
+        config.rebalance_threshold = shares("rebalance_threshold", self.rebalance_threshold, config.rebalance_threshold)?;
+        config.max_take_size = shares("max_take_size", self.max_take_size, config.max_take_size)?;
+        config.take_band = shares("take_band", self.take_band, config.take_band)?;
+        config.margin_ticks = self.margin_ticks.unwrap_or(config.margin_ticks);
+        config.take_premium_ticks = self.take_premium_ticks.unwrap_or(config.take_premium_ticks);
+        config.ladder_rungs = self.ladder_rungs.unwrap_or(config.ladder_rungs);
+        config.rung_spacing = self.rung_spacing.unwrap_or(config.rung_spacing);
+        config.expire_before_end_secs = self.expire_before_end_secs.or(config.expire_before_end_secs);
+        if let Some(secs) = &self.rung_tif {
+            config.rung_tif = secs
+                .iter()
+                .map(|&s| match s {
+                    0 => TimeInForce::Gtc,
+                    expires_in_secs => TimeInForce::Gtd { expires_in_secs },
+                })
+                .collect();
+        }
+        if let Some(margins) = &self.rung_margins {
+            if margins.is_empty() {
+                anyhow::bail!("[strategy] rung_margins must list at least one rung");
+            }
+            config.set_rung_margins(margins.clone()).map_err(|e| anyhow::anyhow!("[strategy] {}", e))?;
+        }
+        if let Some(v) = self.inventory_final_tolerance {
+            if v < 0.0 {
+                anyhow::bail!("[strategy] inventory_final_tolerance can't be negative, got {}", v);
+            }
+            config.inventory.final_tolerance =
+                Decimal::try_from(v).map_err(|e| anyhow::anyhow!("[strategy] inventory_final_tolerance: {}", e))?;
+        }
+        if let Some(secs) = self.inventory_flat_secs {
+            if secs < 0.0 {
+                anyhow::bail!("[strategy] inventory_flat_secs can't be negative, got {}", secs);
+            }
+            config.inventory.flat_secs = secs;
+        }
+        if let Some(ticks) = self.lean_ticks_per_share {
+            if ticks < 0.0 {
+                anyhow::bail!("[strategy] lean_ticks_per_share can't be negative, got {}", ticks);
+            }
+            config.inventory.lean_ticks_per_share = ticks;
+        }
+        config.inventory.max_lean_ticks = self.max_lean_ticks.unwrap_or(config.inventory.max_lean_ticks);
+        if let Some(p) = self.min_fill_prob {
+            if !(0.0..=1.0).contains(&p) {
+                anyhow::bail!("[strategy] min_fill_prob must be between 0 and 1, got {}", p);
+            }
+            config.min_fill_prob = p;
+        }
+        if let Some(secs) = self.fill_horizon_secs {
+            if secs <= 0.0 {
+                anyhow::bail!("[strategy] fill_horizon_secs must be positive, got {}", secs);
+            }
+            config.fill_horizon_secs = secs;
+        }
+        if let Some(path) = &self.fill_model_journal {
+            let snapshots = read_journal(path).map_err(|e| anyhow::anyhow!("[strategy] fill_model_journal {}: {:#}", path, e))?;
+            let model = CalibratedFillModel::from_journal(&snapshots, config.fill_horizon_secs, FILL_BUCKET_TICKS);
+            config.fill_model = Arc::new(model);
+        }
+        Ok(())
+    }
+
+    /// Apply the cent amounts for a market's tick size, if either is set.
+    pub fn apply_cents(&self, config: &mut StrategyConfig, venue_tick: Decimal) -> anyhow::Result<()> {
+        if self.margin_cents.is_none() && self.rung_spacing_cents.is_none() {
+            return Ok(());
+        }
+        // A tick is 0.1c
+        let cents = |value: Option<f64>, ticks: u16| {
+            value.and_then(|c| Decimal::try_from(c).ok()).unwrap_or(Decimal::from(ticks) / Decimal::TEN)
+        };
+        config.set_cents(
+            cents(self.margin_cents, config.margin_ticks),
+            cents(self.rung_spacing_cents, config.rung_spacing),
+            venue_tick,
+        )
+    }
+}
+
+/// Metrics backends. Each one is enabled by setting its address.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Metrics {
+    /// Prefix for all metric names
+    pub prefix: String,
+    /// Serve Prometheus text format on this address (e.g. "0.0.0.0:9100")
+    pub prometheus_addr: Option<String>,
+    /// Push to a StatsD agent over UDP (e.g. "127.0.0.1:8125")
+    pub statsd_addr: Option<String>,
+    /// OTLP/HTTP metrics endpoint (e.g. "http://localhost:4318/v1/metrics")
+    pub otlp_endpoint: Option<String>,
+    /// Seconds between OTLP exports
+    pub otlp_interval_secs: u64,
+    /// Latency objectives, enforced whether or not a backend is set
+    pub slo: Slo,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            prefix: "polybot".to_string(),
+            prometheus_addr: None,
+            statsd_addr: None,
+            otlp_endpoint: None,
+            otlp_interval_secs: 10,
+            slo: Slo::default(),
+        }
+    }
+}
+
+/// Latency SLOs (`[metrics.slo]`). Each is off unless set; a breach
+/// alerts and halts quoting until it clears.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Slo {
+    /// p99 of book update to cancel returned, over `window_secs` (ms)
+    pub book_to_cancel_p99_ms: Option<f64>,
+    /// Longest silence tolerated from a feed (ms)
+    pub feed_gap_ms: Option<f64>,
+    /// Rolling window for latency percentiles
+    pub window_secs: f64,
+    /// Samples needed in the window before the p99 is judged
+    pub min_samples: usize,
+}
+
+impl Default for Slo {
+    fn default() -> Self {
+        Self {
+            book_to_cancel_p99_ms: None,
+            feed_gap_ms: None,
+            window_secs: 60.0,
+            min_samples: 20,
+        }
+    }
+}
+
+/// Log sinks. Any combination can be enabled; file sinks rotate by size.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Logging {
+    /// Human-readable logs on stdout
+    pub stdout: bool,
+    /// Format stdout as JSON lines instead of text
+    pub stdout_json: bool,
+    /// Text log file (e.g. "logs/polybot.log")
+    pub file: Option<String>,
+    /// JSON-lines log file for ingestion (e.g. "logs/polybot.jsonl")
+    pub json_file: Option<String>,
+    /// Rotate a file once it reaches this size
+    pub max_file_bytes: u64,
+    /// Rotated files to keep per sink
+    pub max_files: usize,
+}
+
+impl Default for Logging {
+    fn default() -> Self {
+        Self {
+            stdout: true,
+            stdout_json: false,
+            file: None,
+            json_file: None,
+            max_file_bytes: 10 * 1024 * 1024, // 10 MB
+            max_files: 5,
+        }
+    }
+}
+
+/// Span export. Enabled by setting `otlp_endpoint`.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Tracing {
+    /// OTLP/HTTP traces endpoint (e.g. "http://localhost:4318/v1/traces")
+    pub otlp_endpoint: Option<String>,
+    /// `service.name` reported with every span
+    pub service_name: String,
+}
+
+impl Default for Tracing {
+    fn default() -> Self {
+        Self {
+            otlp_endpoint: None,
+            service_name: "polybot".to_string(),
+        }
+    }
+}
+
+/// Raw feed recorder (needs the `recorder` feature). Off by default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Recorder {
+    pub enabled: bool,
+    /// Directory for the compressed JSONL files
+    pub dir: String,
+    /// Start a new file after this many bytes of raw messages
+    pub max_file_bytes: u64,
+    /// zstd compression level
+    pub level: i32,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: "logs/feeds".to_string(),
+            max_file_bytes: 256 * 1024 * 1024, // 256 MB raw
+            level: 3,
+        }
+    }
+}
+
+/// Decision dataset for offline research (needs the `dataset` feature).
+/// Off by default.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Dataset {
+    pub enabled: bool,
+    /// Directory for the Parquet files
+    pub dir: String,
+    /// How long after a decision its outcome (the mid move) is measured
+    pub markout_secs: f64,
+    /// Rows per Parquet file
+    pub rows_per_file: usize,
+}
+
+impl Default for Dataset {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: "logs/dataset".to_string(),
+            markout_secs: 5.0,
+            rows_per_file: 50_000, // About 40 minutes of 50ms ticks
+        }
+    }
+}
+
+/// Circuit breaker thresholds, resumption and the position schedule.
+/// Tripping cancels everything and holds quoting off for `cooldown_secs`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Risk {
+    /// BTC high-low range within the window that trips (bps)
+    pub btc_move_bps: f64,
+    pub btc_window_secs: f64,
+    /// No book update for this long trips (ms)
+    pub max_book_age_ms: i64,
+    /// This many of our fills within the window trips
+    pub max_fills: usize,
+    pub fill_window_secs: f64,
+    /// Worst-case window P&L at or below -this trips (USD)
+    pub max_loss_usd: f64,
+    pub cooldown_secs: f64,
+    /// After the cooldown, warm-up checks must pass this long straight
+    pub resume_warmup_secs: f64,
+    /// Then size ramps from this fraction back to full over `resume_ramp_secs`
+    pub resume_size_start: f64,
+    pub resume_ramp_secs: f64,
+    /// (seconds left, fraction of `[strategy] max_position`): the limit
+    /// shrinks to the tightest step at or under its seconds left
+    pub position_schedule: Vec<(f64, f64)>,
+    /// Hold reconcile batches placing more than `supervised_min_shares`
+    /// for approval over the control API
+    pub supervised: bool,
+    pub supervised_min_shares: f64,
+    /// A held batch not approved within this is dropped
+    pub approval_timeout_secs: f64,
+    /// Our YES and NO bids must sum to under $1 plus this (ticks), or the
+    /// placement is refused as a self-trade
+    pub self_trade_overlap_ticks: u16,
+    /// Orders left resting by a previous run: track them as our own
+    /// (true) or cancel them (false) at startup
+    pub adopt_open_orders: bool,
+    /// Backstop on each side's resting orders, for the ladder and the
+    /// executor alike: how many, and how many shares in total
+    pub max_orders_per_side: usize,
+    pub max_exposure_per_side: f64,
+}
+
+impl Default for Risk {
+    fn default() -> Self {
+        Self {
+            btc_move_bps: 50.0,        // 0.5%; BtcGuard's 0.3%/2s covers the usual dips
+            btc_window_secs: 10.0,
+            max_book_age_ms: 10_000,   // Well past the STALE_BOOK halt
+            max_fills: 20,             // Normal is a handful per minute
+            fill_window_secs: 10.0,
+            max_loss_usd: 50.0,
+            cooldown_secs: 60.0,
+            resume_warmup_secs: 10.0,
+            resume_size_start: 0.25,   // Quarter size...
+            resume_ramp_secs: 60.0,    // ...back to full over a minute
+            position_schedule: vec![(120.0, 0.5), (60.0, 0.25), (20.0, 0.1)],
+            supervised: false,
+            supervised_min_shares: 20.0, // Four minimum orders
+            approval_timeout_secs: 30.0,
+            self_trade_overlap_ticks: 0, // Bids stay under $1 a pair
+            adopt_open_orders: false,
+            max_orders_per_side: 5,
+            max_exposure_per_side: 60.0, // 60 shares resting
+        }
+    }
+}
+
+impl Risk {
+    /// The per-side order limits.
+    ///
+    /// # Errors
+    /// A zero order count, or an exposure that isn't a positive number.
+    pub fn limits(&self) -> anyhow::Result<RiskLimits> {
+        if self.max_orders_per_side == 0 {
+            anyhow::bail!("[risk] max_orders_per_side must be positive");
+        }
+        let max_exposure_per_side = Decimal::try_from(self.max_exposure_per_side)
+            .ok()
+            .filter(|e| *e > Decimal::ZERO)
+            .ok_or_else(|| anyhow::anyhow!("[risk] max_exposure_per_side must be positive, got {}", self.max_exposure_per_side))?;
+        Ok(RiskLimits { max_orders_per_side: self.max_orders_per_side, max_exposure_per_side })
+    }
+}
+
+/// Message bus sink for fills, quotes and snapshots. Off unless a bus is set.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Sink {
+    /// NATS server (e.g. "nats://127.0.0.1:4222"), needs the `nats` feature
+    pub nats_url: Option<String>,
+    /// Kafka bootstrap servers (e.g. "localhost:9092"), needs the `kafka` feature
+    pub kafka_brokers: Option<String>,
+    /// Topics are `<prefix>.fills`, `<prefix>.quotes`, `<prefix>.snapshots`
+    pub topic_prefix: String,
+    /// Publish a topic's batch at this many records...
+    pub batch_size: usize,
+    /// ...or after this long (ms)
+    pub flush_ms: u64,
+    /// Records buffered before new ones are dropped
+    pub queue_size: usize,
+}
+
+impl Default for Sink {
+    fn default() -> Self {
+        Self {
+            nats_url: None,
+            kafka_brokers: None,
+            topic_prefix: "polybot".to_string(),
+            batch_size: 100,
+            flush_ms: 250,
+            queue_size: 10_000,
+        }
+    }
+}
+
+/// External signal input. Off unless `http_addr` is set.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Signal {
+    /// Accept signal POSTs on this address (e.g. "127.0.0.1:9200")
+    pub http_addr: Option<String>,
+    /// Weight of the external fair value in the pricing mid (0-1)
+    pub fair_weight: f64,
+    /// Extra margin on the side a full-strength direction is against (cents)
+    pub against_cents: f64,
+    /// Longest TTL honoured (ms)
+    pub max_ttl_ms: u64,
+}
+
+impl Default for Signal {
+    fn default() -> Self {
+        Self {
+            http_addr: None,
+            fair_weight: 0.5,
+            against_cents: 1.0,
+            max_ttl_ms: 60_000,
+        }
+    }
+}
+
+/// Perp-spot basis prior. Always logged; only moves quotes with a weight.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Basis {
+    /// Weight of the prior in the pricing mid (0 = log only)
+    pub weight: f64,
+    /// Basis change (bps) that gives the full shift
+    pub full_bps: f64,
+    /// Largest mid shift at weight 1 (cents)
+    pub max_shift_cents: f64,
+}
+
+impl Default for Basis {
+    fn default() -> Self {
+        Self {
+            weight: 0.0,
+            full_bps: 5.0,
+            max_shift_cents: 2.0,
+        }
+    }
+}
+
+/// An extra strategy instance (`[[instances]]`), quoting its own ladder
+/// under the main targets with its own budget.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Instance {
+    pub name: String,
+    /// Top rung this far under the main target (cents)
+    pub offset_cents: f64,
+    pub rungs: u16,
+    /// Between rungs (cents)
+    pub spacing_cents: f64,
+    /// Shares per rung
+    pub size: f64,
+    /// Shares per side, filled plus resting
+    pub max_position: f64,
+    /// USDC, filled cost plus resting notional
+    pub max_notional: f64,
+}
+
+impl Default for Instance {
+    fn default() -> Self {
+        Self {
+            name: "instance".to_string(),
+            offset_cents: 0.0,
+            rungs: 1,
+            spacing_cents: 1.0,
+            size: 5.0,          // API minimum
+            max_position: 50.0,
+            max_notional: 25.0,
+        }
+    }
+}
+
+/// Up/down markets to trade (`[markets]`): an engine per asset and market
+/// length, each with its own Binance feeds.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Markets {
+    /// Asset symbols: "btc", "eth", "sol"
+    pub assets: Vec<String>,
+}
+
+impl Default for Markets {
+    fn default() -> Self {
+        Self { assets: vec!["btc".to_string()] }
+    }
+}
+
+/// Subsystems off the hot path. All on by default; `--lean` turns them all
+/// off. A subsystem compiled out (see the Cargo features) stays off.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Subsystems {
+    /// Per-second JSON-lines journal
+    pub journal: bool,
+    /// Quote/fill/trade heatmap written at exit
+    pub heatmap: bool,
+    /// Metrics backends from `[metrics]`
+    pub metrics: bool,
+    /// Feed/API alerts
+    pub alerts: bool,
+    /// A CSV row per 50ms tick (other rows are always written)
+    pub tick_rows: bool,
+}
+
+impl Default for Subsystems {
+    fn default() -> Self {
+        Self {
+            journal: true,
+            heatmap: true,
+            metrics: true,
+            alerts: true,
+            tick_rows: true,
+        }
+    }
+}
+
+impl Subsystems {
+    /// Everything off: feeds -> strategy -> executor only.
+    pub fn lean() -> Self {
+        Self {
+            journal: false,
+            heatmap: false,
+            metrics: false,
+            alerts: false,
+            tick_rows: false,
+        }
+    }
+}
+
+/// Paper trading: simulated venue and its latency.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Paper {
+    /// Trade against the simulated venue instead of the CLOB (no keys needed)
+    pub enabled: bool,
+    /// Delay before a placed order rests on the simulated book
+    pub place_ack: Latency,
+    /// Delay before a cancel takes effect (fills can still land meanwhile)
+    pub cancel_ack: Latency,
+    /// Delay between a simulated fill and us hearing about it
+    pub fill_report: Latency,
+    /// RNG seed, so a paper run can be reproduced exactly
+    pub seed: u64,
+    /// Fault injection for soak runs (`--soak HOURS`)
+    pub faults: Faults,
+}
+
+impl Default for Paper {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            place_ack: Latency { min_ms: 40, max_ms: 120, spike_prob: 0.02, spike_ms: 800 },
+            cancel_ack: Latency { min_ms: 40, max_ms: 120, spike_prob: 0.02, spike_ms: 800 },
+            fill_report: Latency { min_ms: 50, max_ms: 250, spike_prob: 0.01, spike_ms: 1500 },
+            seed: 1,
+            faults: Faults::default(),
+        }
+    }
+}
+
+/// Fault rates for a soak run. Probabilities are per event (feeds), per
+/// fill report, or per executor batch.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct Faults {
+    /// A feed event starts an outage of that feed
+    pub disconnect_prob: f64,
+    /// Outage length, up to this long (ms)
+    pub disconnect_max_ms: u64,
+    /// A feed event arrives malformed: dropped, or garbage that still parses
+    pub malformed_prob: f64,
+    /// A fill report (or the trade print a paper fill comes from) is held back
+    pub fill_delay_prob: f64,
+    /// ...by up to this long (ms)
+    pub fill_delay_max_ms: u64,
+    /// An executor batch times out
+    pub api_timeout_prob: f64,
+    /// How long a timed-out call hangs first (ms)
+    pub api_timeout_ms: u64,
+}
+
+impl Default for Faults {
+    fn default() -> Self {
+        Self {
+            disconnect_prob: 0.0005,
+            disconnect_max_ms: 10_000,
+            malformed_prob: 0.001,
+            fill_delay_prob: 0.2,
+            fill_delay_max_ms: 3000,
+            api_timeout_prob: 0.01,
+            api_timeout_ms: 3000,
+        }
+    }
+}
+
+/// Latency distribution: uniform between `min_ms` and `max_ms`, plus an
+/// occasional `spike_ms` tail with probability `spike_prob`.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct Latency {
+    pub min_ms: u64,
+    pub max_ms: u64,
+    pub spike_prob: f64,
+    pub spike_ms: u64,
+}
+
 impl Config {
     pub fn load(path: &str) -> anyhow::Result<Self> {
         let contents = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&contents)?;
+        Self::parse(&contents)
+    }
+
+    /// `load`, or the built-in defaults when there's no file. A file that
+    /// can't be read or doesn't parse is still an error.
+    pub fn load_or_default(path: &str) -> anyhow::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents).with_context(|| format!("Invalid {}", path)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("Reading {}", path)),
+        }
+    }
+
+    pub fn parse(contents: &str) -> anyhow::Result<Self> {
+        let config: Config = toml::from_str(contents)?;
         Ok(config)
     }
 }
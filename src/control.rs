@@ -0,0 +1,176 @@
+//! Runtime control surface for the engine.
+//!
+//! Lets something outside the main loop pause/resume quoting and change a
+//! few parameters, and subscribe to what the engine is doing. Transport
+//! agnostic: the gRPC server (feature `grpc`) is one front end; anything
+//! else that holds a `ControlHandle` works the same way.
+
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+use crate::events::Side;
+use crate::state::PnlReport;
+
+/// Something to change in the running engine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlCommand {
+    /// Cancel everything and stop quoting until resumed
+    Pause { reason: String },
+    /// Resume quoting
+    Resume,
+    /// Change a runtime parameter (see `RuntimeParams::set`)
+    SetParam { name: String, value: f64 },
+    /// Execute a held batch (supervised mode)
+    Approve { id: u64 },
+    /// Drop a held batch (supervised mode)
+    Reject { id: u64 },
+}
+
+/// A command plus where to send the outcome.
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    pub reply: oneshot::Sender<Result<String, String>>,
+}
+
+/// Something the engine did, for subscribers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineEvent {
+    /// One of our orders filled
+    Fill { side: Side, price: u16, size: f64, order_id: String },
+    /// Quoting halted and resting orders were cancelled
+    Halt { reason: String },
+    /// Recovery after a breaker trip moved on ("tripped", "warming_up",
+    /// "ramping", "normal"), with the size being quoted
+    Recovery { stage: String, size_fraction: f64 },
+    /// Realized and unrealized P&L, per market and for the session
+    Pnl(PnlReport),
+    /// A batch held for approval (supervised mode)
+    Batch(BatchPreview),
+    /// Periodic snapshot
+    Status(EngineStatus),
+}
+
+/// Periodic engine snapshot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EngineStatus {
+    pub market: String,
+    pub paused: bool,
+    pub time_left: f64,
+    pub yes_target: u16,
+    pub no_target: u16,
+    pub qty_yes: f64,
+    pub qty_no: f64,
+    pub resting_orders: usize,
+}
+
+/// A held batch, as shown to whoever approves it.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatchPreview {
+    pub id: u64,
+    /// One line per order, e.g. "place YES 5 @ 480"
+    pub actions: Vec<String>,
+    /// Shares the batch would buy
+    pub shares: f64,
+    /// Dropped if not approved within this
+    pub expires_in_secs: f64,
+}
+
+/// Parameters that can be changed while running.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeParams {
+    /// A-S risk aversion
+    pub gamma: f64,
+    /// Extra margin on both sides on top of everything else (ticks)
+    pub extra_margin: u16,
+}
+
+impl RuntimeParams {
+    /// Set a parameter by name.
+    ///
+    /// # Errors
+    /// Unknown name or out-of-range value.
+    pub fn set(&mut self, name: &str, value: f64) -> Result<(), String> {
+        match name {
+            "gamma" if value > 0.0 && value.is_finite() => self.gamma = value,
+            "extra_margin" if (0.0..=100.0).contains(&value) => self.extra_margin = value as u16,
+            "gamma" | "extra_margin" => return Err(format!("{} out of range: {}", name, value)),
+            _ => return Err(format!("unknown parameter: {}", name)),
+        }
+        Ok(())
+    }
+}
+
+/// Cloneable handle for sending commands and subscribing to events.
+#[derive(Clone)]
+pub struct ControlHandle {
+    requests: mpsc::Sender<ControlRequest>,
+    events: broadcast::Sender<EngineEvent>,
+}
+
+impl ControlHandle {
+    /// Create a handle and the receiver the main loop reads commands from.
+    pub fn new() -> (Self, mpsc::Receiver<ControlRequest>) {
+        let (requests, rx) = mpsc::channel(32);
+        let (events, _) = broadcast::channel(1024);
+        (Self { requests, events }, rx)
+    }
+
+    /// Send a command and wait for the engine to apply it.
+    pub async fn send(&self, command: ControlCommand) -> Result<String, String> {
+        let (reply, outcome) = oneshot::channel();
+        self.requests
+            .send(ControlRequest { command, reply })
+            .await
+            .map_err(|_| "engine stopped".to_string())?;
+        outcome.await.map_err(|_| "engine dropped the request".to_string())?
+    }
+
+    /// Subscribe to engine events. Slow subscribers miss events rather
+    /// than block the engine.
+    pub fn subscribe(&self) -> broadcast::Receiver<EngineEvent> {
+        self.events.subscribe()
+    }
+
+    /// Publish an event (no-op without subscribers).
+    pub fn publish(&self, event: EngineEvent) {
+        let _ = self.events.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_params() {
+        let mut params = RuntimeParams { gamma: 0.05, extra_margin: 0 };
+        params.set("gamma", 0.1).unwrap();
+        params.set("extra_margin", 20.0).unwrap();
+        assert_eq!(params, RuntimeParams { gamma: 0.1, extra_margin: 20 });
+
+        assert!(params.set("gamma", -1.0).is_err());
+        assert!(params.set("extra_margin", 500.0).is_err());
+        assert!(params.set("nope", 1.0).unwrap_err().contains("unknown"));
+    }
+
+    #[tokio::test]
+    async fn test_command_round_trip() {
+        let (handle, mut rx) = ControlHandle::new();
+        let engine = tokio::spawn(async move {
+            let req = rx.recv().await.unwrap();
+            assert_eq!(req.command, ControlCommand::Resume);
+            let _ = req.reply.send(Ok("resumed".to_string()));
+        });
+        assert_eq!(handle.send(ControlCommand::Resume).await, Ok("resumed".to_string()));
+        engine.await.unwrap();
+    }
+
+    #[test]
+    fn test_publish_to_subscribers() {
+        let (handle, _rx) = ControlHandle::new();
+        handle.publish(EngineEvent::Halt { reason: "nobody listening".into() });
+
+        let mut sub = handle.subscribe();
+        handle.publish(EngineEvent::Halt { reason: "PAUSED".into() });
+        assert_eq!(sub.try_recv().unwrap(), EngineEvent::Halt { reason: "PAUSED".into() });
+    }
+}
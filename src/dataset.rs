@@ -0,0 +1,376 @@
+//! Decision dataset for offline research.
+//!
+//! Every reconcile cycle becomes one row: the features the strategy saw
+//! (book, position, signals), what it did (orders placed, cancelled and
+//! taken), and the outcome, the move in the YES mid over the following
+//! `markout_secs`. Rows wait on the main loop for their markout, then go
+//! through a bounded queue to a writer thread that writes Parquet files of
+//! `rows_per_file` rows each. A row still waiting when its market ends is
+//! written without a markout.
+//!
+//! Writing needs the `dataset` feature (off by default: it pulls in
+//! arrow/parquet). Rows are dropped, and counted, if the writer falls
+//! behind.
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{SyncSender, TrySendError};
+
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::journal::Snapshot;
+use crate::strategy::Action;
+use crate::tasks::TaskRegistry;
+
+/// Rows queued for the writer before new ones are dropped
+#[cfg(feature = "dataset")]
+const QUEUE_SIZE: usize = 100_000;
+
+/// One reconcile cycle: features, actions, outcome.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecisionRow {
+    pub ts: f64,
+    pub market: String,
+    pub time_left: f64,
+    // Features
+    pub yes_bid: Option<u16>,
+    pub yes_ask: Option<u16>,
+    pub no_bid: Option<u16>,
+    pub no_ask: Option<u16>,
+    pub qty_yes: f64,
+    pub qty_no: f64,
+    pub cost_yes: f64,
+    pub cost_no: f64,
+    pub resting_yes: u32,
+    pub resting_no: u32,
+    pub btc: f64,
+    pub mid: f64,
+    pub var: f64,
+    pub k: f64,
+    pub yes_target: u16,
+    pub no_target: u16,
+    // Actions
+    pub places: u32,
+    pub place_shares: f64,
+    pub cancels: u32,
+    pub takes: u32,
+    pub take_shares: f64,
+    // Outcome: YES mid after `markout_secs` minus mid now (ticks)
+    pub markout_ticks: Option<f64>,
+}
+
+impl DecisionRow {
+    /// A row from the frame the strategy decided on and the actions it
+    /// took, markout pending.
+    pub fn new(frame: &Snapshot, actions: &[Action]) -> Self {
+        let mut row = Self {
+            ts: frame.ts,
+            market: frame.market.clone(),
+            time_left: frame.time_left,
+            yes_bid: frame.book.yes_bid,
+            yes_ask: frame.book.yes_ask,
+            no_bid: frame.book.no_bid,
+            no_ask: frame.book.no_ask,
+            qty_yes: frame.position.qty_yes,
+            qty_no: frame.position.qty_no,
+            cost_yes: frame.position.cost_yes,
+            cost_no: frame.position.cost_no,
+            resting_yes: frame.ladder.yes.len() as u32,
+            resting_no: frame.ladder.no.len() as u32,
+            btc: frame.signals.btc,
+            mid: frame.signals.mid,
+            var: frame.signals.var,
+            k: frame.signals.k,
+            yes_target: frame.signals.yes_target,
+            no_target: frame.signals.no_target,
+            ..Default::default()
+        };
+        for action in actions {
+            match action {
+                Action::Place { size, .. } => {
+                    row.places += 1;
+                    row.place_shares += size.to_f64().unwrap_or(0.0);
+                }
+                Action::Cancel { .. } => row.cancels += 1,
+                Action::CancelAll => row.cancels += frame.ladder.yes.len() as u32 + frame.ladder.no.len() as u32,
+                Action::Take { size, .. } => {
+                    row.takes += 1;
+                    row.take_shares += size.to_f64().unwrap_or(0.0);
+                }
+            }
+        }
+        row
+    }
+}
+
+/// Handle the main loop records decisions through. A disabled dataset
+/// does nothing.
+#[derive(Default)]
+pub struct Dataset {
+    tx: Option<SyncSender<DecisionRow>>,
+    markout_secs: f64,
+    /// Rows waiting for their markout, oldest first
+    pending: VecDeque<DecisionRow>,
+    dropped: u64,
+}
+
+impl Dataset {
+    /// A dataset that records nothing.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Start the writer from `[dataset]`; disabled unless enabled. The
+    /// writer runs on a blocking thread, registered as a sink.
+    #[cfg(feature = "dataset")]
+    pub fn from_config(config: &crate::config::Dataset, tasks: &mut TaskRegistry) -> anyhow::Result<Self> {
+        if !config.enabled {
+            return Ok(Self::disabled());
+        }
+        std::fs::create_dir_all(&config.dir)?;
+        let (tx, rx) = std::sync::mpsc::sync_channel(QUEUE_SIZE);
+        let writer = writer::Writer::new(config.dir.clone(), config.rows_per_file);
+        tasks.spawn_with(crate::tasks::Stage::Sinks, "dataset", move |stop| async move {
+            let _ = tokio::task::spawn_blocking(move || writer.run(rx, stop)).await;
+        });
+        println!("[DATASET] Writing decisions to {}", config.dir);
+        Ok(Self { tx: Some(tx), markout_secs: config.markout_secs, ..Default::default() })
+    }
+
+    /// Built without the `dataset` feature: disabled.
+    #[cfg(not(feature = "dataset"))]
+    pub fn from_config(config: &crate::config::Dataset, _tasks: &mut TaskRegistry) -> anyhow::Result<Self> {
+        if config.enabled {
+            println!("[DATASET] Ignored: built without the `dataset` feature");
+        }
+        Ok(Self::disabled())
+    }
+
+    /// Whether decisions go anywhere (skip building rows otherwise).
+    pub fn is_enabled(&self) -> bool {
+        self.tx.is_some()
+    }
+
+    /// Queue a decision for its markout.
+    pub fn record(&mut self, row: DecisionRow) {
+        if self.is_enabled() {
+            self.pending.push_back(row);
+        }
+    }
+
+    /// Settle the rows whose markout is due against the current YES mid.
+    pub fn update(&mut self, now: f64, mid: f64) {
+        while self.pending.front().is_some_and(|row| now - row.ts >= self.markout_secs) {
+            if let Some(mut row) = self.pending.pop_front() {
+                row.markout_ticks = Some((mid - row.mid) * 1000.0);
+                self.send(row);
+            }
+        }
+    }
+
+    /// Market over: write what's still waiting, without a markout.
+    pub fn end_market(&mut self) {
+        while let Some(row) = self.pending.pop_front() {
+            self.send(row);
+        }
+    }
+
+    /// Rows dropped because the writer fell behind.
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    fn send(&mut self, row: DecisionRow) {
+        if let Some(tx) = &self.tx {
+            if let Err(TrySendError::Full(_)) = tx.try_send(row) {
+                self.dropped += 1;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "dataset")]
+mod writer {
+    use std::fs::File;
+    use std::path::PathBuf;
+    use std::sync::mpsc::{Receiver, RecvTimeoutError};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use arrow_array::{ArrayRef, Float64Array, RecordBatch, StringArray, UInt16Array, UInt32Array};
+    use arrow_schema::{DataType, Field, Schema};
+    use parquet::arrow::ArrowWriter;
+    use tokio_util::sync::CancellationToken;
+
+    use super::DecisionRow;
+
+    /// Batches rows into Parquet files; runs on its own thread.
+    pub struct Writer {
+        dir: String,
+        rows_per_file: usize,
+        rows: Vec<DecisionRow>,
+        seq: u32,
+    }
+
+    impl Writer {
+        pub fn new(dir: String, rows_per_file: usize) -> Self {
+            Self { dir, rows_per_file: rows_per_file.max(1), rows: Vec::new(), seq: 0 }
+        }
+
+        /// Write until every Dataset is dropped or `stop` is cancelled,
+        /// then write out what's queued as a final, shorter file.
+        pub fn run(mut self, rx: Receiver<DecisionRow>, stop: CancellationToken) {
+            while !stop.is_cancelled() {
+                match rx.recv_timeout(Duration::from_millis(500)) {
+                    Ok(row) => self.push(row),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            while let Ok(row) = rx.try_recv() {
+                self.push(row);
+            }
+            self.flush_logged();
+        }
+
+        pub fn push(&mut self, row: DecisionRow) {
+            self.rows.push(row);
+            if self.rows.len() >= self.rows_per_file {
+                self.flush_logged();
+            }
+        }
+
+        fn flush_logged(&mut self) {
+            if let Err(e) = self.flush() {
+                println!("[DATASET] Write failed: {:#}", e);
+            }
+        }
+
+        /// Write the buffered rows as a new file.
+        pub fn flush(&mut self) -> anyhow::Result<Option<PathBuf>> {
+            if self.rows.is_empty() {
+                return Ok(None);
+            }
+            self.seq += 1;
+            let name = format!("decisions_{}_{:03}.parquet", chrono::Utc::now().timestamp(), self.seq);
+            let path: PathBuf = [self.dir.as_str(), name.as_str()].iter().collect();
+            let batch = to_batch(&self.rows)?;
+            let mut writer = ArrowWriter::try_new(File::create(&path)?, batch.schema(), None)?;
+            writer.write(&batch)?;
+            writer.close()?;
+            self.rows.clear();
+            Ok(Some(path))
+        }
+    }
+
+    /// Rows as one record batch, a column per field.
+    pub fn to_batch(rows: &[DecisionRow]) -> anyhow::Result<RecordBatch> {
+        let f64s = |f: fn(&DecisionRow) -> f64| Arc::new(Float64Array::from_iter_values(rows.iter().map(f))) as ArrayRef;
+        let u16s = |f: fn(&DecisionRow) -> Option<u16>| Arc::new(rows.iter().map(f).collect::<UInt16Array>()) as ArrayRef;
+        let u32s = |f: fn(&DecisionRow) -> u32| Arc::new(UInt32Array::from_iter_values(rows.iter().map(f))) as ArrayRef;
+
+        let columns: Vec<(&str, DataType, bool, ArrayRef)> = vec![
+            ("ts", DataType::Float64, false, f64s(|r| r.ts)),
+            ("market", DataType::Utf8, false, Arc::new(rows.iter().map(|r| Some(r.market.as_str())).collect::<StringArray>())),
+            ("time_left", DataType::Float64, false, f64s(|r| r.time_left)),
+            ("yes_bid", DataType::UInt16, true, u16s(|r| r.yes_bid)),
+            ("yes_ask", DataType::UInt16, true, u16s(|r| r.yes_ask)),
+            ("no_bid", DataType::UInt16, true, u16s(|r| r.no_bid)),
+            ("no_ask", DataType::UInt16, true, u16s(|r| r.no_ask)),
+            ("qty_yes", DataType::Float64, false, f64s(|r| r.qty_yes)),
+            ("qty_no", DataType::Float64, false, f64s(|r| r.qty_no)),
+            ("cost_yes", DataType::Float64, false, f64s(|r| r.cost_yes)),
+            ("cost_no", DataType::Float64, false, f64s(|r| r.cost_no)),
+            ("resting_yes", DataType::UInt32, false, u32s(|r| r.resting_yes)),
+            ("resting_no", DataType::UInt32, false, u32s(|r| r.resting_no)),
+            ("btc", DataType::Float64, false, f64s(|r| r.btc)),
+            ("mid", DataType::Float64, false, f64s(|r| r.mid)),
+            ("var", DataType::Float64, false, f64s(|r| r.var)),
+            ("k", DataType::Float64, false, f64s(|r| r.k)),
+            ("yes_target", DataType::UInt16, false, u16s(|r| Some(r.yes_target))),
+            ("no_target", DataType::UInt16, false, u16s(|r| Some(r.no_target))),
+            ("places", DataType::UInt32, false, u32s(|r| r.places)),
+            ("place_shares", DataType::Float64, false, f64s(|r| r.place_shares)),
+            ("cancels", DataType::UInt32, false, u32s(|r| r.cancels)),
+            ("takes", DataType::UInt32, false, u32s(|r| r.takes)),
+            ("take_shares", DataType::Float64, false, f64s(|r| r.take_shares)),
+            ("markout_ticks", DataType::Float64, true, Arc::new(rows.iter().map(|r| r.markout_ticks).collect::<Float64Array>())),
+        ];
+        let schema = Schema::new(columns.iter().map(|(name, ty, nullable, _)| Field::new(*name, ty.clone(), *nullable)).collect::<Vec<_>>());
+        let arrays = columns.into_iter().map(|(_, _, _, array)| array).collect();
+        Ok(RecordBatch::try_new(Arc::new(schema), arrays)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Side;
+    use crate::journal::Signals;
+    use crate::state::{Book, OrderTracker, Position};
+    use rust_decimal_macros::dec;
+
+    fn frame(ts: f64, mid: f64) -> Snapshot {
+        let mut book = Book::default();
+        book.update(Side::Yes, 480, 490, 0);
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "y1".into(), 470, dec!(5));
+        let signals = Signals { mid, yes_target: 470, ..Default::default() };
+        Snapshot::capture(ts, "m", 200.0, &book, &orders, &Position::default(), signals)
+    }
+
+    #[test]
+    fn test_row_counts_actions() {
+        let actions = vec![
+            Action::place(Side::Yes, 460, dec!(5)),
+            Action::place(Side::No, 500, dec!(7.5)),
+            Action::cancel("y1"),
+            Action::take(Side::No, dec!(10), 510),
+        ];
+        let row = DecisionRow::new(&frame(1.0, 0.485), &actions);
+        assert_eq!((row.yes_bid, row.no_bid, row.resting_yes, row.yes_target), (Some(480), None, 1, 470));
+        assert_eq!((row.places, row.place_shares, row.cancels, row.takes, row.take_shares), (2, 12.5, 1, 1, 10.0));
+        assert_eq!(row.markout_ticks, None);
+    }
+
+    #[test]
+    fn test_markout_settles_when_due() {
+        let (tx, rx) = std::sync::mpsc::sync_channel(10);
+        let mut dataset = Dataset { tx: Some(tx), markout_secs: 5.0, ..Default::default() };
+        dataset.record(DecisionRow::new(&frame(1.0, 0.485), &[]));
+        dataset.record(DecisionRow::new(&frame(2.0, 0.490), &[]));
+
+        dataset.update(5.0, 0.5);
+        assert!(rx.try_recv().is_err());
+        dataset.update(6.0, 0.5);
+        assert_eq!(rx.try_recv().unwrap().markout_ticks.map(f64::round), Some(15.0));
+
+        // Market over before the second matured
+        dataset.end_market();
+        assert_eq!(rx.try_recv().unwrap().markout_ticks, None);
+        assert_eq!(dataset.dropped(), 0);
+    }
+
+    #[cfg(feature = "dataset")]
+    #[test]
+    fn test_parquet_round_trip() {
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let dir = std::env::temp_dir().join(format!("polybot_dataset_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut writer = writer::Writer::new(dir.to_string_lossy().into_owned(), 10);
+        let mut row = DecisionRow::new(&frame(1.0, 0.485), &[]);
+        row.markout_ticks = Some(-5.0);
+        writer.push(row);
+        writer.push(DecisionRow::new(&frame(2.0, 0.49), &[]));
+        let path = writer.flush().unwrap().unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(path).unwrap()).unwrap().build().unwrap();
+        let batch = reader.map(|b| b.unwrap()).next().unwrap();
+        assert_eq!((batch.num_rows(), batch.num_columns()), (2, 25));
+        let markouts = batch.column_by_name("markout_ticks").unwrap();
+        assert_eq!(markouts.null_count(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,272 @@
+//! State drift: rebuild the bot's state from its inputs and check it
+//! against what the journal recorded live.
+//!
+//! The state ledger has every placement, cancel and fill, and the feed
+//! recorder (optionally) every book update. Replaying them from the
+//! journal's first frame recomputes `Position`, `OrderTracker` and `Book`.
+//! At each journal snapshot the recomputed state is captured the same way
+//! and compared. A state-update bug shows up as the first snapshot where
+//! they disagree, and the events since the last one that agreed are the
+//! suspects.
+//!
+//! The ledger writes orders at the next tick, so a difference that's gone
+//! by the next snapshot is lag rather than drift: a mismatch has to hold
+//! for `PERSIST_SNAPSHOTS` snapshots in a row.
+
+use rust_decimal::Decimal;
+
+use crate::events::Side;
+use crate::journal::{PositionSnapshot, Signals, Snapshot};
+use crate::ledger::Entry;
+use crate::state::{Book, OrderTracker, Position};
+
+/// Snapshots in a row a mismatch must hold to count as drift
+pub const PERSIST_SNAPSHOTS: usize = 2;
+
+/// Shares and ticks closer than this are equal (f64 round trips)
+const EPSILON: f64 = 1e-6;
+
+/// One input event, in time order.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    Ledger(Entry),
+    /// Best bid/ask from the market feed (ticks)
+    Book { ts: f64, side: Side, bid: u16, ask: u16 },
+}
+
+impl Step {
+    pub fn ts(&self) -> f64 {
+        match self {
+            Step::Ledger(entry) => entry.ts(),
+            Step::Book { ts, .. } => *ts,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Step::Ledger(Entry::Place { ts, order_id, side, price, size, .. }) => {
+                format!("{:.3} PLACE  {:?} {} @ {} {}", ts, side, size, price, order_id)
+            }
+            Step::Ledger(Entry::Cancel { ts, order_id, remaining, .. }) => {
+                format!("{:.3} CANCEL {} ({} left)", ts, order_id, remaining)
+            }
+            Step::Ledger(Entry::Fill { ts, order_id, side, price, size, .. }) => {
+                format!("{:.3} FILL   {:?} {} @ {} {}", ts, side, size, price, order_id)
+            }
+            Step::Ledger(Entry::Snapshot { ts, market, .. }) => format!("{:.3} ROLL   {}", ts, market),
+            Step::Book { ts, side, bid, ask } => format!("{:.3} BOOK   {:?} {}/{}", ts, side, bid, ask),
+        }
+    }
+}
+
+/// State rebuilt from the steps.
+#[derive(Debug)]
+pub struct Replayed {
+    pub market: String,
+    pub book: Book,
+    pub position: Position,
+    pub orders: OrderTracker,
+}
+
+impl Replayed {
+    /// Start from a journal frame: its position and book (orders come from
+    /// the ledger, which writes every resting order on a run's first tick).
+    pub fn seed(snapshot: &Snapshot) -> Self {
+        Self {
+            market: snapshot.market.clone(),
+            book: Book {
+                yes_bid: snapshot.book.yes_bid,
+                yes_ask: snapshot.book.yes_ask,
+                no_bid: snapshot.book.no_bid,
+                no_ask: snapshot.book.no_ask,
+                last_update_ms: 0,
+            },
+            position: snapshot.position.to_position(),
+            orders: OrderTracker::new(),
+        }
+    }
+
+    pub fn apply(&mut self, step: &Step) {
+        match step {
+            Step::Ledger(Entry::Place { order_id, side, price, size, .. }) => {
+                self.orders.add(*side, order_id.clone(), *price, dec(*size));
+            }
+            Step::Ledger(Entry::Cancel { order_id, .. }) => {
+                if let Some(side) = self.orders.find_side_by_id(order_id) {
+                    self.orders.remove_by_id(side, order_id);
+                }
+            }
+            Step::Ledger(Entry::Fill { order_id, side, price, size, .. }) => {
+                self.position.apply_fill(*side, *price, dec(*size));
+                self.orders.update_fill(*side, order_id, dec(*size));
+            }
+            // A rollover starts the window flat, as the main loop does
+            Step::Ledger(Entry::Snapshot { market, .. }) => {
+                if *market != self.market {
+                    self.market = market.clone();
+                    self.book = Book::default();
+                    self.position.reset();
+                    self.orders.clear_all();
+                }
+            }
+            Step::Book { ts, side, bid, ask } => self.book.update(*side, *bid, *ask, (ts * 1000.0) as i64),
+        }
+    }
+
+    /// Differences from a recorded frame, one line each.
+    pub fn compare(&self, recorded: &Snapshot, check_book: bool) -> Vec<String> {
+        let ours = Snapshot::capture(
+            recorded.ts,
+            &self.market,
+            recorded.time_left,
+            &self.book,
+            &self.orders,
+            &self.position,
+            Signals::default(),
+        );
+        let mut diffs = Vec::new();
+        if ours.market != recorded.market {
+            diffs.push(format!("market: replayed {} recorded {}", ours.market, recorded.market));
+        }
+        diffs.extend(position_diffs(&ours.position, &recorded.position));
+        for (name, mine, theirs) in [("yes", &ours.ladder.yes, &recorded.ladder.yes), ("no", &ours.ladder.no, &recorded.ladder.no)] {
+            let same = mine.len() == theirs.len()
+                && mine.iter().zip(theirs).all(|(a, b)| a.price == b.price && (a.size - b.size).abs() < EPSILON);
+            if !same {
+                diffs.push(format!("{} ladder: replayed {} recorded {}", name, ladder(mine), ladder(theirs)));
+            }
+        }
+        if check_book && ours.book != recorded.book {
+            diffs.push(format!("book: replayed {:?} recorded {:?}", ours.book, recorded.book));
+        }
+        diffs
+    }
+}
+
+fn position_diffs(ours: &PositionSnapshot, recorded: &PositionSnapshot) -> Vec<String> {
+    [
+        ("qty_yes", ours.qty_yes, recorded.qty_yes),
+        ("qty_no", ours.qty_no, recorded.qty_no),
+        ("cost_yes", ours.cost_yes, recorded.cost_yes),
+        ("cost_no", ours.cost_no, recorded.cost_no),
+    ]
+    .into_iter()
+    .filter(|(_, a, b)| (a - b).abs() >= EPSILON)
+    .map(|(name, a, b)| format!("{}: replayed {} recorded {}", name, a, b))
+    .collect()
+}
+
+fn ladder(rungs: &[crate::journal::Rung]) -> String {
+    let rungs: Vec<String> = rungs.iter().map(|r| format!("{}@{}", r.size, r.price)).collect();
+    format!("[{}]", rungs.join(" "))
+}
+
+/// Where replay and journal first part ways.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    /// Index of the first disagreeing snapshot
+    pub snapshot: usize,
+    pub ts: f64,
+    pub diffs: Vec<String>,
+    /// Steps between the last agreeing snapshot and this one
+    pub suspects: Vec<Step>,
+}
+
+/// Replay `steps` (in time order) from the first snapshot and find the
+/// first lasting disagreement. Steps before the first snapshot are skipped:
+/// the seed already has them. Steps at its time aren't, since the ledger
+/// writes a run's first orders on the tick the first frame is taken.
+pub fn find_drift(snapshots: &[Snapshot], steps: &[Step], check_book: bool) -> Option<Drift> {
+    let first = snapshots.first()?;
+    let mut replayed = Replayed::seed(first);
+    let mut next = steps.partition_point(|s| s.ts() < first.ts);
+    let mut last_good = next;
+    let mut pending: Option<Drift> = None;
+    let mut persisted = 0;
+
+    for (i, snapshot) in snapshots.iter().enumerate().skip(1) {
+        while next < steps.len() && steps[next].ts() <= snapshot.ts {
+            replayed.apply(&steps[next]);
+            next += 1;
+        }
+
+        let diffs = replayed.compare(snapshot, check_book);
+        if diffs.is_empty() {
+            pending = None;
+            persisted = 0;
+            last_good = next;
+            continue;
+        }
+        persisted += 1;
+        let drift = pending.get_or_insert_with(|| Drift {
+            snapshot: i,
+            ts: snapshot.ts,
+            diffs,
+            suspects: steps[last_good..next].to_vec(),
+        });
+        if persisted >= PERSIST_SNAPSHOTS {
+            return Some(drift.clone());
+        }
+    }
+    // A mismatch on the last frame can't be confirmed, but report it
+    pending
+}
+
+fn dec(v: f64) -> Decimal {
+    Decimal::try_from(v).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn frame(ts: f64, orders: &OrderTracker, position: &Position) -> Snapshot {
+        Snapshot::capture(ts, "m", 300.0, &Book::default(), orders, position, Signals::default())
+    }
+
+    fn place(ts: f64, id: &str, price: u16) -> Step {
+        Step::Ledger(Entry::Place { ts, market: "m".into(), order_id: id.into(), side: Side::Yes, price, size: 5.0 })
+    }
+
+    fn fill(ts: f64, id: &str, price: u16) -> Step {
+        Step::Ledger(Entry::Fill { ts, market: "m".into(), order_id: id.into(), side: Side::Yes, price, size: 5.0 })
+    }
+
+    /// Live state as the journal saw it: y1 placed, then filled.
+    fn journal() -> Vec<Snapshot> {
+        let mut orders = OrderTracker::new();
+        let mut position = Position::default();
+        let mut frames = vec![frame(0.0, &orders, &position)];
+        orders.add(Side::Yes, "y1".into(), 470, dec!(5));
+        frames.push(frame(1.0, &orders, &position));
+        orders.update_fill(Side::Yes, "y1", dec!(5));
+        position.apply_fill(Side::Yes, 470, dec!(5));
+        frames.push(frame(2.0, &orders, &position));
+        frames.push(frame(3.0, &orders, &position));
+        frames
+    }
+
+    #[test]
+    fn test_consistent_replay_has_no_drift() {
+        let steps = vec![place(0.5, "y1", 470), fill(1.5, "y1", 470)];
+        assert_eq!(find_drift(&journal(), &steps, false), None);
+    }
+
+    #[test]
+    fn test_drift_names_the_suspect() {
+        // The ledger has the fill at the wrong price
+        let steps = vec![place(0.5, "y1", 470), fill(1.5, "y1", 480)];
+        let drift = find_drift(&journal(), &steps, false).unwrap();
+        assert_eq!(drift.snapshot, 2);
+        assert_eq!(drift.diffs, ["cost_yes: replayed 2400 recorded 2350"]);
+        assert_eq!(drift.suspects, [fill(1.5, "y1", 480)]);
+    }
+
+    #[test]
+    fn test_one_frame_lag_is_not_drift() {
+        // The placement was written a tick after the frame that shows it
+        let steps = vec![place(1.05, "y1", 470), fill(1.5, "y1", 470)];
+        assert_eq!(find_drift(&journal(), &steps, false), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! Concurrent markets: one engine per asset and market length, run side by
+//! side.
+//!
+//! `[markets] assets` lists the assets to trade (BTC by default) and
+//! `--concurrent 5m,15m` the market lengths; every pair gets an engine. Each
+//! engine is a full trading loop with its own feeds (the market's books and
+//! the asset's Binance streams), book, position, order tracker, strategy
+//! instance and ledger, so the windows roll over independently. The
+//! `MarketManager` drives them on one task and waits for all of them to
+//! stop; an engine that fails is reported and the others keep trading
+//! (dropping them mid-window would strand their orders).
+//!
+//! What the engines share is the wallet: `SharedRisk` takes each engine's
+//! resting bids, and an engine may lock only the collateral the others
+//! haven't. Process-wide services (gRPC, metrics, the signal server, the
+//! feed recorder and the decision dataset) run in the first engine only.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Result};
+use futures_util::future::join_all;
+use rust_decimal::Decimal;
+
+use crate::api::gamma::MarketSelector;
+use crate::strategy::MarketDuration;
+
+/// One engine: the market it trades.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineSpec {
+    pub market: MarketSelector,
+    /// Runs the process-wide services, and keeps the plain file names
+    pub primary: bool,
+}
+
+impl EngineSpec {
+    /// "eth-15m"
+    pub fn label(&self) -> String {
+        self.market.label()
+    }
+
+    /// Appended to the engine's file names, so concurrent engines write
+    /// apart: "" for the primary, "_eth-15m" for an ETH 15m secondary.
+    pub fn suffix(&self) -> String {
+        if self.primary {
+            String::new()
+        } else {
+            format!("_{}", self.label())
+        }
+    }
+}
+
+/// An engine per asset in `assets` and market length in `lengths`
+/// (`--concurrent`: comma-separated, e.g. "5m,15m"), asset by asset. The
+/// first is the primary.
+pub fn parse_engines(assets: &[String], lengths: &str) -> Result<Vec<EngineSpec>> {
+    let mut durations: Vec<MarketDuration> = Vec::new();
+    for label in lengths.split(',').filter(|l| !l.trim().is_empty()) {
+        let Some(duration) = MarketDuration::from_label(label) else {
+            bail!("Unknown market length {:?} in --concurrent, expected \"5m\" or \"15m\"", label.trim());
+        };
+        if durations.contains(&duration) {
+            bail!("{} listed twice in --concurrent", duration.label());
+        }
+        durations.push(duration);
+    }
+
+    let mut engines: Vec<EngineSpec> = Vec::new();
+    for asset in assets {
+        for &duration in &durations {
+            let market = MarketSelector::new(asset, duration)?;
+            if engines.iter().any(|e| e.market == market) {
+                bail!("{} listed twice in [markets] assets", market.asset);
+            }
+            engines.push(EngineSpec { market, primary: engines.is_empty() });
+        }
+    }
+    if engines.is_empty() {
+        bail!("Nothing to trade: [markets] assets and --concurrent need at least one entry each");
+    }
+    Ok(engines)
+}
+
+/// Collateral shared by the engines. Each reports the USDC its resting bids
+/// lock every tick; `available` is what's left of the wallet for one engine.
+#[derive(Debug, Clone, Default)]
+pub struct SharedRisk {
+    locked: Arc<Mutex<HashMap<String, Decimal>>>,
+}
+
+impl SharedRisk {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// USDC `engine`'s resting bids lock now.
+    pub fn report(&self, engine: &str, locked_usd: Decimal) {
+        self.locked.lock().unwrap().insert(engine.to_string(), locked_usd);
+    }
+
+    /// An engine that stopped locks nothing.
+    pub fn release(&self, engine: &str) {
+        self.locked.lock().unwrap().remove(engine);
+    }
+
+    /// USDC `engine`'s bids may lock: `balance` less what the other engines'
+    /// bids lock. None if the balance isn't known.
+    pub fn available(&self, engine: &str, balance: Option<Decimal>) -> Option<Decimal> {
+        let others: Decimal = self
+            .locked
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(name, _)| **name != engine)
+            .map(|(_, locked)| *locked)
+            .sum();
+        balance.map(|b| (b - others).max(Decimal::ZERO))
+    }
+}
+
+/// Runs the engines concurrently and waits for every one to stop.
+pub struct MarketManager {
+    engines: Vec<EngineSpec>,
+    risk: SharedRisk,
+}
+
+impl MarketManager {
+    pub fn new(engines: Vec<EngineSpec>) -> Self {
+        Self { engines, risk: SharedRisk::new() }
+    }
+
+    /// Start an engine per spec with `start` and drive them to completion.
+    ///
+    /// # Returns
+    /// The first engine error, after every engine has stopped.
+    pub async fn run<F, Fut>(&self, start: F) -> Result<()>
+    where
+        F: Fn(EngineSpec, SharedRisk) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let concurrent = self.engines.len() > 1;
+        let runs = self.engines.iter().map(|engine| {
+            let run = start(engine.clone(), self.risk.clone());
+            let risk = self.risk.clone();
+            let label = engine.label();
+            async move {
+                let result = run.await;
+                risk.release(&label);
+                if concurrent {
+                    match &result {
+                        Ok(()) => println!("[ENGINE {}] Stopped", label),
+                        Err(e) => println!("[ENGINE {}] Failed: {:#}", label, e),
+                    }
+                }
+                result
+            }
+        });
+        join_all(runs).await.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_engines() {
+        let assets = |list: &[&str]| list.iter().map(|a| a.to_string()).collect::<Vec<_>>();
+        let engines = parse_engines(&assets(&["btc", "eth"]), "5m, 15m").unwrap();
+        let labels: Vec<String> = engines.iter().map(|e| e.label()).collect();
+        assert_eq!(labels, ["btc-5m", "btc-15m", "eth-5m", "eth-15m"]);
+        assert!(engines[0].primary && engines[1..].iter().all(|e| !e.primary));
+        assert_eq!((engines[0].suffix(), engines[3].suffix()), (String::new(), "_eth-15m".to_string()));
+
+        assert!(parse_engines(&assets(&["btc"]), "5m,1h").is_err());
+        assert!(parse_engines(&assets(&["btc"]), "5m,5m").is_err());
+        assert!(parse_engines(&assets(&["btc", "BTC"]), "5m").is_err());
+        assert!(parse_engines(&assets(&["btc"]), "").is_err());
+        assert!(parse_engines(&[], "5m").is_err());
+    }
+
+    #[test]
+    fn test_shared_risk_leaves_others_locked() {
+        let risk = SharedRisk::new();
+        risk.report("btc-5m", dec!(30));
+        risk.report("eth-15m", dec!(50));
+        assert_eq!(risk.available("btc-5m", Some(dec!(100))), Some(dec!(50)));
+        assert_eq!(risk.available("eth-15m", Some(dec!(60))), Some(dec!(30)));
+        assert_eq!(risk.available("eth-15m", Some(dec!(20))), Some(dec!(0)));
+        assert_eq!(risk.available("btc-5m", None), None);
+
+        risk.release("eth-15m");
+        assert_eq!(risk.available("btc-5m", Some(dec!(100))), Some(dec!(100)));
+    }
+}
@@ -1,9 +1,19 @@
+use std::time::Duration;
+
+use crate::api::settlement::Resolved;
+use crate::config::Config;
+use crate::strategy::ExternalSignal;
+
 // enum = "one of these things". Like a dropdown menu of possible event types.
 // Each variant can carry different data.
 pub enum Event {
-    // Binance sends new BTC price
+    // Binance sends new spot price of the market's asset (BTC unless
+    // [markets] lists others)
     BtcPrice { price: f64 },
 
+    // Binance perp mark price of the same asset (for the perp-spot basis)
+    PerpPrice { price: f64 },
+
     // Polymarket book update for ONE side (prices in ticks, 1 tick = 0.1 cent)
     BookUpdate {
         side: Side,
@@ -11,11 +21,32 @@ pub enum Event {
         ask: u16,
     },
 
+    // Depth: one price level's new size on one token (0 = level gone)
+    BookDelta {
+        side: Side,
+        is_bid: bool,
+        price: u16,
+        size: f64,
+    },
+
+    // Depth: the full book for one token (on subscribe / resync)
+    BookSnapshot {
+        side: Side,
+        bids: Vec<(u16, f64)>,
+        asks: Vec<(u16, f64)>,
+    },
+
+    // Depth: our book for one token stopped matching the feed's checksum
+    // (a BookSnapshot from REST follows)
+    BookMismatch { side: Side },
+
     // A trade happened on Polymarket (last_trade_price event)
-    // Used for flow estimator (k)
+    // Used for flow estimator (k) and tape velocity
     Trade {
         side: Side,
         price: u16,
+        // Taker bought this token (None if the feed didn't say)
+        taker_buy: Option<bool>,
     },
 
     // One of our orders got filled
@@ -27,6 +58,61 @@ pub enum Event {
         is_maker: bool,
     },
 
+    // The exchange accepted one of our orders (sent by the executor)
+    OrderAck {
+        order_id: String,
+        side: Side,
+        price: u16,
+        size: f64,
+    },
+
+    // The user channel saw one of our orders go live on the book
+    OrderPlaced {
+        order_id: String,
+        side: Side,
+        price: u16,
+        size: f64,
+    },
+
+    // The user channel saw one of our orders cancelled (by us or the exchange)
+    OrderCancelled { order_id: String, side: Side },
+
+    // The exchange (or signing) refused one of our orders
+    OrderReject {
+        side: Side,
+        price: u16,
+        size: f64,
+        reason: String,
+    },
+
+    // Our holdings in this market as the Data API reports them (shares)
+    ReportedPosition { yes: f64, no: f64 },
+
+    // Proxy wallet's USDC, and its allowance for each exchange (see feeds::balance)
+    BalanceUpdate { balance: f64, allowances: Vec<f64> },
+
+    // A canary finished: its round trip, or why it failed (see Executor::canary)
+    CanaryResult { result: Result<Duration, String> },
+
+    // A closed window's settlement check finished: resolved (and redeemed,
+    // when live), not yet resolved (None), or why it failed
+    SettlementResult { slug: String, result: Result<Option<Resolved>, String> },
+
+    // Exchange health changed (None = healthy again)
+    ExchangeStatus { degraded: Option<String> },
+
+    // A market was paused, closed or resolved early (None = trading again)
+    MarketStatus { condition_id: String, halted: Option<String> },
+
+    // A large taker is running over one side (None = cleared)
+    WhaleFlow { side: Option<Side>, detail: Option<String> },
+
+    // An external model pushed a fair value / direction (see feeds::signal)
+    ExternalSignal { signal: ExternalSignal },
+
+    // config.toml changed and still parses (see feeds::config_watch)
+    ConfigChanged { config: Box<Config> },
+
     // Timer tick (every second)
     Tick,
 
@@ -35,7 +121,8 @@ pub enum Event {
 }
 
 // Another enum - just two options, no data attached
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Side {
     Yes,
     No,
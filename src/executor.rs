@@ -2,16 +2,55 @@
 //!
 //! Handles order placement, cancellation, and OrderTracker updates.
 
-use anyhow::Result;
-use polyfill_rs::{ClobClient, OrderArgs, OrderType, Side as PolySide};
-use polyfill_rs::types::ExtraOrderArgs;
+use std::any::Any;
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Result};
+use futures_util::FutureExt;
+use polyfill_rs::{ClobClient, OrderArgs, OrderType, PolyfillError, Side as PolySide};
+use polyfill_rs::types::{ExtraOrderArgs, OpenOrderParams};
 use alloy_primitives::U256;
 use rust_decimal::Decimal;
-use tracing::{info, warn, error};
+use rust_decimal::prelude::ToPrimitive;
+use tokio::sync::mpsc;
+use tracing::{info, instrument, warn, error, Instrument, Span};
+
+use crate::api::book;
+use crate::events::{Event, Side};
+use crate::config::Paper;
+use crate::paper::{FaultInjector, PaperExecutor, PaperFill};
+use crate::risk::{OrderRules, RiskLimits, Variant, VariantBudget, VariantFills, VariantRisk};
+use crate::state::{ClientOrderIds, ExchangeOrder, OrderTracker, PendingPlace};
+use crate::strategy::{Action, TimeInForce, TICKS_PER_DOLLAR};
+
+/// The CLOB rejects GTD expirations less than a minute out, so the
+/// requested lifetime is added on top of this threshold.
+const GTD_SECURITY_SECS: u64 = 60;
+
+/// Canary order: a minimum-size bid at 1c, far from any real quote.
+const CANARY_PRICE: u16 = 10;
+const CANARY_SIZE: i64 = 5;
 
-use crate::events::Side;
-use crate::state::OrderTracker;
-use crate::strategy::Action;
+/// How long to wait for a cancel to be acknowledged before escalating.
+const CANCEL_TIMEOUT_SECS: f64 = 3.0;
+/// Cancel retries before querying the order's status.
+const MAX_CANCEL_RETRIES: u32 = 2;
+/// Pause between cancel retries on shutdown (no time for the usual timeout).
+const SHUTDOWN_RETRY_MS: u64 = 250;
+/// How long a placement whose response was lost waits for the user channel
+/// to show it live before it's given up on.
+const PLACE_TIMEOUT_SECS: f64 = 3.0;
+
+fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs_f64()
+}
 
 /// Configuration for the executor.
 pub struct ExecutorConfig {
@@ -21,32 +60,276 @@ pub struct ExecutorConfig {
     pub yes_token: String,
     /// NO token ID for this market.
     pub no_token: String,
+    /// Backstop limits checked before every placement.
+    pub limits: RiskLimits,
+    /// Minimum time between Takes on the same side, so a persistent
+    /// imbalance can't fire another Take before the last one shows up
+    /// in Position.
+    pub take_cooldown: Duration,
+    /// Position/notional budget per strategy variant.
+    pub variant_budgets: HashMap<Variant, VariantBudget>,
+    /// Market close (ms since epoch). None = unknown, no close check.
+    pub end_timestamp_ms: Option<i64>,
+    /// No places or takes this close to the end, whatever the strategy
+    /// asks for: they'd be rejected, or fill on a decided market.
+    pub close_buffer: Duration,
+    /// Tick grid, minimum size, price band and balance, checked before
+    /// signing so a bad order never costs a round trip.
+    pub order_rules: OrderRules,
+    /// Most of the visible ask depth (within max_price) one Take may use;
+    /// the rest of the size waits for the next Take after the cooldown.
+    pub take_max_depth_fraction: Decimal,
+    /// Fee rate (bps) signed into every order; fetched per market, since
+    /// the CLOB rejects orders that don't carry the market's rate.
+    pub fee_rate_bps: u32,
 }
 
 /// Executes actions via the Polymarket CLOB API.
 pub struct Executor {
-    client: ClobClient,
+    client: Arc<ClobClient>,
     config: ExecutorConfig,
+    take_throttle: Mutex<TakeThrottle>,
+    variant_risk: Mutex<VariantRisk>,
+    /// Soak runs: injects API timeouts into batches
+    faults: Option<Mutex<FaultInjector>>,
+    /// Where order acks and rejects are reported (the main event channel)
+    events: Option<mpsc::Sender<Event>>,
+    /// Paper mode: orders go to a simulated venue instead of the CLOB
+    paper: Option<Mutex<PaperExecutor>>,
+    /// Our IDs for placements, so responses and fills can be matched to them
+    client_ids: Mutex<ClientOrderIds>,
 }
 
 impl Executor {
     /// Create a new executor with the given client and config.
     pub fn new(client: ClobClient, config: ExecutorConfig) -> Self {
-        Self { client, config }
+        let take_throttle = Mutex::new(TakeThrottle::new(config.take_cooldown));
+        let variant_risk = Mutex::new(VariantRisk::new(config.variant_budgets.clone()));
+        let mut client_ids = ClientOrderIds::new((now_secs() * 1000.0) as u64);
+        if let Some(end_ms) = config.end_timestamp_ms {
+            client_ids.set_market(end_ms / 1000);
+        }
+        Self {
+            client: Arc::new(client),
+            config,
+            take_throttle,
+            variant_risk,
+            faults: None,
+            events: None,
+            paper: None,
+            client_ids: Mutex::new(client_ids),
+        }
+    }
+
+    /// Report OrderAck/OrderReject events on `tx`.
+    pub fn with_events(mut self, tx: mpsc::Sender<Event>) -> Self {
+        self.events = Some(tx);
+        self
+    }
+
+    /// Best effort: a full channel drops the report rather than stall orders.
+    fn report(&self, event: Event) {
+        if let Some(tx) = &self.events {
+            let _ = tx.try_send(event);
+        }
+    }
+
+    /// Paper trading: simulate places, cancels and takes instead of sending
+    /// them. Simulated fills come back as OrderFill events, so this needs
+    /// `with_events` too.
+    pub fn with_paper(mut self, config: &Paper) -> Self {
+        self.paper = Some(Mutex::new(PaperExecutor::new(config)));
+        self
+    }
+
+    /// Paper mode: a market print on `side` at `price` fills our resting
+    /// bids above it. No-op when live.
+    pub fn on_trade(&self, side: Side, price: u16) {
+        let fills = match &self.paper {
+            Some(paper) => paper.lock().map(|mut p| p.on_trade(now_secs(), side, price)).unwrap_or_default(),
+            None => return,
+        };
+        self.deliver(fills);
+    }
+
+    /// Paper mode: keep the simulated ask book in step with the depth
+    /// feed. No-op when live.
+    pub fn on_book_snapshot(&self, side: Side, asks: &[(u16, f64)]) {
+        if let Some(Ok(mut paper)) = self.paper.as_ref().map(|p| p.lock()) {
+            paper.set_asks(side, asks.iter().map(|&(price, size)| (price, Decimal::try_from(size).unwrap_or_default())));
+        }
+    }
+
+    /// Paper mode: one ask level changed. No-op when live.
+    pub fn on_ask_delta(&self, side: Side, price: u16, size: f64) {
+        if let Some(Ok(mut paper)) = self.paper.as_ref().map(|p| p.lock()) {
+            paper.set_ask(side, price, Decimal::try_from(size).unwrap_or_default());
+        }
+    }
+
+    /// Report paper fills as OrderFill events once their report delay passes.
+    fn deliver(&self, fills: Vec<PaperFill>) {
+        let Some(tx) = &self.events else {
+            return;
+        };
+        for fill in fills {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(fill.delay).await;
+                let _ = tx
+                    .send(Event::OrderFill {
+                        order_id: fill.order_id,
+                        side: fill.side,
+                        price: fill.price,
+                        size: fill.size.to_f64().unwrap_or(0.0),
+                        is_maker: fill.is_maker,
+                    })
+                    .await;
+            });
+        }
+    }
+
+    /// Time out batches at random (soak runs).
+    pub fn with_faults(mut self, injector: FaultInjector) -> Self {
+        self.faults = Some(Mutex::new(injector));
+        self
+    }
+
+    /// Record a fill against the variant that placed the order.
+    pub fn record_fill(&self, variant: Variant, side: Side, price: u16, size: Decimal) {
+        if let Ok(mut risk) = self.variant_risk.lock() {
+            risk.record_fill(variant, side, price, size);
+        }
     }
 
-    /// Update market tokens (on market switch).
-    pub fn set_market(&mut self, yes_token: String, no_token: String) {
+    /// Fills so far this market for a variant.
+    pub fn variant_fills(&self, variant: Variant) -> VariantFills {
+        self.variant_risk.lock().map(|r| r.fills(variant)).unwrap_or_default()
+    }
+
+    /// Reset per-variant fills (on market switch).
+    pub fn reset_variants(&self) {
+        if let Ok(mut risk) = self.variant_risk.lock() {
+            risk.reset();
+        }
+    }
+
+    fn variant_allows(&self, variant: Variant, side: Side, price: u16, size: Decimal, orders: &OrderTracker) -> bool {
+        self.variant_risk
+            .lock()
+            .map(|r| r.allows(variant, side, price, size, orders))
+            .unwrap_or(false)
+    }
+
+    /// Update market tokens, close time, tick size and fee rate (on market switch).
+    pub fn set_market(
+        &mut self,
+        yes_token: String,
+        no_token: String,
+        end_timestamp_ms: i64,
+        tick_size: Decimal,
+        fee_rate_bps: u32,
+    ) {
+        self.config.fee_rate_bps = fee_rate_bps;
         self.config.yes_token = yes_token;
         self.config.no_token = no_token;
+        self.config.end_timestamp_ms = Some(end_timestamp_ms);
+        self.config.order_rules.set_tick_size(tick_size);
+        if let Ok(mut ids) = self.client_ids.lock() {
+            ids.set_market(end_timestamp_ms / 1000);
+        }
+        if let Some(Ok(mut paper)) = self.paper.as_ref().map(|p| p.lock()) {
+            paper.reset();
+        }
+    }
+
+    /// Overlap allowed between our YES and NO bids (on a config reload).
+    pub fn set_self_trade_overlap(&mut self, ticks: u16) {
+        self.config.order_rules.self_trade_overlap_ticks = ticks;
     }
 
-    /// Execute a list of actions, updating the order tracker.
+    /// Per-side order limits (on a config reload).
+    pub fn set_limits(&mut self, limits: RiskLimits) {
+        self.config.limits = limits;
+    }
+
+    /// USDC available for resting bids (None: unchecked).
+    pub fn set_balance(&mut self, balance: Option<Decimal>) {
+        self.config.order_rules.balance = balance;
+    }
+
+    /// Milliseconds to close, if we're inside the close buffer.
+    fn closing(&self) -> Option<i64> {
+        closes_within(self.config.end_timestamp_ms, self.config.close_buffer, (now_secs() * 1000.0) as i64)
+    }
+
+    /// Execute a list of actions for the maker ladder.
     pub async fn execute(&self, actions: Vec<Action>, orders: &mut OrderTracker) -> Result<()> {
+        self.execute_as(Variant::Ladder, actions, orders).await
+    }
+
+    /// Execute a list of actions on behalf of a strategy variant, updating
+    /// the order tracker. Placed orders are tagged with the variant, and
+    /// places/takes that would exceed its budget are skipped.
+    ///
+    /// A panic mid-batch is caught: every order on this market's tokens is
+    /// cancelled (including any in flight we never recorded), the tracker is
+    /// resynced, and the panic is returned as an error so the caller can keep
+    /// running.
+    pub async fn execute_as(&self, variant: Variant, actions: Vec<Action>, orders: &mut OrderTracker) -> Result<()> {
+        let result = AssertUnwindSafe(self.execute_batch(variant, actions, orders))
+            .catch_unwind()
+            .await;
+
+        match result {
+            Ok(result) => result,
+            Err(payload) => {
+                let msg = panic_message(payload.as_ref());
+                error!("[ALERT] Executor panicked mid-batch: {}", msg);
+                self.recover_after_panic(orders).await;
+                Err(anyhow!("executor panicked: {}", msg))
+            }
+        }
+    }
+
+    /// Bring exchange and tracker back in line after a panic. Only this
+    /// market's orders are touched, since other engines trade from the same
+    /// account: whatever the exchange has resting on our two tokens
+    /// (including placements in flight we never recorded) is adopted, then
+    /// every tracked order is cancelled by ID.
+    async fn recover_after_panic(&self, orders: &mut OrderTracker) {
+        match self.open_orders().await {
+            Ok(open) => {
+                for order in open {
+                    if orders.find_side_by_id(&order.order_id).is_none() {
+                        orders.add(order.side, order.order_id, order.price, order.remaining);
+                    }
+                }
+            }
+            Err(e) => error!("Open orders query after panic failed, cancelling tracked orders only: {}", e),
+        }
+
+        if let Err(e) = self.execute_cancel_all(orders).await {
+            error!("Cancel after panic failed: {}", e);
+        }
+        // Unacknowledged cancels stay pending and go through escalation
+        match orders.pending_cancel_count() {
+            0 => warn!("Recovered from executor panic: cancelled this market's orders"),
+            n => error!("Recovered from executor panic: {} cancels unacknowledged", n),
+        }
+    }
+
+    async fn execute_batch(&self, variant: Variant, actions: Vec<Action>, orders: &mut OrderTracker) -> Result<()> {
         if actions.is_empty() {
             return Ok(());
         }
 
+        let injected = self.faults.as_ref().and_then(|f| f.lock().ok().and_then(|mut f| f.api_timeout()));
+        if let Some(hang) = injected {
+            tokio::time::sleep(hang).await;
+            return Err(anyhow!("injected API timeout after {}ms", hang.as_millis()));
+        }
+
         if self.config.log_only {
             for action in &actions {
                 info!("[DRY RUN] {:?}", action);
@@ -81,17 +364,71 @@ impl Executor {
             }
         }
 
+        // Pre-trade close check: cancels above still go out, nothing new does
+        let closing = self.closing();
+
         // Execute places (could batch these, but start simple)
         for action in places {
-            if let Action::Place { side, price, size } = action {
-                self.execute_place(*side, *price, *size, orders).await?;
+            if let Action::Place { side, price, size, tif } = action {
+                if let Some(ms_left) = closing {
+                    warn!("Skipping place {:?} {} @ {} ticks: market closes in {}ms", side, size, price, ms_left);
+                    continue;
+                }
+                if let Err(reason) = self.config.order_rules.check_place(*side, *price, *size, orders) {
+                    warn!("Rejecting place {:?} {} @ {} ticks locally: {}", side, size, price, reason);
+                    self.report(Event::OrderReject {
+                        side: *side,
+                        price: *price,
+                        size: size.to_f64().unwrap_or(0.0),
+                        reason: format!("local: {}", reason),
+                    });
+                    continue;
+                }
+                if !self.config.limits.allows_place(*side, *size, orders) {
+                    warn!(
+                        "Skipping place {:?} {} @ {} ticks: over limits ({} orders, {} resting, {} in flight)",
+                        side, size, price, orders.count(*side), orders.total_exposure(*side),
+                        orders.pending_exposure(*side)
+                    );
+                    continue;
+                }
+                if !self.variant_allows(variant, *side, *price, *size, orders) {
+                    warn!("Skipping place {:?} {} @ {} ticks: over {} budget", side, size, price, variant);
+                    continue;
+                }
+                self.execute_place(variant, *side, *price, *size, *tif, orders).await?;
             }
         }
 
-        // Execute takes (IOC orders for rebalancing)
+        // Execute takes (IOC orders for rebalancing), at most one per side per cooldown
         for action in takes {
             if let Action::Take { side, size, max_price } = action {
-                self.execute_take(*side, *size, *max_price).await?;
+                if let Some(ms_left) = closing {
+                    warn!("Skipping take {:?} {} (max {}): market closes in {}ms", side, size, max_price, ms_left);
+                    continue;
+                }
+                let now = Instant::now();
+                let ready = self.take_throttle.lock().map(|t| t.ready(*side, now)).unwrap_or(false);
+                if !ready {
+                    warn!("Skipping take {:?} {} (max {}): side in take cooldown", side, size, max_price);
+                    continue;
+                }
+                if !self.variant_allows(variant, *side, *max_price, *size, orders) {
+                    warn!("Skipping take {:?} {} (max {}): over {} budget", side, size, max_price, variant);
+                    continue;
+                }
+                // Only a take that goes out starts the cooldown
+                if let Ok(mut t) = self.take_throttle.lock() {
+                    t.claim(*side, now);
+                }
+                let report = self.execute_take(*side, *size, *max_price).await?;
+                if !report.deferred.is_zero() {
+                    // Imbalance is still there: the strategy asks again after the cooldown
+                    info!("Take {:?}: {} left for the next take", side, report.deferred);
+                }
+                if let Some(avg) = report.avg_price() {
+                    self.record_fill(variant, *side, avg.round() as u16, report.filled);
+                }
             }
         }
 
@@ -99,42 +436,106 @@ impl Executor {
     }
 
     /// Place a limit order.
+    #[instrument(skip(self, orders), fields(order_id = tracing::field::Empty, client_id = tracing::field::Empty))]
     async fn execute_place(
         &self,
+        variant: Variant,
         side: Side,
         price: u16,
         size: Decimal,
+        tif: TimeInForce,
         orders: &mut OrderTracker,
     ) -> Result<()> {
+        // Pending until the response says live: if it's lost, the order may
+        // still be on the book, and reconcile mustn't place the rung again
+        let client_id = self
+            .client_ids
+            .lock()
+            .map_err(|_| anyhow!("client order ID lock poisoned"))?
+            .next_id();
+        Span::current().record("client_id", client_id.as_str());
+        orders.add_pending(side, client_id.clone(), price, size, variant, now_secs());
+
+        if let Some(paper) = &self.paper {
+            let order_id = paper
+                .lock()
+                .map_err(|_| anyhow!("paper venue lock poisoned"))?
+                .place(now_secs(), side, price, size);
+            info!("Paper placed {:?} {} @ {} ticks → {} ({})", side, size, price, order_id, client_id);
+            self.report(Event::OrderAck { order_id: order_id.clone(), side, price, size: size.to_f64().unwrap_or(0.0) });
+            orders.confirm_place(&client_id, order_id);
+            return Ok(());
+        }
+
         let token_id = self.token_for_side(side);
 
         // Convert price from ticks (0-1000) to decimal (0.001-1.000)
         let price_dec = Decimal::new(price as i64, 3);
 
         let args = OrderArgs::new(token_id, price_dec, size, PolySide::BUY);
-        let extras = ExtraOrderArgs {
-            fee_rate_bps: 1000,
-            nonce: U256::ZERO,
-            taker: "0x0000000000000000000000000000000000000000".to_string(),
+        let extras = self.extras();
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let (order_type, expiration) = match tif {
+            TimeInForce::Gtc => (OrderType::GTC, None),
+            TimeInForce::Gtd { expires_in_secs } => (OrderType::GTD, Some(now + GTD_SECURITY_SECS + expires_in_secs)),
+            // Close time unknown: rest until cancelled, as before
+            TimeInForce::GtdMarketEnd { buffer_secs } => match self.config.end_timestamp_ms {
+                None => (OrderType::GTC, None),
+                Some(end_ms) => match market_end_expiration(end_ms, buffer_secs, now) {
+                    Some(expiration) => (OrderType::GTD, Some(expiration)),
+                    None => {
+                        warn!("Skipping {:?} @ {}: it would expire before it could rest", side, price);
+                        orders.fail_place(&client_id);
+                        return Ok(());
+                    }
+                },
+            },
         };
 
-        match self.client.create_order(&args, None, Some(extras), None).await {
+        let size_f64 = size.to_f64().unwrap_or(0.0);
+        let reject = |reason: String| Event::OrderReject { side, price, size: size_f64, reason };
+        match self.client.create_order(&args, expiration, Some(extras), None).await {
             Ok(order) => {
-                match self.client.post_order(order, OrderType::GTC).await {
+                match self.client.post_order(order, order_type).await {
+                    Ok(response) if !response.success => {
+                        // Answered and refused: nothing rests, so the rung is free again
+                        warn!("Order refused: {:?} {} @ {} ticks ({})", side, size, price, response.status);
+                        orders.fail_place(&client_id);
+                        self.report(reject(format!("refused: {}", response.status)));
+                    }
                     Ok(response) => {
+                        Span::current().record("order_id", response.order_id.as_str());
                         info!(
-                            "Placed {:?} {} @ {} ticks → {}",
-                            side, size, price, &response.order_id[..20.min(response.order_id.len())]
+                            "Placed {:?} {} @ {} ticks → {} ({})",
+                            side, size, price, &response.order_id[..20.min(response.order_id.len())], client_id
                         );
-                        orders.add(side, response.order_id, price, size);
+                        self.report(Event::OrderAck {
+                            order_id: response.order_id.clone(),
+                            side,
+                            price,
+                            size: size_f64,
+                        });
+                        orders.confirm_place(&client_id, response.order_id);
+                    }
+                    Err(e) if post_refused(&e) => {
+                        error!("Order rejected: {}", e);
+                        orders.fail_place(&client_id);
+                        self.report(reject(format!("post: {}", e)));
                     }
                     Err(e) => {
+                        // Lost in transit: stays pending until the user channel
+                        // shows it live or PLACE_TIMEOUT_SECS passes
                         error!("Failed to post order: {}", e);
+                        self.report(reject(format!("post: {}", e)));
                     }
                 }
             }
             Err(e) => {
+                // Never sent
                 error!("Failed to create order: {}", e);
+                orders.fail_place(&client_id);
+                self.report(reject(format!("create: {}", e)));
             }
         }
 
@@ -142,79 +543,378 @@ impl Executor {
     }
 
     /// Cancel a specific order.
+    /// The order is a pending cancel from the moment the cancel is sent until
+    /// it's confirmed: by the response listing it as canceled, by the user
+    /// channel's removal, or by escalation.
+    #[instrument(skip(self, orders))]
     async fn execute_cancel(&self, order_id: &str, orders: &mut OrderTracker) -> Result<()> {
-        match self.client.cancel(order_id).await {
-            Ok(_) => {
-                info!("Cancelled order {}", &order_id[..20.min(order_id.len())]);
-                // Try to remove from both sides (we may not know which side)
-                if orders.remove_by_id(Side::Yes, order_id).is_none() {
-                    orders.remove_by_id(Side::No, order_id);
-                }
-            }
-            Err(e) => {
-                warn!("Failed to cancel order {}: {}", &order_id[..20.min(order_id.len())], e);
+        if let Some(paper) = &self.paper {
+            // Not resting any more means it filled; the fill is on its way
+            if let Ok(mut paper) = paper.lock() {
+                paper.cancel(now_secs(), order_id);
             }
+            orders.confirm_cancel(order_id);
+            return Ok(());
         }
+        orders.mark_cancel_pending(order_id, now_secs());
+        self.send_cancel(order_id, orders).await;
         Ok(())
     }
 
     /// Cancel all orders.
+    #[instrument(skip_all)]
     async fn execute_cancel_all(&self, orders: &mut OrderTracker) -> Result<()> {
-        // Cancel YES orders
-        for order_id in orders.all_order_ids(Side::Yes) {
-            if let Err(e) = self.client.cancel(order_id).await {
-                warn!("Failed to cancel {}: {}", &order_id[..20.min(order_id.len())], e);
+        if let Some(paper) = &self.paper {
+            if let Ok(mut paper) = paper.lock() {
+                paper.cancel_all(now_secs());
             }
+            orders.clear_all();
+            return Ok(());
+        }
+
+        let order_ids: Vec<String> = orders
+            .all_order_ids(Side::Yes)
+            .into_iter()
+            .chain(orders.all_order_ids(Side::No))
+            .map(String::from)
+            .collect();
+
+        let now = now_secs();
+        for order_id in &order_ids {
+            orders.mark_cancel_pending(order_id, now);
         }
+        for order_id in &order_ids {
+            self.send_cancel(order_id, orders).await;
+        }
+        info!("Cancelled all orders ({} unconfirmed)", orders.pending_cancel_count());
+        Ok(())
+    }
 
-        // Cancel NO orders
-        for order_id in orders.all_order_ids(Side::No) {
-            if let Err(e) = self.client.cancel(order_id).await {
-                warn!("Failed to cancel {}: {}", &order_id[..20.min(order_id.len())], e);
+    /// Send a cancel for an order already marked pending, and resolve it if
+    /// the response confirms it.
+    async fn send_cancel(&self, order_id: &str, orders: &mut OrderTracker) {
+        let short_id = &order_id[..20.min(order_id.len())];
+        match self.client.cancel(order_id).await {
+            Ok(response) if cancel_confirmed(&response, order_id) => {
+                info!("Cancelled order {}", short_id);
+                orders.ack_cancel(order_id);
             }
+            Ok(response) => warn!("Cancel for {} not confirmed: {}", short_id, response["not_canceled"]),
+            Err(e) => warn!("Failed to cancel order {}: {}", short_id, e),
         }
+    }
 
-        orders.clear_all();
-        info!("Cancelled all orders");
-        Ok(())
+    /// Cancel everything and wait for the exchange to confirm, for
+    /// shutdown: the escalation ladder without its timeouts. Failed
+    /// cancels are retried straight away, then their status is queried.
+    ///
+    /// # Returns
+    /// Order IDs that may still be live.
+    pub async fn cancel_all_confirmed(&self, orders: &mut OrderTracker) -> Vec<String> {
+        if let Err(e) = self.execute(vec![Action::CancelAll], orders).await {
+            warn!("Cancel-all failed: {}", e);
+        }
+
+        let mut live = Vec::new();
+        for order_id in orders.timed_out_cancels(f64::MAX, 0.0) {
+            let short_id = &order_id[..20.min(order_id.len())];
+            let mut confirmed = false;
+            for _ in 0..MAX_CANCEL_RETRIES {
+                tokio::time::sleep(Duration::from_millis(SHUTDOWN_RETRY_MS)).await;
+                match self.client.cancel(&order_id).await {
+                    Ok(response) if cancel_confirmed(&response, &order_id) => {
+                        confirmed = true;
+                        break;
+                    }
+                    Ok(response) => warn!("Cancel retry for {} not confirmed: {}", short_id, response["not_canceled"]),
+                    Err(e) => warn!("Cancel retry failed for {}: {}", short_id, e),
+                }
+            }
+            if !confirmed {
+                match self.client.get_order(&order_id).await {
+                    Ok(order) if order.status != "LIVE" => confirmed = true,
+                    Ok(_) => {}
+                    Err(e) => warn!("Status query failed for {}: {}", short_id, e),
+                }
+            }
+            orders.ack_cancel(&order_id);
+            if confirmed {
+                info!("Cancel confirmed for {}", short_id);
+            } else {
+                error!("Cancel for {} never confirmed; order may still be live", short_id);
+                live.push(order_id);
+            }
+        }
+        live
     }
 
-    /// Execute a taker order (IOC) for rebalancing.
-    async fn execute_take(&self, side: Side, size: Decimal, max_price: u16) -> Result<()> {
-        let token_id = self.token_for_side(side);
+    /// Our orders resting on this market's tokens, for adopting or
+    /// cancelling at startup. None in paper or dry runs.
+    pub async fn open_orders(&self) -> Result<Vec<ExchangeOrder>> {
+        if self.paper.is_some() || self.config.log_only {
+            return Ok(Vec::new());
+        }
+        let mut open = Vec::new();
+        for side in [Side::Yes, Side::No] {
+            let params = OpenOrderParams {
+                id: None,
+                market: None,
+                asset_id: Some(self.token_for_side(side).to_string()),
+            };
+            for order in self.client.get_orders(Some(&params), None).await? {
+                let Some(price) = (order.price * Decimal::from(TICKS_PER_DOLLAR)).round().to_u16() else {
+                    warn!("Open order {} has an unreadable price {}", order.id, order.price);
+                    continue;
+                };
+                open.push(ExchangeOrder {
+                    remaining: order.original_size - order.size_matched,
+                    order_id: order.id,
+                    side,
+                    price,
+                });
+            }
+        }
+        Ok(open)
+    }
 
-        // Convert price from ticks to decimal
-        let price_dec = Decimal::new(max_price as i64, 3);
+    /// Give up on placements whose response was lost and that haven't
+    /// shown up on the user channel within `PLACE_TIMEOUT_SECS`.
+    pub fn expire_placements(&self, orders: &mut OrderTracker) -> Vec<PendingPlace> {
+        orders.expire_pending_places(now_secs(), PLACE_TIMEOUT_SECS)
+    }
 
-        let args = OrderArgs::new(token_id, price_dec, size, PolySide::BUY);
-        let extras = ExtraOrderArgs {
-            fee_rate_bps: 1000,
-            nonce: U256::ZERO,
-            taker: "0x0000000000000000000000000000000000000000".to_string(),
-        };
+    /// Escalate cancels that haven't been acknowledged in time:
+    /// retry the cancel, then query the order's status, then give up and alert.
+    ///
+    /// # Returns
+    /// Order IDs that exhausted escalation and may still be live.
+    pub async fn escalate_cancels(&self, orders: &mut OrderTracker) -> Vec<String> {
+        let now = now_secs();
+        let mut alerts = Vec::new();
 
-        match self.client.create_order(&args, None, Some(extras), None).await {
-            Ok(order) => {
-                // Use FOK (Fill or Kill) for taker orders
-                match self.client.post_order(order, OrderType::FOK).await {
-                    Ok(response) => {
-                        info!(
-                            "Take {:?} {} @ max {} ticks → {}",
-                            side, size, max_price, &response.order_id[..20.min(response.order_id.len())]
-                        );
-                        // Note: Don't add to OrderTracker - FOK fills immediately or fails
+        for order_id in orders.timed_out_cancels(now, CANCEL_TIMEOUT_SECS) {
+            let Some((retries, queried)) = orders
+                .pending_cancel(&order_id)
+                .map(|p| (p.retries, p.queried))
+            else {
+                continue;
+            };
+            let short_id = &order_id[..20.min(order_id.len())];
+
+            if retries < MAX_CANCEL_RETRIES {
+                // 1. Retry the cancel
+                match self.client.cancel(&order_id).await {
+                    Ok(response) if cancel_confirmed(&response, &order_id) => {
+                        info!("Cancelled order {} on retry", short_id);
+                        orders.ack_cancel(&order_id);
                     }
-                    Err(e) => {
-                        warn!("Take order failed (may not have filled): {}", e);
+                    result => {
+                        match result {
+                            Ok(response) => warn!("Cancel retry for {} not confirmed: {}", short_id, response["not_canceled"]),
+                            Err(e) => warn!("Cancel retry failed for {}: {}", short_id, e),
+                        }
+                        if let Some(p) = orders.pending_cancel_mut(&order_id) {
+                            p.retries += 1;
+                            p.sent_at = now;
+                        }
                     }
                 }
+            } else if !queried {
+                // 2. Ask the exchange whether the order is still live
+                match self.client.get_order(&order_id).await {
+                    Ok(order) if order.status != "LIVE" => {
+                        info!("Order {} is {}, cancel confirmed", short_id, order.status);
+                        orders.ack_cancel(&order_id);
+                    }
+                    result => {
+                        if let Err(e) = result {
+                            warn!("Status query failed for {}: {}", short_id, e);
+                        }
+                        if let Some(p) = orders.pending_cancel_mut(&order_id) {
+                            p.queried = true;
+                            p.sent_at = now;
+                        }
+                    }
+                }
+            } else {
+                // 3. Out of options: alert and stop tracking
+                error!(
+                    "Cancel for {} never acknowledged after {} retries; order may still be live",
+                    short_id, MAX_CANCEL_RETRIES
+                );
+                orders.ack_cancel(&order_id);
+                alerts.push(order_id);
+            }
+        }
+
+        alerts
+    }
+
+    /// Execute a taker buy for rebalancing by walking the visible asks:
+    /// one IOC order per level, best first, until filled or the next level
+    /// is above `max_price`. The size is first cut to `take_max_depth_fraction`
+    /// of the depth within `max_price` (nothing below the minimum size), so a
+    /// thin book isn't swept; the remainder is reported as deferred. Falls
+    /// back to a single IOC at `max_price` if the book snapshot can't be
+    /// fetched.
+    ///
+    /// # Returns
+    /// Size taken and its average price. Each level counts what the
+    /// exchange reports it matched (its taking amount, capped at the
+    /// level's planned size), so a partial match counts only its fill.
+    #[instrument(skip(self))]
+    async fn execute_take(&self, side: Side, size: Decimal, max_price: u16) -> Result<TakeReport> {
+        let token_id = self.token_for_side(side);
+
+        let snapshot = book::fetch_asks(token_id).await;
+        let fetched = snapshot.is_ok();
+        let (asks, take_size) = match snapshot {
+            Ok(asks) => {
+                // Impact guard: use at most a fraction of the visible depth
+                let capped = impact_capped(&asks, size, max_price, self.config.take_max_depth_fraction);
+                let capped = if capped < self.config.order_rules.min_size { Decimal::ZERO } else { capped };
+                (asks, capped)
             }
+            Err(e) => {
+                warn!("Book snapshot failed, taking at max price: {}", e);
+                (vec![(max_price, size)], size)
+            }
+        };
+
+        let mut report = TakeReport { deferred: size - take_size, ..Default::default() };
+        if !report.deferred.is_zero() {
+            warn!(
+                "Take {:?} shrunk to {} of {}: book too thin below {} ticks, deferring {}",
+                side, take_size, size, max_price, report.deferred
+            );
+        }
+        if let Some(paper) = &self.paper {
+            let fills = paper
+                .lock()
+                .map(|mut p| {
+                    // No depth from the feed yet: start from the REST snapshot
+                    if fetched && !p.has_asks(side) {
+                        p.set_asks(side, asks.iter().copied());
+                    }
+                    p.take(side, take_size, max_price)
+                })
+                .unwrap_or_default();
+            for fill in &fills {
+                report.record(fill.price, fill.size);
+            }
+            self.deliver(fills);
+            info!("Paper take {:?} filled {} of {} (max {})", side, report.filled, size, max_price);
+            return Ok(report);
+        }
+        // A leg that partly matched still counts what it got; one that
+        // matched nothing means the book moved, so stop walking
+        for (price, leg_size) in walk_levels(&asks, take_size, max_price) {
+            let matched = self.post_ioc(token_id, price, leg_size).await;
+            if matched.is_zero() {
+                break;
+            }
+            report.record(price, matched);
+        }
+
+        info!(
+            "Take {:?} filled {} of {} @ avg {:.1} ticks (max {})",
+            side, report.filled, size, report.avg_price().unwrap_or(0.0), max_price
+        );
+        Ok(report)
+    }
+
+    /// Post one IOC buy. Returns the shares it matched (zero if nothing
+    /// did, or it failed).
+    /// Don't add to OrderTracker: IOC never rests.
+    #[instrument(skip(self, token_id), fields(order_id = tracing::field::Empty))]
+    async fn post_ioc(&self, token_id: &str, price: u16, size: Decimal) -> Decimal {
+        let price_dec = Decimal::new(price as i64, 3);
+        let args = OrderArgs::new(token_id, price_dec, size, PolySide::BUY);
+        let extras = self.extras();
+
+        let order = match self.client.create_order(&args, None, Some(extras), None).await {
+            Ok(order) => order,
             Err(e) => {
                 error!("Failed to create take order: {}", e);
+                return Decimal::ZERO;
+            }
+        };
+        match self.client.post_order(order, OrderType::FAK).await {
+            Ok(response) => {
+                Span::current().record("order_id", response.order_id.as_str());
+                if !response.success || !response.status.eq_ignore_ascii_case("matched") {
+                    return Decimal::ZERO;
+                }
+                // A buy's taking amount is the shares we got
+                let matched = response.taking_amount.min(size);
+                if matched < size {
+                    info!("Take leg {} @ {} matched {}", size, price, matched);
+                }
+                matched
+            }
+            Err(e) => {
+                warn!("Take leg {} @ {} failed (may not have filled): {}", size, price, e);
+                Decimal::ZERO
             }
         }
+    }
 
-        Ok(())
+    /// Place and immediately cancel a canary order to test the order path.
+    /// Never touches the OrderTracker. The probe owns what it needs, so it
+    /// can be spawned off the trading loop.
+    ///
+    /// # Returns
+    /// Place + cancel round trip time. An error if either leg failed; a
+    /// failed cancel leaves a 5-share 1c bid resting until the next cancel-all.
+    pub fn canary(&self, side: Side) -> impl Future<Output = Result<Duration>> + Send + 'static {
+        let closing = self.closing();
+        let paper = self.paper.is_some();
+        let client = Arc::clone(&self.client);
+        let token_id = self.token_for_side(side).to_string();
+        let extras = self.extras();
+        let span = tracing::info_span!("canary", ?side, order_id = tracing::field::Empty);
+
+        async move {
+            if let Some(ms_left) = closing {
+                return Err(anyhow!("market closes in {}ms", ms_left));
+            }
+            if paper {
+                // Nothing real to probe
+                return Ok(Duration::ZERO);
+            }
+            let started = Instant::now();
+            let price_dec = Decimal::new(CANARY_PRICE as i64, 3);
+            let size = Decimal::from(CANARY_SIZE);
+            let args = OrderArgs::new(&token_id, price_dec, size, PolySide::BUY);
+
+            let order = client
+                .create_order(&args, None, Some(extras), None)
+                .await
+                .map_err(|e| anyhow!("create: {}", e))?;
+            let response = client
+                .post_order(order, OrderType::GTC)
+                .await
+                .map_err(|e| anyhow!("post: {}", e))?;
+            Span::current().record("order_id", response.order_id.as_str());
+            client
+                .cancel(&response.order_id)
+                .await
+                .map_err(|e| anyhow!("cancel {}: {}", response.order_id, e))?;
+
+            let rtt = started.elapsed();
+            info!("Canary round trip {}ms", rtt.as_millis());
+            Ok(rtt)
+        }
+        .instrument(span)
+    }
+
+    /// Order extras: the market's fee rate, no nonce, open to any taker.
+    fn extras(&self) -> ExtraOrderArgs {
+        ExtraOrderArgs {
+            fee_rate_bps: self.config.fee_rate_bps,
+            nonce: U256::ZERO,
+            taker: "0x0000000000000000000000000000000000000000".to_string(),
+        }
     }
 
     /// Get token ID for a side.
@@ -225,3 +925,207 @@ impl Executor {
         }
     }
 }
+
+/// Result of a Take.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TakeReport {
+    /// Shares taken
+    pub filled: Decimal,
+    /// Sum of price × size over filled legs (ticks)
+    cost: Decimal,
+    /// Size held back by the impact guard, left for a later Take
+    pub deferred: Decimal,
+}
+
+impl TakeReport {
+    fn record(&mut self, price: u16, size: Decimal) {
+        self.filled += size;
+        self.cost += Decimal::from(price) * size;
+    }
+
+    /// Average fill price in ticks, None if nothing filled.
+    pub fn avg_price(&self) -> Option<f64> {
+        if self.filled.is_zero() {
+            return None;
+        }
+        (self.cost / self.filled).to_string().parse().ok()
+    }
+}
+
+/// Per-side cooldown between Takes.
+#[derive(Debug)]
+struct TakeThrottle {
+    cooldown: Duration,
+    /// Last Take per side (YES, NO)
+    last: [Option<Instant>; 2],
+}
+
+impl TakeThrottle {
+    fn new(cooldown: Duration) -> Self {
+        Self { cooldown, last: [None; 2] }
+    }
+
+    fn slot(side: Side) -> usize {
+        if side == Side::Yes { 0 } else { 1 }
+    }
+
+    /// Whether `side`'s cooldown has passed.
+    fn ready(&self, side: Side, now: Instant) -> bool {
+        self.last[Self::slot(side)].is_none_or(|last| now.duration_since(last) >= self.cooldown)
+    }
+
+    /// Start `side`'s cooldown: a Take is going out.
+    fn claim(&mut self, side: Side, now: Instant) {
+        self.last[Self::slot(side)] = Some(now);
+    }
+}
+
+/// Plan a Take across ask levels: (price, size) legs, best level first,
+/// stopping at `size` or the first level above `max_price`.
+fn walk_levels(asks: &[(u16, Decimal)], size: Decimal, max_price: u16) -> Vec<(u16, Decimal)> {
+    let mut legs = Vec::new();
+    let mut remaining = size;
+    for &(price, available) in asks {
+        if remaining <= Decimal::ZERO || price > max_price {
+            break;
+        }
+        let leg = remaining.min(available);
+        legs.push((price, leg));
+        remaining -= leg;
+    }
+    legs
+}
+
+/// Impact guard: the part of `size` that stays within `fraction` of the
+/// ask depth visible at or below `max_price`.
+fn impact_capped(asks: &[(u16, Decimal)], size: Decimal, max_price: u16, fraction: Decimal) -> Decimal {
+    let depth: Decimal = asks.iter().filter(|(price, _)| *price <= max_price).map(|(_, available)| *available).sum();
+    size.min(depth * fraction)
+}
+
+/// Milliseconds left if `now_ms` is within `buffer` of `end_ms` (or past it).
+fn closes_within(end_ms: Option<i64>, buffer: Duration, now_ms: i64) -> Option<i64> {
+    let end_ms = end_ms?;
+    let ms_left = end_ms - now_ms;
+    (ms_left <= buffer.as_millis() as i64).then_some(ms_left.max(0))
+}
+
+/// Exchange expiration (unix secs) for an order that should stop resting
+/// `buffer_secs` before the market ends at `end_ms`, with the exchange's
+/// security threshold on top. None if that time has already passed.
+fn market_end_expiration(end_ms: i64, buffer_secs: u64, now: u64) -> Option<u64> {
+    let expires_at = end_ms / 1000 - buffer_secs as i64;
+    (expires_at > now as i64).then(|| expires_at as u64 + GTD_SECURITY_SECS)
+}
+
+/// Whether a cancel response lists `order_id` as canceled. The exchange
+/// answers a refused cancel (e.g. the order already matched) with the ID
+/// under `not_canceled` instead.
+fn cancel_confirmed(response: &serde_json::Value, order_id: &str) -> bool {
+    response["canceled"]
+        .as_array()
+        .is_some_and(|ids| ids.iter().any(|id| id.as_str() == Some(order_id)))
+}
+
+/// Whether a failed post was answered by the exchange (a 4xx with a
+/// reason), as opposed to lost on the way: only then is it known that
+/// nothing rests.
+fn post_refused(e: &PolyfillError) -> bool {
+    matches!(e, PolyfillError::Api { status, .. } if (400..500).contains(status))
+}
+
+/// Best-effort string from a panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_cancel_confirmed() {
+        let canceled = serde_json::json!({ "canceled": ["0xabc"], "not_canceled": {} });
+        assert!(cancel_confirmed(&canceled, "0xabc"));
+        assert!(!cancel_confirmed(&canceled, "0xdef"));
+
+        let refused = serde_json::json!({ "canceled": [], "not_canceled": { "0xabc": "order already matched" } });
+        assert!(!cancel_confirmed(&refused, "0xabc"));
+        assert!(!cancel_confirmed(&serde_json::Value::Null, "0xabc"));
+    }
+
+    #[test]
+    fn test_walk_levels_best_first_until_filled() {
+        let asks = [(520, dec!(4)), (530, dec!(10)), (540, dec!(50))];
+        assert_eq!(
+            walk_levels(&asks, dec!(12), 540),
+            vec![(520, dec!(4)), (530, dec!(8))]
+        );
+    }
+
+    #[test]
+    fn test_walk_levels_stops_at_max_price() {
+        let asks = [(520, dec!(4)), (530, dec!(10))];
+        assert_eq!(walk_levels(&asks, dec!(12), 525), vec![(520, dec!(4))]);
+        assert!(walk_levels(&asks, dec!(12), 510).is_empty());
+    }
+
+    #[test]
+    fn test_impact_capped_to_depth_fraction() {
+        let asks = [(520, dec!(10)), (530, dec!(10)), (600, dec!(100))];
+        // 20 shares visible within 530, half of it usable
+        assert_eq!(impact_capped(&asks, dec!(30), 530, dec!(0.5)), dec!(10));
+        assert_eq!(impact_capped(&asks, dec!(8), 530, dec!(0.5)), dec!(8));
+        assert_eq!(impact_capped(&asks, dec!(30), 510, dec!(0.5)), dec!(0));
+    }
+
+    #[test]
+    fn test_take_throttle_per_side() {
+        let mut throttle = TakeThrottle::new(Duration::from_secs(10));
+        let t0 = Instant::now();
+        assert!(throttle.ready(Side::Yes, t0));
+        // Checking alone doesn't start the cooldown
+        assert!(throttle.ready(Side::Yes, t0 + Duration::from_secs(1)));
+        throttle.claim(Side::Yes, t0);
+        assert!(!throttle.ready(Side::Yes, t0 + Duration::from_secs(5)));
+        // Other side has its own cooldown
+        assert!(throttle.ready(Side::No, t0 + Duration::from_secs(5)));
+        assert!(throttle.ready(Side::Yes, t0 + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_closes_within() {
+        let buffer = Duration::from_secs(3);
+        assert_eq!(closes_within(None, buffer, 1_000_000), None);
+        assert_eq!(closes_within(Some(1_000_000), buffer, 996_999), None);
+        assert_eq!(closes_within(Some(1_000_000), buffer, 997_000), Some(3000));
+        assert_eq!(closes_within(Some(1_000_000), buffer, 999_500), Some(500));
+        assert_eq!(closes_within(Some(1_000_000), buffer, 1_002_000), Some(0));
+    }
+
+    #[test]
+    fn test_market_end_expiration() {
+        // Stops resting 10s before a close at 1000s; the exchange gets 60s on top
+        assert_eq!(market_end_expiration(1_000_000, 10, 900), Some(990 + GTD_SECURITY_SECS));
+        assert_eq!(market_end_expiration(1_000_000, 10, 990), None);
+        assert_eq!(market_end_expiration(1_000_000, 0, 999), Some(1000 + GTD_SECURITY_SECS));
+    }
+
+    #[test]
+    fn test_take_report_avg_price() {
+        let mut report = TakeReport::default();
+        assert_eq!(report.avg_price(), None);
+        report.record(520, dec!(4));
+        report.record(530, dec!(8));
+        assert_eq!(report.filled, dec!(12));
+        let avg = report.avg_price().unwrap();
+        assert!((avg - 526.666).abs() < 0.01);
+    }
+}
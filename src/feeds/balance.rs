@@ -0,0 +1,37 @@
+//! Proxy wallet collateral while trading.
+//!
+//! Polls the wallet's USDC balance and exchange allowances over Polygon
+//! JSON-RPC and sends them as BalanceUpdate events; the main loop caps
+//! resting bids at what's both held and approved.
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use crate::api::account;
+use crate::events::Event;
+
+const POLL_SECS: u64 = 30;
+const TIMEOUT_SECS: u64 = 5;
+
+/// Reports the wallet's collateral now and every 30s after, until the
+/// event loop goes away.
+pub async fn run(wallet: String, tx: mpsc::Sender<Event>) {
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(TIMEOUT_SECS))
+        .build()
+        .unwrap_or_default();
+    let rpc = account::polygon_rpc();
+
+    loop {
+        match account::fetch(&http, &rpc, &wallet).await {
+            Ok((balance, allowances)) => {
+                if tx.send(Event::BalanceUpdate { balance, allowances }).await.is_err() {
+                    return;
+                }
+            }
+            Err(e) => println!("[ACCOUNT] Balance check failed: {}", e),
+        }
+        tokio::time::sleep(Duration::from_secs(POLL_SECS)).await;
+    }
+}
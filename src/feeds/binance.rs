@@ -1,42 +1,91 @@
 use futures_util::StreamExt;
 use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 use tokio_tungstenite::connect_async;
 
+use super::recorder::Recorder;
+use super::ParseFailures;
+use crate::alerts;
 use crate::events::Event;
 
-const BINANCE_WS_URL: &str = "wss://stream.binance.com:9443/ws/btcusdt@trade";
+/// Spot trades, per symbol: `<symbol>@trade`
+const BINANCE_WS_BASE: &str = "wss://stream.binance.com:9443/ws";
 
-// Binance sends this JSON shape for each trade
+/// USD-M futures mark price, once a second (same "p" field as trades):
+/// `<symbol>@markPrice@1s`
+const BINANCE_PERP_WS_BASE: &str = "wss://fstream.binance.com/ws";
+
+// Binance sends this JSON shape for each trade (and each mark price update)
 #[derive(serde::Deserialize)]
 struct BinanceTrade {
+    #[serde(rename = "e")]
+    event: Option<String>,
     #[serde(rename = "p")]
-    price: String,
+    price: Option<String>,
+}
+
+/// The price in one raw frame, None for frames without one. An error
+/// means the frame isn't in a shape we know: malformed, or a trade or
+/// mark price update without a usable price.
+pub fn parse_price(text: &str) -> anyhow::Result<Option<f64>> {
+    // simd-json parses in place
+    let mut bytes = text.as_bytes().to_vec();
+    let frame = simd_json::from_slice::<BinanceTrade>(&mut bytes)?;
+    match (frame.event.as_deref(), frame.price) {
+        (_, Some(price)) => Ok(Some(price.parse()?)),
+        (Some(event @ ("trade" | "markPriceUpdate")), None) => anyhow::bail!("{} without a price", event),
+        _ => Ok(None),
+    }
+}
+
+/// Spawns a task that connects to Binance's `symbol` (e.g. "ethusdt")
+/// trades and sends BtcPrice events. Raw messages are tapped into
+/// `recorder`.
+pub fn spawn(symbol: &str, tx: mpsc::Sender<Event>, recorder: Recorder) -> JoinHandle<()> {
+    let url = format!("{}/{}@trade", BINANCE_WS_BASE, symbol);
+    stream(url, "binance", "feed:binance", tx, recorder, |price| Event::BtcPrice { price })
+}
+
+/// Spawns a task that follows the `symbol` perp and sends PerpPrice events
+pub fn spawn_perp(symbol: &str, tx: mpsc::Sender<Event>, recorder: Recorder) -> JoinHandle<()> {
+    let url = format!("{}/{}@markPrice@1s", BINANCE_PERP_WS_BASE, symbol);
+    stream(url, "binance-perp", "feed:binance-perp", tx, recorder, |price| Event::PerpPrice { price })
 }
 
-/// Spawns a task that connects to Binance and sends BtcPrice events
-pub fn spawn(tx: mpsc::Sender<Event>) {
+fn stream(
+    url: String,
+    name: &'static str,
+    alert_key: &'static str,
+    tx: mpsc::Sender<Event>,
+    recorder: Recorder,
+    event: fn(f64) -> Event,
+) -> JoinHandle<()> {
     tokio::spawn(async move {
         loop {
-            println!("[binance] Connecting...");
+            println!("[{}] Connecting...", name);
 
-            match connect_async(BINANCE_WS_URL).await {
+            match connect_async(url.as_str()).await {
                 Ok((ws_stream, _)) => {
-                    println!("[binance] Connected!");
+                    println!("[{}] Connected!", name);
+                    alerts::resolve(alert_key);
 
                     let (_, mut read) = ws_stream.split();
+                    let mut failures = ParseFailures::default();
 
                     while let Some(msg) = read.next().await {
                         match msg {
                             Ok(tungstenite::Message::Text(text)) => {
-                                let mut bytes = text.into_bytes();
-                                if let Ok(trade) = simd_json::from_slice::<BinanceTrade>(&mut bytes) {
-                                    if let Ok(price) = trade.price.parse::<f64>() {
-                                        let _ = tx.send(Event::BtcPrice { price }).await;
+                                recorder.record(name, &text);
+                                match parse_price(&text) {
+                                    Ok(Some(price)) => {
+                                        let _ = tx.send(event(price)).await;
                                     }
+                                    Ok(None) => {}
+                                    Err(e) => failures.record(name, &e, &text),
                                 }
                             }
                             Err(e) => {
-                                println!("[binance] Error: {}", e);
+                                println!("[{}] Error: {}", name, e);
                                 break;
                             }
                             _ => {}
@@ -44,13 +93,15 @@ pub fn spawn(tx: mpsc::Sender<Event>) {
                     }
                 }
                 Err(e) => {
-                    println!("[binance] Failed to connect: {}", e);
+                    println!("[{}] Failed to connect: {}", name, e);
                 }
             }
 
+            alerts::raise(alert_key, &format!("{} feed down, reconnecting", name));
+
             // Wait before reconnecting
-            println!("[binance] Reconnecting in 5 seconds...");
+            println!("[{}] Reconnecting in 5 seconds...", name);
             tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
         }
-    });
+    })
 }
@@ -0,0 +1,182 @@
+//! Order book checksums.
+//!
+//! Polymarket stamps `book` messages, and each `price_change` entry, with
+//! a SHA-1 of the token's book after the update: the book summary as
+//! compact JSON with `hash` blanked. We keep each token's levels as the
+//! feed sent them (the hash covers the exact strings), apply deltas, and
+//! hash the same way.
+//!
+//! Whether a hash is reproducible depends on fields we might not see, so
+//! a token is only checked once a snapshot's own levels hash to the
+//! snapshot's `hash`. A delta that then leaves us with a different hash is
+//! a mismatch, and the token goes unchecked until its next snapshot
+//! (the resync).
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+
+/// A price level, strings exactly as received.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RawLevel {
+    pub price: String,
+    pub size: String,
+}
+
+/// Outcome of checking one update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    /// Our book hashes to the feed's
+    Match,
+    /// Nothing to check against (no hash, or the token isn't verified)
+    Unchecked,
+    /// Our book diverged from the feed's
+    Mismatch,
+}
+
+/// The summary the hash is taken over, in the feed's field order.
+#[derive(Serialize)]
+struct Summary<'a> {
+    market: &'a str,
+    asset_id: &'a str,
+    timestamp: &'a str,
+    hash: &'a str,
+    bids: Vec<&'a RawLevel>,
+    asks: Vec<&'a RawLevel>,
+}
+
+/// One token's book, levels keyed by price in ticks.
+#[derive(Debug, Default)]
+struct TokenBook {
+    market: String,
+    bids: BTreeMap<u16, RawLevel>,
+    asks: BTreeMap<u16, RawLevel>,
+    /// Its last snapshot hashed to its own `hash`
+    verified: bool,
+}
+
+impl TokenBook {
+    /// Hex SHA-1 of the book as of `timestamp`. Bids best-last and asks
+    /// best-last, as the feed lists them.
+    fn hash(&self, asset_id: &str, timestamp: &str) -> String {
+        let bids: Vec<_> = self.bids.values().collect();
+        let asks: Vec<_> = self.asks.values().rev().collect();
+        book_hash(&self.market, asset_id, timestamp, bids, asks)
+    }
+}
+
+/// Hex SHA-1 of a book summary, levels in the order given.
+pub fn book_hash(market: &str, asset_id: &str, timestamp: &str, bids: Vec<&RawLevel>, asks: Vec<&RawLevel>) -> String {
+    let summary = Summary { market, asset_id, timestamp, hash: "", bids, asks };
+    let json = serde_json::to_string(&summary).unwrap_or_default();
+    Sha1::digest(json.as_bytes()).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Dollar price string to ticks.
+fn ticks(price: &str) -> Option<u16> {
+    price.parse::<f64>().ok().map(|p| (p * 1000.0).round() as u16)
+}
+
+fn levels(raw: &[RawLevel]) -> BTreeMap<u16, RawLevel> {
+    raw.iter().filter_map(|l| Some((ticks(&l.price)?, l.clone()))).collect()
+}
+
+/// Per-token books kept for checking the feed's hashes.
+#[derive(Debug, Default)]
+pub struct BookChecksum {
+    books: HashMap<String, TokenBook>,
+}
+
+impl BookChecksum {
+    /// Replace a token's book with a full snapshot.
+    ///
+    /// # Returns
+    /// Whether the snapshot verified against its own hash, so its deltas
+    /// will be checked.
+    pub fn snapshot(
+        &mut self,
+        asset_id: &str,
+        market: &str,
+        timestamp: &str,
+        bids: &[RawLevel],
+        asks: &[RawLevel],
+        hash: Option<&str>,
+    ) -> bool {
+        let mut book = TokenBook { market: market.to_string(), bids: levels(bids), asks: levels(asks), verified: false };
+        book.verified = hash.is_some_and(|h| book.hash(asset_id, timestamp) == h);
+        let verified = book.verified;
+        self.books.insert(asset_id.to_string(), book);
+        verified
+    }
+
+    /// Apply one level change (size "0" removes the level) and check the
+    /// result against `hash`.
+    pub fn delta(&mut self, asset_id: &str, timestamp: &str, is_bid: bool, level: RawLevel, hash: Option<&str>) -> Check {
+        let Some(book) = self.books.get_mut(asset_id) else {
+            return Check::Unchecked;
+        };
+        let Some(price) = ticks(&level.price) else {
+            return Check::Unchecked;
+        };
+        let side = if is_bid { &mut book.bids } else { &mut book.asks };
+        if level.size.parse::<f64>().is_ok_and(|s| s > 0.0) {
+            side.insert(price, level);
+        } else {
+            side.remove(&price);
+        }
+
+        let Some(hash) = hash.filter(|_| book.verified) else {
+            return Check::Unchecked;
+        };
+        if book.hash(asset_id, timestamp) == hash {
+            Check::Match
+        } else {
+            book.verified = false;
+            Check::Mismatch
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(price: &str, size: &str) -> RawLevel {
+        RawLevel { price: price.into(), size: size.into() }
+    }
+
+    /// The hash the feed would send for this book.
+    fn feed_hash(bids: &[RawLevel], asks: &[RawLevel], timestamp: &str) -> String {
+        TokenBook { market: "m".into(), bids: levels(bids), asks: levels(asks), verified: false }.hash("Y", timestamp)
+    }
+
+    #[test]
+    fn test_deltas_checked_after_verified_snapshot() {
+        let mut checksum = BookChecksum::default();
+        let (bids, asks) = (vec![level("0.47", "10"), level("0.48", "30")], vec![level("0.5", "12.5")]);
+        let hash = feed_hash(&bids, &asks, "1");
+        assert!(checksum.snapshot("Y", "m", "1", &bids, &asks, Some(&hash)));
+
+        // Level changes the feed agrees with
+        let after = feed_hash(&[level("0.47", "10"), level("0.48", "30"), level("0.49", "5")], &asks, "2");
+        assert_eq!(checksum.delta("Y", "2", true, level("0.49", "5"), Some(&after)), Check::Match);
+        let after = feed_hash(&[level("0.48", "30"), level("0.49", "5")], &asks, "3");
+        assert_eq!(checksum.delta("Y", "3", true, level("0.47", "0"), Some(&after)), Check::Match);
+        assert_eq!(checksum.delta("Y", "4", false, level("0.51", "1"), None), Check::Unchecked);
+
+        // A change we missed: mismatch, then unchecked until the next snapshot
+        assert_eq!(checksum.delta("Y", "5", true, level("0.46", "1"), Some("beef")), Check::Mismatch);
+        assert_eq!(checksum.delta("Y", "6", true, level("0.45", "1"), Some("beef")), Check::Unchecked);
+        assert_eq!(checksum.delta("N", "6", true, level("0.45", "1"), Some("beef")), Check::Unchecked);
+    }
+
+    #[test]
+    fn test_unreproducible_snapshot_not_checked() {
+        let mut checksum = BookChecksum::default();
+        let bids = vec![level("0.48", "30")];
+        assert!(!checksum.snapshot("Y", "m", "1", &bids, &[], Some("not-our-scheme")));
+        assert!(!checksum.snapshot("N", "m", "1", &bids, &[], None));
+        assert_eq!(checksum.delta("Y", "2", true, level("0.49", "5"), Some("x")), Check::Unchecked);
+    }
+}
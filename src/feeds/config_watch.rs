@@ -0,0 +1,88 @@
+//! Config file watcher.
+//!
+//! Re-reads `config.toml` every couple of seconds and sends a
+//! ConfigChanged event when its contents change and still parse. An edit
+//! that doesn't parse is reported once and never reaches the main loop,
+//! so a half-saved file can't knock out the running settings.
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::config::Config;
+use crate::events::Event;
+use crate::run_info::config_hash;
+
+const POLL_SECS: u64 = 2;
+
+/// Spawns a task that watches `path` and sends ConfigChanged on each
+/// change that parses.
+///
+/// # Arguments
+/// * `loaded_hash` - Hash of the contents already in effect (changes
+///   are relative to it)
+pub fn spawn(path: String, loaded_hash: String, tx: mpsc::Sender<Event>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut last = Some(loaded_hash);
+        let mut interval = tokio::time::interval(Duration::from_secs(POLL_SECS));
+        loop {
+            interval.tick().await;
+            match check(&path, &mut last) {
+                Some(Ok(config)) => {
+                    let event = Event::ConfigChanged { config: Box::new(config) };
+                    if tx.send(event).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Err(e)) => println!("[CONFIG] {} changed but doesn't parse, ignoring: {:#}", path, e),
+                None => {}
+            }
+        }
+    })
+}
+
+/// Read the file once.
+///
+/// # Returns
+/// None while the contents match `last` (or the file can't be read),
+/// otherwise the new config or why it didn't parse.
+fn check(path: &str, last: &mut Option<String>) -> Option<anyhow::Result<Config>> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let hash = config_hash(&text);
+    if last.as_deref() == Some(hash.as_str()) {
+        return None;
+    }
+    *last = Some(hash);
+    Some(Config::parse(&text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = "[credentials]\nprivate_key = \"\"\nproxy_wallet = \"\"\n[general]\nlog_level = \"info\"\n";
+
+    #[test]
+    fn test_reports_each_change_once() {
+        let path = std::env::temp_dir().join(format!("polybot_config_watch_{}.toml", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        std::fs::write(&path, CONFIG).unwrap();
+        let mut last = Some(config_hash(CONFIG));
+        assert!(check(path_str, &mut last).is_none());
+
+        std::fs::write(&path, format!("{}[strategy]\nmargin_ticks = 30\n", CONFIG)).unwrap();
+        let config = check(path_str, &mut last).unwrap().unwrap();
+        assert_eq!(config.strategy.margin_ticks, Some(30));
+        assert!(check(path_str, &mut last).is_none());
+
+        // A broken edit is reported once, then fixed
+        std::fs::write(&path, format!("{}[strategy\n", CONFIG)).unwrap();
+        assert!(check(path_str, &mut last).unwrap().is_err());
+        assert!(check(path_str, &mut last).is_none());
+        std::fs::write(&path, CONFIG).unwrap();
+        assert!(check(path_str, &mut last).unwrap().is_ok());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
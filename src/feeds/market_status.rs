@@ -0,0 +1,46 @@
+//! Trading status of the live market.
+//!
+//! Polymarket can pause a market or resolve it before its end time. This
+//! polls Gamma for the market's status and sends a MarketStatus event
+//! whenever it changes, so the main loop can cancel and stand aside
+//! instead of sending orders the exchange will reject until rollover.
+//! The market channel's `market_resolved` message gives the same event
+//! (see feeds::polymarket).
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::api::gamma::fetch_market_status;
+use crate::events::Event;
+
+const POLL_SECS: u64 = 5;
+const TIMEOUT_SECS: u64 = 3;
+
+/// Spawns a task that polls one market's status. Abort it on market
+/// switch.
+pub fn spawn(slug: String, condition_id: String, tx: mpsc::Sender<Event>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        let mut last: Option<String> = None;
+
+        loop {
+            tokio::time::sleep(Duration::from_secs(POLL_SECS)).await;
+            // A failed poll says nothing about the market; keep the last status
+            let halted = match fetch_market_status(&http, &slug).await {
+                Ok(status) => status.halt_reason(),
+                Err(_) => continue,
+            };
+            if halted != last {
+                last = halted.clone();
+                if tx.send(Event::MarketStatus { condition_id: condition_id.clone(), halted }).await.is_err() {
+                    return;
+                }
+            }
+        }
+    })
+}
@@ -1,4 +1,45 @@
 // This file declares what's in the feeds folder
+pub mod balance;
 pub mod binance;
+pub mod checksum;
+pub mod config_watch;
+pub mod market_status;
 pub mod polymarket;
+pub mod positions;
+pub mod recorder;
+pub mod signal;
+pub mod status;
 pub mod user_ws;
+pub mod whale;
+
+use serde::de::DeserializeOwned;
+
+/// The messages in one Polymarket frame: an object, or an array of them
+/// (the initial book snapshots come batched). Heartbeats carry none.
+fn frames<T: DeserializeOwned>(text: &str) -> serde_json::Result<Vec<T>> {
+    let text = text.trim_start();
+    if text.eq_ignore_ascii_case("pong") {
+        Ok(Vec::new())
+    } else if text.starts_with('[') {
+        serde_json::from_str(text)
+    } else {
+        serde_json::from_str(text).map(|message| vec![message])
+    }
+}
+
+/// Frames a feed couldn't parse. Warns on the first and every 1000th, so
+/// a format change is visible without flooding the log.
+#[derive(Debug, Default)]
+struct ParseFailures {
+    count: u64,
+}
+
+impl ParseFailures {
+    fn record(&mut self, feed: &str, error: &anyhow::Error, text: &str) {
+        self.count += 1;
+        if self.count == 1 || self.count.is_multiple_of(1000) {
+            let sample: String = text.chars().take(200).collect();
+            println!("[{}] Unparsed frame #{}: {:#} in {}", feed, self.count, error, sample);
+        }
+    }
+}
@@ -1,11 +1,25 @@
+use std::collections::HashMap;
+
 use futures_util::{SinkExt, StreamExt};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
+use tokio::time::Instant;
 use tokio_tungstenite::connect_async;
 
+use anyhow::{anyhow, bail};
+
+use super::checksum::{BookChecksum, Check, RawLevel};
+use super::recorder::Recorder;
+use super::{frames, ParseFailures};
+use crate::alerts;
+use crate::api::book;
 use crate::events::{Event, Side};
 
-const POLYMARKET_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
+pub const POLYMARKET_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/market";
+
+/// Minimum seconds between REST resyncs of one token after checksum
+/// mismatches, so a feed we can't keep up with doesn't hammer the API.
+const RESYNC_COOLDOWN_SECS: u64 = 5;
 
 // Message we send to subscribe
 #[derive(serde::Serialize)]
@@ -25,16 +39,238 @@ struct PolyMessage {
     best_ask: Option<String>,
     // For last_trade_price
     price: Option<String>,
+    // Taker side for last_trade_price: "BUY" or "SELL"
+    side: Option<String>,
+    // For book (full depth)
+    bids: Option<Vec<Level>>,
+    asks: Option<Vec<Level>>,
+    // For price_change (level deltas); older messages call it `changes`
+    #[serde(alias = "changes")]
+    price_changes: Option<Vec<LevelChange>>,
+    // For checksums: condition ID, ms timestamp (string or number) and the
+    // book's hash after a `book`
+    market: Option<String>,
+    timestamp: Option<serde_json::Value>,
+    hash: Option<String>,
+    // For market_resolved: the market's tokens and the outcome that won
+    assets_ids: Option<Vec<String>>,
+    winning_outcome: Option<String>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct Level {
+    price: String,
+    size: String,
+}
+
+impl From<&Level> for RawLevel {
+    fn from(l: &Level) -> Self {
+        RawLevel { price: l.price.clone(), size: l.size.clone() }
+    }
+}
+
+#[derive(serde::Deserialize, Debug)]
+struct LevelChange {
+    // Per change in newer messages, else the message's
+    asset_id: Option<String>,
+    price: String,
+    size: String,
+    // "BUY" = bid level, "SELL" = ask level
+    side: String,
+    // The token's book hash after this change
+    hash: Option<String>,
+}
+
+/// Dollar price string to ticks.
+fn parse_ticks(price: &str) -> Option<u16> {
+    price.parse::<f64>().ok().map(|p| (p * 1000.0).round() as u16)
+}
+
+fn parse_levels(levels: &[Level]) -> Vec<(u16, f64)> {
+    levels
+        .iter()
+        .filter_map(|l| Some((parse_ticks(&l.price)?, l.size.parse::<f64>().ok()?)))
+        .collect()
+}
+
+/// Events in one market channel message. Messages for other tokens and
+/// types we don't use (tick_size_change, new_market) give none; a
+/// type we use that's missing its fields is an error.
+fn message_events(msg: &PolyMessage, yes_token: &str, no_token: &str) -> anyhow::Result<Vec<Event>> {
+    let side_of = |asset_id: &str| {
+        if asset_id == yes_token {
+            Some(Side::Yes)
+        } else if asset_id == no_token {
+            Some(Side::No)
+        } else {
+            None
+        }
+    };
+    let msg_side = msg.asset_id.as_deref().and_then(side_of);
+    let kind = msg.event_type.as_deref().unwrap_or("message");
+    let ticks = |field: &Option<String>, name: &str| {
+        field.as_deref().and_then(parse_ticks).ok_or_else(|| anyhow!("{} without a valid {}", kind, name))
+    };
+
+    let events = match msg.event_type.as_deref() {
+        Some("best_bid_ask") => {
+            let (bid, ask) = (ticks(&msg.best_bid, "best_bid")?, ticks(&msg.best_ask, "best_ask")?);
+            msg_side.map(|side| Event::BookUpdate { side, bid, ask }).into_iter().collect()
+        }
+        Some("book") => {
+            let (Some(bids), Some(asks)) = (&msg.bids, &msg.asks) else {
+                bail!("book without bids/asks");
+            };
+            msg_side
+                .map(|side| Event::BookSnapshot { side, bids: parse_levels(bids), asks: parse_levels(asks) })
+                .into_iter()
+                .collect()
+        }
+        Some("price_change") => {
+            let Some(changes) = &msg.price_changes else {
+                bail!("price_change without price_changes");
+            };
+            let mut events = Vec::new();
+            for c in changes {
+                let Some(side) = c.asset_id.as_deref().map_or(msg_side, side_of) else {
+                    continue;
+                };
+                events.push(Event::BookDelta {
+                    side,
+                    is_bid: c.side == "BUY",
+                    price: parse_ticks(&c.price).ok_or_else(|| anyhow!("bad change price {:?}", c.price))?,
+                    size: c.size.parse().map_err(|_| anyhow!("bad change size {:?}", c.size))?,
+                });
+            }
+            events
+        }
+        Some("last_trade_price") => {
+            let price = ticks(&msg.price, "price")?;
+            // Taker bought this token (None if the feed didn't say)
+            let taker_buy = match msg.side.as_deref() {
+                Some("BUY") => Some(true),
+                Some("SELL") => Some(false),
+                _ => None,
+            };
+            msg_side.map(|side| Event::Trade { side, price, taker_buy }).into_iter().collect()
+        }
+        Some("market_resolved") => {
+            let ours = msg.assets_ids.iter().flatten().any(|id| side_of(id).is_some());
+            let reason = match &msg.winning_outcome {
+                Some(outcome) => format!("resolved {}", outcome),
+                None => "resolved".to_string(),
+            };
+            let condition_id = msg.market.clone().unwrap_or_default();
+            ours.then_some(Event::MarketStatus { condition_id, halted: Some(reason) }).into_iter().collect()
+        }
+        _ => Vec::new(),
+    };
+    Ok(events)
+}
+
+/// Check one message's book hashes against `checksum`, updating it.
+///
+/// # Returns
+/// The assets whose book no longer matches the feed's.
+fn message_checks(msg: &PolyMessage, checksum: &mut BookChecksum) -> Vec<String> {
+    let timestamp = match &msg.timestamp {
+        Some(serde_json::Value::String(t)) => t.clone(),
+        Some(t) => t.to_string(),
+        None => String::new(),
+    };
+    let market = msg.market.as_deref().unwrap_or_default();
+    let mut mismatched = Vec::new();
+    match (msg.event_type.as_deref(), msg.asset_id.as_deref()) {
+        (Some("book"), Some(asset_id)) => {
+            let raw = |levels: &Option<Vec<Level>>| levels.iter().flatten().map(RawLevel::from).collect::<Vec<_>>();
+            checksum.snapshot(asset_id, market, &timestamp, &raw(&msg.bids), &raw(&msg.asks), msg.hash.as_deref());
+        }
+        (Some("price_change"), _) => {
+            for c in msg.price_changes.iter().flatten() {
+                let Some(asset_id) = c.asset_id.as_deref().or(msg.asset_id.as_deref()) else {
+                    continue;
+                };
+                let level = RawLevel { price: c.price.clone(), size: c.size.clone() };
+                if checksum.delta(asset_id, &timestamp, c.side == "BUY", level, c.hash.as_deref()) == Check::Mismatch {
+                    mismatched.push(asset_id.to_string());
+                }
+            }
+        }
+        _ => {}
+    }
+    mismatched
 }
 
 pub struct PolymarketFeed {
     yes_token: String,
     no_token: String,
+    recorder: Recorder,
 }
 
 impl PolymarketFeed {
     pub fn new(yes_token: String, no_token: String) -> Self {
-        Self { yes_token, no_token }
+        Self { yes_token, no_token, recorder: Recorder::disabled() }
+    }
+
+    /// Tap every raw message into `recorder`.
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = recorder;
+        self
+    }
+
+    /// Events in one raw frame (top of book unfiltered). An error means
+    /// the frame isn't in a shape we know: malformed, or a message type
+    /// we use that's missing fields.
+    pub fn parse(&self, text: &str) -> anyhow::Result<Vec<Event>> {
+        self.parse_checked(text, &mut BookChecksum::default())
+    }
+
+    /// `parse`, checking book hashes on the way: a BookMismatch follows
+    /// the deltas of a token whose book stopped matching the feed's.
+    fn parse_checked(&self, text: &str, checksum: &mut BookChecksum) -> anyhow::Result<Vec<Event>> {
+        let mut events = Vec::new();
+        for msg in frames::<PolyMessage>(text)? {
+            events.extend(message_events(&msg, &self.yes_token, &self.no_token)?);
+            for asset_id in message_checks(&msg, checksum) {
+                if let Some(side) = self.side_of(&asset_id) {
+                    events.push(Event::BookMismatch { side });
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    fn side_of(&self, asset_id: &str) -> Option<Side> {
+        if asset_id == self.yes_token {
+            Some(Side::Yes)
+        } else if asset_id == self.no_token {
+            Some(Side::No)
+        } else {
+            None
+        }
+    }
+
+    fn token(&self, side: Side) -> &str {
+        match side {
+            Side::Yes => &self.yes_token,
+            Side::No => &self.no_token,
+        }
+    }
+
+    /// Fetch a token's book over REST, reset its checksum from it and
+    /// turn it into a BookSnapshot.
+    async fn resync(&self, side: Side, checksum: &mut BookChecksum) -> anyhow::Result<Event> {
+        let token = self.token(side);
+        let snapshot = book::fetch_book(token).await?;
+        let raw = |levels: &[book::Level]| {
+            levels.iter().map(|l| RawLevel { price: l.price.clone(), size: l.size.clone() }).collect::<Vec<_>>()
+        };
+        let (bids, asks) = (raw(&snapshot.bids), raw(&snapshot.asks));
+        checksum.snapshot(token, &snapshot.market, &snapshot.timestamp, &bids, &asks, snapshot.hash.as_deref());
+        let parse = |levels: &[RawLevel]| {
+            levels.iter().filter_map(|l| Some((parse_ticks(&l.price)?, l.size.parse::<f64>().ok()?))).collect()
+        };
+        Ok(Event::BookSnapshot { side, bids: parse(&bids), asks: parse(&asks) })
     }
 
     /// Spawns a task that connects and sends BookUpdate events.
@@ -64,79 +300,57 @@ impl PolymarketFeed {
                         }
 
                         println!("[polymarket] Subscribed to tokens");
+                        alerts::resolve("feed:polymarket");
 
                         // Track last prices to detect changes
                         let mut last_yes: (u16, u16) = (0, 0);
                         let mut last_no: (u16, u16) = (0, 0);
+                        let mut failures = ParseFailures::default();
+                        let mut checksum = BookChecksum::default();
+                        let mut last_resync: HashMap<Side, Instant> = HashMap::new();
 
                         while let Some(msg) = read.next().await {
                             match msg {
                                 Ok(tungstenite::Message::Text(text)) => {
-                                    if let Ok(msg) = serde_json::from_str::<PolyMessage>(&text) {
-                                        let asset_id = msg.asset_id.as_deref().unwrap_or("");
-
-                                        // Determine which side
-                                        let side = if asset_id == self.yes_token {
-                                            Some(Side::Yes)
-                                        } else if asset_id == self.no_token {
-                                            Some(Side::No)
-                                        } else {
-                                            None
+                                    self.recorder.record("polymarket", &text);
+                                    let events = match self.parse_checked(&text, &mut checksum) {
+                                        Ok(events) => events,
+                                        Err(e) => {
+                                            failures.record("polymarket", &e, &text);
+                                            continue;
+                                        }
+                                    };
+                                    for event in events {
+                                        // Top of book: only send changes
+                                        if let Event::BookUpdate { side, bid, ask } = event {
+                                            let last = match side {
+                                                Side::Yes => &mut last_yes,
+                                                Side::No => &mut last_no,
+                                            };
+                                            if (bid, ask) == *last {
+                                                continue;
+                                            }
+                                            *last = (bid, ask);
+                                        }
+                                        let mismatch = match event {
+                                            Event::BookMismatch { side } => Some(side),
+                                            _ => None,
                                         };
+                                        let _ = tx.send(event).await;
 
-                                        match msg.event_type.as_deref() {
-                                            Some("best_bid_ask") => {
-                                                // Parse prices
-                                                let bid = msg.best_bid
-                                                    .as_ref()
-                                                    .and_then(|s| s.parse::<f64>().ok())
-                                                    .map(|p| (p * 1000.0) as u16);
-
-                                                let ask = msg.best_ask
-                                                    .as_ref()
-                                                    .and_then(|s| s.parse::<f64>().ok())
-                                                    .map(|p| (p * 1000.0) as u16);
-
-                                                // Only send if changed
-                                                if let (Some(s), Some(b), Some(a)) = (side, bid, ask) {
-                                                    let changed = match s {
-                                                        Side::Yes => {
-                                                            let c = (b, a) != last_yes;
-                                                            last_yes = (b, a);
-                                                            c
-                                                        }
-                                                        Side::No => {
-                                                            let c = (b, a) != last_no;
-                                                            last_no = (b, a);
-                                                            c
-                                                        }
-                                                    };
-
-                                                    if changed {
-                                                        let _ = tx.send(Event::BookUpdate {
-                                                            side: s,
-                                                            bid: b,
-                                                            ask: a,
-                                                        }).await;
-                                                    }
-                                                }
-                                            }
-                                            Some("last_trade_price") => {
-                                                // Trade event - for flow estimator (k)
-                                                if let Some(s) = side {
-                                                    let price = msg.price
-                                                        .as_ref()
-                                                        .and_then(|p| p.parse::<f64>().ok())
-                                                        .map(|p| (p * 1000.0) as u16)
-                                                        .unwrap_or(0);
-
-                                                    let _ = tx.send(Event::Trade {
-                                                        side: s,
-                                                        price,
-                                                    }).await;
-                                                }
+                                        // Resync the token from REST, at most once per cooldown
+                                        let Some(side) = mismatch else {
+                                            continue;
+                                        };
+                                        if last_resync.get(&side).is_some_and(|t| t.elapsed().as_secs() < RESYNC_COOLDOWN_SECS) {
+                                            continue;
+                                        }
+                                        last_resync.insert(side, Instant::now());
+                                        match self.resync(side, &mut checksum).await {
+                                            Ok(snapshot) => {
+                                                let _ = tx.send(snapshot).await;
                                             }
-                                            _ => {}
+                                            Err(e) => println!("[polymarket] Resync of {:?} failed: {:#}", side, e),
                                         }
                                     }
                                 }
@@ -153,9 +367,87 @@ impl PolymarketFeed {
                     }
                 }
 
+                alerts::raise("feed:polymarket", "Polymarket book feed down, reconnecting");
                 println!("[polymarket] Reconnecting in 5 seconds...");
                 tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
             }
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feeds::checksum::book_hash;
+
+    fn parse(json: &str) -> Vec<Event> {
+        PolymarketFeed::new("Y".into(), "N".into()).parse(json).unwrap()
+    }
+
+    #[test]
+    fn test_book_snapshot() {
+        let events = parse(
+            r#"{"event_type":"book","asset_id":"Y","bids":[{"price":"0.48","size":"30"}],"asks":[{"price":"0.5","size":"12.5"}]}"#,
+        );
+        assert!(matches!(
+            events.as_slice(),
+            [Event::BookSnapshot { side: Side::Yes, bids, asks }] if bids == &[(480, 30.0)] && asks == &[(500, 12.5)]
+        ));
+    }
+
+    #[test]
+    fn test_price_change() {
+        let events = parse(
+            r#"{"event_type":"price_change","price_changes":[
+                {"asset_id":"N","price":"0.51","size":"0","side":"SELL"},
+                {"asset_id":"Y","price":"0.49","size":"200","side":"BUY"},
+                {"asset_id":"other","price":"0.49","size":"200","side":"BUY"}
+            ]}"#,
+        );
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], Event::BookDelta { side: Side::No, is_bid: false, price: 510, size } if size == 0.0));
+        assert!(matches!(events[1], Event::BookDelta { side: Side::Yes, is_bid: true, price: 490, size } if size == 200.0));
+
+        // Older shape: asset on the message, changes without one
+        let events = parse(r#"{"event_type":"price_change","asset_id":"Y","changes":[{"price":"0.47","size":"5","side":"BUY"}]}"#);
+        assert!(matches!(events[0], Event::BookDelta { side: Side::Yes, is_bid: true, price: 470, .. }));
+    }
+
+    #[test]
+    fn test_market_resolved() {
+        let events = parse(r#"{"event_type":"market_resolved","market":"0xm","assets_ids":["Y","N"],"winning_outcome":"Up"}"#);
+        assert!(matches!(
+            events.as_slice(),
+            [Event::MarketStatus { condition_id, halted: Some(reason) }] if condition_id == "0xm" && reason == "resolved Up"
+        ));
+        assert!(parse(r#"{"event_type":"market_resolved","market":"0xo","assets_ids":["A","B"]}"#).is_empty());
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        let feed = PolymarketFeed::new("Y".into(), "N".into());
+        let mut checksum = BookChecksum::default();
+        let level = |price: &str, size: &str| RawLevel { price: price.into(), size: size.into() };
+        let (bid, ask) = (level("0.48", "30"), level("0.5", "12.5"));
+        let hash = book_hash("m", "Y", "1", vec![&bid], vec![&ask]);
+        let book = format!(
+            r#"{{"event_type":"book","asset_id":"Y","market":"m","timestamp":"1","hash":"{}","bids":[{{"price":"0.48","size":"30"}}],"asks":[{{"price":"0.5","size":"12.5"}}]}}"#,
+            hash
+        );
+        assert_eq!(feed.parse_checked(&book, &mut checksum).unwrap().len(), 1);
+
+        // Agrees with the feed
+        let moved = level("0.49", "5");
+        let hash = book_hash("m", "Y", "2", vec![&bid, &moved], vec![&ask]);
+        let change = format!(
+            r#"{{"event_type":"price_change","market":"m","timestamp":2,"price_changes":[{{"asset_id":"Y","price":"0.49","size":"5","side":"BUY","hash":"{}"}}]}}"#,
+            hash
+        );
+        assert_eq!(feed.parse_checked(&change, &mut checksum).unwrap().len(), 1);
+
+        // Doesn't
+        let change = r#"{"event_type":"price_change","market":"m","timestamp":"3","price_changes":[{"asset_id":"Y","price":"0.47","size":"1","side":"BUY","hash":"beef"}]}"#;
+        let events = feed.parse_checked(change, &mut checksum).unwrap();
+        assert!(matches!(events.as_slice(), [Event::BookDelta { .. }, Event::BookMismatch { side: Side::Yes }]));
+    }
+}
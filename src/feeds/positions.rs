@@ -0,0 +1,39 @@
+//! Exchange-reported position for the shadow accounting check.
+//!
+//! Polls the Data API for our wallet's holdings in one market and sends
+//! them as ReportedPosition events; the main loop diffs each snapshot
+//! against the Position it built from fills (see state::ShadowPosition).
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::api::data::fetch_positions;
+use crate::events::Event;
+
+const POLL_SECS: u64 = 60;
+const TIMEOUT_SECS: u64 = 5;
+
+/// Spawns a task that reports our holdings in one market every minute.
+/// Abort it on market switch.
+pub fn spawn(wallet: String, condition_id: String, tx: mpsc::Sender<Event>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+
+        loop {
+            tokio::time::sleep(Duration::from_secs(POLL_SECS)).await;
+            match fetch_positions(&http, &wallet, &condition_id).await {
+                Ok((yes, no)) => {
+                    if tx.send(Event::ReportedPosition { yes, no }).await.is_err() {
+                        return;
+                    }
+                }
+                Err(e) => println!("[SHADOW] Position fetch failed: {}", e),
+            }
+        }
+    })
+}
@@ -0,0 +1,257 @@
+//! Raw feed recorder.
+//!
+//! Taps every raw Polymarket and Binance WebSocket message, stamped with
+//! its receive time, and writes them as JSON lines to zstd-compressed files
+//! for backtests and for going back over a bad fill. Lines are compressed
+//! once a second as a complete zstd frame, so a crash loses at most the
+//! last second, and a new file is started every `max_file_bytes` of raw
+//! input.
+//!
+//! Feeds never wait on the disk: messages go through a bounded queue to a
+//! writer thread, and are dropped (and counted) when it's full. The writer
+//! is a sink: on shutdown it writes out what's queued and stops.
+//!
+//! Writing and reading need the `recorder` feature (on by default).
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{SyncSender, TrySendError};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tasks::TaskRegistry;
+
+/// Messages queued for the writer before new ones are dropped
+#[cfg(feature = "recorder")]
+const QUEUE_SIZE: usize = 50_000;
+/// Compress and write buffered lines at least this often (ms)
+#[cfg(feature = "recorder")]
+const FLUSH_MS: u64 = 1000;
+
+/// One raw message as received.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RawMessage {
+    /// Receive time (ms since epoch)
+    pub ts_ms: i64,
+    /// Feed name ("polymarket", "binance", "binance-perp")
+    pub source: String,
+    /// The message text, untouched
+    pub raw: String,
+}
+
+/// Handle the feeds record through. Cheap to clone; a disabled recorder
+/// does nothing.
+#[derive(Clone, Default)]
+pub struct Recorder {
+    tx: Option<SyncSender<RawMessage>>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Recorder {
+    /// A recorder that records nothing.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Start the writer from `[recorder]`; disabled unless enabled. The
+    /// writer runs on a blocking thread, registered as a sink.
+    #[cfg(feature = "recorder")]
+    pub fn from_config(config: &crate::config::Recorder, tasks: &mut TaskRegistry) -> anyhow::Result<Self> {
+        if !config.enabled {
+            return Ok(Self::disabled());
+        }
+        std::fs::create_dir_all(&config.dir)?;
+        let (tx, rx) = std::sync::mpsc::sync_channel(QUEUE_SIZE);
+        let writer = writer::Writer::new(config.dir.clone(), config.max_file_bytes, config.level);
+        tasks.spawn_with(crate::tasks::Stage::Sinks, "recorder", move |stop| async move {
+            let _ = tokio::task::spawn_blocking(move || writer.run(rx, stop)).await;
+        });
+        println!("[RECORDER] Writing raw feeds to {}", config.dir);
+        Ok(Self { tx: Some(tx), dropped: Arc::default() })
+    }
+
+    /// Built without the `recorder` feature: disabled.
+    #[cfg(not(feature = "recorder"))]
+    pub fn from_config(config: &crate::config::Recorder, _tasks: &mut TaskRegistry) -> anyhow::Result<Self> {
+        if config.enabled {
+            println!("[RECORDER] Ignored: built without the `recorder` feature");
+        }
+        Ok(Self::disabled())
+    }
+
+    /// Record one raw message from `source`, stamped now.
+    pub fn record(&self, source: &str, raw: &str) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+        let message = RawMessage {
+            ts_ms: chrono::Utc::now().timestamp_millis(),
+            source: source.to_string(),
+            raw: raw.to_string(),
+        };
+        if let Err(TrySendError::Full(_)) = tx.try_send(message) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Messages dropped because the writer fell behind.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Read a recorded file back, in receive order.
+#[cfg(feature = "recorder")]
+pub fn read(path: impl AsRef<std::path::Path>) -> anyhow::Result<Vec<RawMessage>> {
+    use std::io::BufRead;
+
+    let file = std::fs::File::open(path.as_ref())?;
+    let reader = std::io::BufReader::new(zstd::stream::read::Decoder::new(file)?);
+    let mut messages = Vec::new();
+    for line in reader.lines() {
+        messages.push(serde_json::from_str(&line?)?);
+    }
+    Ok(messages)
+}
+
+#[cfg(feature = "recorder")]
+mod writer {
+    use std::fs::{File, OpenOptions};
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::sync::mpsc::{Receiver, RecvTimeoutError};
+    use std::time::{Duration, Instant};
+
+    use tokio_util::sync::CancellationToken;
+
+    use super::{RawMessage, FLUSH_MS};
+
+    /// Owns the current file; runs on its own thread.
+    pub struct Writer {
+        dir: String,
+        max_file_bytes: u64,
+        level: i32,
+        file: Option<File>,
+        /// Raw bytes written to the current file
+        written: u64,
+        /// Lines not yet compressed
+        buffer: Vec<u8>,
+        seq: u32,
+    }
+
+    impl Writer {
+        pub fn new(dir: String, max_file_bytes: u64, level: i32) -> Self {
+            Self { dir, max_file_bytes, level, file: None, written: 0, buffer: Vec::new(), seq: 0 }
+        }
+
+        /// Write until every Recorder is dropped or `stop` is cancelled,
+        /// then write out what's queued.
+        pub fn run(mut self, rx: Receiver<RawMessage>, stop: CancellationToken) {
+            let interval = Duration::from_millis(FLUSH_MS);
+            let mut last_flush = Instant::now();
+            while !stop.is_cancelled() {
+                match rx.recv_timeout(interval) {
+                    Ok(message) => self.push(&message),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                if last_flush.elapsed() >= interval {
+                    self.flush_logged();
+                    last_flush = Instant::now();
+                }
+            }
+            while let Ok(message) = rx.try_recv() {
+                self.push(&message);
+            }
+            self.flush_logged();
+        }
+
+        pub fn push(&mut self, message: &RawMessage) {
+            if serde_json::to_writer(&mut self.buffer, message).is_ok() {
+                self.buffer.push(b'\n');
+            }
+        }
+
+        fn flush_logged(&mut self) {
+            if let Err(e) = self.flush() {
+                println!("[RECORDER] Write failed: {}", e);
+            }
+        }
+
+        /// Compress the buffered lines as one frame and append it, moving
+        /// to a new file first if this one is full.
+        pub fn flush(&mut self) -> anyhow::Result<()> {
+            if self.buffer.is_empty() {
+                return Ok(());
+            }
+            if self.file.is_none() || self.written >= self.max_file_bytes {
+                self.file = Some(self.open_next()?);
+                self.written = 0;
+            }
+            let frame = zstd::stream::encode_all(&self.buffer[..], self.level)?;
+            if let Some(file) = self.file.as_mut() {
+                file.write_all(&frame)?;
+            }
+            self.written += self.buffer.len() as u64;
+            self.buffer.clear();
+            Ok(())
+        }
+
+        fn open_next(&mut self) -> anyhow::Result<File> {
+            self.seq += 1;
+            let name = format!("feeds_{}_{:03}.jsonl.zst", chrono::Utc::now().timestamp(), self.seq);
+            let path: PathBuf = [self.dir.as_str(), name.as_str()].iter().collect();
+            Ok(OpenOptions::new().create(true).append(true).open(path)?)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "recorder"))]
+mod tests {
+    use super::writer::Writer;
+    use super::*;
+
+    fn message(i: i64) -> RawMessage {
+        RawMessage { ts_ms: i, source: "polymarket".into(), raw: format!(r#"{{"event_type":"book","n":{}}}"#, i) }
+    }
+
+    #[test]
+    fn test_round_trip_and_rotation() {
+        let dir = std::env::temp_dir().join(format!("polybot_recorder_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // Tiny files: every flush after the first fills one, so the next rotates
+        let mut writer = Writer::new(dir.to_string_lossy().into_owned(), 1, 3);
+        for i in 0..3 {
+            writer.push(&message(i));
+            writer.push(&message(i + 10));
+            writer.flush().unwrap();
+        }
+
+        let mut files: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|e| e.unwrap().path()).collect();
+        files.sort();
+        assert_eq!(files.len(), 3);
+        let first = read(&files[0]).unwrap();
+        assert_eq!(first, vec![message(0), message(10)]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_frames_append_within_a_file() {
+        let dir = std::env::temp_dir().join(format!("polybot_recorder_frames_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut writer = Writer::new(dir.to_string_lossy().into_owned(), 1 << 20, 3);
+        writer.push(&message(1));
+        writer.flush().unwrap();
+        writer.push(&message(2));
+        writer.flush().unwrap();
+
+        let files: Vec<_> = std::fs::read_dir(&dir).unwrap().map(|e| e.unwrap().path()).collect();
+        assert_eq!(files.len(), 1);
+        assert_eq!(read(&files[0]).unwrap(), vec![message(1), message(2)]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+//! External signal ingestion over HTTP.
+//!
+//! An outside model POSTs JSON to any path:
+//!
+//! ```text
+//! {"fair": 0.56, "ttl_ms": 5000}
+//! {"direction": "YES", "strength": 0.8, "ttl_ms": 3000}
+//! ```
+//!
+//! `fair` is a YES probability, `direction` the side expected to gain.
+//! Valid signals are answered 202 and forwarded as `Event::ExternalSignal`;
+//! anything else gets 400 and never reaches the strategy.
+
+use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::events::{Event, Side};
+use crate::strategy::ExternalSignal;
+
+/// Largest request we'll read (headers + body)
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+
+#[derive(Debug, Deserialize)]
+struct SignalPost {
+    fair: Option<f64>,
+    direction: Option<String>,
+    #[serde(default = "full_strength")]
+    strength: f64,
+    ttl_ms: u64,
+}
+
+fn full_strength() -> f64 {
+    1.0
+}
+
+/// Parse and validate a POST body.
+fn parse(body: &[u8]) -> Result<ExternalSignal, String> {
+    let post: SignalPost = serde_json::from_slice(body).map_err(|e| format!("bad JSON: {}", e))?;
+    let direction = match post.direction.as_deref().map(str::to_ascii_uppercase).as_deref() {
+        None => None,
+        Some("YES") => Some(Side::Yes),
+        Some("NO") => Some(Side::No),
+        Some(other) => return Err(format!("direction must be YES or NO: {}", other)),
+    };
+    let signal = ExternalSignal {
+        fair: post.fair,
+        direction,
+        strength: post.strength,
+        ttl_secs: post.ttl_ms as f64 / 1000.0,
+    };
+    signal.validate()?;
+    Ok(signal)
+}
+
+/// Bind `addr` and forward signals until the returned task is stopped.
+pub async fn serve(addr: &str, tx: mpsc::Sender<Event>) -> anyhow::Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(addr).await?;
+    Ok(tokio::spawn(async move {
+        loop {
+            let (socket, _) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(_) => continue,
+            };
+            let tx = tx.clone();
+            tokio::spawn(handle(socket, tx));
+        }
+    }))
+}
+
+async fn handle(mut socket: TcpStream, tx: mpsc::Sender<Event>) {
+    let (status, message) = match read_request(&mut socket).await {
+        Ok((method, _)) if method != "POST" => ("405 Method Not Allowed", "POST a signal".to_string()),
+        Ok((_, body)) => match parse(&body) {
+            Ok(signal) => {
+                // try_send: a full channel drops the signal rather than block the feed
+                match tx.try_send(Event::ExternalSignal { signal }) {
+                    Ok(()) => ("202 Accepted", "ok".to_string()),
+                    Err(_) => ("503 Service Unavailable", "engine busy".to_string()),
+                }
+            }
+            Err(e) => ("400 Bad Request", e),
+        },
+        Err(e) => ("400 Bad Request", e),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        message.len(),
+        message
+    );
+    let _ = socket.write_all(response.as_bytes()).await;
+}
+
+/// Read one request: (method, body). Honours Content-Length.
+async fn read_request(socket: &mut TcpStream) -> Result<(String, Vec<u8>), String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 2048];
+    loop {
+        let n = socket.read(&mut chunk).await.map_err(|e| e.to_string())?;
+        if n == 0 {
+            return Err("connection closed mid-request".to_string());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if buf.len() > MAX_REQUEST_BYTES {
+            return Err("request too large".to_string());
+        }
+        if let Some((method, body)) = split_request(&buf)? {
+            return Ok((method, body.to_vec()));
+        }
+    }
+}
+
+/// Split a complete request into (method, body); None if more bytes are due.
+fn split_request(buf: &[u8]) -> Result<Option<(String, &[u8])>, String> {
+    let Some(header_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else {
+        return Ok(None);
+    };
+    let head = std::str::from_utf8(&buf[..header_end]).map_err(|_| "headers not UTF-8".to_string())?;
+    let method = head.split_whitespace().next().unwrap_or_default().to_string();
+    let content_length = head
+        .lines()
+        .filter_map(|l| l.split_once(':'))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case("content-length"))
+        .map(|(_, v)| v.trim().parse::<usize>().map_err(|_| "bad Content-Length".to_string()))
+        .transpose()?
+        .unwrap_or(0);
+    let body = &buf[header_end + 4..];
+    if body.len() < content_length {
+        return Ok(None);
+    }
+    Ok(Some((method, &body[..content_length])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let s = parse(br#"{"fair": 0.56, "ttl_ms": 5000}"#).unwrap();
+        assert_eq!(s.fair, Some(0.56));
+        assert_eq!(s.direction, None);
+        assert_eq!(s.ttl_secs, 5.0);
+
+        let s = parse(br#"{"direction": "no", "strength": 0.5, "ttl_ms": 100}"#).unwrap();
+        assert_eq!(s.direction, Some(Side::No));
+        assert_eq!(s.strength, 0.5);
+
+        assert!(parse(br#"{"ttl_ms": 100}"#).is_err());
+        assert!(parse(br#"{"fair": 0.5}"#).is_err());
+        assert!(parse(br#"{"direction": "UP", "ttl_ms": 100}"#).is_err());
+        assert!(parse(b"not json").is_err());
+    }
+
+    #[test]
+    fn test_split_request() {
+        let req = b"POST /signal HTTP/1.1\r\nHost: x\r\nContent-Length: 4\r\n\r\nab";
+        assert_eq!(split_request(req).unwrap(), None);
+
+        let req = b"POST /signal HTTP/1.1\r\nHost: x\r\ncontent-length: 4\r\n\r\nabcd";
+        let (method, body) = split_request(req).unwrap().unwrap();
+        assert_eq!(method, "POST");
+        assert_eq!(body, b"abcd");
+
+        let (method, body) = split_request(b"GET / HTTP/1.1\r\n\r\n").unwrap().unwrap();
+        assert_eq!(method, "GET");
+        assert!(body.is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+//! Exchange health poller.
+//!
+//! Polls Polymarket's public status page and the CLOB root endpoint. When
+//! either reports trouble we want to be cancel-only *before* a burst of
+//! order timeouts tells us the same thing while we hold exposure.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::events::Event;
+
+const STATUS_URL: &str = "https://status.polymarket.com/api/v2/status.json";
+const CLOB_HEALTH_URL: &str = "https://clob.polymarket.com/";
+const POLL_SECS: u64 = 15;
+const TIMEOUT_SECS: u64 = 5;
+/// Consecutive failed CLOB health checks before calling it an outage
+const CLOB_FAILS_TO_DEGRADE: u32 = 3;
+
+/// Statuspage.io summary shape.
+#[derive(Deserialize)]
+struct StatusPage {
+    status: PageStatus,
+}
+
+#[derive(Deserialize)]
+struct PageStatus {
+    /// "none", "minor", "major" or "critical"
+    indicator: String,
+    description: String,
+}
+
+/// Decide whether the exchange is degraded.
+///
+/// # Arguments
+/// * `page` - (indicator, description) from the status page, if it answered
+/// * `clob_failures` - Consecutive failed CLOB health checks
+///
+/// # Returns
+/// A reason if we should stop quoting, None if healthy.
+fn assess(page: Option<(&str, &str)>, clob_failures: u32) -> Option<String> {
+    if clob_failures >= CLOB_FAILS_TO_DEGRADE {
+        return Some(format!("CLOB health check failed {} times", clob_failures));
+    }
+    match page {
+        // Minor incidents are often unrelated to trading (e.g. the website)
+        Some((indicator @ ("major" | "critical"), description)) => {
+            Some(format!("status page {}: {}", indicator, description))
+        }
+        _ => None,
+    }
+}
+
+/// Spawns a task that polls exchange health and sends an
+/// ExchangeStatus event whenever it changes.
+pub fn spawn(tx: mpsc::Sender<Event>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        let mut clob_failures = 0u32;
+        let mut last: Option<String> = None;
+
+        loop {
+            let page = match http.get(STATUS_URL).send().await {
+                Ok(resp) => resp.json::<StatusPage>().await.ok(),
+                Err(_) => None, // Status page down isn't itself an outage
+            };
+
+            let clob_ok = matches!(
+                http.get(CLOB_HEALTH_URL).send().await,
+                Ok(resp) if resp.status().is_success()
+            );
+            clob_failures = if clob_ok { 0 } else { clob_failures + 1 };
+
+            let degraded = assess(
+                page.as_ref().map(|p| (p.status.indicator.as_str(), p.status.description.as_str())),
+                clob_failures,
+            );
+            if degraded != last {
+                last = degraded.clone();
+                if tx.send(Event::ExchangeStatus { degraded }).await.is_err() {
+                    return;
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(POLL_SECS)).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assess() {
+        assert_eq!(assess(Some(("none", "All Systems Operational")), 0), None);
+        assert_eq!(assess(Some(("minor", "Website slow")), 0), None);
+        assert_eq!(assess(None, 2), None);
+
+        let major = assess(Some(("major", "Order matching delayed")), 0).unwrap();
+        assert!(major.contains("Order matching delayed"));
+        assert!(assess(None, CLOB_FAILS_TO_DEGRADE).unwrap().contains("CLOB"));
+    }
+
+    #[test]
+    fn test_parse_status_page() {
+        let json = r#"{"page":{"id":"x"},"status":{"indicator":"critical","description":"Major Outage"}}"#;
+        let page: StatusPage = serde_json::from_str(json).unwrap();
+        assert_eq!(page.status.indicator, "critical");
+        assert_eq!(page.status.description, "Major Outage");
+    }
+}
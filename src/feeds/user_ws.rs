@@ -1,5 +1,6 @@
 //! Polymarket User Channel WebSocket handler.
-//! Receives real-time fill notifications with actual execution prices.
+//! Receives real-time fill notifications with actual execution prices, and
+//! order placements and cancellations so the tracker follows the exchange.
 //!
 //! Mirrors the Python implementation in polybot/ingestion/user_ws.py
 
@@ -7,6 +8,8 @@ use futures_util::{SinkExt, StreamExt};
 use tokio::sync::mpsc;
 use tokio_tungstenite::connect_async;
 
+use super::{frames, ParseFailures};
+use crate::alerts;
 use crate::events::{Event, Side};
 
 const USER_WS_URL: &str = "wss://ws-subscriptions-clob.polymarket.com/ws/user";
@@ -30,7 +33,6 @@ struct AuthCredentials {
 /// Trade event from the user channel
 #[derive(serde::Deserialize, Debug)]
 struct TradeEvent {
-    event_type: Option<String>,
     status: Option<String>,
     trader_side: Option<String>,
     // For taker fills
@@ -53,7 +55,21 @@ struct MakerOrder {
     matched_amount: Option<String>,
 }
 
+/// Order lifecycle event from the user channel
+#[derive(serde::Deserialize, Debug)]
+struct OrderEvent {
+    // PLACEMENT, UPDATE (partial match) or CANCELLATION
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    id: Option<String>,
+    asset_id: Option<String>,
+    price: Option<String>,
+    original_size: Option<String>,
+    size_matched: Option<String>,
+}
+
 /// Configuration for the user WebSocket feed
+#[derive(Clone)]
 pub struct UserFeedConfig {
     pub api_key: String,
     pub api_secret: String,
@@ -72,7 +88,8 @@ impl UserFeed {
         Self { config }
     }
 
-    /// Spawns a task that connects and sends OrderFill events.
+    /// Spawns a task that connects and sends OrderFill, OrderPlaced and
+    /// OrderCancelled events.
     /// Returns a JoinHandle that can be aborted on market switch.
     pub fn spawn(self, tx: mpsc::Sender<Event>) -> tokio::task::JoinHandle<()> {
         tokio::spawn(async move {
@@ -106,12 +123,14 @@ impl UserFeed {
 
                         println!("[user_ws] Authenticated");
                         reconnect_delay = 1; // Reset on successful connect
+                        alerts::resolve("feed:user_ws");
 
                         // Process messages
+                        let mut failures = ParseFailures::default();
                         while let Some(msg) = read.next().await {
                             match msg {
                                 Ok(tungstenite::Message::Text(text)) => {
-                                    self.process_message(&text, &tx).await;
+                                    self.process_message(&text, &tx, &mut failures).await;
                                 }
                                 Err(e) => {
                                     println!("[user_ws] Error: {}", e);
@@ -126,6 +145,7 @@ impl UserFeed {
                     }
                 }
 
+                alerts::raise("feed:user_ws", "User fill feed down, reconnecting");
                 println!(
                     "[user_ws] Reconnecting in {} seconds...",
                     reconnect_delay
@@ -136,45 +156,55 @@ impl UserFeed {
         })
     }
 
-    async fn process_message(&self, text: &str, tx: &mpsc::Sender<Event>) {
-        // Try to parse as trade event
-        let Ok(data) = serde_json::from_str::<TradeEvent>(text) else {
-            // Not a trade event, ignore (could be heartbeat, order update, etc.)
-            return;
-        };
+    async fn process_message(&self, text: &str, tx: &mpsc::Sender<Event>, failures: &mut ParseFailures) {
+        match self.parse(text) {
+            Ok(events) => {
+                for event in events {
+                    let _ = tx.send(event).await;
+                }
+            }
+            Err(e) => failures.record("user_ws", &e, text),
+        }
+    }
 
-        // Only handle trade events (same as Python: event_type == "trade")
-        if data.event_type.as_deref() != Some("trade") {
-            return;
+    /// Events in one raw frame: our fills and order lifecycle. Other
+    /// messages give none. An error means the frame isn't in a shape we
+    /// know: malformed, or an order or trade event missing fields.
+    pub fn parse(&self, text: &str) -> anyhow::Result<Vec<Event>> {
+        let mut events = Vec::new();
+        for message in frames::<serde_json::Value>(text)? {
+            match message.get("event_type").and_then(|t| t.as_str()) {
+                Some("order") => events.extend(self.order_event(serde_json::from_value(message)?)?),
+                Some("trade") => events.extend(self.trade_events(serde_json::from_value(message)?)),
+                // Not a trade event, ignore (could be heartbeat, subscription ack, etc.)
+                _ => {}
+            }
         }
+        Ok(events)
+    }
 
+    fn trade_events(&self, data: TradeEvent) -> Vec<Event> {
         // Only handle MATCHED status (same as Python)
         if data.status.as_deref() != Some("MATCHED") {
-            return;
+            return Vec::new();
         }
 
         let trader_side = data.trader_side.as_deref().unwrap_or("");
 
         match trader_side {
-            "TAKER" => {
-                // My order crossed the spread and filled as taker
-                self.handle_taker_fill(&data, tx).await;
-            }
-            "MAKER" => {
-                // My resting orders got matched
-                self.handle_maker_fills(&data, tx).await;
-            }
-            _ => {}
+            // My order crossed the spread and filled as taker
+            "TAKER" => self.taker_fill(&data).into_iter().collect(),
+            // My resting orders got matched
+            "MAKER" => self.maker_fills(&data),
+            _ => Vec::new(),
         }
     }
 
-    async fn handle_taker_fill(&self, data: &TradeEvent, tx: &mpsc::Sender<Event>) {
+    fn taker_fill(&self, data: &TradeEvent) -> Option<Event> {
         let order_id = data.taker_order_id.clone().unwrap_or_default();
         let asset_id = data.asset_id.as_deref().unwrap_or("");
 
-        let Some(side) = self.asset_to_side(asset_id) else {
-            return;
-        };
+        let side = self.asset_to_side(asset_id)?;
 
         let price = data
             .price
@@ -199,23 +229,22 @@ impl UserFeed {
             &order_id[..order_id.len().min(20)]
         );
 
-        let _ = tx
-            .send(Event::OrderFill {
-                order_id,
-                side,
-                price: price_ticks,
-                size,
-                is_maker: false,
-            })
-            .await;
+        Some(Event::OrderFill {
+            order_id,
+            side,
+            price: price_ticks,
+            size,
+            is_maker: false,
+        })
     }
 
-    async fn handle_maker_fills(&self, data: &TradeEvent, tx: &mpsc::Sender<Event>) {
+    fn maker_fills(&self, data: &TradeEvent) -> Vec<Event> {
         let Some(maker_orders) = &data.maker_orders else {
-            return;
+            return Vec::new();
         };
 
         let maker_address_lower = self.config.maker_address.to_lowercase();
+        let mut events = Vec::new();
 
         for maker in maker_orders {
             // Only process fills where we are the maker (same as Python)
@@ -254,15 +283,50 @@ impl UserFeed {
                 &order_id[..order_id.len().min(20)]
             );
 
-            let _ = tx
-                .send(Event::OrderFill {
-                    order_id,
-                    side,
-                    price: price_ticks,
+            events.push(Event::OrderFill {
+                order_id,
+                side,
+                price: price_ticks,
+                size,
+                is_maker: true,
+            });
+        }
+        events
+    }
+
+    /// Placement or cancellation of one of our orders.
+    /// UPDATEs are skipped: the matching trade event carries the fill.
+    fn order_event(&self, data: OrderEvent) -> anyhow::Result<Option<Event>> {
+        let Some(order_id) = data.id else {
+            anyhow::bail!("order event without an id");
+        };
+        let Some(side) = self.asset_to_side(data.asset_id.as_deref().unwrap_or("")) else {
+            return Ok(None);
+        };
+        let parse = |v: &Option<String>| v.as_ref().and_then(|s| s.parse::<f64>().ok()).unwrap_or(0.0);
+
+        match data.kind.as_deref() {
+            Some("PLACEMENT") => {
+                let price_ticks = (parse(&data.price) * 1000.0).round() as u16;
+                let size = parse(&data.original_size) - parse(&data.size_matched);
+                println!(
+                    "[user_ws] PLACED: {} {:.1} @ {} ticks (order {})",
+                    if side == Side::Yes { "YES" } else { "NO" },
                     size,
-                    is_maker: true,
-                })
-                .await;
+                    price_ticks,
+                    &order_id[..order_id.len().min(20)]
+                );
+                Ok(Some(Event::OrderPlaced { order_id, side, price: price_ticks, size }))
+            }
+            Some("CANCELLATION") => {
+                println!(
+                    "[user_ws] CANCELLED: {} (order {})",
+                    if side == Side::Yes { "YES" } else { "NO" },
+                    &order_id[..order_id.len().min(20)]
+                );
+                Ok(Some(Event::OrderCancelled { order_id, side }))
+            }
+            _ => Ok(None),
         }
     }
 
@@ -277,3 +341,44 @@ impl UserFeed {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed() -> UserFeed {
+        UserFeed::new(UserFeedConfig {
+            api_key: String::new(),
+            api_secret: String::new(),
+            api_passphrase: String::new(),
+            maker_address: "0xabc".to_string(),
+            yes_token: "111".to_string(),
+            no_token: "222".to_string(),
+        })
+    }
+
+    #[test]
+    fn test_order_lifecycle_events() {
+        let f = feed();
+        let placed = r#"{"event_type":"order","type":"PLACEMENT","id":"0x1","asset_id":"111",
+            "price":"0.48","original_size":"10","size_matched":"2","side":"BUY"}"#;
+        match f.parse(placed).unwrap().as_slice() {
+            [Event::OrderPlaced { order_id, side, price, size }] => {
+                assert_eq!((order_id.as_str(), *side, *price, *size), ("0x1", Side::Yes, 480, 8.0));
+            }
+            _ => panic!("expected OrderPlaced"),
+        }
+
+        let cancelled = r#"{"event_type":"order","type":"CANCELLATION","id":"0x2","asset_id":"222"}"#;
+        match f.parse(cancelled).unwrap().as_slice() {
+            [Event::OrderCancelled { order_id, side }] => assert_eq!((order_id.as_str(), *side), ("0x2", Side::No)),
+            _ => panic!("expected OrderCancelled"),
+        }
+
+        // Partial matches come through trade events; other markets and trades are ignored
+        assert!(f.parse(r#"{"event_type":"order","type":"UPDATE","id":"0x1","asset_id":"111"}"#).unwrap().is_empty());
+        assert!(f.parse(r#"{"event_type":"order","type":"PLACEMENT","id":"0x3","asset_id":"999"}"#).unwrap().is_empty());
+        assert!(f.parse(r#"{"event_type":"trade","status":"MATCHED","asset_id":"111"}"#).unwrap().is_empty());
+        assert!(f.parse(r#"{"event_type":"order","type":"PLACEMENT","asset_id":"111"}"#).is_err());
+    }
+}
@@ -0,0 +1,85 @@
+//! Large-taker watch over the Data API.
+//!
+//! Polls the market's recent public trades and flags when one wallet has
+//! been taking heavily in one direction. The Data API lags the book by a
+//! few seconds, so this is a slow toxicity input, not a fast pull.
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::api::data::{fetch_trades, FlowSummary};
+use crate::events::{Event, Side};
+
+const POLL_SECS: u64 = 15;
+const TIMEOUT_SECS: u64 = 5;
+/// Only look at trades this recent (seconds)
+const LOOKBACK_SECS: i64 = 60;
+/// A single taker with at least this many shares in the lookback...
+const WHALE_MIN_VOLUME: f64 = 200.0;
+/// ...all (or nearly all) in one direction
+const WHALE_MIN_PRESSURE: f64 = 0.8;
+
+/// The side a whale is running over, if any.
+fn assess(summary: &FlowSummary) -> Option<(Side, String)> {
+    let whale = summary.top_takers.first()?;
+    if whale.volume < WHALE_MIN_VOLUME || whale.pressure.abs() < WHALE_MIN_PRESSURE {
+        return None;
+    }
+    let side = whale.pressured_side();
+    Some((side, format!("{} took {:.0} shares ({:+.2})", whale.who, whale.volume, whale.pressure)))
+}
+
+/// Spawns a task that watches one market and sends a WhaleFlow event
+/// whenever the pressured side changes. Abort it on market switch.
+pub fn spawn(condition_id: String, our_wallet: Option<String>, tx: mpsc::Sender<Event>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default();
+        let mut last: Option<Side> = None;
+
+        loop {
+            if let Ok(trades) = fetch_trades(&http, &condition_id, 500).await {
+                let cutoff = chrono::Utc::now().timestamp() - LOOKBACK_SECS;
+                let recent: Vec<_> = trades.into_iter().filter(|t| t.timestamp >= cutoff).collect();
+                let summary = FlowSummary::from_trades(&recent, our_wallet.as_deref(), 1);
+                let flagged = assess(&summary);
+                let side = flagged.as_ref().map(|(s, _)| *s);
+                if side != last {
+                    last = side;
+                    let detail = flagged.map(|(_, d)| d);
+                    if tx.send(Event::WhaleFlow { side, detail }).await.is_err() {
+                        return;
+                    }
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(POLL_SECS)).await;
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::data::Taker;
+
+    fn summary(volume: f64, pressure: f64) -> FlowSummary {
+        FlowSummary {
+            total_volume: volume,
+            top_takers: vec![Taker { who: "whale".to_string(), volume, pressure }],
+        }
+    }
+
+    #[test]
+    fn test_assess() {
+        assert_eq!(assess(&FlowSummary::default()), None);
+        assert_eq!(assess(&summary(100.0, 1.0)), None);
+        assert_eq!(assess(&summary(500.0, 0.5)), None);
+        assert_eq!(assess(&summary(500.0, -0.9)).map(|(s, _)| s), Some(Side::Yes));
+        assert_eq!(assess(&summary(500.0, 1.0)).map(|(s, _)| s), Some(Side::No));
+    }
+}
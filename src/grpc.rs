@@ -0,0 +1,146 @@
+//! gRPC front end for the control surface (feature `grpc`).
+//!
+//! Control maps one-to-one onto `ControlCommand`; Stream forwards engine
+//! events from `ControlHandle::subscribe`. Definitions are in
+//! proto/polybot.proto.
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+
+use tokio::sync::broadcast::error::RecvError;
+use tonic::{Request, Response, Status};
+
+use crate::control::{ControlCommand, ControlHandle, EngineEvent};
+use crate::events::Side;
+
+pub mod pb {
+    tonic::include_proto!("polybot.v1");
+}
+
+use pb::control_server::{Control, ControlServer};
+use pb::stream_server::{Stream, StreamServer};
+
+/// Serve Control and Stream on `addr` until the task is dropped.
+pub async fn serve(addr: SocketAddr, handle: ControlHandle) -> anyhow::Result<()> {
+    tonic::transport::Server::builder()
+        .add_service(ControlServer::new(ControlService { handle: handle.clone() }))
+        .add_service(StreamServer::new(StreamService { handle }))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+struct ControlService {
+    handle: ControlHandle,
+}
+
+impl ControlService {
+    async fn run(&self, command: ControlCommand) -> Result<Response<pb::CommandReply>, Status> {
+        match self.handle.send(command).await {
+            Ok(message) => Ok(Response::new(pb::CommandReply { message })),
+            Err(e) => Err(Status::failed_precondition(e)),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Control for ControlService {
+    async fn pause(&self, req: Request<pb::PauseRequest>) -> Result<Response<pb::CommandReply>, Status> {
+        let reason = req.into_inner().reason;
+        self.run(ControlCommand::Pause { reason }).await
+    }
+
+    async fn resume(&self, _req: Request<pb::ResumeRequest>) -> Result<Response<pb::CommandReply>, Status> {
+        self.run(ControlCommand::Resume).await
+    }
+
+    async fn set_param(&self, req: Request<pb::SetParamRequest>) -> Result<Response<pb::CommandReply>, Status> {
+        let pb::SetParamRequest { name, value } = req.into_inner();
+        self.run(ControlCommand::SetParam { name, value }).await
+    }
+
+    async fn approve(&self, req: Request<pb::BatchRequest>) -> Result<Response<pb::CommandReply>, Status> {
+        self.run(ControlCommand::Approve { id: req.into_inner().id }).await
+    }
+
+    async fn reject(&self, req: Request<pb::BatchRequest>) -> Result<Response<pb::CommandReply>, Status> {
+        self.run(ControlCommand::Reject { id: req.into_inner().id }).await
+    }
+}
+
+struct StreamService {
+    handle: ControlHandle,
+}
+
+type EventStream = Pin<Box<dyn futures_util::Stream<Item = Result<pb::EngineEvent, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Stream for StreamService {
+    type SubscribeStream = EventStream;
+
+    async fn subscribe(&self, req: Request<pb::SubscribeRequest>) -> Result<Response<EventStream>, Status> {
+        let filter = req.into_inner();
+        let everything = !filter.fills && !filter.halts && !filter.status && !filter.recovery && !filter.pnl && !filter.batches;
+        let wanted = move |event: &EngineEvent| {
+            everything
+                || match event {
+                    EngineEvent::Fill { .. } => filter.fills,
+                    EngineEvent::Halt { .. } => filter.halts,
+                    EngineEvent::Recovery { .. } => filter.recovery,
+                    EngineEvent::Pnl(_) => filter.pnl,
+                    EngineEvent::Batch(_) => filter.batches,
+                    EngineEvent::Status(_) => filter.status,
+                }
+        };
+
+        let rx = self.handle.subscribe();
+        let stream = futures_util::stream::unfold(rx, move |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) if wanted(&event) => return Some((Ok(to_pb(event)), rx)),
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+fn to_pb(event: EngineEvent) -> pb::EngineEvent {
+    use pb::engine_event::Event;
+    let event = match event {
+        EngineEvent::Fill { side, price, size, order_id } => Event::Fill(pb::Fill {
+            side: if side == Side::Yes { "YES" } else { "NO" }.to_string(),
+            price: price as u32,
+            size,
+            order_id,
+        }),
+        EngineEvent::Halt { reason } => Event::Halt(pb::Halt { reason }),
+        EngineEvent::Recovery { stage, size_fraction } => Event::Recovery(pb::Recovery { stage, size_fraction }),
+        EngineEvent::Pnl(p) => Event::Pnl(pb::Pnl {
+            market: p.market,
+            realized: p.realized_usd,
+            unrealized: p.unrealized_usd,
+            session_realized: p.session_realized_usd,
+            session_unrealized: p.session_unrealized_usd,
+        }),
+        EngineEvent::Batch(b) => Event::Batch(pb::Batch {
+            id: b.id,
+            actions: b.actions,
+            shares: b.shares,
+            expires_in_secs: b.expires_in_secs,
+        }),
+        EngineEvent::Status(s) => Event::Status(pb::Status {
+            market: s.market,
+            paused: s.paused,
+            time_left: s.time_left,
+            yes_target: s.yes_target as u32,
+            no_target: s.no_target as u32,
+            qty_yes: s.qty_yes,
+            qty_no: s.qty_no,
+            resting_orders: s.resting_orders as u32,
+        }),
+    };
+    pb::EngineEvent { event: Some(event) }
+}
@@ -0,0 +1,174 @@
+//! Quote/fill/trade heatmap: where we sat vs where the action was.
+//!
+//! Counts, per time bucket and price, how often we had an order resting
+//! there, how much we got filled there, and how many trades printed there.
+//! Written as long-format CSV at session end (one row per non-empty cell)
+//! so it can be pivoted into a time × price matrix and plotted.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::events::Side;
+
+/// Default time bucket width (seconds)
+pub const HEATMAP_BUCKET_SECS: f64 = 10.0;
+
+/// Counts for one (bucket, side, price) cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Cell {
+    /// Quoting ticks with one of our orders resting here
+    pub quoted: u32,
+    /// Our fills here
+    pub fills: u32,
+    /// Our filled size here
+    pub fill_size: f64,
+    /// Trades printed here (anyone's)
+    pub trades: u32,
+}
+
+/// Accumulates heatmap cells over a session.
+pub struct Heatmap {
+    path: PathBuf,
+    start: f64,
+    bucket_secs: f64,
+    cells: BTreeMap<(u32, Side, u16), Cell>,
+    enabled: bool,
+}
+
+impl Heatmap {
+    /// New heatmap under `logs/` named after the current time and `suffix`.
+    pub fn new(start: f64, bucket_secs: f64, suffix: &str) -> Self {
+        let ts = chrono::Utc::now().timestamp();
+        Self::at(format!("logs/polybot_{}{}.heatmap.csv", ts, suffix), start, bucket_secs)
+    }
+
+    /// New heatmap written to a specific path.
+    pub fn at(path: impl Into<PathBuf>, start: f64, bucket_secs: f64) -> Self {
+        Self {
+            path: path.into(),
+            start,
+            bucket_secs,
+            cells: BTreeMap::new(),
+            enabled: true,
+        }
+    }
+
+    /// A heatmap that records nothing (`[subsystems] heatmap = false`).
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::at(PathBuf::new(), 0.0, HEATMAP_BUCKET_SECS)
+        }
+    }
+
+    fn cell(&mut self, now: f64, side: Side, price: u16) -> &mut Cell {
+        let bucket = ((now - self.start).max(0.0) / self.bucket_secs) as u32;
+        self.cells.entry((bucket, side, price)).or_default()
+    }
+
+    /// Record the prices we have resting this tick.
+    pub fn record_quotes(&mut self, now: f64, side: Side, prices: &[u16]) {
+        if !self.enabled {
+            return;
+        }
+        for &price in prices {
+            self.cell(now, side, price).quoted += 1;
+        }
+    }
+
+    /// Record one of our fills.
+    pub fn record_fill(&mut self, now: f64, side: Side, price: u16, size: f64) {
+        if !self.enabled {
+            return;
+        }
+        let cell = self.cell(now, side, price);
+        cell.fills += 1;
+        cell.fill_size += size;
+    }
+
+    /// Record a trade print from the market feed.
+    pub fn record_trade(&mut self, now: f64, side: Side, price: u16) {
+        if !self.enabled {
+            return;
+        }
+        self.cell(now, side, price).trades += 1;
+    }
+
+    /// Get a cell (for tests and quick checks).
+    pub fn get(&self, bucket: u32, side: Side, price: u16) -> Option<&Cell> {
+        self.cells.get(&(bucket, side, price))
+    }
+
+    /// Write the CSV to the heatmap's path. Does nothing if empty.
+    pub fn write(&self) -> anyhow::Result<Option<&Path>> {
+        if self.cells.is_empty() {
+            return Ok(None);
+        }
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = File::create(&self.path)
+            .with_context(|| format!("creating heatmap {}", self.path.display()))?;
+        let mut w = BufWriter::new(file);
+        writeln!(w, "bucket_start,side,price,quoted,fills,fill_size,trades")?;
+        for (&(bucket, side, price), cell) in &self.cells {
+            writeln!(
+                w,
+                "{:.0},{},{},{},{},{:.1},{}",
+                self.start + bucket as f64 * self.bucket_secs,
+                if side == Side::Yes { "YES" } else { "NO" },
+                price,
+                cell.quoted,
+                cell.fills,
+                cell.fill_size,
+                cell.trades
+            )?;
+        }
+        w.flush()?;
+        Ok(Some(&self.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buckets_and_counts() {
+        let mut map = Heatmap::at("unused.csv", 1000.0, 10.0);
+        map.record_quotes(1001.0, Side::Yes, &[480, 470]);
+        map.record_quotes(1002.0, Side::Yes, &[480]);
+        map.record_fill(1005.0, Side::Yes, 480, 5.0);
+        map.record_trade(1005.0, Side::Yes, 480);
+        map.record_trade(1012.0, Side::No, 500);
+
+        let cell = map.get(0, Side::Yes, 480).unwrap();
+        assert_eq!(cell.quoted, 2);
+        assert_eq!(cell.fills, 1);
+        assert_eq!(cell.trades, 1);
+        assert_eq!(map.get(0, Side::Yes, 470).unwrap().quoted, 1);
+        assert_eq!(map.get(1, Side::No, 500).unwrap().trades, 1);
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let path = std::env::temp_dir().join(format!("heatmap_test_{}.csv", std::process::id()));
+        let mut map = Heatmap::at(&path, 1000.0, 10.0);
+        assert!(map.write().unwrap().is_none());
+
+        map.record_quotes(1001.0, Side::Yes, &[480]);
+        map.record_fill(1011.0, Side::No, 500, 5.0);
+        map.write().unwrap();
+
+        let csv = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "1000,YES,480,1,0,0.0,0");
+        assert_eq!(lines[2], "1010,NO,500,0,1,5.0,0");
+        let _ = fs::remove_file(&path);
+    }
+}
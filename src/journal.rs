@@ -0,0 +1,387 @@
+//! Session journal: compact state snapshots for replay and debugging.
+//!
+//! Once per second the main loop records what the bot saw (book, signals)
+//! and what it had out (ladder, position). Snapshots are written as JSON
+//! lines so a session can be scrubbed frame-by-frame after the fact.
+//!
+//! Writing needs the `journal` feature (on by default); reading doesn't.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::events::Side;
+use crate::run_info::RunInfo;
+use crate::state::{Book, CandleSignal, Position, RestingLadder};
+
+/// Default interval between snapshots (seconds)
+pub const SNAPSHOT_INTERVAL_SECS: f64 = 1.0;
+
+/// Best bid/ask for both sides, in ticks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookSnapshot {
+    pub yes_bid: Option<u16>,
+    pub yes_ask: Option<u16>,
+    pub no_bid: Option<u16>,
+    pub no_ask: Option<u16>,
+}
+
+/// One resting rung: (price in ticks, remaining size).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rung {
+    pub price: u16,
+    pub size: f64,
+}
+
+/// Resting orders on both sides, best price first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LadderSnapshot {
+    pub yes: Vec<Rung>,
+    pub no: Vec<Rung>,
+}
+
+/// Inventory and cost basis (cost in ticks).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PositionSnapshot {
+    pub qty_yes: f64,
+    pub qty_no: f64,
+    pub cost_yes: f64,
+    pub cost_no: f64,
+}
+
+impl From<&Position> for PositionSnapshot {
+    fn from(position: &Position) -> Self {
+        Self {
+            qty_yes: to_f64(position.qty_yes),
+            qty_no: to_f64(position.qty_no),
+            cost_yes: to_f64(position.cost_yes),
+            cost_no: to_f64(position.cost_no),
+        }
+    }
+}
+
+impl PositionSnapshot {
+    /// Back to a `Position` (crash recovery).
+    pub fn to_position(&self) -> Position {
+        let dec = |v: f64| rust_decimal::Decimal::try_from(v).unwrap_or_default();
+        Position {
+            qty_yes: dec(self.qty_yes),
+            qty_no: dec(self.qty_no),
+            cost_yes: dec(self.cost_yes),
+            cost_no: dec(self.cost_no),
+        }
+    }
+}
+
+/// Strategy inputs and outputs at the time of the snapshot.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Signals {
+    pub btc: f64,
+    pub mid: f64,
+    pub var: f64,
+    pub k: f64,
+    pub yes_target: u16,
+    pub no_target: u16,
+    /// Halt reason if not quoting this tick
+    pub halt: Option<String>,
+    /// Latest BTC candle per resolution, with its momentum and volatility
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub candles: Vec<CandleSignal>,
+}
+
+/// A single frame of session state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub ts: f64,
+    pub market: String,
+    pub time_left: f64,
+    pub book: BookSnapshot,
+    pub ladder: LadderSnapshot,
+    pub position: PositionSnapshot,
+    pub signals: Signals,
+}
+
+impl Snapshot {
+    /// Capture the current state.
+    pub fn capture(
+        ts: f64,
+        market: &str,
+        time_left: f64,
+        book: &Book,
+        orders: &impl RestingLadder,
+        position: &Position,
+        signals: Signals,
+    ) -> Self {
+        let ladder_side = |side: Side| {
+            let mut prices = orders.prices(side);
+            prices.sort_unstable_by(|a, b| b.cmp(a));
+            prices
+                .into_iter()
+                .map(|price| Rung {
+                    price,
+                    size: to_f64(orders.total_size_at_price(side, price)),
+                })
+                .collect()
+        };
+
+        Self {
+            ts,
+            market: market.to_string(),
+            time_left,
+            book: BookSnapshot {
+                yes_bid: book.yes_bid,
+                yes_ask: book.yes_ask,
+                no_bid: book.no_bid,
+                no_ask: book.no_ask,
+            },
+            ladder: LadderSnapshot {
+                yes: ladder_side(Side::Yes),
+                no: ladder_side(Side::No),
+            },
+            position: PositionSnapshot::from(position),
+            signals,
+        }
+    }
+}
+
+/// First line of a journal: the run that wrote it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Header {
+    run: RunInfo,
+}
+
+/// Appends snapshots to a JSON-lines file, at most once per interval.
+/// A disabled journal keeps the cadence but writes nothing.
+pub struct Journal {
+    writer: Option<BufWriter<File>>,
+    interval_secs: f64,
+    last_snapshot: f64,
+}
+
+impl Journal {
+    /// Open a new journal under `logs/` named after the current time and
+    /// `suffix`.
+    #[cfg(feature = "journal")]
+    pub fn new(suffix: &str) -> anyhow::Result<Self> {
+        std::fs::create_dir_all("logs")?;
+        let ts = chrono::Utc::now().timestamp();
+        let path = format!("logs/polybot_{}{}.journal.jsonl", ts, suffix);
+        let journal = Self::open(&path, SNAPSHOT_INTERVAL_SECS)?;
+        println!("[JOURNAL] Writing to {}", path);
+        Ok(journal)
+    }
+
+    /// Built without the `journal` feature: a disabled journal.
+    #[cfg(not(feature = "journal"))]
+    pub fn new(_suffix: &str) -> anyhow::Result<Self> {
+        Ok(Self::disabled())
+    }
+
+    /// A journal that writes nothing (`[subsystems] journal = false`).
+    pub fn disabled() -> Self {
+        Self {
+            writer: None,
+            interval_secs: SNAPSHOT_INTERVAL_SECS,
+            last_snapshot: 0.0,
+        }
+    }
+
+    /// Whether snapshots go anywhere.
+    pub fn is_enabled(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// Open (or append to) a journal at a specific path.
+    pub fn open(path: impl AsRef<Path>, interval_secs: f64) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())
+            .with_context(|| format!("opening journal {}", path.as_ref().display()))?;
+        Ok(Self {
+            writer: Some(BufWriter::new(file)),
+            interval_secs,
+            last_snapshot: 0.0,
+        })
+    }
+
+    /// Whether a snapshot is due at `now`.
+    pub fn is_due(&self, now: f64) -> bool {
+        now - self.last_snapshot >= self.interval_secs
+    }
+
+    /// Record a snapshot if one is due. `capture` is only called when it is,
+    /// so the per-tick cost is a single comparison. It's still called when
+    /// disabled: the event sink publishes snapshots from it.
+    pub fn record_if_due(&mut self, now: f64, capture: impl FnOnce() -> Snapshot) {
+        if !self.is_due(now) {
+            return;
+        }
+        self.last_snapshot = now;
+        if let Err(e) = self.write(&capture()) {
+            println!("[JOURNAL] Write failed: {}", e);
+        }
+    }
+
+    /// Write the run header (before the first snapshot).
+    pub fn write_header(&mut self, run: &RunInfo) -> anyhow::Result<()> {
+        let Some(writer) = self.writer.as_mut() else {
+            return Ok(());
+        };
+        serde_json::to_writer(&mut *writer, &Header { run: run.clone() })?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Write a snapshot unconditionally.
+    pub fn write(&mut self, snapshot: &Snapshot) -> anyhow::Result<()> {
+        let Some(writer) = self.writer.as_mut() else {
+            return Ok(());
+        };
+        serde_json::to_writer(&mut *writer, snapshot)?;
+        writer.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Flush buffered snapshots to disk.
+    pub fn flush(&mut self) {
+        if let Some(writer) = self.writer.as_mut() {
+            writer.flush().ok();
+        }
+    }
+}
+
+/// Read all snapshots from a journal file, skipping unparseable lines
+/// (e.g. a truncated final line after a crash) and the run header.
+pub fn read_journal(path: impl AsRef<Path>) -> anyhow::Result<Vec<Snapshot>> {
+    let file = File::open(path.as_ref())
+        .with_context(|| format!("opening journal {}", path.as_ref().display()))?;
+    let mut snapshots = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(snapshot) = serde_json::from_str(&line) {
+            snapshots.push(snapshot);
+        }
+    }
+    Ok(snapshots)
+}
+
+/// The run that wrote a journal, from its header. None for journals
+/// written before headers were added.
+pub fn read_run_info(path: impl AsRef<Path>) -> anyhow::Result<Option<RunInfo>> {
+    let file = File::open(path.as_ref())
+        .with_context(|| format!("opening journal {}", path.as_ref().display()))?;
+    let Some(first) = BufReader::new(file).lines().next().transpose()? else {
+        return Ok(None);
+    };
+    Ok(serde_json::from_str::<Header>(&first).ok().map(|h| h.run))
+}
+
+/// Index of the last snapshot at or before `ts` (0 if `ts` is before the first).
+pub fn seek_index(snapshots: &[Snapshot], ts: f64) -> usize {
+    snapshots
+        .partition_point(|s| s.ts <= ts)
+        .saturating_sub(1)
+}
+
+fn to_f64(d: rust_decimal::Decimal) -> f64 {
+    d.to_string().parse().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::OrderTracker;
+    use rust_decimal_macros::dec;
+    use std::fs;
+
+    fn sample(ts: f64) -> Snapshot {
+        let mut book = Book::default();
+        book.update(Side::Yes, 480, 490, 0);
+        book.update(Side::No, 500, 510, 0);
+
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "y1".into(), 470, dec!(5));
+        orders.add(Side::Yes, "y2".into(), 460, dec!(5));
+        orders.add(Side::No, "n1".into(), 490, dec!(5));
+
+        let mut position = Position::default();
+        position.apply_fill(Side::Yes, 470, dec!(5));
+
+        Snapshot::capture(
+            ts,
+            "btc-updown-15m-0",
+            600.0,
+            &book,
+            &orders,
+            &position,
+            Signals {
+                mid: 0.485,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_capture() {
+        let snap = sample(1.0);
+        assert_eq!(snap.book.yes_ask, Some(490));
+        assert_eq!(snap.ladder.yes.len(), 2);
+        assert_eq!(snap.ladder.yes[0].price, 470); // Best first
+        assert_eq!(snap.ladder.no[0].size, 5.0);
+        assert_eq!(snap.position.qty_yes, 5.0);
+        assert_eq!(snap.position.cost_yes, 2350.0);
+    }
+
+    #[test]
+    fn test_roundtrip_and_throttle() {
+        let path = std::env::temp_dir().join(format!(
+            "polybot_journal_test_{}.jsonl",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+
+        let run = RunInfo::new(None, &crate::strategy::StrategyConfig::default());
+        let mut journal = Journal::open(&path, 1.0).unwrap();
+        journal.write_header(&run).unwrap();
+        journal.record_if_due(100.0, || sample(100.0));
+        journal.record_if_due(100.5, || sample(100.5)); // Throttled
+        journal.record_if_due(101.0, || sample(101.0));
+        journal.flush();
+
+        let snaps = read_journal(&path).unwrap();
+        assert_eq!(snaps.len(), 2);
+        assert_eq!(snaps[0], sample(100.0));
+        assert_eq!(snaps[1].ts, 101.0);
+        assert_eq!(read_run_info(&path).unwrap(), Some(run));
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_disabled_keeps_cadence() {
+        let mut journal = Journal::disabled();
+        assert!(!journal.is_enabled());
+        let mut captured = 0;
+        journal.record_if_due(100.0, || { captured += 1; sample(100.0) });
+        journal.record_if_due(100.5, || { captured += 1; sample(100.5) });
+        assert_eq!(captured, 1);
+        journal.flush();
+    }
+
+    #[test]
+    fn test_seek_index() {
+        let snaps: Vec<Snapshot> = [10.0, 11.0, 12.0, 13.0].iter().map(|&t| sample(t)).collect();
+        assert_eq!(seek_index(&snaps, 5.0), 0);
+        assert_eq!(seek_index(&snaps, 11.0), 1);
+        assert_eq!(seek_index(&snaps, 12.5), 2);
+        assert_eq!(seek_index(&snaps, 99.0), 3);
+        assert_eq!(seek_index(&[], 1.0), 0);
+    }
+}
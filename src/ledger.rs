@@ -0,0 +1,325 @@
+//! State ledger: every fill, placement and cancel, appended as it happens,
+//! so position and P&L survive a restart and can be replayed for audit.
+//!
+//! Unlike the session journal (1s frames in a new file per run), the ledger
+//! is one JSON-lines file across runs. Each entry is flushed when written.
+//! A snapshot of the position and session P&L goes in every
+//! `SNAPSHOT_INTERVAL_SECS` and at each rollover, so recovery starts from
+//! the last one and replays only the fills after it. A line torn by a
+//! crash is skipped.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use anyhow::Context;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::events::Side;
+use crate::journal::PositionSnapshot;
+use crate::state::{OrderTracker, Position};
+
+/// Ledger for live trading
+pub const LEDGER_PATH: &str = "logs/state.jsonl";
+/// Ledger for paper trading, kept apart so simulated fills never restore
+/// into a live run
+pub const PAPER_LEDGER_PATH: &str = "logs/state_paper.jsonl";
+
+/// Ledger of one engine: `suffix` keeps a concurrent engine's ledger
+/// apart (see `engine::EngineSpec::suffix`).
+pub fn path(paper: bool, suffix: &str) -> String {
+    let base = if paper { PAPER_LEDGER_PATH } else { LEDGER_PATH };
+    match base.strip_suffix(".jsonl") {
+        Some(stem) if !suffix.is_empty() => format!("{}{}.jsonl", stem, suffix),
+        _ => base.to_string(),
+    }
+}
+
+/// Interval between snapshots (seconds)
+pub const SNAPSHOT_INTERVAL_SECS: f64 = 30.0;
+
+/// Session P&L carries over a restart only if the ledger was written this
+/// recently (seconds); an older one belongs to a finished session
+pub const RESUME_SECS: f64 = 900.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Entry {
+    /// An order started resting (size in shares)
+    Place { ts: f64, market: String, order_id: String, side: Side, price: u16, size: f64 },
+    /// An order stopped resting with `remaining` shares unfilled
+    Cancel { ts: f64, market: String, order_id: String, remaining: f64 },
+    Fill { ts: f64, market: String, order_id: String, side: Side, price: u16, size: f64 },
+    /// Position and session realized P&L at `ts`
+    Snapshot { ts: f64, market: String, position: PositionSnapshot, session_realized_usd: f64 },
+}
+
+impl Entry {
+    pub fn ts(&self) -> f64 {
+        match self {
+            Entry::Place { ts, .. } | Entry::Cancel { ts, .. } | Entry::Fill { ts, .. } | Entry::Snapshot { ts, .. } => *ts,
+        }
+    }
+}
+
+/// A resting order as last written: (side, price, remaining).
+type Live = (Side, u16, Decimal);
+
+/// Appends entries to the ledger. A disabled ledger (dry runs) writes
+/// nothing.
+pub struct Ledger {
+    file: Option<File>,
+    /// Orders written as placed and not yet cancelled or filled
+    live: HashMap<String, Live>,
+    last_snapshot: f64,
+}
+
+impl Ledger {
+    /// Open (or append to) the ledger at `path`.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        if let Some(dir) = path.as_ref().parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.as_ref())
+            .with_context(|| format!("opening ledger {}", path.as_ref().display()))?;
+        Ok(Self { file: Some(file), live: HashMap::new(), last_snapshot: 0.0 })
+    }
+
+    pub fn disabled() -> Self {
+        Self { file: None, live: HashMap::new(), last_snapshot: 0.0 }
+    }
+
+    fn append(&mut self, entry: &Entry) {
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        let written = serde_json::to_string(entry)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| Ok(writeln!(file, "{}", line)?));
+        if let Err(e) = written {
+            println!("[LEDGER] Write failed: {}", e);
+        }
+    }
+
+    pub fn fill(&mut self, ts: f64, market: &str, order_id: &str, side: Side, price: u16, size: Decimal) {
+        if let Some((_, _, remaining)) = self.live.get_mut(order_id) {
+            *remaining -= size;
+        }
+        self.append(&Entry::Fill {
+            ts,
+            market: market.to_string(),
+            order_id: order_id.to_string(),
+            side,
+            price,
+            size: to_f64(size),
+        });
+    }
+
+    /// Write placements and cancels since the last sync, by comparing what's
+    /// resting now with what was last written. An order that left fully
+    /// filled gets no cancel.
+    pub fn sync(&mut self, ts: f64, market: &str, orders: &OrderTracker) {
+        let mut resting: HashMap<String, Live> = HashMap::new();
+        for side in [Side::Yes, Side::No] {
+            for order in orders.all_orders(side) {
+                resting.insert(order.order_id.clone(), (side, order.price, order.remaining_size));
+            }
+        }
+
+        let mut gone: Vec<(String, Decimal)> = self
+            .live
+            .iter()
+            .filter(|(id, _)| !resting.contains_key(*id))
+            .map(|(id, (_, _, remaining))| (id.clone(), *remaining))
+            .collect();
+        gone.sort_by(|a, b| a.0.cmp(&b.0));
+        for (order_id, remaining) in gone {
+            self.live.remove(&order_id);
+            if remaining > Decimal::ZERO {
+                self.append(&Entry::Cancel { ts, market: market.to_string(), order_id, remaining: to_f64(remaining) });
+            }
+        }
+
+        let mut new: Vec<(String, Live)> = resting.into_iter().filter(|(id, _)| !self.live.contains_key(id)).collect();
+        new.sort_by(|a, b| a.0.cmp(&b.0));
+        for (order_id, (side, price, size)) in new {
+            self.append(&Entry::Place {
+                ts,
+                market: market.to_string(),
+                order_id: order_id.clone(),
+                side,
+                price,
+                size: to_f64(size),
+            });
+            self.live.insert(order_id, (side, price, size));
+        }
+    }
+
+    pub fn snapshot(&mut self, ts: f64, market: &str, position: &Position, session_realized_usd: f64) {
+        self.last_snapshot = ts;
+        self.append(&Entry::Snapshot {
+            ts,
+            market: market.to_string(),
+            position: PositionSnapshot::from(position),
+            session_realized_usd,
+        });
+    }
+
+    pub fn snapshot_if_due(&mut self, ts: f64, market: &str, position: &Position, session_realized_usd: f64) {
+        if ts - self.last_snapshot >= SNAPSHOT_INTERVAL_SECS {
+            self.snapshot(ts, market, position, session_realized_usd);
+        }
+    }
+
+    /// A new window: the old window's orders are no longer tracked, and the
+    /// snapshot marks where its fills stop counting.
+    pub fn roll(&mut self, ts: f64, market: &str, position: &Position, session_realized_usd: f64) {
+        self.live.clear();
+        self.snapshot(ts, market, position, session_realized_usd);
+    }
+}
+
+/// Every entry in a ledger, skipping unparseable lines.
+pub fn read_ledger(path: impl AsRef<Path>) -> anyhow::Result<Vec<Entry>> {
+    let file = File::open(path.as_ref())
+        .with_context(|| format!("opening ledger {}", path.as_ref().display()))?;
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        if let Ok(entry) = serde_json::from_str(&line?) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// State rebuilt from a ledger.
+#[derive(Debug, Clone, Default)]
+pub struct Recovered {
+    /// Market of the last entry
+    pub market: String,
+    pub position: Position,
+    pub session_realized_usd: f64,
+    /// Time of the last entry
+    pub ts: f64,
+    /// Fills applied on top of the last snapshot
+    pub fills_replayed: usize,
+}
+
+/// Rebuild position and session P&L: the last snapshot, then the fills
+/// after it. A fill for another market means a rollover the ledger didn't
+/// snapshot, so the position starts over there.
+pub fn recover(entries: &[Entry]) -> Option<Recovered> {
+    let last = entries.last()?;
+    let start = entries.iter().rposition(|e| matches!(e, Entry::Snapshot { .. }));
+    let mut recovered = Recovered { ts: last.ts(), ..Default::default() };
+    if let Some(Entry::Snapshot { market, position, session_realized_usd, .. }) = start.map(|i| &entries[i]) {
+        recovered.market = market.clone();
+        recovered.position = position.to_position();
+        recovered.session_realized_usd = *session_realized_usd;
+    }
+
+    for entry in &entries[start.map_or(0, |i| i + 1)..] {
+        if let Entry::Fill { market, side, price, size, .. } = entry {
+            if *market != recovered.market {
+                recovered.market = market.clone();
+                recovered.position = Position::default();
+            }
+            recovered.position.apply_fill(*side, *price, Decimal::try_from(*size).unwrap_or(Decimal::ZERO));
+            recovered.fills_replayed += 1;
+        }
+    }
+    Some(recovered)
+}
+
+fn to_f64(d: Decimal) -> f64 {
+    d.to_string().parse().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use std::fs;
+
+    #[test]
+    fn test_sync_writes_places_and_cancels() {
+        let path = std::env::temp_dir().join(format!("polybot_ledger_test_{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut ledger = Ledger::open(&path).unwrap();
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "y1".into(), 470, dec!(5));
+        orders.add(Side::No, "n1".into(), 490, dec!(5));
+        ledger.sync(1.0, "m", &orders);
+        ledger.sync(1.5, "m", &orders); // Nothing new
+
+        // y1 fills in full, n1 is cancelled
+        ledger.fill(2.0, "m", "y1", Side::Yes, 470, dec!(5));
+        orders.update_fill(Side::Yes, "y1", dec!(5));
+        orders.remove_by_id(Side::No, "n1");
+        ledger.sync(2.0, "m", &orders);
+
+        let entries = read_ledger(&path).unwrap();
+        let kinds: Vec<&str> = entries
+            .iter()
+            .map(|e| match e {
+                Entry::Place { .. } => "place",
+                Entry::Cancel { .. } => "cancel",
+                Entry::Fill { .. } => "fill",
+                Entry::Snapshot { .. } => "snapshot",
+            })
+            .collect();
+        assert_eq!(kinds, ["place", "place", "fill", "cancel"]);
+        assert!(matches!(&entries[3], Entry::Cancel { order_id, remaining, .. } if order_id == "n1" && *remaining == 5.0));
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_recover_from_snapshot_and_fills() {
+        let mut position = Position::default();
+        position.apply_fill(Side::Yes, 450, dec!(10));
+        let snapshot = |ts: f64, market: &str, position: &Position| Entry::Snapshot {
+            ts,
+            market: market.to_string(),
+            position: PositionSnapshot::from(position),
+            session_realized_usd: 1.5,
+        };
+        let fill = |ts: f64, market: &str| Entry::Fill {
+            ts,
+            market: market.to_string(),
+            order_id: "n1".to_string(),
+            side: Side::No,
+            price: 500,
+            size: 5.0,
+        };
+
+        let entries = vec![fill(1.0, "m0"), snapshot(2.0, "m1", &position), fill(3.0, "m1")];
+        let recovered = recover(&entries).unwrap();
+        assert_eq!(recovered.market, "m1");
+        assert_eq!((recovered.position.qty_yes, recovered.position.qty_no), (dec!(10), dec!(5)));
+        assert_eq!(recovered.position.cost_no, dec!(2500));
+        assert_eq!(recovered.session_realized_usd, 1.5);
+        assert_eq!(recovered.fills_replayed, 1);
+        assert_eq!(recovered.ts, 3.0);
+
+        // Fills in a window the ledger never snapshotted start it flat
+        let entries = vec![snapshot(2.0, "m1", &position), fill(3.0, "m2")];
+        let recovered = recover(&entries).unwrap();
+        assert_eq!(recovered.market, "m2");
+        assert_eq!((recovered.position.qty_yes, recovered.position.qty_no), (dec!(0), dec!(5)));
+
+        assert!(recover(&[]).is_none());
+    }
+
+    #[test]
+    fn test_path() {
+        assert_eq!(path(false, ""), LEDGER_PATH);
+        assert_eq!(path(true, "_15m"), "logs/state_paper_15m.jsonl");
+    }
+}
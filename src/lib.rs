@@ -1,7 +1,30 @@
+pub mod alerts;
 pub mod api;
+pub mod canary;
+pub mod clock;
 pub mod config;
+pub mod control;
+pub mod dataset;
+pub mod drift;
+pub mod engine;
 pub mod events;
 pub mod executor;
 pub mod feeds;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod heatmap;
+pub mod journal;
+pub mod ledger;
+pub mod metrics;
+pub mod paper;
+pub mod plugin;
+pub mod preflight;
+pub mod replay;
+pub mod report;
+pub mod risk;
+pub mod run_info;
+pub mod sink;
 pub mod state;
 pub mod strategy;
+pub mod tasks;
+pub mod telemetry;
@@ -3,9 +3,13 @@
 use std::collections::VecDeque;
 use std::fs::{self, File, OpenOptions};
 use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::events::Side;
+use crate::api::data::{FlowSummary, Holder};
+use crate::run_info::RunInfo;
+use crate::strategy::{Adjustment, BasisReading, TapeSignal};
 
 /// CSV header for log file
 const CSV_HEADER: &str = "type,timestamp,time_left,market,mid,var,k,inventory,yes_target,no_target,yes_resting,no_resting,pair_cost,spread,side,price,size,order_id,is_maker,reason,error";
@@ -17,6 +21,87 @@ struct UnmatchedFill {
     size: f64, // Reserved for future partial fill support
 }
 
+/// Average entry price per side, ours vs a naive joiner that always rests
+/// at the best bid. The joiner is filled at its bid by every print at or
+/// through it, so the gap is what the pricer buys us over just joining.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct EntryBenchmark {
+    /// Per side (YES, NO): (sum of price * size, size)
+    ours: [(f64, f64); 2],
+    joiner: [(f64, f64); 2],
+}
+
+fn side_idx(side: Side) -> usize {
+    match side {
+        Side::Yes => 0,
+        Side::No => 1,
+    }
+}
+
+impl EntryBenchmark {
+    /// One of our fills.
+    pub fn record_ours(&mut self, side: Side, price: u16, size: f64) {
+        let (notional, shares) = &mut self.ours[side_idx(side)];
+        *notional += price as f64 * size;
+        *shares += size;
+    }
+
+    /// A market print. Fills the joiner's `size` at `best_bid` if the print
+    /// traded at or through it.
+    pub fn record_print(&mut self, side: Side, price: u16, best_bid: Option<u16>, size: f64) {
+        let Some(bid) = best_bid.filter(|&b| b > 0 && price <= b) else {
+            return;
+        };
+        let (notional, shares) = &mut self.joiner[side_idx(side)];
+        *notional += bid as f64 * size;
+        *shares += size;
+    }
+
+    /// Our size-weighted average entry (ticks).
+    pub fn avg_ours(&self, side: Side) -> Option<f64> {
+        avg(self.ours[side_idx(side)])
+    }
+
+    /// The joiner's average entry (ticks).
+    pub fn avg_joiner(&self, side: Side) -> Option<f64> {
+        avg(self.joiner[side_idx(side)])
+    }
+
+    /// Joiner's average minus ours (ticks, + = we bought cheaper).
+    pub fn improvement(&self, side: Side) -> Option<f64> {
+        Some(self.avg_joiner(side)? - self.avg_ours(side)?)
+    }
+
+    pub fn merge(&mut self, other: &EntryBenchmark) {
+        for i in 0..2 {
+            self.ours[i].0 += other.ours[i].0;
+            self.ours[i].1 += other.ours[i].1;
+            self.joiner[i].0 += other.joiner[i].0;
+            self.joiner[i].1 += other.joiner[i].1;
+        }
+    }
+
+    /// One-line summary for stdout, e.g. `Y 47.2c vs 48.0c (+0.8c)`.
+    pub fn summary(&self) -> String {
+        [Side::Yes, Side::No]
+            .iter()
+            .map(|&side| {
+                let label = if side == Side::Yes { "Y" } else { "N" };
+                let cents = |t: Option<f64>| t.map_or("-".to_string(), |t| format!("{:.1}c", t / 10.0));
+                let delta = self
+                    .improvement(side)
+                    .map_or(String::new(), |d| format!(" ({:+.1}c)", d / 10.0));
+                format!("{} {} vs {}{}", label, cents(self.avg_ours(side)), cents(self.avg_joiner(side)), delta)
+            })
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+}
+
+fn avg((notional, shares): (f64, f64)) -> Option<f64> {
+    (shares > 0.0).then(|| notional / shares)
+}
+
 /// Per-window statistics
 #[derive(Default)]
 pub struct WindowStats {
@@ -31,6 +116,14 @@ pub struct WindowStats {
     pub ticks_halted: u32,
     pub toxic_cancels: u32,
     pub stale_halts: u32,
+    /// Most USDC tied up (inventory cost + resting orders) at once
+    pub peak_capital: f64,
+    capital_sum: f64,
+    capital_samples: u32,
+    /// Last mark-to-market inventory value
+    pub nav: f64,
+    /// Our entries vs a best-bid joiner
+    pub entries: EntryBenchmark,
     // For FIFO matching
     yes_queue: VecDeque<UnmatchedFill>,
     no_queue: VecDeque<UnmatchedFill>,
@@ -81,6 +174,32 @@ impl WindowStats {
         }
     }
 
+    /// Sample capital employed and NAV (once per quoting tick).
+    pub fn record_capital(&mut self, capital: f64, nav: f64) {
+        self.peak_capital = self.peak_capital.max(capital);
+        self.capital_sum += capital;
+        self.capital_samples += 1;
+        self.nav = nav;
+    }
+
+    /// Average capital employed over sampled ticks.
+    pub fn avg_capital(&self) -> f64 {
+        if self.capital_samples == 0 {
+            0.0
+        } else {
+            self.capital_sum / self.capital_samples as f64
+        }
+    }
+
+    /// Gross PnL as a fraction of peak capital employed.
+    pub fn return_on_capital(&self) -> f64 {
+        if self.peak_capital > 0.0 {
+            self.gross_pnl / self.peak_capital
+        } else {
+            0.0
+        }
+    }
+
     /// Finalize window stats (count unmatched)
     pub fn finalize(&mut self) {
         self.unmatched_yes = self.yes_queue.len() as u32;
@@ -106,8 +225,22 @@ pub struct SessionStats {
     pub order_fails: u32,
     pub toxic_cancels: u32,
     pub stale_halts: u32,
+    pub btc_rejected: u64,
+    pub late_fills: u32,
     pub ticks_total: u32,
     pub ticks_quoted: u32,
+    /// Largest single-window peak capital (capital is recycled between windows)
+    pub peak_capital: f64,
+    capital_sum: f64,
+    capital_samples: u32,
+    /// Every in-session margin adjustment, in order
+    pub margin_adjustments: Vec<String>,
+    /// Shares traded in our markets, per the Data API
+    pub market_volume: f64,
+    /// Our filled shares in the markets that have a Data API report
+    pub our_volume: f64,
+    /// Our entries vs a best-bid joiner, across windows
+    pub entries: EntryBenchmark,
 }
 
 impl SessionStats {
@@ -139,6 +272,43 @@ impl SessionStats {
         self.ticks_quoted += window.ticks_quoted;
         self.toxic_cancels += window.toxic_cancels;
         self.stale_halts += window.stale_halts;
+        self.peak_capital = self.peak_capital.max(window.peak_capital);
+        self.capital_sum += window.capital_sum;
+        self.capital_samples += window.capital_samples;
+        self.entries.merge(&window.entries);
+    }
+
+    /// Record one window's Data API volume and our fills in it.
+    pub fn record_flow(&mut self, market_volume: f64, our_volume: f64) {
+        self.market_volume += market_volume;
+        self.our_volume += our_volume;
+    }
+
+    /// Share of market volume we were filled on.
+    pub fn volume_share(&self) -> f64 {
+        if self.market_volume > 0.0 {
+            (self.our_volume / self.market_volume).min(1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// Average capital employed across all sampled ticks.
+    pub fn avg_capital(&self) -> f64 {
+        if self.capital_samples == 0 {
+            0.0
+        } else {
+            self.capital_sum / self.capital_samples as f64
+        }
+    }
+
+    /// Session gross PnL as a fraction of peak capital employed.
+    pub fn return_on_capital(&self) -> f64 {
+        if self.peak_capital > 0.0 {
+            self.gross_pnl / self.peak_capital
+        } else {
+            0.0
+        }
     }
 
     pub fn duration_str(&self) -> String {
@@ -164,17 +334,21 @@ impl SessionStats {
 /// Logger handles both CSV file and stdout
 pub struct Logger {
     file: File,
+    path: PathBuf,
     last_quote_log: f64,  // Throttle stdout to 1/sec
+    tick_rows: bool,      // Write TICK rows to the CSV
 }
 
 impl Logger {
-    pub fn new() -> anyhow::Result<Self> {
+    /// Open a new session CSV under `logs/` named after the current time
+    /// and `suffix` (which tells concurrent engines apart).
+    pub fn new(suffix: &str) -> anyhow::Result<Self> {
         fs::create_dir_all("logs")?;
         let ts = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs();
-        let path = format!("logs/polybot_{}.csv", ts);
+        let path = format!("logs/polybot_{}{}.csv", ts, suffix);
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
@@ -186,10 +360,22 @@ impl Logger {
 
         Ok(Self {
             file,
+            path: path.into(),
             last_quote_log: 0.0,
+            tick_rows: true,
         })
     }
 
+    /// The session CSV.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Skip the per-tick CSV rows (`[subsystems] tick_rows = false`).
+    pub fn set_tick_rows(&mut self, enabled: bool) {
+        self.tick_rows = enabled;
+    }
+
     fn timestamp_str(&self) -> String {
         let now = chrono::Local::now();
         now.format("%H:%M:%S").to_string()
@@ -214,12 +400,14 @@ impl Logger {
         let spread = 1.0 - pair_cost;
 
         // CSV
-        writeln!(
-            self.file,
-            "TICK,{:.3},{:.1},{},{:.4},{:.6},{:.4},{:.1},{},{},{},{},{:.4},{:.4},,,,,,",
-            now, time_left, market, mid, var, k, inventory,
-            yes_target, no_target, yes_resting, no_resting, pair_cost, spread
-        ).ok();
+        if self.tick_rows {
+            writeln!(
+                self.file,
+                "TICK,{:.3},{:.1},{},{:.4},{:.6},{:.4},{:.1},{},{},{},{},{:.4},{:.4},,,,,,",
+                now, time_left, market, mid, var, k, inventory,
+                yes_target, no_target, yes_resting, no_resting, pair_cost, spread
+            ).ok();
+        }
 
         // Stdout (throttled to 1/sec)
         if now - self.last_quote_log >= 1.0 {
@@ -398,12 +586,105 @@ impl Logger {
         );
     }
 
-    /// Log WINDOW_START
-    pub fn window_start(&mut self, market: &str) {
+    /// Log TAPE (trade tape signal at the time of one of our fills)
+    pub fn tape(&mut self, time_left: f64, market: &str, side: Side, order_id: &str, signal: TapeSignal) {
+        let now = now_secs();
+        let side_str = if side == Side::Yes { "YES" } else { "NO" };
+
+        // CSV: rate in the k column, pressure in reason
+        writeln!(
+            self.file,
+            "TAPE,{:.3},{:.1},{},,,{:.3},,,,,,,,{},,,{},,{:+.2},",
+            now, time_left, market, signal.rate, side_str, order_id, signal.pressure
+        ).ok();
+
+        // Stdout
+        println!(
+            "[{}] TAPE {} fill | {:.1} trades/s, pressure {:+.2}",
+            self.timestamp_str(), side_str, signal.rate, signal.pressure
+        );
+    }
+
+    /// Log ANNEAL (in-session margin adjustment)
+    pub fn anneal(&mut self, time_left: f64, market: &str, adj: &Adjustment) {
+        let now = now_secs();
+
+        // CSV
+        writeln!(
+            self.file,
+            "ANNEAL,{:.3},{:.1},{},,,,,,,,,,,,,,,,{},",
+            now, time_left, market, adj
+        ).ok();
+
+        // Stdout
+        println!("[{}] ANNEAL {}", self.timestamp_str(), adj);
+    }
+
+    /// Log BASIS (perp-spot basis and the mid shift it gave)
+    pub fn basis(&mut self, time_left: f64, market: &str, mid: f64, reading: &BasisReading) {
+        let now = now_secs();
+
+        // CSV: book mid in mid, the rest in reason
+        writeln!(
+            self.file,
+            "BASIS,{:.3},{:.1},{},{:.4},,,,,,,,,,,,,,,spot={:.2} perp={:.2} basis_bps={:.2} signal_bps={:.2} shift={:.4},",
+            now, time_left, market, mid, reading.spot, reading.perp, reading.basis_bps, reading.signal_bps, reading.shift
+        ).ok();
+
+        // Stdout
+        println!(
+            "[{}] BASIS {:+.2}bps (signal {:+.2}bps) | mid shift {:+.4}",
+            self.timestamp_str(), reading.basis_bps, reading.signal_bps, reading.shift
+        );
+    }
+
+    /// Log FLOW (Data API volume and counterparty report for a window)
+    pub fn flow_report(&mut self, market: &str, summary: &FlowSummary, our_volume: f64, holders: &[Holder]) {
+        let now = now_secs();
+        let share = summary.our_share(our_volume);
+
+        // CSV: our share in reason
+        writeln!(
+            self.file,
+            "FLOW,{:.3},,{},,,,,,,,,,,,,{:.1},,,share={:.4},",
+            now, market, summary.total_volume, share
+        ).ok();
+
+        // Stdout
+        println!(
+            "    flow: {:.0} shares traded | ours={:.0} ({:.1}%)",
+            summary.total_volume, our_volume, share * 100.0
+        );
+        for taker in &summary.top_takers {
+            println!(
+                "      taker {:<20} {:>6.0} shares | pressure {:+.2}",
+                taker.who, taker.volume, taker.pressure
+            );
+        }
+        for holder in holders {
+            let who = holder.pseudonym.as_deref().unwrap_or(&holder.proxy_wallet);
+            let side = if holder.outcome_index == 0 { "YES" } else { "NO" };
+            println!("      top {} holder {} ({:.0} shares)", side, who, holder.amount);
+        }
+    }
+
+    /// Log RUN: build and config identity, once at startup
+    pub fn run_info(&mut self, run: &RunInfo) {
+        let now = now_secs();
+
+        // CSV - summary in the reason column
+        writeln!(self.file, "RUN,{:.3},,,,,,,,,,,,,,,,,,{},", now, run.summary()).ok();
+
+        // Stdout
+        println!("[RUN] {}", run.summary());
+    }
+
+    /// Log WINDOW_START with the fee rate the market's orders are signed at
+    pub fn window_start(&mut self, market: &str, fee_rate_bps: u32) {
         let now = now_secs();
 
         // CSV
-        writeln!(self.file, "WINDOW_START,{:.3},,{},,,,,,,,,,,,,,,,,,", now, market).ok();
+        writeln!(self.file, "WINDOW_START,{:.3},,{},fee_bps={},,,,,,,,,,,,,,,,,", now, market, fee_rate_bps).ok();
 
         // Stdout
         println!(">>> WINDOW START {}", market);
@@ -445,6 +726,13 @@ impl Logger {
             "    unmatched: Y={} N={} | gross=${:.2}",
             stats.unmatched_yes, stats.unmatched_no, stats.gross_pnl
         );
+        println!(
+            "    capital: peak=${:.2} avg=${:.2} | roc={:.2}% | nav=${:.2}",
+            stats.peak_capital,
+            stats.avg_capital(),
+            stats.return_on_capital() * 100.0,
+            stats.nav
+        );
         println!(
             "    quoted={}/{} ticks | toxic={} stale={}",
             stats.ticks_quoted,
@@ -452,12 +740,14 @@ impl Logger {
             stats.toxic_cancels,
             stats.stale_halts
         );
+        println!("    entry vs joiner: {}", stats.entries.summary());
         println!("    params: gamma={:.4} halt_secs={:.0}", gamma, halt_secs);
     }
 
     /// Log session summary on shutdown
-    pub fn session_summary(&mut self, stats: &SessionStats) {
+    pub fn session_summary(&mut self, stats: &SessionStats, run: &RunInfo) {
         println!("\n=== SESSION SUMMARY ===");
+        println!("Run: {}", run.summary());
         println!("Duration: {}", stats.duration_str());
         println!("Windows: {}", stats.windows);
         println!("Total fills: Y={} N={}", stats.yes_fills, stats.no_fills);
@@ -465,13 +755,32 @@ impl Logger {
         println!("Avg pair cost: {:.4}", stats.avg_pair_cost());
         println!("Unmatched: Y={} N={}", stats.unmatched_yes, stats.unmatched_no);
         println!("Gross PnL: ${:.2}", stats.gross_pnl);
+        println!(
+            "Capital: peak ${:.2}, avg ${:.2} | Return on capital: {:.2}%",
+            stats.peak_capital,
+            stats.avg_capital(),
+            stats.return_on_capital() * 100.0
+        );
         println!("Orders placed: {}", stats.orders_placed);
         println!("Orders cancelled: {}", stats.orders_cancelled);
         println!("Cancel fails: {}", stats.cancel_fails);
         println!("Order fails: {}", stats.order_fails);
         println!("Toxic cancels: {}", stats.toxic_cancels);
         println!("Stale halts: {}", stats.stale_halts);
+        println!("BTC ticks rejected: {}", stats.btc_rejected);
+        println!("Late/unknown fills: {}", stats.late_fills);
         println!("Avg ticks quoted: {:.0}%", stats.pct_quoted());
+        println!(
+            "Volume share: {:.1}% ({:.0} of {:.0} shares)",
+            stats.volume_share() * 100.0,
+            stats.our_volume,
+            stats.market_volume
+        );
+        println!("Avg entry vs best-bid joiner: {}", stats.entries.summary());
+        println!("Margin adjustments: {}", stats.margin_adjustments.len());
+        for adj in &stats.margin_adjustments {
+            println!("    {}", adj);
+        }
     }
 
     /// Log price replacement (stdout only)
@@ -495,3 +804,33 @@ fn now_secs() -> f64 {
         .unwrap()
         .as_secs_f64()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entry_benchmark() {
+        let mut e = EntryBenchmark::default();
+        assert_eq!(e.improvement(Side::Yes), None);
+
+        e.record_ours(Side::Yes, 470, 5.0);
+        e.record_ours(Side::Yes, 480, 15.0);
+        // Print above the bid doesn't reach the joiner; at/through it does
+        e.record_print(Side::Yes, 500, Some(490), 5.0);
+        e.record_print(Side::Yes, 490, Some(490), 5.0);
+        e.record_print(Side::Yes, 470, Some(480), 5.0);
+        e.record_print(Side::Yes, 470, None, 5.0);
+
+        assert_eq!(e.avg_ours(Side::Yes), Some(477.5));
+        assert_eq!(e.avg_joiner(Side::Yes), Some(485.0));
+        assert_eq!(e.improvement(Side::Yes), Some(7.5));
+        assert_eq!(e.improvement(Side::No), None);
+
+        let mut session = EntryBenchmark::default();
+        session.merge(&e);
+        session.merge(&e);
+        assert_eq!(session.improvement(Side::Yes), Some(7.5));
+        assert_eq!(session.summary(), "Y 47.8c vs 48.5c (+0.8c) | N - vs -");
+    }
+}
@@ -1,53 +1,103 @@
-//! Polymarket BTC 5-minute market maker.
+//! Polymarket crypto up/down market maker (BTC 5-minute markets unless
+//! configured otherwise).
 //!
 //! Usage:
 //!     cargo run                          # Live trading (indefinite)
 //!     cargo run -- --log-only            # Dry run (no orders)
 //!     cargo run -- --markets 3           # Trade 3 markets then quit
 //!     cargo run -- --log-only --markets 1
+//!     cargo run -- --observe             # No credentials, hypothetical fills
+//!     cargo run -- --concurrent 5m,15m   # Quote 5m and 15m markets at once
+//!                                        # ([markets] assets in config.toml adds ETH, SOL, ...)
+//!     cargo run -- preflight             # Pass/fail checklist before going live
+//!     cargo run -- replay <journal> --tui  # Step through a recorded session
 //!
 //! Required env vars:
 //!     POLY_PRIVATE_KEY=0x...
 //!     POLY_PROXY_WALLET=0x...
 
+mod alerts;
 mod api;
+mod canary;
+mod clock;
 mod config;
+mod control;
+mod dataset;
+mod engine;
 mod events;
 mod executor;
 mod feeds;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod heatmap;
+mod journal;
+mod ledger;
 mod logging;
+mod metrics;
+mod paper;
+mod plugin;
+mod preflight;
+mod replay;
+mod report;
+mod risk;
+mod run_info;
+mod sink;
 mod state;
 mod strategy;
+mod tasks;
+mod telemetry;
 
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 
 use alloy::primitives::{B256, U256};
 use alloy::providers::ProviderBuilder;
 use alloy::signers::Signer as _;
 use alloy::signers::local::LocalSigner;
 use alloy_primitives::Address;
-use anyhow::Result;
+use anyhow::{Context, Result};
 use polyfill_rs::orders::SigType;
 use polyfill_rs::ClobClient;
 use polymarket_client_sdk::ctf;
-use polymarket_client_sdk::ctf::types::MergePositionsRequest;
+use polymarket_client_sdk::ctf::types::{MergePositionsRequest, RedeemPositionsRequest};
 use polymarket_client_sdk::types::address;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use std::str::FromStr;
 use tokio::sync::mpsc;
 use tokio::time::interval;
+use tracing::{info_span, Instrument};
 
-use api::gamma;
+use api::{account, data, fees as fee_rates, gamma, settlement};
+use clock::{Clock, SystemClock};
+use control::{ControlCommand, ControlHandle, EngineEvent, EngineStatus, RuntimeParams};
 use events::{Event, Side};
 use executor::{Executor, ExecutorConfig};
-use feeds::binance;
+use feeds::{balance, binance, config_watch, market_status, positions, signal, status, whale};
 use feeds::polymarket::PolymarketFeed;
+use feeds::recorder::Recorder;
 use feeds::user_ws::{UserFeed, UserFeedConfig};
+use heatmap::{Heatmap, HEATMAP_BUCKET_SECS};
+use dataset::{Dataset, DecisionRow};
+use engine::{EngineSpec, MarketManager, SharedRisk};
+use journal::{Journal, Signals, Snapshot};
+use ledger::Ledger;
 use logging::{Logger, SessionStats, WindowStats};
-use state::{Book, OrderTracker, Position};
+use metrics::{Metrics, SloChange, SloMonitor};
+use paper::{FaultCounts, FaultInjector, SoakReport};
+use sink::{EventSink, FillInfo};
+use plugin::WasmPlugin;
+use risk::{
+    CanaryConfig, CanaryMonitor, CircuitBreaker, InvariantChecker, InvariantConfig, OrderRules, PositionSchedule, Recovery, RecoveryStage, Supervisor, Variant,
+    VariantBudget,
+};
+use run_info::RunInfo;
+use state::{adopt_orders, position_from_holdings, Book, Candles, FillMatch, OrderTracker, Pnl, PnlReport, Position, ShadowPosition};
+use tasks::{Stage, TaskRegistry};
 use strategy::{
-    Action, AvellanedaStoikov, BtcGuard, BtcGuardConfig, FlowEstimator, Quotes, VarianceEstimator,
+    breakeven, cents_to_ticks, ladder_notional, Action, AnnealConfig, AvellanedaStoikov, BtcGuard, BtcGuardConfig, Deviation, ExternalInput,
+    BasisConfig, BasisTracker, ExternalSignalConfig, FeeSchedule, InstanceConfig, MarketDuration, StrategyInstance, FillStreakGuard, FlowEstimator, OfiConfig,
+    OfiTracker, PriceFilter, PriceFilterConfig, Quotes, RewardBand, SideQuote, StrategyConfig, MarginAnnealer,
+    TapeVelocity, TickQuotes, tick_volatility, VarianceEstimator,
 };
 
 // =============================================================================
@@ -58,6 +108,7 @@ use strategy::{
 const TICK_MS: u64 = 50;           // Order management interval
 const WARMUP_SECS: f64 = 15.0;     // Wait after market open
 const HALT_SECS: f64 = 15.0;       // Stop before market ends
+const CLOSE_BUFFER_SECS: f64 = 3.0; // Executor refuses new orders this close to the end
 
 /// A-S Pricer
 const AS_GAMMA: f64 = 0.05;        // Risk aversion (higher = wider spreads)
@@ -71,32 +122,83 @@ const VAR_FLOOR: f64 = 0.001;      // Minimum variance
 const FLOW_WINDOW_SECS: f64 = 30.0; // Trade counting window
 const FLOW_K_FLOOR: f64 = 0.1;      // Minimum k (trades/sec)
 
+/// Order flow imbalance from depth deltas: widen the side it's against
+const OFI_WINDOW_SECS: f64 = 5.0;
+const OFI_DEPTH_TICKS: u16 = 20;       // Levels within 2c of the touch
+const OFI_WIDEN_SHARES: f64 = 500.0;   // Net YES pressure that counts
+const OFI_WIDEN_TICKS: u16 = 10;       // 1c
+
+/// Perp-spot basis prior (weight and scale from [basis] in config.toml)
+const BASIS_FAST_SECS: f64 = 30.0;     // Smoothing
+const BASIS_SLOW_SECS: f64 = 900.0;    // Standing premium (funding) estimate
+const BASIS_LOG_SECS: f64 = 10.0;      // BASIS row cadence for offline evaluation
+
+/// Margin annealing: widen after a toxic fill, tighten after clean stretches
+const ANNEAL_STEP_TICKS: u16 = 10;     // 1c per adjustment
+const ANNEAL_MAX_EXTRA: u16 = 30;      // At most 3c wider
+const ANNEAL_MARKOUT_SECS: f64 = 5.0;  // Check fills 5s later
+const ANNEAL_TOXIC_TICKS: u16 = 20;    // Bid 2c below our fill = toxic
+const ANNEAL_CLEAN_SECS: f64 = 120.0;  // Tighten after 2 clean minutes
+
+/// Warm start: at rollover, keep this much of the ending volatility, trade
+/// flow and toxicity margin so a new market doesn't start from the floors
+const WARM_START_DECAY: f64 = 0.5;
+
+/// Whale guard (--whale-guard): widen the side a large taker is running over
+const WHALE_WIDEN_TICKS: u16 = 10; // 1c
+
+/// Data API flow report at window end
+const FLOW_REPORT_TRADES: usize = 500; // Most recent public trades
+const FLOW_REPORT_TOP: usize = 3;      // Largest takers to show
+
 /// BTC Guard
 const BTC_MAX_DROP_PCT: f64 = 0.003;  // 0.3% triggers cancel
 const BTC_WINDOW_SECS: f64 = 2.0;     // Drop detection window
 const BTC_COOLDOWN_SECS: f64 = 5.0;   // Pause after crash
+const BTC_FILTER_WINDOW: usize = 21;  // Median of last N trades
+const BTC_FILTER_MAX_DEV: f64 = 0.01; // 1% off median = bad print
 
 /// Order size
 const ORDER_SIZE: i64 = 5;         // Shares per order
 
-/// Staleness detection
-const STALE_MS: i64 = 5000;        // Halt if no book update for 5s
+/// Take cooldown: at most one taker rebalance per side per this long
+const TAKE_COOLDOWN_SECS: f64 = 10.0;
+/// Take impact guard: one Take uses at most this fraction of the visible asks within its max price
+const TAKE_MAX_DEPTH_FRACTION: f64 = 0.5;
 
 /// Fill cooldown: don't re-place on a side for this long after a fill
 const FILL_COOLDOWN_SECS: f64 = 1.0;
 
-/// Pair cost cap: max pair cost in ticks (990 = 99c = 1c margin)
+/// Pair cost cap: max pair cost in ticks (990 = 99c = 1c margin).
+/// Default for `[strategy] margin_ticks`.
 const MAX_PAIR_TICKS: u16 = 990;
 
+/// Inventory target: imbalance tolerated at the open, narrowing to 5 shares
+/// by T-30s; the heavy side widens 1c per 20 shares past it (at most 3c).
+/// Default for `[strategy] rebalance_threshold`.
+const INVENTORY_TOLERANCE: i64 = 30;
 
-const USDC_ADDR: alloy::primitives::Address = address!("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174");
+/// Canary order (--canary): place + cancel a 1c bid to test the order path
+const CANARY_INTERVAL_SECS: f64 = 300.0;
+const CANARY_MAX_RTT_MS: u64 = 1500;   // Slower round trip = degraded
+const CANARY_TIMEOUT_SECS: u64 = 10;   // No answer by then = failed
+// Resolution check plus redeem; under settlement::POLL_SECS so attempts on a window don't overlap
+const SETTLE_TIMEOUT_SECS: u64 = 25;
 
-fn now_secs() -> f64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs_f64()
-}
+/// Fee schedule (bps of notional)
+const MAKER_FEE_BPS: u32 = 0;      // Makers pay nothing on crypto up/down
+const TAKER_FEE_BPS: u32 = 0;
+/// Refuse to sign orders if the CLOB's fee rate for a market is above this
+const MAX_FEE_RATE_BPS: u32 = 1000;
+
+/// Control surface: how often to publish an engine status snapshot
+const STATUS_INTERVAL_SECS: f64 = 1.0;
+
+/// Shutdown: how long each stage's tasks get to finish before being aborted
+const SHUTDOWN_GRACE_MS: u64 = 2000;
+
+
+const USDC_ADDR: alloy::primitives::Address = address!("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174");
 
 /// Round ticks to cent boundary (multiple of 10).
 fn round_to_cents(ticks: u16) -> u16 {
@@ -107,17 +209,126 @@ fn round_to_cents(ticks: u16) -> u16 {
 async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
+    let args: Vec<String> = std::env::args().collect();
+    match args.get(1).map(String::as_str) {
+        Some("preflight") => return preflight::run().await,
+        Some("replay") => return replay::run(&args[2..]),
+        Some("report") => return report::run(&args[2..]).await,
+        _ => {}
+    }
+
+    // Tracing is process-wide: set up once, before any engine starts
+    let config::Config { general, logging: logging_config, tracing: tracing_config, markets: markets_config, .. } =
+        config::Config::load_or_default("config.toml")?;
+    let telemetry = telemetry::init(&general.log_level, &logging_config, &tracing_config)?;
+
+    // An engine per [markets] asset and --concurrent length (5m,15m), sharing the wallet
+    let lengths = args.iter()
+        .position(|a| a == "--concurrent")
+        .and_then(|i| args.get(i + 1))
+        .map_or("5m", |s| s.as_str());
+    let engines = engine::parse_engines(&markets_config.assets, lengths)?;
+    if engines.len() > 1 && args.iter().any(|a| a == "--canary-deploy") {
+        anyhow::bail!("--canary-deploy trials one market at a time; drop --concurrent and list one asset");
+    }
+    if engines.len() > 1 {
+        let labels: Vec<String> = engines.iter().map(|e| e.label()).collect();
+        println!("=== CONCURRENT MARKETS {} (shared collateral) ===", labels.join(" + "));
+    }
+
+    let result = MarketManager::new(engines)
+        .run(|engine, risk| {
+            let span = info_span!("engine", market = %engine.label());
+            run_engine(engine, risk).instrument(span)
+        })
+        .await;
+    telemetry.shutdown();
+    result
+}
+
+/// One market's trading loop: its own feeds, book, position, orders and
+/// strategy, from the first window to shutdown.
+async fn run_engine(engine: EngineSpec, risk: SharedRisk) -> Result<()> {
+    let label = engine.label();
+    let suffix = engine.suffix();
+
     // Parse args
     let args: Vec<String> = std::env::args().collect();
-    let log_only = args.iter().any(|a| a == "--log-only" || a == "--dry-run");
+    let observe = args.iter().any(|a| a == "--observe");
+    // Parse --soak HOURS (paper trading under injected faults)
+    let soak_hours: Option<f64> = args.iter()
+        .position(|a| a == "--soak")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|s| s.parse().ok());
+    let log_only = observe || soak_hours.is_some() || args.iter().any(|a| a == "--log-only" || a == "--dry-run");
+    let paranoid = args.iter().any(|a| a == "--paranoid");
+    let canary_enabled = args.iter().any(|a| a == "--canary");
+    let whale_guard = args.iter().any(|a| a == "--whale-guard");
+    let reward_band_only = args.iter().any(|a| a == "--reward-band");
+    let lean = args.iter().any(|a| a == "--lean");
+    let clock = SystemClock;
+
+    // Parse --canary-deploy USD (hard cap on notional at risk, then a comparison report)
+    let canary_cap: Option<Decimal> = args.iter()
+        .position(|a| a == "--canary-deploy")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|s| s.parse().ok());
 
-    // Parse --markets N
+    // Parse --markets N (a canary deploy defaults to an hour of markets)
     let max_markets: Option<u32> = args.iter()
         .position(|a| a == "--markets")
         .and_then(|i| args.get(i + 1))
+        .and_then(|s| s.parse().ok())
+        .or(canary_cap.map(|_| canary::DEFAULT_MARKETS));
+
+    // Parse --balance USD (USDC available for resting bids)
+    let balance_usd: Option<f64> = args.iter()
+        .position(|a| a == "--balance")
+        .and_then(|i| args.get(i + 1))
         .and_then(|s| s.parse().ok());
 
-    if log_only {
+    // Parse --grpc ADDR (e.g. 127.0.0.1:50051)
+    let grpc_addr: Option<std::net::SocketAddr> = args.iter()
+        .position(|a| a == "--grpc")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|s| s.parse().ok());
+
+    // Parse --plugin FILE.wasm
+    let plugin_path: Option<String> = args.iter()
+        .position(|a| a == "--plugin")
+        .and_then(|i| args.get(i + 1))
+        .cloned();
+
+    // Optional config file: strategy overrides and instances, metrics backends, signal and basis inputs, subsystems, paper faults,
+    // feed recorder, circuit breaker and decision dataset
+    let config::Config {
+        strategy: mut strategy_config,
+        instances: instance_configs,
+        metrics: metrics_config,
+        signal: signal_config,
+        sink: sink_config,
+        basis: basis_config,
+        subsystems,
+        paper: paper_config,
+        recorder: recorder_config,
+        risk: risk_config,
+        dataset: dataset_config,
+        ..
+    } = config::Config::load_or_default("config.toml")?;
+    let mut configured_duration = duration_override(&strategy_config);
+    let mut strategy = builtin_strategy();
+    strategy_config.apply(&mut strategy)?;
+    strategy.limits = risk_config.limits()?;
+    let subsystems = if lean { config::Subsystems::lean() } else { subsystems };
+    alerts::set_enabled(subsystems.alerts);
+
+    // Paper trading: real feeds and strategy, simulated venue
+    let paper_mode = paper_config.enabled && !observe;
+    if observe {
+        println!("=== OBSERVER MODE (no credentials, hypothetical fills only) ===");
+    } else if paper_mode && !log_only {
+        println!("=== PAPER TRADING MODE (simulated venue, no credentials) ===");
+    } else if log_only {
         println!("=== DRY RUN MODE (no orders will be placed) ===");
     } else {
         println!("=== LIVE TRADING MODE ===");
@@ -125,120 +336,413 @@ async fn main() -> Result<()> {
     if let Some(n) = max_markets {
         println!("Will trade {} market(s) then quit", n);
     }
-    println!();
+    if let Some(cap) = canary_cap {
+        println!("=== CANARY DEPLOY (${} at risk at most, compared with production at exit) ===", cap);
+    }
+    if let Some(hours) = soak_hours {
+        println!("=== SOAK TEST for {:.1}h (paper trading, injected faults, violations tallied) ===", hours);
+    }
+    if lean {
+        println!("=== LEAN MODE (no journal, heatmap, metrics, alerts or tick rows) ===");
+    }
 
-    // Load credentials from env
-    let private_key = std::env::var("POLY_PRIVATE_KEY").expect("Set POLY_PRIVATE_KEY");
-    let proxy_wallet = std::env::var("POLY_PROXY_WALLET").expect("Set POLY_PROXY_WALLET");
-    let funder = Address::from_str(&proxy_wallet)?;
-
-    // Create CLOB client and derive API credentials
-    println!("Creating CLOB client...");
-    let l1_client = ClobClient::with_l1_headers("https://clob.polymarket.com", &private_key, 137);
-    let api_creds = l1_client.create_or_derive_api_key(None).await?;
-    println!("API Key: {}...", &api_creds.api_key[..20.min(api_creds.api_key.len())]);
-
-    // Create CTF client for merge/redeem
-    let rpc_url = std::env::var("POLYGON_RPC_URL").unwrap_or_else(|_| "https://polygon-rpc.com".to_string());
-    let ctf_signer = LocalSigner::from_str(&private_key)?.with_chain_id(Some(137u64));
-    let ctf_provider = ProviderBuilder::new()
-        .wallet(ctf_signer)
-        .connect(&rpc_url)
-        .await?;
-    let ctf_client = ctf::Client::new(ctf_provider, 137)?;
-    println!("CTF client ready (RPC: {}...)", &rpc_url[..rpc_url.len().min(40)]);
-
-    // Save credentials for UserFeed before moving to ClobClient
-    let user_api_key = api_creds.api_key.clone();
-    let user_api_secret = api_creds.secret.clone();
-    let user_api_passphrase = api_creds.passphrase.clone();
-
-    let client = ClobClient::with_l2_headers(
-        "https://clob.polymarket.com",
-        &private_key,
-        137,
-        api_creds,
-        Some(SigType::PolyProxy),
-        Some(funder),
-    );
+    // Background tasks, stopped stage by stage on exit
+    let mut tasks = TaskRegistry::new();
+
+    // Control surface: pause/resume/params in, fills/halts/status out
+    let (control, mut control_rx) = ControlHandle::new();
+    if let Some(addr) = grpc_addr.filter(|_| engine.primary) {
+        #[cfg(feature = "grpc")]
+        {
+            println!("=== gRPC control on {} ===", addr);
+            let handle = control.clone();
+            tasks.spawn(Stage::Feeds, "grpc", async move {
+                if let Err(e) = grpc::serve(addr, handle).await {
+                    tracing::error!("gRPC server stopped: {}", e);
+                }
+            });
+        }
+        #[cfg(not(feature = "grpc"))]
+        println!("--grpc {} ignored: built without the `grpc` feature", addr);
+    }
+    // WASM strategy plugin replaces the built-in ladder logic (halts still apply)
+    let mut plugin = plugin_path.as_ref().map(WasmPlugin::load).transpose()?;
+    if let Some(path) = &plugin_path {
+        println!("=== STRATEGY PLUGIN {} ===", path);
+    }
+    let mut params = RuntimeParams { gamma: AS_GAMMA, extra_margin: 0 };
+    let mut paused: Option<String> = None;
+    let mut last_status = 0.0;
+
+
+    // Observer and paper modes never read keys: unauthenticated client, no CTF or user feed
+    let (client, ctf_client, user_feed_template) = if observe || paper_mode {
+        (ClobClient::new(api::CLOB_BASE), None, None)
+    } else {
+        // Load credentials from env
+        let private_key = std::env::var("POLY_PRIVATE_KEY").expect("Set POLY_PRIVATE_KEY");
+        let proxy_wallet = std::env::var("POLY_PROXY_WALLET").expect("Set POLY_PROXY_WALLET");
+        let funder = Address::from_str(&proxy_wallet)?;
+
+        // Create CLOB client and derive API credentials
+        println!("Creating CLOB client...");
+        let l1_client = ClobClient::with_l1_headers(api::CLOB_BASE, &private_key, 137);
+        let api_creds = l1_client.create_or_derive_api_key(None).await?;
+        println!("API Key: {}...", &api_creds.api_key[..20.min(api_creds.api_key.len())]);
+
+        // Create CTF client for merge/redeem
+        let rpc_url = std::env::var("POLYGON_RPC_URL").unwrap_or_else(|_| "https://polygon-rpc.com".to_string());
+        let ctf_signer = LocalSigner::from_str(&private_key)?.with_chain_id(Some(137u64));
+        let ctf_provider = ProviderBuilder::new()
+            .wallet(ctf_signer)
+            .connect(&rpc_url)
+            .await?;
+        let ctf_client = ctf::Client::new(ctf_provider, 137)?;
+        println!("CTF client ready (RPC: {}...)", &rpc_url[..rpc_url.len().min(40)]);
+
+        // Save credentials for UserFeed before moving to ClobClient
+        // (tokens are filled in per market)
+        let user_feed_template = UserFeedConfig {
+            api_key: api_creds.api_key.clone(),
+            api_secret: api_creds.secret.clone(),
+            api_passphrase: api_creds.passphrase.clone(),
+            maker_address: proxy_wallet,
+            yes_token: String::new(),
+            no_token: String::new(),
+        };
+
+        let client = ClobClient::with_l2_headers(
+            api::CLOB_BASE,
+            &private_key,
+            137,
+            api_creds,
+            Some(SigType::PolyProxy),
+            Some(funder),
+        );
+        (client, Some(std::sync::Arc::new(ctf_client)), Some(user_feed_template))
+    };
 
     // Get current market and wait for next one to start fresh
     println!("Fetching current market...");
-    let mut market = gamma::get_current_5m_market().await?;
-    let mut market_start = gamma::parse_start_epoch(&market.slug)
-        .map(|e| e as f64)
-        .unwrap_or(now_secs());
-    let mut market_end = market_start + 300.0;
+    let mut market = engine.market.fetch_at(&clock).await?;
+    let (mut market_start, mut market_end, mut market_duration) = market_window(&market, configured_duration, &clock);
 
     // Wait for next market if we're mid-market
-    let time_left = market_end - now_secs();
-    if time_left < 300.0 - WARMUP_SECS {
+    let time_left = market_end - clock.now_secs();
+    if time_left < market_duration.total_secs() as f64 - WARMUP_SECS {
         println!("Current market {} has {:.0}s left, waiting for next...", market.slug, time_left);
         // Wait until this market ends + a bit
         tokio::time::sleep(Duration::from_secs_f64(time_left + 1.0)).await;
 
         // Fetch the new market
-        market = gamma::get_current_5m_market().await?;
-        market_start = gamma::parse_start_epoch(&market.slug)
-            .map(|e| e as f64)
-            .unwrap_or(now_secs());
-        market_end = market_start + 300.0;
+        market = engine.market.fetch_at(&clock).await?;
+        (market_start, market_end, market_duration) = market_window(&market, configured_duration, &clock);
     }
 
-    println!("Market: {}", market.slug);
+    apply_market_strategy(&strategy_config, &mut strategy, &market, market_duration);
+    println!(
+        "Market: {} ({}, YES={}, NO={})",
+        market.slug, market_duration.label(), market.yes_label, market.no_label
+    );
     println!(
         "Start: {:.0}, End: {:.0} (in {:.0}s)\n",
         market_start,
         market_end,
-        market_end - now_secs()
+        market_end - clock.now_secs()
+    );
+
+    // Refuse to quote a margin that can't pay its own fees
+    let fees = FeeSchedule { maker_bps: MAKER_FEE_BPS, taker_bps: TAKER_FEE_BPS };
+    let margin = strategy.margin_ticks;
+    let be = breakeven(&fees, margin);
+    println!(
+        "Fees: maker {}bps, taker {}bps | margin {} ticks needs {:.0}% of fills paired (min margin {} ticks)",
+        fees.maker_bps, fees.taker_bps, margin, be.pair_rate * 100.0, be.min_margin_ticks
     );
+    if !be.is_viable() {
+        anyhow::bail!(
+            "Margin of {} ticks can't cover {:.2} ticks of fees per pair",
+            margin, be.pair_fee_ticks
+        );
+    }
+    println!();
 
     // Track markets completed
     let mut markets_completed: u32 = 0;
 
+    // Extra strategy instances, each with its own ladder shape and budget
+    // Offsets hang off the main target, which is always whole cents
+    let to_ticks = |name: &str, key: &str, cents: f64| {
+        let cents = cents.to_string().parse::<Decimal>().unwrap_or(Decimal::ZERO);
+        cents_to_ticks(cents, Decimal::new(1, 2)).with_context(|| format!("[[instances]] {}: {}", name, key))
+    };
+    let to_dec = |v: f64| Decimal::try_from(v).unwrap_or(Decimal::ZERO);
+    let instances: Vec<StrategyInstance> = instance_configs
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            Ok(StrategyInstance::new(i as u8, InstanceConfig {
+                name: c.name.clone(),
+                offset_ticks: to_ticks(&c.name, "offset_cents", c.offset_cents)?,
+                rungs: c.rungs,
+                spacing_ticks: to_ticks(&c.name, "spacing_cents", c.spacing_cents)?,
+                size: to_dec(c.size),
+                max_position: to_dec(c.max_position),
+                max_notional: to_dec(c.max_notional),
+            }))
+        })
+        .collect::<Result<_>>()?;
+    let mut variant_budgets = VariantBudget::defaults();
+    for inst in &instances {
+        println!(
+            "Instance {}: {} rung(s) x {} every {}c, {}c under target (max {} shares, ${})",
+            inst.config.name,
+            inst.config.rungs,
+            inst.config.size,
+            inst.config.spacing_ticks as f64 / 10.0,
+            inst.config.offset_ticks as f64 / 10.0,
+            inst.config.max_position,
+            inst.config.max_notional
+        );
+        variant_budgets.insert(inst.variant, inst.budget());
+    }
+    if let Some(cap) = canary_cap {
+        canary::cap_budgets(&mut variant_budgets, cap, strategy.max_position);
+    }
+
+    // Fee rate signed into orders, from the CLOB (errors if unexpectedly high)
+    let mut fee_rate_bps = fee_rates::fee_rate(&market.yes_token, MAX_FEE_RATE_BPS).await?;
+    println!("Fee rate: {}bps", fee_rate_bps);
+
     // Create executor
+    let mut order_rules = OrderRules {
+        balance: balance_usd.and_then(|b| Decimal::try_from(b).ok()),
+        self_trade_overlap_ticks: risk_config.self_trade_overlap_ticks,
+        ..Default::default()
+    };
+    order_rules.set_tick_size(market.tick_size);
     let executor_config = ExecutorConfig {
         log_only,
         yes_token: market.yes_token.clone(),
         no_token: market.no_token.clone(),
+        limits: strategy.limits.clone(),
+        take_cooldown: Duration::from_secs_f64(TAKE_COOLDOWN_SECS),
+        variant_budgets,
+        end_timestamp_ms: Some((market_end * 1000.0) as i64),
+        close_buffer: Duration::from_secs_f64(CLOSE_BUFFER_SECS),
+        order_rules,
+        take_max_depth_fraction: Decimal::try_from(TAKE_MAX_DEPTH_FRACTION).unwrap_or(Decimal::ONE),
+        fee_rate_bps,
     };
     let mut executor = Executor::new(client, executor_config);
 
-    // Create logger and stats
-    let mut logger = Logger::new()?;
+    // Soak run: one injector for the feeds, one for the executor, shared counts
+    let fault_counts = std::sync::Arc::new(FaultCounts::default());
+    let mut soak_report = soak_hours.map(|hours| SoakReport::new(clock.now_secs(), hours * 3600.0));
+    if soak_report.is_some() {
+        let injector = FaultInjector::new(paper_config.faults, paper_config.seed.wrapping_add(1), fault_counts.clone());
+        executor = executor.with_faults(injector);
+    }
+
+    // Create logger and stats, each stamped with the build and config behind this run
+    let mut run_info = RunInfo::capture("config.toml", &strategy);
+    let mut logger = Logger::new(&suffix)?;
+    logger.set_tick_rows(subsystems.tick_rows);
+    logger.run_info(&run_info);
+    let mut journal = if subsystems.journal { Journal::new(&suffix)? } else { Journal::disabled() };
+    journal.write_header(&run_info)?;
+    let mut heatmap = if subsystems.heatmap {
+        Heatmap::new(clock.now_secs(), HEATMAP_BUCKET_SECS, &suffix)
+    } else {
+        Heatmap::disabled()
+    };
+    let metrics = if subsystems.metrics && engine.primary { Metrics::from_config(&metrics_config, &mut tasks).await? } else { Metrics::disabled() };
+    metrics.info("build_info", &run_info.labels());
+    // Latency SLOs: a breach alerts and halts quoting until it clears
+    let mut slo = SloMonitor::new(metrics_config.slo.clone());
+    if slo.is_enabled() {
+        println!("[SLO] Enforcing {:?}", metrics_config.slo);
+    }
+    let mut last_reacted_book_ms: i64 = 0;
+    let sink = EventSink::from_config(&sink_config, &mut tasks).await?;
+    let mut dataset = if engine.primary { Dataset::from_config(&dataset_config, &mut tasks)? } else { Dataset::disabled() };
     let mut session_stats = SessionStats::new();
     let mut window_stats = WindowStats::new();
 
     // Create event channel
     let (tx, mut rx) = mpsc::channel::<Event>(1000);
 
+    // Order acks/rejects come back on the main channel (never through the soak proxy)
+    executor = executor.with_events(tx.clone());
+    if paper_mode {
+        executor = executor.with_paper(&paper_config);
+    }
+
+    // Soak run: feeds go through the fault-injecting proxy
+    let feed_tx = if soak_report.is_some() {
+        paper::spawn_proxy(FaultInjector::new(paper_config.faults, paper_config.seed, fault_counts.clone()), tx.clone())
+    } else {
+        tx.clone()
+    };
+
     // Spawn feeds
-    binance::spawn(tx.clone());
-    let poly_feed = PolymarketFeed::new(market.yes_token.clone(), market.no_token.clone());
-    let mut poly_handle = poly_feed.spawn(tx.clone());
-
-    // Spawn user WebSocket for fill notifications
-    // Save credentials for UserFeed restarts
-    let user_api_key_saved = user_api_key.clone();
-    let user_api_secret_saved = user_api_secret.clone();
-    let user_api_passphrase_saved = user_api_passphrase.clone();
-    let proxy_wallet_saved = proxy_wallet.clone();
-
-    let user_feed_config = UserFeedConfig {
-        api_key: user_api_key,
-        api_secret: user_api_secret,
-        api_passphrase: user_api_passphrase,
-        maker_address: proxy_wallet,
-        yes_token: market.yes_token.clone(),
-        no_token: market.no_token.clone(),
+    let recorder = if engine.primary { Recorder::from_config(&recorder_config, &mut tasks)? } else { Recorder::disabled() };
+    let symbol = engine.market.binance_symbol();
+    tasks.track(Stage::Feeds, "binance", binance::spawn(&symbol, feed_tx.clone(), recorder.clone()));
+    tasks.track(Stage::Feeds, "binance-perp", binance::spawn_perp(&symbol, feed_tx.clone(), recorder.clone()));
+    tasks.track(Stage::Feeds, "status", status::spawn(feed_tx.clone()));
+    // config.toml edits reach the loop as ConfigChanged
+    tasks.track(Stage::Feeds, "config-watch", config_watch::spawn("config.toml".to_string(), run_info.config_hash.clone(), feed_tx.clone()));
+
+    // Ctrl+C stops the loop through the event channel, like any other
+    // shutdown; a second Ctrl+C exits without waiting for cancels
+    let shutdown_tx = tx.clone();
+    tasks.spawn(Stage::Feeds, "ctrl-c", async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            println!("\n[SHUTDOWN] Ctrl+C: cancelling orders (Ctrl+C again to exit now)");
+            let _ = shutdown_tx.send(Event::Shutdown).await;
+            if tokio::signal::ctrl_c().await.is_ok() {
+                println!("[SHUTDOWN] Exiting without waiting; orders may still be live");
+                std::process::exit(130);
+            }
+        }
+    });
+    if let Some(addr) = signal_config.http_addr.as_ref().filter(|_| engine.primary) {
+        tasks.track(Stage::Feeds, "signal", signal::serve(addr, feed_tx.clone()).await?);
+        println!("=== EXTERNAL SIGNALS on http://{} ===", addr);
+    }
+    let poly_feed = PolymarketFeed::new(market.yes_token.clone(), market.no_token.clone())
+        .with_recorder(recorder.clone());
+    let mut poly_handle = poly_feed.spawn(feed_tx.clone());
+
+    // Spawn user WebSocket for fill notifications (none in observer mode)
+    let spawn_user_feed = |yes_token: &str, no_token: &str| {
+        user_feed_template.as_ref().map(|template| {
+            let config = UserFeedConfig {
+                yes_token: yes_token.to_string(),
+                no_token: no_token.to_string(),
+                ..template.clone()
+            };
+            UserFeed::new(config).spawn(feed_tx.clone())
+        })
+    };
+    let mut user_handle = spawn_user_feed(&market.yes_token, &market.no_token);
+
+    // Data API: window-end flow report, and the optional whale guard
+    let data_http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(3))
+        .build()
+        .unwrap_or_default();
+    let our_wallet = std::env::var("POLY_PROXY_WALLET").ok();
+    let spawn_whale = |condition_id: &str| {
+        whale_guard.then(|| whale::spawn(condition_id.to_string(), our_wallet.clone(), feed_tx.clone()))
+    };
+    let mut whale_handle = spawn_whale(&market.condition_id);
+    // Closed windows with unpaired shares, redeemed once they resolve
+    let mut settlement = settlement::Settlement::new();
+
+    // Collateral: resting bids are capped at the USDC both held and approved
+    // (and at --balance, if lower), polled every 30s (live trading only)
+    let mut wallet_balance: Option<Decimal> = balance_usd.and_then(|b| Decimal::try_from(b).ok());
+    if let Some(wallet) = our_wallet.clone().filter(|_| !log_only && !paper_mode) {
+        tasks.spawn(Stage::Feeds, "balance", balance::run(wallet, feed_tx.clone()));
+    }
+    let mut whale_side: Option<Side> = None;
+
+    // Shadow accounting: the exchange's view of our holdings, diffed against
+    // Position every minute (live trading only; dry and paper runs hold nothing)
+    let spawn_positions = |condition_id: &str| {
+        our_wallet
+            .clone()
+            .filter(|_| !log_only && !paper_mode)
+            .map(|wallet| positions::spawn(wallet, condition_id.to_string(), feed_tx.clone()))
+    };
+    let mut positions_handle = spawn_positions(&market.condition_id);
+    let mut shadow = ShadowPosition::new();
+
+    // Paused or resolved early: stand aside until rollover (or until it trades again)
+    let spawn_market_status = |market: &gamma::MarketInfo| {
+        market_status::spawn(market.slug.clone(), market.condition_id.clone(), feed_tx.clone())
+    };
+    let mut market_status_handle = spawn_market_status(&market);
+    let mut market_halt: Option<String> = None;
+
+    // --reward-band: only quote inside the market's liquidity-reward band
+    let reward_band_for = |market: &gamma::MarketInfo| {
+        market.rewards_max_spread.filter(|_| reward_band_only).and_then(RewardBand::from_cents)
     };
-    let user_feed = UserFeed::new(user_feed_config);
-    let mut user_handle = user_feed.spawn(tx.clone());
+    let mut reward_band = reward_band_for(&market);
+
+    // Set while the exchange reports an outage: cancel-only
+    let mut exchange_down: Option<String> = None;
+
+    // Observer mode: the ladder we'd be quoting, for hypothetical fills
+    let mut would_be_yes: u16 = 0;
+    let mut would_be_no: u16 = 0;
 
     // Create state
     let mut book = Book::default();
     let mut position = Position::default();
+    // Realized/unrealized P&L, marked at the last YES mid seen
+    let mut pnl = Pnl::new(&market.slug);
+    let mut last_mark: u16 = 500;
     let mut orders = OrderTracker::new();
+
+    // Restarted mid-market: start from the exchange's open orders and our
+    // holdings rather than blind (live trading only)
+    if !log_only && !paper_mode {
+        match executor.open_orders().await {
+            Ok(open) if open.is_empty() => {}
+            Ok(open) if risk_config.adopt_open_orders => {
+                let adopted = adopt_orders(&mut orders, &open);
+                println!("[STARTUP] Adopted {} open order(s) from a previous run", adopted);
+            }
+            Ok(open) => {
+                println!("[STARTUP] Cancelling {} open order(s) from a previous run", open.len());
+                let cancels = open.into_iter().map(|o| Action::cancel(o.order_id)).collect();
+                if let Err(e) = executor.execute(cancels, &mut orders).await {
+                    println!("[STARTUP] Cancelling leftover orders failed: {}", e);
+                }
+            }
+            Err(e) => println!("[STARTUP] Open order query failed, starting without them: {}", e),
+        }
+        if let Some(wallet) = &our_wallet {
+            match data::fetch_holdings(&data_http, wallet, &market.condition_id).await {
+                Ok(holdings) => {
+                    position = position_from_holdings(&holdings);
+                    if position.qty_yes > Decimal::ZERO || position.qty_no > Decimal::ZERO {
+                        println!("[STARTUP] Holding {} YES / {} NO from a previous run", position.qty_yes, position.qty_no);
+                    }
+                }
+                Err(e) => println!("[STARTUP] Holdings query failed, starting flat: {}", e),
+            }
+        }
+    }
+
+    // Crash recovery: a restart in the window the ledger was last written in
+    // picks its position back up (unless the exchange already gave us one),
+    // and a recent ledger carries session P&L over
+    let ledger_path = ledger::path(paper_mode, &suffix);
+    if !log_only && std::path::Path::new(&ledger_path).exists() {
+        match ledger::read_ledger(&ledger_path).map(|entries| ledger::recover(&entries)) {
+            Ok(Some(recovered)) => {
+                if clock.now_secs() - recovered.ts < ledger::RESUME_SECS {
+                    pnl.resume(recovered.session_realized_usd);
+                    println!("[LEDGER] Resumed session realized P&L ${:+.2}", recovered.session_realized_usd);
+                }
+                if recovered.market == market.slug && position.is_empty() && !recovered.position.is_empty() {
+                    println!(
+                        "[LEDGER] Restored {} YES / {} NO ({} fill(s) replayed)",
+                        recovered.position.qty_yes, recovered.position.qty_no, recovered.fills_replayed
+                    );
+                    position = recovered.position;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => println!("[LEDGER] Recovery failed, starting fresh: {}", e),
+        }
+    }
+    let mut ledger = if log_only { Ledger::disabled() } else { Ledger::open(&ledger_path)? };
     let mut last_fill_time_yes: f64 = 0.0;
     let mut last_fill_time_no: f64 = 0.0;
 
@@ -250,24 +754,202 @@ async fn main() -> Result<()> {
         window_secs: BTC_WINDOW_SECS,
         cooldown_secs: BTC_COOLDOWN_SECS,
     });
+    // Max position shrinks as expiry approaches; the heavy side stops quoting at it
+    let mut position_schedule = PositionSchedule::new(&risk_config.position_schedule);
+    let mut breaker_cooldown = risk_config.cooldown_secs;
+    let mut recovery = Recovery::new(&risk_config);
+    // Supervised mode: large batches wait for Approve/Reject
+    let mut supervisor = Supervisor::new(&risk_config);
+    if supervisor.is_enabled() {
+        println!("=== SUPERVISED: batches over {} shares wait for approval ===", risk_config.supervised_min_shares);
+    }
+    let mut breaker = CircuitBreaker::new(risk_config);
+    // Tape, fill streak and staleness guards from [strategy]
+    let mut tape = TapeVelocity::new(strategy.tape.clone());
+    let mut ofi = OfiTracker::new(OfiConfig {
+        window_secs: OFI_WINDOW_SECS,
+        depth_ticks: OFI_DEPTH_TICKS,
+        widen_threshold: OFI_WIDEN_SHARES,
+        widen_ticks: OFI_WIDEN_TICKS,
+    });
+    let mut external = ExternalInput::new(ExternalSignalConfig {
+        fair_weight: signal_config.fair_weight,
+        against_ticks: (signal_config.against_cents * 10.0).round() as u16,
+        max_ttl_secs: signal_config.max_ttl_ms as f64 / 1000.0,
+    });
+    let mut basis = BasisTracker::new(BasisConfig {
+        weight: basis_config.weight,
+        fast_secs: BASIS_FAST_SECS,
+        slow_secs: BASIS_SLOW_SECS,
+        full_bps: basis_config.full_bps,
+        max_shift: basis_config.max_shift_cents / 100.0,
+    });
+    let mut last_basis_log = 0.0;
+    let mut annealer = MarginAnnealer::new(AnnealConfig {
+        step_ticks: ANNEAL_STEP_TICKS,
+        min_extra: 0,
+        max_extra: ANNEAL_MAX_EXTRA,
+        markout_secs: ANNEAL_MARKOUT_SECS,
+        toxic_ticks: ANNEAL_TOXIC_TICKS,
+        clean_secs: ANNEAL_CLEAN_SECS,
+    }, clock.now_secs());
+    let mut fill_streak = FillStreakGuard::new(strategy.fill_streak.clone());
+    let staleness = strategy.staleness.clone();
+    let mut btc_filter = PriceFilter::new(PriceFilterConfig {
+        window: BTC_FILTER_WINDOW,
+        max_deviation_pct: BTC_FILTER_MAX_DEV,
+        ..Default::default()
+    });
 
     // Create A-S pricer
-    let as_pricer = AvellanedaStoikov::new(AS_GAMMA);
+    let mut as_pricer = AvellanedaStoikov::new(params.gamma);
 
     // 50ms tick interval
     let mut tick_interval = interval(Duration::from_millis(TICK_MS));
     let mut last_btc_price: f64 = 0.0;
+    // 1s/5s/1m BTC candles (kept across markets)
+    let mut candles = Candles::default();
 
     println!("Starting event loop... (Ctrl+C to quit)\n");
-    logger.window_start(&market.slug);
+    logger.window_start(&market.slug, fee_rate_bps);
+    let mut session_span = info_span!("market_session", slug = %market.slug);
+
+    let mut invariants = InvariantChecker::new(InvariantConfig::default());
+    if paranoid {
+        println!("=== PARANOID MODE (halting on any invariant violation) ===");
+    }
+    let mut canary = (canary_enabled && !log_only).then(|| {
+        println!("=== CANARY every {:.0}s ===", CANARY_INTERVAL_SECS);
+        CanaryMonitor::new(CanaryConfig {
+            interval_secs: CANARY_INTERVAL_SECS,
+            max_rtt: Duration::from_millis(CANARY_MAX_RTT_MS),
+        })
+    });
 
     loop {
+        // Paranoid mode: validate state after every processed event.
+        // A soak run tallies violations instead of halting on them.
+        if let Some(report) = soak_report.as_mut() {
+            let now = clock.now_secs();
+            let violations = invariants.check(now, &book, &orders, &position);
+            for v in report.record(now, &violations) {
+                println!("[SOAK] Invariant violated: {}", v);
+            }
+            if report.is_done(now) {
+                println!("[SOAK] Run complete");
+                break;
+            }
+        } else if paranoid {
+            let violations = invariants.check(clock.now_secs(), &book, &orders, &position);
+            if !violations.is_empty() {
+                for v in &violations {
+                    println!("[INVARIANT] {}", v);
+                    tracing::error!("Invariant violated: {}", v);
+                }
+                println!("[INVARIANT] Halting: cancelling all orders and exiting");
+                break;
+            }
+        }
+
         tokio::select! {
             // 50ms strategy tick
             _ = tick_interval.tick() => {
-                let now = now_secs();
+                let now = clock.now_secs();
                 let time_left = market_end - now;
                 let market_age = now - market_start;
+                candles.roll(now);
+                // Orders placed and cancelled since the last tick, whichever path did it
+                ledger.sync(now, &market.slug, &orders);
+                ledger.snapshot_if_due(now, &market.slug, &position, pnl.report(&position, last_mark).session_realized_usd);
+                // Bids lock what the other engines' bids leave of the wallet
+                risk.report(&label, orders.locked_notional() / Decimal::from(1000));
+                let balance = risk.available(&label, wallet_balance);
+                executor.set_balance(match canary_cap {
+                    Some(cap) => Some(canary::available(cap, &position, balance)),
+                    None => balance,
+                });
+
+                if now - last_status >= STATUS_INTERVAL_SECS {
+                    last_status = now;
+                    control.publish(EngineEvent::Status(EngineStatus {
+                        market: market.slug.clone(),
+                        paused: paused.is_some(),
+                        time_left,
+                        yes_target: would_be_yes,
+                        no_target: would_be_no,
+                        qty_yes: position.qty_yes.to_string().parse().unwrap_or(0.0),
+                        qty_no: position.qty_no.to_string().parse().unwrap_or(0.0),
+                        resting_orders: orders.total_count(),
+                    }));
+                    let report = pnl.report(&position, last_mark);
+                    metrics.gauge("pnl_realized", report.realized_usd);
+                    metrics.gauge("pnl_unrealized", report.unrealized_usd);
+                    metrics.gauge("session_pnl_realized", report.session_realized_usd);
+                    metrics.gauge("session_pnl_unrealized", report.session_unrealized_usd);
+                    control.publish(EngineEvent::Pnl(report));
+                }
+
+                // Closed windows: check and redeem off the tick; the payout is
+                // booked when the SettlementResult comes back
+                for pending in settlement.due(now) {
+                    let redeem = match ctf_client.as_ref().filter(|_| !log_only) {
+                        Some(ctf_client) => match B256::from_str(&pending.condition_id) {
+                            Ok(cid) => Some((ctf_client.clone(), cid)),
+                            Err(_) => {
+                                settlement.settled(&pending.slug);
+                                continue;
+                            }
+                        },
+                        None => None,
+                    };
+                    let http = data_http.clone();
+                    let tx = tx.clone();
+                    tokio::spawn(async move {
+                        let attempt = async {
+                            let resolution = settlement::fetch_resolution(&http, &pending.slug)
+                                .await
+                                .map_err(|e| format!("resolution check failed: {}", e))?;
+                            let Some(winner) = resolution.winner(&pending.yes_label) else {
+                                return Ok(None);
+                            };
+                            let tx_hash = match redeem {
+                                Some((ctf_client, cid)) => {
+                                    let redeem_req = RedeemPositionsRequest::for_binary_market(USDC_ADDR, cid);
+                                    let resp = ctf_client
+                                        .redeem_positions(&redeem_req)
+                                        .await
+                                        .map_err(|e| format!("redeem failed: {}", e))?;
+                                    Some(resp.transaction_hash.to_string())
+                                }
+                                None => None,
+                            };
+                            Ok::<_, String>(Some(settlement::Resolved { winner, tx: tx_hash }))
+                        };
+                        let result = match tokio::time::timeout(Duration::from_secs(SETTLE_TIMEOUT_SECS), attempt).await {
+                            Ok(result) => result,
+                            Err(_) => Err(format!("no answer in {}s", SETTLE_TIMEOUT_SECS)),
+                        };
+                        let _ = tx.send(Event::SettlementResult { slug: pending.slug, result }).await;
+                    });
+                }
+                for slug in settlement.expire(now) {
+                    println!("[SETTLE] {} still unresolved after {:.0}m, leaving it to `redeem`", slug, settlement::MAX_WAIT_SECS / 60.0);
+                }
+
+                // Placements whose response was lost and never showed up live
+                if orders.pending_place_count() > 0 {
+                    for lost in executor.expire_placements(&mut orders) {
+                        println!("[ORDERS] Placement {} ({:?} {} @ {}) never confirmed, dropped", lost.order.order_id, lost.side, lost.order.original_size, lost.order.price);
+                    }
+                }
+
+                // Escalate cancels the exchange never acknowledged
+                if orders.pending_cancel_count() > 0 {
+                    for order_id in executor.escalate_cancels(&mut orders).await {
+                        session_stats.cancel_fails += 1;
+                        logger.cancel_fail(time_left, &market.slug, &order_id, "CANCEL_TIMEOUT");
+                    }
+                }
 
                 // Check if we need to switch markets
                 if time_left <= HALT_SECS {
@@ -287,6 +969,7 @@ async fn main() -> Result<()> {
 
                     // End current window
                     window_stats.finalize();
+                    dataset.end_market();
                     let yes_shares = position.qty_yes.to_string().parse::<f64>().unwrap_or(0.0);
                     let yes_avg = position.avg_price_yes().map(|p| p.to_string().parse::<f64>().unwrap_or(0.0) / 10.0).unwrap_or(0.0);
                     let no_shares = position.qty_no.to_string().parse::<f64>().unwrap_or(0.0);
@@ -300,14 +983,45 @@ async fn main() -> Result<()> {
                         no_shares,
                         no_avg,
                         min_pnl,
-                        AS_GAMMA,
+                        as_pricer.gamma,
                         HALT_SECS,
                     );
                     session_stats.merge_window(&window_stats);
+                    ledger.sync(clock.now_secs(), &market.slug, &orders);
+                    let closed = pnl.close_market(&position, last_mark);
+                    print_pnl(&closed);
+                    control.publish(EngineEvent::Pnl(closed));
                     markets_completed += 1;
+                    for variant in Variant::ALL {
+                        let fills = executor.variant_fills(variant);
+                        if fills.cost_ticks > Decimal::ZERO {
+                            println!(
+                                "    variant {}: Y={} N={} | cost=${:.2}",
+                                variant, fills.qty[0], fills.qty[1], fills.cost_usd()
+                            );
+                        }
+                    }
+                    for inst in &instances {
+                        let fills = executor.variant_fills(inst.variant);
+                        println!(
+                            "    instance {}: Y={} N={} | cost=${:.2}",
+                            inst.config.name, fills.qty[0], fills.qty[1], fills.cost_usd()
+                        );
+                    }
+
+                    // Who traded this market, and how much of it we got
+                    match data::fetch_trades(&data_http, &market.condition_id, FLOW_REPORT_TRADES).await {
+                        Ok(trades) => {
+                            let summary = data::FlowSummary::from_trades(&trades, our_wallet.as_deref(), FLOW_REPORT_TOP);
+                            let holders = data::fetch_holders(&data_http, &market.condition_id, 1).await.unwrap_or_default();
+                            logger.flow_report(&market.slug, &summary, yes_shares + no_shares, &holders);
+                            session_stats.record_flow(summary.total_volume, yes_shares + no_shares);
+                        }
+                        Err(e) => println!("[FLOW] Data API unavailable: {}", e),
+                    }
 
                     // Merge paired shares to reclaim USDC
-                    if !log_only {
+                    if let Some(ctf_client) = ctf_client.as_ref().filter(|_| !log_only) {
                         let merge_qty = position.qty_yes.min(position.qty_no);
                         if merge_qty > Decimal::ZERO {
                             let merge_f64 = merge_qty.to_string().parse::<f64>().unwrap_or(0.0);
@@ -322,6 +1036,11 @@ async fn main() -> Result<()> {
                         }
                     }
 
+                    // Unpaired shares pay out once the market resolves
+                    if position.qty_yes != position.qty_no {
+                        settlement.queue(&market, position.qty_yes, position.qty_no, clock.now_secs());
+                    }
+
                     // Check if we should quit
                     if let Some(max) = max_markets {
                         if markets_completed >= max {
@@ -331,50 +1050,100 @@ async fn main() -> Result<()> {
                     }
 
                     // Fetch current market (which is now the new one since we're at T-0)
-                    match gamma::get_current_5m_market().await {
+                    match engine.market.fetch_at(&clock).await {
                         Ok(new_market) => {
                             market = new_market;
-                            market_start = gamma::parse_start_epoch(&market.slug)
-                                .map(|e| e as f64)
-                                .unwrap_or(now_secs());
-                            market_end = market_start + 300.0;
-
-                            // Reset state
-                            var_est.reset();
-                            flow_est.reset();
+                            (market_start, market_end, market_duration) =
+                                market_window(&market, configured_duration, &clock);
+                            apply_market_strategy(&strategy_config, &mut strategy, &market, market_duration);
+
+                            // Reset state, warm-starting the market signals
+                            var_est.carry_over(WARM_START_DECAY);
+                            flow_est.carry_over(WARM_START_DECAY);
+                            tape.reset();
+                            external.reset();
+                            ofi.reset();
                             btc_guard.reset();
+                            fill_streak.reset();
+                            annealer.carry_over(clock.now_secs(), WARM_START_DECAY);
+                            invariants.reset();
                             position.reset();
+                            pnl.open_market(&market.slug);
+                            last_mark = 500;
                             orders.clear_all();
+                            ledger.roll(clock.now_secs(), &market.slug, &position, pnl.report(&position, last_mark).session_realized_usd);
+                            supervisor.clear();
                             last_fill_time_yes = 0.0;
                             last_fill_time_no = 0.0;
                             book = Book::default();
                             window_stats = WindowStats::new();
 
+                            // Fee rate for the new market: a failed fetch keeps the last
+                            // one, a rate above the max stops the bot
+                            match fee_rates::fetch_fee_rate(&market.yes_token).await {
+                                Ok(bps) => fee_rate_bps = fee_rates::check_fee_rate(&market.yes_token, bps, MAX_FEE_RATE_BPS)?,
+                                Err(e) => println!("[FEES] Fee rate fetch failed, keeping {}bps: {}", fee_rate_bps, e),
+                            }
+
                             // Update executor tokens
-                            executor.set_market(market.yes_token.clone(), market.no_token.clone());
+                            executor.set_market(
+                                market.yes_token.clone(),
+                                market.no_token.clone(),
+                                (market_end * 1000.0) as i64,
+                                market.tick_size,
+                                fee_rate_bps,
+                            );
+                            executor.reset_variants();
+
+                            // Swap in a rebuilt plugin between windows, never mid-window
+                            if let Some(p) = plugin.as_mut() {
+                                match WasmPlugin::load(p.path()) {
+                                    Ok(fresh) => *p = fresh,
+                                    Err(e) => println!("[PLUGIN] Reload failed, keeping previous: {:#}", e),
+                                }
+                            }
 
                             // Restart polymarket feed
                             poly_handle.abort();
+                            slo.forget_feed("polymarket");
                             let new_feed = PolymarketFeed::new(
                                 market.yes_token.clone(),
                                 market.no_token.clone(),
-                            );
-                            poly_handle = new_feed.spawn(tx.clone());
+                            )
+                            .with_recorder(recorder.clone());
+                            poly_handle = new_feed.spawn(feed_tx.clone());
 
                             // Restart user feed with new tokens
-                            user_handle.abort();
-                            let new_user_config = UserFeedConfig {
-                                api_key: user_api_key_saved.clone(),
-                                api_secret: user_api_secret_saved.clone(),
-                                api_passphrase: user_api_passphrase_saved.clone(),
-                                maker_address: proxy_wallet_saved.clone(),
-                                yes_token: market.yes_token.clone(),
-                                no_token: market.no_token.clone(),
-                            };
-                            let new_user_feed = UserFeed::new(new_user_config);
-                            user_handle = new_user_feed.spawn(tx.clone());
+                            if let Some(handle) = user_handle.take() {
+                                handle.abort();
+                            }
+                            user_handle = spawn_user_feed(&market.yes_token, &market.no_token);
 
-                            logger.window_start(&market.slug);
+                            // Restart whale guard for the new market
+                            if let Some(handle) = whale_handle.take() {
+                                handle.abort();
+                            }
+                            whale_handle = spawn_whale(&market.condition_id);
+                            whale_side = None;
+
+                            // Shadow position check for the new market
+                            if let Some(handle) = positions_handle.take() {
+                                handle.abort();
+                            }
+                            positions_handle = spawn_positions(&market.condition_id);
+                            shadow.reset();
+
+                            // Status of the new market
+                            market_status_handle.abort();
+                            market_status_handle = spawn_market_status(&market);
+                            if market_halt.take().is_some() {
+                                alerts::resolve("market:status");
+                            }
+                            reward_band = reward_band_for(&market);
+
+                            logger.window_start(&market.slug, fee_rate_bps);
+                            println!(">>> {} YES={} NO={}", market_duration.label(), market.yes_label, market.no_label);
+                            session_span = info_span!("market_session", slug = %market.slug);
                         }
                         Err(e) => {
                             println!(">>> Failed to fetch current market: {}", e);
@@ -384,11 +1153,81 @@ async fn main() -> Result<()> {
                     continue;
                 }
 
+                // Canary: test the order path before real quotes depend on it.
+                // It runs off the tick and reports back as a CanaryResult.
+                if let Some(canary) = canary.as_mut().filter(|_| market_halt.is_none()) {
+                    if canary.is_due(now) {
+                        canary.start(now);
+                        let probe = executor.canary(Side::Yes);
+                        let tx = tx.clone();
+                        tokio::spawn(async move {
+                            let result = match tokio::time::timeout(Duration::from_secs(CANARY_TIMEOUT_SECS), probe).await {
+                                Ok(result) => result.map_err(|e| e.to_string()),
+                                Err(_) => Err(format!("no response in {}s", CANARY_TIMEOUT_SECS)),
+                            };
+                            let _ = tx.send(Event::CanaryResult { result }).await;
+                        });
+                    }
+                }
+                let is_degraded = canary.as_ref().is_some_and(|c| c.degraded().is_some());
+
+                // Latency SLOs
+                match slo.check(now) {
+                    Some(SloChange::Breached(reason)) => {
+                        println!("[SLO] Breached: {} (halting)", reason);
+                        alerts::raise("latency:slo", &reason);
+                        metrics.incr("slo_breaches", 1);
+                    }
+                    Some(SloChange::Cleared) => {
+                        println!("[SLO] Met again, resuming");
+                        alerts::resolve("latency:slo");
+                    }
+                    None => {}
+                }
+                if let Some(p99) = slo.cancel_p99() {
+                    metrics.gauge("book_to_cancel_p99_ms", p99);
+                }
+                let is_slo_breached = slo.breach().is_some();
+
+                // Circuit breaker: cancel everything on an anomaly, then hold off
+                let book_age_ms = book.is_synced().then(|| (now * 1000.0) as i64 - book.last_update_ms);
+                let worst_pnl = position.min_pnl_usd().to_string().parse::<f64>().unwrap_or(0.0);
+                let trip_actions = breaker.check(now, book_age_ms, worst_pnl);
+                if !trip_actions.is_empty() {
+                    let reason = breaker.trip().map(|t| t.to_string()).unwrap_or_default();
+                    println!("[BREAKER] Tripped: {} (cancelling all, {:.0}s cooldown)", reason, breaker_cooldown);
+                    alerts::raise("risk:breaker", &reason);
+                    metrics.incr("breaker_trips", 1);
+                    session_stats.orders_cancelled += orders.total_count() as u32;
+                    let _ = executor.execute(trip_actions, &mut orders).await;
+                }
+                let is_tripped = breaker.in_cooldown(now);
+
                 // Check halt conditions
                 let in_warmup = market_age < WARMUP_SECS;
                 let in_cooldown = btc_guard.in_cooldown(now);
                 let now_ms = (now * 1000.0) as i64;
-                let is_stale = now_ms - book.last_update_ms > STALE_MS;
+                let stale_extra = staleness.extra_margin(now_ms - book.last_update_ms);
+                let is_stale = stale_extra.is_none();
+                let is_exchange_down = exchange_down.is_some();
+                let is_market_halted = market_halt.is_some();
+
+                // Recovery from a trip: warm up once the cooldown's over, then ramp size back
+                let healthy = book.is_synced() && !is_stale && !book.is_locked_or_crossed() && !in_cooldown && !is_degraded && !is_exchange_down && !is_slo_breached;
+                if let Some(stage) = recovery.update(now, is_tripped, healthy) {
+                    let size_fraction = recovery.size_fraction(now);
+                    match stage {
+                        RecoveryStage::WarmingUp { .. } => {
+                            println!("[RECOVERY] Cooldown over, warming up");
+                            alerts::resolve("risk:breaker");
+                        }
+                        RecoveryStage::Ramping { .. } => println!("[RECOVERY] Resuming at {:.0}% size", size_fraction * 100.0),
+                        RecoveryStage::Normal => println!("[RECOVERY] Back to full size"),
+                        RecoveryStage::Tripped => {}
+                    }
+                    control.publish(EngineEvent::Recovery { stage: stage.to_string(), size_fraction });
+                }
+                let is_recovering = recovery.is_halted();
 
                 // Need book data
                 if !book.is_synced() {
@@ -399,28 +1238,83 @@ async fn main() -> Result<()> {
                 let yes_bid = book.best_bid(Side::Yes).unwrap();
                 let yes_ask = book.best_ask(Side::Yes).unwrap();
                 let mid = (yes_bid + yes_ask) as f64 / 2.0 / 1000.0;
+                dataset.update(now, mid);
                 let mid_valid = Quotes::should_quote(mid);
+                let is_locked = book.is_locked_or_crossed();
 
                 // If any halt condition, cancel all and skip
-                if in_warmup || !mid_valid || in_cooldown || is_stale {
+                if in_warmup
+                    || !mid_valid
+                    || in_cooldown
+                    || is_stale
+                    || is_locked
+                    || is_degraded
+                    || is_slo_breached
+                    || is_tripped
+                    || is_recovering
+                    || is_exchange_down
+                    || is_market_halted
+                    || paused.is_some()
+                {
                     window_stats.ticks_halted += 1;
+                    metrics.incr("ticks_halted", 1);
+                    let reason = if in_warmup {
+                        "WARMUP"
+                    } else if !mid_valid {
+                        "MID_RANGE"
+                    } else if in_cooldown {
+                        "BTC_COOLDOWN"
+                    } else if is_locked {
+                        "LOCKED_BOOK"
+                    } else if is_degraded {
+                        "DEGRADED"
+                    } else if is_slo_breached {
+                        "SLO"
+                    } else if is_tripped {
+                        "BREAKER"
+                    } else if is_recovering {
+                        "RECOVERING"
+                    } else if is_exchange_down {
+                        "EXCHANGE_DOWN"
+                    } else if is_market_halted {
+                        "MARKET_HALTED"
+                    } else if paused.is_some() {
+                        "PAUSED"
+                    } else {
+                        "STALE_BOOK"
+                    };
+                    if let Some(id) = supervisor.clear() {
+                        println!("[SUPERVISOR] Batch {} dropped: {}", id, reason);
+                    }
+                    journal.record_if_due(now, || {
+                        let snapshot = Snapshot::capture(now, &market.slug, time_left, &book, &orders, &position, Signals {
+                            btc: last_btc_price,
+                            mid,
+                            var: var_est.current_var(),
+                            k: flow_est.current_k(),
+                            halt: Some(reason.to_string()),
+                            candles: candles.signals(),
+                            ..Default::default()
+                        });
+                        sink.snapshot(&snapshot);
+                        snapshot
+                    });
                     if orders.total_count() > 0 {
-                        let reason = if in_warmup {
-                            "WARMUP"
-                        } else if !mid_valid {
-                            "MID_RANGE"
-                        } else if in_cooldown {
-                            "BTC_COOLDOWN"
-                        } else {
+                        if reason == "STALE_BOOK" {
                             window_stats.stale_halts += 1;
-                            "STALE_BOOK"
-                        };
+                        }
                         let cancelled = orders.total_count();
                         logger.halt(time_left, &market.slug, mid, var_est.current_var(), reason, cancelled);
+                        control.publish(EngineEvent::Halt { reason: reason.to_string() });
                         let actions = vec![Action::CancelAll];
                         let _ = executor.execute(actions, &mut orders).await;
                         session_stats.orders_cancelled += cancelled as u32;
                     }
+                    if would_be_yes > 0 || would_be_no > 0 {
+                        sink.quote(now, &market.slug, 0, 0);
+                    }
+                    would_be_yes = 0;
+                    would_be_no = 0;
                     continue;
                 }
 
@@ -429,27 +1323,84 @@ async fn main() -> Result<()> {
                 let var = var_est.current_var();
                 let k = flow_est.current_k();
 
-                let quotes = as_pricer.compute_quotes(mid, inventory, var, k, time_left);
+                // External fair value (if live) and the basis prior move the pricing mid, not the halt checks
+                let pricing_mid = basis.adjust_mid(external.adjust_mid(now, mid));
+                if now - last_basis_log >= BASIS_LOG_SECS {
+                    if let Some(reading) = basis.reading() {
+                        last_basis_log = now;
+                        logger.basis(time_left, &market.slug, mid, &reading);
+                    }
+                }
+                let quotes = as_pricer.compute_quotes(pricing_mid, inventory, var, k, time_left);
+
+                // Margin annealing from fill markouts
+                if let Some(adj) = annealer.update(now, &book) {
+                    logger.anneal(time_left, &market.slug, &adj);
+                    session_stats.margin_adjustments.push(format!("{} {}", market.slug, adj));
+                    metrics.incr("margin_adjustments", 1);
+                }
 
-                // Convert to ticks and round to cents, clamp to stay maker
+                // Tape pressure: extra margin per side, None = pull that side
+                let tape_signal = tape.signal(now);
+                let yes_tape = tape_signal.extra_margin(Side::Yes, tape.config());
+                let no_tape = tape_signal.extra_margin(Side::No, tape.config());
+
+                // Convert to ticks, widen for book age, tape, flow and inventory, round to cents, clamp to stay maker
+                let stale_extra = stale_extra.unwrap_or(0) + annealer.extra() + params.extra_margin;
+                let whale_extra = |side| if whale_side == Some(side) { WHALE_WIDEN_TICKS } else { 0 };
+                let flow_extra = |side| whale_extra(side) + external.extra_margin(now, side) + ofi.extra_margin(now, side);
+                let tolerance = strategy.inventory.tolerance(
+                    strategy.rebalance_threshold,
+                    time_left,
+                    market_duration.total_secs() as f64,
+                );
+                let deviation = Deviation::measure(&position, tolerance);
+                let lean = |side| deviation.filter(|d| d.heavy == side).map_or(0, |d| d.lean_ticks(&strategy.inventory));
+                let yes_extra = stale_extra + yes_tape.unwrap_or(0) + flow_extra(Side::Yes) + lean(Side::Yes);
+                let no_extra = stale_extra + no_tape.unwrap_or(0) + flow_extra(Side::No) + lean(Side::No);
                 let no_ask = book.best_ask(Side::No).unwrap_or(1000);
-                let mut yes_target = round_to_cents(AvellanedaStoikov::to_ticks(quotes.yes_bid))
+                let mut yes_target = round_to_cents(AvellanedaStoikov::to_ticks(quotes.yes_bid).saturating_sub(yes_extra))
                     .min(yes_ask.saturating_sub(NO_CROSS_MARGIN));
-                let mut no_target = round_to_cents(AvellanedaStoikov::to_ticks(quotes.no_bid))
+                let mut no_target = round_to_cents(AvellanedaStoikov::to_ticks(quotes.no_bid).saturating_sub(no_extra))
                     .min(no_ask.saturating_sub(NO_CROSS_MARGIN));
 
-                // Pair cost cap: don't bid more than would keep pair cost < MAX_PAIR_TICKS
+                // Pair cost cap: don't bid more than would keep pair cost under $1 - margin
+                let max_pair_ticks = 1000u16.saturating_sub(strategy.margin_ticks);
                 if let Some(avg_no) = position.avg_price_no() {
-                    let cap = MAX_PAIR_TICKS.saturating_sub(avg_no.to_string().parse::<f64>().unwrap_or(0.0) as u16);
+                    let cap = max_pair_ticks.saturating_sub(avg_no.to_string().parse::<f64>().unwrap_or(0.0) as u16);
                     yes_target = yes_target.min(round_to_cents(cap));
                 }
                 if let Some(avg_yes) = position.avg_price_yes() {
-                    let cap = MAX_PAIR_TICKS.saturating_sub(avg_yes.to_string().parse::<f64>().unwrap_or(0.0) as u16);
+                    let cap = max_pair_ticks.saturating_sub(avg_yes.to_string().parse::<f64>().unwrap_or(0.0) as u16);
                     no_target = no_target.min(round_to_cents(cap));
                 }
 
+                // Reward band: a target outside it doesn't earn, so don't quote that side
+                let yes_mid = (mid * 1000.0).round() as u16;
+                let in_band = |side, target| reward_band.is_none_or(|b| b.contains(side, target, yes_mid));
+                let yes_in_band = in_band(Side::Yes, yes_target);
+                let no_in_band = in_band(Side::No, no_target);
+
+                let quoted = (
+                    if yes_in_band { yes_target } else { 0 },
+                    if no_in_band { no_target } else { 0 },
+                );
+                if quoted != (would_be_yes, would_be_no) {
+                    sink.quote(now, &market.slug, quoted.0, quoted.1);
+                }
+                (would_be_yes, would_be_no) = quoted;
+
                 // Track that we're quoting this tick
                 window_stats.ticks_quoted += 1;
+                let capital = position.capital_employed_usd(orders.locked_notional());
+                let nav = position.nav_usd((mid * 1000.0) as u16);
+                window_stats.record_capital(
+                    capital.to_string().parse::<f64>().unwrap_or(0.0),
+                    nav.to_string().parse::<f64>().unwrap_or(0.0),
+                );
+
+                heatmap.record_quotes(now, Side::Yes, &orders.prices(Side::Yes));
+                heatmap.record_quotes(now, Side::No, &orders.prices(Side::No));
 
                 // Get current resting prices
                 let yes_resting = orders.top_price(Side::Yes).unwrap_or(0);
@@ -469,27 +1420,100 @@ async fn main() -> Result<()> {
                     no_resting,
                 );
 
-                // Reconcile orders
+                let signals = Signals {
+                    btc: last_btc_price,
+                    mid,
+                    var,
+                    k,
+                    yes_target,
+                    no_target,
+                    halt: None,
+                    candles: candles.signals(),
+                };
+                journal.record_if_due(now, || {
+                    let snapshot = Snapshot::capture(now, &market.slug, time_left, &book, &orders, &position, signals.clone());
+                    sink.snapshot(&snapshot);
+                    snapshot
+                });
+                // What the decision below is made from, for the dataset
+                let decision_frame = dataset
+                    .is_enabled()
+                    .then(|| Snapshot::capture(now, &market.slug, time_left, &book, &orders, &position, signals.clone()));
+
+                // Reconcile orders. The main ladder only sees its own orders;
+                // extra instances manage theirs below.
                 let mut actions = Vec::new();
-
-                // YES side: skip if in fill cooldown
-                let old_yes = yes_resting;
-                if now - last_fill_time_yes >= FILL_COOLDOWN_SECS {
-                    reconcile_side(Side::Yes, yes_target, &orders, &mut actions);
+                let order_size = Decimal::from(ORDER_SIZE);
+                // Position limit for the time left, scaled down while ramping back from a trip
+                let size_fraction = Decimal::try_from(recovery.size_fraction(now)).unwrap_or(Decimal::ONE);
+                let capped = |side| !position_schedule.allows(side, &position, strategy.max_position * size_fraction, time_left);
+                let yes_pulled = fill_streak.is_paused(Side::Yes, now) || yes_tape.is_none() || !yes_in_band || capped(Side::Yes);
+                let no_pulled = fill_streak.is_paused(Side::No, now) || no_tape.is_none() || !no_in_band || capped(Side::No);
+
+                if let Some(p) = plugin.as_mut() {
+                    // Plugin decides the ladder from the same frame the journal records
+                    let ladder_orders = orders.of(Variant::Ladder);
+                    let frame = Snapshot::capture(now, &market.slug, time_left, &book, &ladder_orders, &position, signals);
+                    match p.decide(&frame).and_then(|decided| plugin::to_actions(&decided, ladder_orders)) {
+                        Ok(decided) => actions = decided,
+                        Err(e) => {
+                            println!("[PLUGIN] {:#}, pulling quotes", e);
+                            metrics.incr("plugin_errors", 1);
+                            if orders.total_count() > 0 {
+                                actions.push(Action::CancelAll);
+                            }
+                        }
+                    }
+                } else {
+                    // Each side: pull if streak-paused, tape-pulled, out of band or at max position, hold if in
+                    // fill cooldown, else reconcile its ladder. Then buy back the light side as taker once the
+                    // imbalance is past the inventory target.
+                    let old_yes = yes_resting;
+                    let old_no = no_resting;
+                    let quote = |pulled: bool, last_fill: f64, target: u16| {
+                        if pulled {
+                            SideQuote::Pull
+                        } else if now - last_fill >= FILL_COOLDOWN_SECS {
+                            SideQuote::Ladder(target)
+                        } else {
+                            SideQuote::Hold
+                        }
+                    };
+                    let tick_quotes = TickQuotes {
+                        yes: quote(yes_pulled, last_fill_time_yes, yes_target),
+                        no: quote(no_pulled, last_fill_time_no, no_target),
+                        size: order_size,
+                        tolerance,
+                        p_mid: pricing_mid,
+                        volatility: tick_volatility(pricing_mid, var),
+                        time_left,
+                    };
+                    actions.extend(strategy::tick_actions(&tick_quotes, &position, &book, orders.of(Variant::Ladder), &strategy));
+
+                    // Log price replacements
+                    if old_yes > 0 && yes_target != old_yes {
+                        logger.replace(Side::Yes, old_yes, yes_target);
+                    }
+                    if old_no > 0 && no_target != old_no {
+                        logger.replace(Side::No, old_no, no_target);
+                    }
                 }
 
-                // NO side: skip if in fill cooldown
-                let old_no = no_resting;
-                if now - last_fill_time_no >= FILL_COOLDOWN_SECS {
-                    reconcile_side(Side::No, no_target, &orders, &mut actions);
+                // Supervised mode: hold a large batch for approval
+                if let Some(id) = supervisor.expire(now) {
+                    println!("[SUPERVISOR] Batch {} not approved in time, dropped", id);
                 }
-
-                // Log price replacements
-                if old_yes > 0 && yes_target != old_yes {
-                    logger.replace(Side::Yes, old_yes, yes_target);
+                let gated = supervisor.gate(now, actions);
+                if let Some(preview) = gated.held {
+                    println!(
+                        "[SUPERVISOR] Holding batch {} ({} shares, {:.0}s to approve): {}",
+                        preview.id, preview.shares, preview.expires_in_secs, preview.actions.join(", ")
+                    );
+                    control.publish(EngineEvent::Batch(preview));
                 }
-                if old_no > 0 && no_target != old_no {
-                    logger.replace(Side::No, old_no, no_target);
+                let actions = gated.run;
+                if let Some(frame) = &decision_frame {
+                    dataset.record(DecisionRow::new(frame, &actions));
                 }
 
                 // Count stats for actions about to execute
@@ -497,9 +1521,11 @@ async fn main() -> Result<()> {
                     match action {
                         Action::Place { .. } => {
                             session_stats.orders_placed += 1;
+                            metrics.incr("orders_placed", 1);
                         }
                         Action::Cancel { order_id } => {
                             session_stats.orders_cancelled += 1;
+                            metrics.incr("orders_cancelled", 1);
                             logger.cancel(time_left, &market.slug, order_id, "REPLACE");
                         }
                         Action::CancelAll => {
@@ -510,29 +1536,84 @@ async fn main() -> Result<()> {
                 }
 
                 // Execute actions (executor logs individual orders internally via tracing)
+                // Cancels reacting to a book update we hadn't seen time book-to-cancel
+                let reacting = book.last_update_ms > last_reacted_book_ms && actions.iter().any(Action::is_cancel);
+                last_reacted_book_ms = book.last_update_ms;
                 if !actions.is_empty() {
-                    if let Err(e) = executor.execute(actions, &mut orders).await {
+                    let cycle = info_span!(parent: &session_span, "reconcile_cycle", actions = actions.len());
+                    if let Err(e) = executor.execute(actions, &mut orders).instrument(cycle).await {
                         session_stats.order_fails += 1;
+                        metrics.incr("order_fails", 1);
                         println!("[ERROR] Executor failed: {}", e);
                     }
                 }
+                if reacting {
+                    let done = clock.now_secs();
+                    slo.record_cancel(done, done * 1000.0 - book.last_update_ms as f64);
+                }
+
+                // Extra instances: own ladders under the same targets, pulled with the main side
+                for inst in &instances {
+                    let mut inst_actions = inst.reconcile(Side::Yes, if yes_pulled { 0 } else { yes_target }, &orders);
+                    inst_actions.extend(inst.reconcile(Side::No, if no_pulled { 0 } else { no_target }, &orders));
+                    if inst_actions.is_empty() {
+                        continue;
+                    }
+                    for action in &inst_actions {
+                        match action {
+                            Action::Place { .. } => session_stats.orders_placed += 1,
+                            Action::Cancel { .. } => session_stats.orders_cancelled += 1,
+                            _ => {}
+                        }
+                    }
+                    if let Err(e) = executor.execute_as(inst.variant, inst_actions, &mut orders).await {
+                        session_stats.order_fails += 1;
+                        metrics.incr("order_fails", 1);
+                        println!("[ERROR] Executor failed for instance {}: {}", inst.config.name, e);
+                    }
+                }
+
+                metrics.gauge("mid", mid);
+                metrics.gauge("qty_yes", position.qty_yes.to_string().parse().unwrap_or(0.0));
+                metrics.gauge("qty_no", position.qty_no.to_string().parse().unwrap_or(0.0));
+                metrics.gauge("resting_orders", orders.total_count() as f64);
+                let capital = position.capital_employed_usd(orders.locked_notional());
+                metrics.gauge("capital_employed", capital.to_string().parse().unwrap_or(0.0));
             }
 
             // Process events
             Some(event) = rx.recv() => {
-                let now = now_secs();
+                let now = clock.now_secs();
 
                 let time_left = market_end - now;
 
+                match &event {
+                    Event::BtcPrice { .. } | Event::PerpPrice { .. } => slo.feed_event("binance", now),
+                    Event::BookUpdate { .. } | Event::BookDelta { .. } | Event::BookSnapshot { .. } | Event::Trade { .. } => {
+                        slo.feed_event("polymarket", now)
+                    }
+                    _ => {}
+                }
+
                 match event {
                     Event::BtcPrice { price } => {
+                        // Drop bad prints before they can trip the guard
+                        if !btc_filter.accept(price) {
+                            session_stats.btc_rejected += 1;
+                            metrics.incr("btc_rejected", 1);
+                            continue;
+                        }
                         let old_price = last_btc_price;
                         last_btc_price = price;
+                        candles.record(now, price);
+                        basis.update_spot(price);
+                        breaker.record_btc(now, price);
                         // Check for crash
                         if btc_guard.update(price, now) {
                             let cancelled = orders.total_count();
                             window_stats.toxic_cancels += 1;
                             session_stats.toxic_cancels += 1;
+                            metrics.incr("toxic_cancels", 1);
                             logger.toxic(time_left, &market.slug, old_price, price, BTC_WINDOW_SECS, cancelled);
                             if cancelled > 0 {
                                 session_stats.orders_cancelled += cancelled as u32;
@@ -545,34 +1626,140 @@ async fn main() -> Result<()> {
                     Event::BookUpdate { side, bid, ask } => {
                         book.update(side, bid, ask, (now * 1000.0) as i64);
 
-                        // Update variance estimator with YES mid
+                        // Update variance estimator and the P&L mark with YES mid
                         if side == Side::Yes && bid > 0 && ask > 0 {
                             let mid = (bid + ask) as f64 / 2.0 / 1000.0;
                             var_est.update_poly(mid, now);
+                            last_mark = (bid + ask) / 2;
+                        }
+                    }
+
+                    Event::BookDelta { side, is_bid, price, size } => {
+                        ofi.apply_delta(now, side, is_bid, price, size);
+                        if !is_bid {
+                            executor.on_ask_delta(side, price, size);
                         }
                     }
 
-                    Event::Trade { .. } => {
+                    Event::BookMismatch { side } => {
+                        println!("[BOOK] {:?} depth diverged from the feed's checksum, resyncing", side);
+                        metrics.incr("book_checksum_mismatches", 1);
+                    }
+
+                    Event::BookSnapshot { side, bids, asks } => {
+                        ofi.apply_snapshot(side, &bids, &asks);
+                        executor.on_book_snapshot(side, &asks);
+                    }
+
+                    Event::Trade { side, price, taker_buy } => {
                         flow_est.record_trade(now);
+                        tape.record(now, side, taker_buy);
+                        heatmap.record_trade(now, side, price);
+                        window_stats.entries.record_print(side, price, book.best_bid(side), ORDER_SIZE as f64);
+                        // Paper mode: fill our simulated bids this print traded through
+                        executor.on_trade(side, price);
+
+                        // Observer mode: a trade at or through our would-be bid
+                        // would have filled it
+                        let (target, last_fill) = match side {
+                            Side::Yes => (would_be_yes, &mut last_fill_time_yes),
+                            Side::No => (would_be_no, &mut last_fill_time_no),
+                        };
+                        if observe && target > 0 && price <= target && now - *last_fill >= FILL_COOLDOWN_SECS {
+                            *last_fill = now;
+                            annealer.record_fill(now, side, target);
+                            heatmap.record_fill(now, side, target, ORDER_SIZE as f64);
+                            position.apply_fill(side, target, Decimal::from(ORDER_SIZE));
+                            let old_matched = window_stats.matched_pairs;
+                            window_stats.record_fill(side, target);
+                            window_stats.entries.record_ours(side, target, ORDER_SIZE as f64);
+                            let pair_cost = if window_stats.matched_pairs > old_matched {
+                                window_stats.pair_costs.last().copied()
+                            } else {
+                                None
+                            };
+                            logger.fill(
+                                time_left,
+                                &market.slug,
+                                side,
+                                target,
+                                ORDER_SIZE as f64,
+                                "HYPOTHETICAL",
+                                true,
+                                position.qty_yes.to_string().parse::<f64>().unwrap_or(0.0),
+                                position.qty_no.to_string().parse::<f64>().unwrap_or(0.0),
+                                pair_cost,
+                                window_stats.gross_pnl,
+                            );
+                            sink.fill(now, &market.slug, &FillInfo { side, price: target, size: ORDER_SIZE as f64, order_id: "HYPOTHETICAL", is_maker: true });
+                        }
                     }
 
                     Event::OrderFill { order_id, side, price, size, is_maker } => {
+                        metrics.incr(if side == Side::Yes { "fills_yes" } else { "fills_no" }, 1);
+                        breaker.record_fill(now);
                         // Record fill time for cooldown
                         match side {
                             Side::Yes => last_fill_time_yes = now,
                             Side::No => last_fill_time_no = now,
                         }
+                        if fill_streak.record_fill(side, now) {
+                            println!(
+                                "[FILL STREAK] {} fills in a row on {:?}, pausing it {:.0}s",
+                                strategy.fill_streak.max_consecutive, side, strategy.fill_streak.cooldown_secs
+                            );
+                            metrics.incr("fill_streak_pauses", 1);
+                        }
+
+                        // A fill on a placement whose response was lost adopts it
+                        orders.adopt_pending(side, price, None, &order_id);
+
+                        // Variant that placed the order (untracked/late fills count against the ladder)
+                        let variant = orders.variant_of(&order_id).unwrap_or_default();
+
+                        // Top rung hit: pull the deeper rungs of that ladder on this side now
+                        let pulls = strategy::pull_on_top_fill(side, &order_id, orders.of(variant));
+                        if !pulls.is_empty() {
+                            for action in &pulls {
+                                if let Action::Cancel { order_id } = action {
+                                    logger.cancel(time_left, &market.slug, order_id, "FILL_PULL");
+                                }
+                            }
+                            session_stats.orders_cancelled += pulls.len() as u32;
+                            let _ = executor.execute(pulls, &mut orders).await;
+                        }
+
+                        // Charge the fill to the variant that placed the order
+                        executor.record_fill(variant, side, price, Decimal::try_from(size).unwrap_or(dec!(0)));
+                        metrics.incr(&format!("fills_{}", variant), 1);
+
+                        // Queue for markout (annealing)
+                        annealer.record_fill(now, side, price);
+                        heatmap.record_fill(now, side, price, size);
 
                         // Update position
                         let size_dec = Decimal::try_from(size).unwrap_or(dec!(0));
                         position.apply_fill(side, price, size_dec);
-
-                        // Remove from order tracker
-                        orders.update_fill(side, &order_id, size_dec);
+                        ledger.fill(now, &market.slug, &order_id, side, price, size_dec);
+
+                        // Remove from order tracker. Position above is updated
+                        // regardless: a fill that raced its cancel is still real.
+                        let race = match orders.update_fill(side, &order_id, size_dec) {
+                            FillMatch::Live => None,
+                            FillMatch::PendingCancel => Some("[LATE FILL] cancel in flight for"),
+                            FillMatch::AfterCancel => Some("[LATE FILL] already cancelled"),
+                            FillMatch::Unknown => Some("[UNKNOWN FILL] untracked"),
+                        };
+                        if let Some(label) = race {
+                            println!("{} {} ({:?} {} @ {})", label, short_id(&order_id), side, size, price);
+                            session_stats.late_fills += 1;
+                            metrics.incr("late_fills", 1);
+                        }
 
                         // Record fill for FIFO matching and pair cost calculation
                         let old_matched = window_stats.matched_pairs;
                         window_stats.record_fill(side, price);
+                        window_stats.entries.record_ours(side, price, size);
 
                         // Get pair cost if a new pair was matched
                         let pair_cost = if window_stats.matched_pairs > old_matched {
@@ -597,34 +1784,308 @@ async fn main() -> Result<()> {
                             pair_cost,
                             window_stats.gross_pnl,
                         );
-                        // Next tick will see missing order via OrderTracker and place new one
+                        // Tape at fill time, for checking offline whether it predicts toxic fills
+                        logger.tape(time_left, &market.slug, side, &order_id, tape.signal(now));
+                        sink.fill(now, &market.slug, &FillInfo { side, price, size, order_id: &order_id, is_maker });
+                        control.publish(EngineEvent::Fill { side, price, size, order_id });
+
+                        // Reconcile now rather than up to a tick later: the position
+                        // changed, so the ladder, leans and any rebalance Take did too
+                        tick_interval.reset_immediately();
                     }
 
-                    Event::Shutdown => {
-                        if orders.total_count() > 0 {
-                            session_stats.orders_cancelled += orders.total_count() as u32;
-                            let actions = vec![Action::CancelAll];
-                            let _ = executor.execute(actions, &mut orders).await;
+                    Event::Shutdown => break,
+
+                    Event::ConfigChanged { config } => {
+                        // Strategy and risk settings apply from the next tick; the
+                        // rest of the file (feeds, sinks, metrics...) needs a restart
+                        let mut next = builtin_strategy();
+                        let checked = config.strategy.apply(&mut next).and_then(|_| {
+                            next.limits = config.risk.limits()?;
+                            let fees = FeeSchedule { maker_bps: MAKER_FEE_BPS, taker_bps: TAKER_FEE_BPS };
+                            let be = breakeven(&fees, next.margin_ticks);
+                            if !be.is_viable() {
+                                anyhow::bail!("margin of {} ticks can't cover {:.2} ticks of fees per pair", next.margin_ticks, be.pair_fee_ticks);
+                            }
+                            Ok(())
+                        });
+                        match checked {
+                            Err(e) => println!("[CONFIG] Reload rejected, keeping the running settings: {:#}", e),
+                            Ok(()) => {
+                                apply_market_strategy(&config.strategy, &mut next, &market, market_duration);
+                                strategy = next;
+                                strategy_config = config.strategy;
+                                configured_duration = duration_override(&strategy_config);
+                                position_schedule = PositionSchedule::new(&config.risk.position_schedule);
+                                breaker_cooldown = config.risk.cooldown_secs;
+                                recovery.reconfigure(&config.risk);
+                                supervisor.reconfigure(&config.risk);
+                                executor.set_self_trade_overlap(config.risk.self_trade_overlap_ticks);
+                                executor.set_limits(strategy.limits.clone());
+                                breaker.reconfigure(config.risk);
+
+                                run_info = RunInfo::capture("config.toml", &strategy);
+                                println!("[CONFIG] Reloaded config.toml");
+                                logger.run_info(&run_info);
+                                metrics.info("build_info", &run_info.labels());
+                            }
                         }
-                        break;
+                    }
+
+                    Event::SettlementResult { slug, result } => match result {
+                        Err(e) => println!("[SETTLE] {} {}, retrying", slug, e),
+                        // Not resolved yet: checked again next poll
+                        Ok(None) => {}
+                        Ok(Some(resolved)) => {
+                            if let Some(hash) = &resolved.tx {
+                                println!("[SETTLE] {} redeemed (tx={})", slug, hash);
+                            }
+                            let winner = resolved.winner;
+                            let payout = settlement.get(&slug).map_or(Decimal::ZERO, |p| p.payout(winner));
+                            // Realized now unless the window's final mark already decided it
+                            match pnl.redeem(&slug, winner) {
+                                Some(realized) => println!(
+                                    "[SETTLE] {} resolved {:?}: ${} payout, realized ${:+.2}",
+                                    slug,
+                                    winner,
+                                    payout,
+                                    realized / Decimal::from(1000)
+                                ),
+                                None => println!("[SETTLE] {} resolved {:?}: ${} payout", slug, winner, payout),
+                            }
+                            metrics.incr("settlements", 1);
+                            settlement.settled(&slug);
+                        }
+                    },
+
+                    Event::CanaryResult { result } => {
+                        if let Some(canary) = canary.as_mut() {
+                            if canary.record(clock.now_secs(), result) {
+                                match canary.degraded() {
+                                    Some(reason) => {
+                                        println!("[CANARY] Execution degraded: {}", reason);
+                                        alerts::raise("exec:canary", reason);
+                                    }
+                                    None => {
+                                        println!("[CANARY] Execution healthy again");
+                                        alerts::resolve("exec:canary");
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    Event::ExchangeStatus { degraded } => {
+                        match &degraded {
+                            Some(reason) => {
+                                println!("[EXCHANGE] Degraded: {} (cancel-only)", reason);
+                                alerts::raise("exchange:status", reason);
+                            }
+                            None => {
+                                println!("[EXCHANGE] Healthy again, resuming");
+                                alerts::resolve("exchange:status");
+                            }
+                        }
+                        exchange_down = degraded;
+                    }
+
+                    // Only the live market's (a late event from the last one is ignored)
+                    Event::MarketStatus { condition_id, halted } if condition_id == market.condition_id => {
+                        match &halted {
+                            Some(reason) => {
+                                println!("[MARKET] {} {}: cancelling, standing aside until rollover", market.slug, reason);
+                                alerts::raise("market:status", &format!("{} {}", market.slug, reason));
+                                metrics.incr("market_halts", 1);
+                                if orders.total_count() > 0 {
+                                    let cancelled = orders.total_count();
+                                    logger.halt(time_left, &market.slug, last_mark as f64 / 1000.0, var_est.current_var(), "MARKET_HALTED", cancelled);
+                                    let _ = executor.execute(vec![Action::CancelAll], &mut orders).await;
+                                    session_stats.orders_cancelled += cancelled as u32;
+                                }
+                            }
+                            None => {
+                                println!("[MARKET] {} trading again, resuming", market.slug);
+                                alerts::resolve("market:status");
+                            }
+                        }
+                        market_halt = halted;
+                    }
+                    Event::MarketStatus { .. } => {}
+
+                    Event::WhaleFlow { side, detail } => {
+                        match (side, &detail) {
+                            (Some(side), Some(detail)) => {
+                                println!("[WHALE] {} → widening {:?}", detail, side)
+                            }
+                            _ => println!("[WHALE] Cleared"),
+                        }
+                        whale_side = side;
+                    }
+
+                    Event::ReportedPosition { yes, no } => {
+                        let divergences = shadow.check(&position, (yes, no));
+                        if divergences.is_empty() {
+                            alerts::resolve("position:shadow");
+                        } else {
+                            let detail = ShadowPosition::describe(&divergences);
+                            println!("[SHADOW] Position diverged from exchange: {}", detail);
+                            alerts::raise("position:shadow", &format!("Position diverged: {}", detail));
+                            metrics.incr("position_divergences", 1);
+                        }
+                    }
+
+                    Event::BalanceUpdate { balance, allowances } => {
+                        let collateral = account::collateral(balance, &allowances, market.neg_risk);
+                        metrics.gauge("usdc_balance", balance);
+                        metrics.gauge("usdc_collateral", collateral);
+                        let collateral = Decimal::try_from(collateral).unwrap_or_default();
+                        let cap = balance_usd
+                            .and_then(|b| Decimal::try_from(b).ok())
+                            .map_or(collateral, |b| b.min(collateral));
+                        wallet_balance = Some(cap);
+                        // This engine's share: what the other engines' bids leave
+                        let cap = risk.available(&label, wallet_balance).unwrap_or(cap);
+
+                        // The ladder we're quoting, in full on both sides
+                        let size = Decimal::from(ORDER_SIZE);
+                        let ladder = (ladder_notional(would_be_yes, size, &strategy) + ladder_notional(would_be_no, size, &strategy))
+                            / Decimal::from(1000);
+                        match account::shortfall(cap, ladder) {
+                            Some(short) => {
+                                let detail = format!(
+                                    "${} usable (${:.2} held) is ${} short of the ${} ladder",
+                                    cap.round_dp(2), balance, short.round_dp(2), ladder.round_dp(2)
+                                );
+                                println!("[ACCOUNT] {}: bids past it are blocked", detail);
+                                alerts::raise("account:collateral", &detail);
+                            }
+                            None => alerts::resolve("account:collateral"),
+                        }
+                    }
+
+                    Event::PerpPrice { price } => {
+                        basis.update_perp(now, price);
+                    }
+
+                    Event::ExternalSignal { signal } => {
+                        match external.update(now, signal) {
+                            Ok(()) => metrics.incr("external_signals", 1),
+                            Err(e) => println!("[SIGNAL] Rejected: {}", e),
+                        }
+                    }
+
+                    Event::OrderAck { order_id, side, price, size } => {
+                        logger.order(time_left, &market.slug, side, price, size, &order_id);
+                    }
+
+                    Event::OrderPlaced { order_id, side, price, size } => {
+                        // Our own placements are tracked when the executor gets the
+                        // response, or adopted here if it was lost; one we still
+                        // don't know was placed outside this loop
+                        let size_dec = Decimal::try_from(size).ok();
+                        let known = orders.state(&order_id).is_some() || orders.adopt_pending(side, price, size_dec, &order_id);
+                        if !known && size > 0.0 {
+                            println!("[USER_WS] Tracking untracked order {} ({:?} {} @ {})", short_id(&order_id), side, size, price);
+                            orders.add(side, order_id, price, Decimal::try_from(size).unwrap_or(dec!(0)));
+                        }
+                    }
+
+                    Event::OrderCancelled { order_id, side } => {
+                        // Cancel we sent (ack may have been lost) or one the exchange made
+                        if orders.ack_cancel(&order_id).is_none() && orders.confirm_cancel(&order_id).is_some() {
+                            println!("[USER_WS] Order {} cancelled on the exchange ({:?})", short_id(&order_id), side);
+                            metrics.incr("exchange_cancels", 1);
+                        }
+                    }
+
+                    Event::OrderReject { side, price, size, reason } => {
+                        session_stats.order_fails += 1;
+                        metrics.incr("order_rejects", 1);
+                        logger.order_fail(time_left, &market.slug, side, price, size, &reason.replace(',', ";"));
                     }
 
                     Event::Tick => {}
                 }
             }
 
-            // Ctrl+C
-            _ = tokio::signal::ctrl_c() => {
-                if orders.total_count() > 0 {
-                    session_stats.orders_cancelled += orders.total_count() as u32;
-                    let actions = vec![Action::CancelAll];
-                    let _ = executor.execute(actions, &mut orders).await;
-                }
-                break;
+            // Control commands (gRPC or any other ControlHandle holder)
+            Some(req) = control_rx.recv() => {
+                let outcome = match req.command {
+                    ControlCommand::Pause { reason } => {
+                        println!("[CONTROL] Paused: {}", reason);
+                        let msg = format!("paused ({} orders to cancel)", orders.total_count());
+                        paused = Some(reason);
+                        Ok(msg)
+                    }
+                    ControlCommand::Resume => match paused.take() {
+                        Some(_) => {
+                            println!("[CONTROL] Resumed");
+                            Ok("resumed".to_string())
+                        }
+                        None => Err("not paused".to_string()),
+                    },
+                    ControlCommand::SetParam { name, value } => {
+                        params.set(&name, value).map(|()| {
+                            as_pricer.gamma = params.gamma;
+                            println!("[CONTROL] {} = {}", name, value);
+                            format!("{} = {}", name, value)
+                        })
+                    }
+                    ControlCommand::Approve { id } => match supervisor.approve(id) {
+                        Ok(actions) => {
+                            println!("[SUPERVISOR] Batch {} approved", id);
+                            let placed = actions.len();
+                            session_stats.orders_placed += placed as u32;
+                            metrics.incr("orders_placed", placed as u64);
+                            match executor.execute(actions, &mut orders).await {
+                                Ok(_) => Ok(format!("batch {} executed ({} orders)", id, placed)),
+                                Err(e) => {
+                                    session_stats.order_fails += 1;
+                                    metrics.incr("order_fails", 1);
+                                    Err(format!("batch {} failed: {}", id, e))
+                                }
+                            }
+                        }
+                        Err(e) => Err(e),
+                    },
+                    ControlCommand::Reject { id } => supervisor.reject(id, clock.now_secs()).map(|()| {
+                        println!("[SUPERVISOR] Batch {} rejected", id);
+                        format!("batch {} dropped", id)
+                    }),
+                };
+                let _ = req.reply.send(outcome);
             }
         }
     }
 
+    // Shutdown: the strategy has stopped, cancel our orders and wait for
+    // the exchange to confirm, then stop the feeds (nothing new comes in
+    // while we wrap up)
+    if orders.total_count() + orders.pending_cancel_count() > 0 {
+        session_stats.orders_cancelled += orders.total_count() as u32;
+        let live = executor.cancel_all_confirmed(&mut orders).await;
+        if live.is_empty() {
+            println!("[SHUTDOWN] All orders cancelled");
+        } else {
+            println!("[SHUTDOWN] {} orders may still be live, check the exchange: {}", live.len(), live.join(", "));
+        }
+    }
+    let grace = Duration::from_millis(SHUTDOWN_GRACE_MS);
+    for (name, handle) in [
+        ("polymarket", Some(poly_handle)),
+        ("user-ws", user_handle),
+        ("whale", whale_handle),
+        ("positions", positions_handle),
+        ("market-status", Some(market_status_handle)),
+    ] {
+        if let Some(handle) = handle {
+            tasks.track(Stage::Feeds, name, handle);
+        }
+    }
+    for name in tasks.stop(Stage::Feeds, grace).await {
+        println!("[SHUTDOWN] {} didn't stop within {}ms, aborted", name, SHUTDOWN_GRACE_MS);
+    }
+
     // Finalize current window and merge into session
     window_stats.finalize();
     let yes_shares = position.qty_yes.to_string().parse::<f64>().unwrap_or(0.0);
@@ -640,13 +2101,19 @@ async fn main() -> Result<()> {
         no_shares,
         no_avg,
         min_pnl,
-        AS_GAMMA,
+        as_pricer.gamma,
         HALT_SECS,
     );
+    // Before the close: a restart in this window picks up from here
+    ledger.sync(clock.now_secs(), &market.slug, &orders);
+    ledger.snapshot(clock.now_secs(), &market.slug, &position, pnl.report(&position, last_mark).session_realized_usd);
     session_stats.merge_window(&window_stats);
+    let closed = pnl.close_market(&position, last_mark);
+    print_pnl(&closed);
+    control.publish(EngineEvent::Pnl(closed));
 
     // Merge paired shares from final market
-    if !log_only {
+    if let Some(ctf_client) = ctf_client.as_ref().filter(|_| !log_only) {
         let merge_qty = position.qty_yes.min(position.qty_no);
         if merge_qty > Decimal::ZERO {
             let merge_f64 = merge_qty.to_string().parse::<f64>().unwrap_or(0.0);
@@ -661,40 +2128,118 @@ async fn main() -> Result<()> {
         }
     }
 
+    if !settlement.is_empty() {
+        println!("[SETTLE] {} window(s) not yet resolved; run `cargo run --bin redeem` once they are", settlement.len());
+    }
+
     // Log session summary
-    logger.session_summary(&session_stats);
+    logger.session_summary(&session_stats, &run_info);
     logger.flush();
+    if canary_cap.is_some() {
+        match canary::write_report(logger.path(), std::path::Path::new("logs")) {
+            Ok((path, report)) => println!("\n=== CANARY REPORT ===\n{}[CANARY] Wrote {}", report, path.display()),
+            Err(e) => println!("[CANARY] Comparison failed: {}", e),
+        }
+    }
+    journal.flush();
+    match heatmap.write() {
+        Ok(Some(path)) => println!("[HEATMAP] Wrote {}", path.display()),
+        Ok(None) => {}
+        Err(e) => println!("[HEATMAP] Failed: {}", e),
+    }
+
+    // Last stage: sinks flush what they hold (event bus, recorder, metrics)
+    for name in tasks.stop(Stage::Sinks, grace).await {
+        println!("[SHUTDOWN] {} didn't stop within {}ms, aborted", name, SHUTDOWN_GRACE_MS);
+    }
+    if sink.is_enabled() && sink.dropped() + sink.failed() > 0 {
+        println!("[SINK] {} records dropped (queue full), {} failed to publish", sink.dropped(), sink.failed());
+    }
+    if recorder.dropped() > 0 {
+        println!("[RECORDER] {} raw messages dropped (writer behind)", recorder.dropped());
+    }
+    if dataset.dropped() > 0 {
+        println!("[DATASET] {} rows dropped (writer behind)", dataset.dropped());
+    }
+
+    if let Some(report) = &soak_report {
+        println!("\n{}", report.render(clock.now_secs(), &fault_counts));
+        if !report.is_clean() {
+            anyhow::bail!("soak run hit {} kind(s) of invariant violation", report.tallies().len());
+        }
+    }
 
     Ok(())
 }
 
-/// Reconcile a single side: cancel if price changed, place if missing.
-/// Uses OrderTracker to get actual resting price (no separate tracking).
-fn reconcile_side(
-    side: Side,
-    target_price: u16,
-    orders: &OrderTracker,
-    actions: &mut Vec<Action>,
-) {
-    let resting_price = orders.top_price(side).unwrap_or(0);
-    let has_order = resting_price > 0;
-    let price_changed = has_order && target_price != resting_price;
-
-    // If price changed and we have orders, cancel them
-    if price_changed {
-        for order in orders.all_orders(side) {
-            actions.push(Action::Cancel {
-                order_id: order.order_id.clone(),
-            });
+/// The built-in ladder: one rung at the target. `[strategy]` overrides
+/// any of it.
+fn builtin_strategy() -> StrategyConfig {
+    StrategyConfig {
+        margin_ticks: 1000 - MAX_PAIR_TICKS,
+        ladder_rungs: 1,
+        rebalance_threshold: Decimal::from(INVENTORY_TOLERANCE),
+        ..Default::default()
+    }
+}
+
+/// `[strategy] duration`, if set and known.
+fn duration_override(overrides: &config::Strategy) -> Option<MarketDuration> {
+    overrides.duration.as_deref().and_then(|d| {
+        let parsed = MarketDuration::from_label(d);
+        if parsed.is_none() {
+            println!("[CONFIG] Unknown [strategy] duration {:?}, expected \"5m\" or \"15m\"", d);
         }
+        parsed
+    })
+}
+
+/// Per-market strategy settings: the window length, and `[strategy]`
+/// cent amounts converted for the market's tick size (kept as they were
+/// if that size can't represent them).
+fn apply_market_strategy(
+    overrides: &config::Strategy,
+    strategy: &mut StrategyConfig,
+    market: &gamma::MarketInfo,
+    duration: MarketDuration,
+) {
+    strategy.duration = duration;
+    if let Err(e) = overrides.apply_cents(strategy, market.tick_size) {
+        println!("[CONFIG] {}: [strategy] cents not usable at tick {}: {:#}", market.slug, market.tick_size, e);
     }
+}
 
-    // If no order (or just cancelled), place new one
-    if !has_order || price_changed {
-        actions.push(Action::Place {
-            side,
-            price: target_price,
-            size: Decimal::from(ORDER_SIZE),
-        });
+/// Start, end and sizing schedule of a market. The duration comes from the
+/// market itself; config only gets a say when the market doesn't tell us.
+fn market_window(
+    market: &gamma::MarketInfo,
+    configured: Option<MarketDuration>,
+    clock: &dyn Clock,
+) -> (f64, f64, MarketDuration) {
+    let (duration, warning) = strategy::resolve_duration(market.duration(), configured);
+    if let Some(warning) = warning {
+        println!("[DURATION] {}: {}", market.slug, warning);
     }
+    let start = gamma::parse_start_epoch(&market.slug)
+        .map(|e| e as f64)
+        .unwrap_or(clock.now_secs());
+    (start, start + duration.total_secs() as f64, duration)
+}
+
+/// Log a closed window's P&L and the session totals.
+fn print_pnl(report: &PnlReport) {
+    println!(
+        "[PNL] {} realized ${:+.2} unrealized ${:+.2} | session realized ${:+.2} unrealized ${:+.2}",
+        report.market,
+        report.realized_usd,
+        report.unrealized_usd,
+        report.session_realized_usd,
+        report.session_unrealized_usd,
+    );
 }
+
+/// First 20 chars of an order ID, for logs.
+fn short_id(order_id: &str) -> &str {
+    &order_id[..20.min(order_id.len())]
+}
+
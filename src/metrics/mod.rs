@@ -0,0 +1,184 @@
+//! Metrics facade with pluggable backends.
+//!
+//! Call sites record counters and gauges through [`Metrics`]; each enabled
+//! backend (Prometheus pull, StatsD push, OTLP export) receives them. With
+//! no backends configured every call is a no-op.
+//!
+//! The exporters are behind the `metrics` feature (on by default). Without
+//! it `from_config` ignores `[metrics]` and every handle is disabled.
+//! Latency SLOs (`slo`) are checked either way.
+
+#[cfg(feature = "metrics")]
+mod otlp;
+#[cfg(feature = "metrics")]
+mod prometheus;
+#[cfg(any(feature = "metrics", test))]
+mod registry;
+mod slo;
+#[cfg(feature = "metrics")]
+mod statsd;
+
+#[cfg(feature = "metrics")]
+pub use otlp::OtlpExporter;
+#[cfg(feature = "metrics")]
+pub use prometheus::PrometheusExporter;
+#[cfg(any(feature = "metrics", test))]
+pub use registry::Registry;
+pub use slo::{SloChange, SloMonitor};
+#[cfg(feature = "metrics")]
+pub use statsd::StatsdSink;
+
+use std::sync::Arc;
+
+use crate::config;
+use crate::tasks::TaskRegistry;
+
+/// A destination for metrics.
+pub trait MetricsBackend: Send + Sync {
+    /// Add `delta` to a monotonic counter.
+    fn counter(&self, name: &str, delta: u64);
+    /// Set a gauge to `value`.
+    fn gauge(&self, name: &str, value: f64);
+    /// Publish constant labels as an info metric (a gauge fixed at 1),
+    /// e.g. `build_info`. Backends without labels ignore it.
+    fn info(&self, _name: &str, _labels: &[(String, String)]) {}
+}
+
+/// Cheap-to-clone handle that fans metrics out to all backends.
+#[derive(Clone, Default)]
+pub struct Metrics {
+    prefix: String,
+    backends: Vec<Arc<dyn MetricsBackend>>,
+}
+
+impl Metrics {
+    /// Metrics with no backends (all calls are no-ops).
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Build from the `[metrics]` config section.
+    /// Must be called inside a tokio runtime (Prometheus and OTLP spawn
+    /// tasks, registered as sinks).
+    #[cfg(feature = "metrics")]
+    pub async fn from_config(cfg: &config::Metrics, tasks: &mut TaskRegistry) -> anyhow::Result<Self> {
+        let mut metrics = Self {
+            prefix: cfg.prefix.clone(),
+            backends: Vec::new(),
+        };
+
+        if let Some(addr) = &cfg.prometheus_addr {
+            let exporter = PrometheusExporter::bind(addr, tasks).await?;
+            println!("[metrics] Prometheus on http://{}/metrics", addr);
+            metrics.backends.push(Arc::new(exporter));
+        }
+        if let Some(addr) = &cfg.statsd_addr {
+            metrics.backends.push(Arc::new(StatsdSink::new(addr)?));
+            println!("[metrics] StatsD to {}", addr);
+        }
+        if let Some(endpoint) = &cfg.otlp_endpoint {
+            let exporter = OtlpExporter::spawn(endpoint.clone(), &cfg.prefix, cfg.otlp_interval_secs, tasks);
+            metrics.backends.push(Arc::new(exporter));
+            println!("[metrics] OTLP to {}", endpoint);
+        }
+
+        Ok(metrics)
+    }
+
+    /// Built without the `metrics` feature: nothing to export to.
+    #[cfg(not(feature = "metrics"))]
+    pub async fn from_config(cfg: &config::Metrics, _tasks: &mut TaskRegistry) -> anyhow::Result<Self> {
+        if cfg.prometheus_addr.is_some() || cfg.statsd_addr.is_some() || cfg.otlp_endpoint.is_some() {
+            println!("[metrics] Built without the `metrics` feature, ignoring [metrics]");
+        }
+        Ok(Self::disabled())
+    }
+
+    /// Add a backend directly.
+    pub fn with_backend(mut self, backend: Arc<dyn MetricsBackend>) -> Self {
+        self.backends.push(backend);
+        self
+    }
+
+    /// Whether any backend is configured.
+    pub fn is_enabled(&self) -> bool {
+        !self.backends.is_empty()
+    }
+
+    /// Increment a counter.
+    pub fn incr(&self, name: &str, delta: u64) {
+        if self.backends.is_empty() || delta == 0 {
+            return;
+        }
+        let name = self.full_name(name);
+        for backend in &self.backends {
+            backend.counter(&name, delta);
+        }
+    }
+
+    /// Set a gauge.
+    pub fn gauge(&self, name: &str, value: f64) {
+        if self.backends.is_empty() {
+            return;
+        }
+        let name = self.full_name(name);
+        for backend in &self.backends {
+            backend.gauge(&name, value);
+        }
+    }
+
+    /// Publish an info metric (constant labels, value 1).
+    pub fn info(&self, name: &str, labels: &[(String, String)]) {
+        if self.backends.is_empty() {
+            return;
+        }
+        let name = self.full_name(name);
+        for backend in &self.backends {
+            backend.info(&name, labels);
+        }
+    }
+
+    fn full_name(&self, name: &str) -> String {
+        if self.prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}_{}", self.prefix, name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_is_noop() {
+        let m = Metrics::disabled();
+        assert!(!m.is_enabled());
+        m.incr("fills", 1);
+        m.gauge("position", 5.0);
+    }
+
+    #[test]
+    fn test_fans_out_with_prefix() {
+        let a = Arc::new(Registry::default());
+        let b = Arc::new(Registry::default());
+        let m = Metrics {
+            prefix: "polybot".to_string(),
+            backends: Vec::new(),
+        }
+        .with_backend(a.clone())
+        .with_backend(b.clone());
+
+        m.incr("fills", 2);
+        m.incr("fills", 1);
+        m.gauge("mid", 0.5);
+        m.info("build_info", &[("commit".to_string(), "abc1234".to_string())]);
+
+        for r in [a, b] {
+            assert_eq!(r.counters().get("polybot_fills"), Some(&3));
+            assert_eq!(r.gauges().get("polybot_mid"), Some(&0.5));
+            assert_eq!(r.infos()["polybot_build_info"][0].1, "abc1234");
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! OTLP export backend: periodically POSTs metrics as OTLP/HTTP JSON
+//! (e.g. to an OpenTelemetry collector at `http://localhost:4318/v1/metrics`).
+
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use super::{MetricsBackend, Registry};
+use crate::tasks::{Stage, TaskRegistry};
+
+pub struct OtlpExporter {
+    registry: Arc<Registry>,
+}
+
+impl OtlpExporter {
+    /// Start exporting to `endpoint` every `interval_secs`, and once more
+    /// when the sinks stage stops.
+    pub fn spawn(endpoint: String, service_name: &str, interval_secs: u64, tasks: &mut TaskRegistry) -> Self {
+        let registry = Arc::new(Registry::default());
+        let exported = registry.clone();
+        let service_name = service_name.to_string();
+        let start_nanos = now_nanos();
+
+        tasks.spawn_with(Stage::Sinks, "otlp-metrics", move |stop| async move {
+            let http = reqwest::Client::new();
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+            loop {
+                let last = tokio::select! {
+                    _ = interval.tick() => false,
+                    _ = stop.cancelled() => true,
+                };
+                let body = encode(&exported, &service_name, start_nanos, now_nanos());
+                if let Err(e) = http.post(&endpoint).json(&body).send().await {
+                    println!("[metrics] OTLP export failed: {}", e);
+                }
+                if last {
+                    break;
+                }
+            }
+        });
+
+        Self { registry }
+    }
+}
+
+impl MetricsBackend for OtlpExporter {
+    fn counter(&self, name: &str, delta: u64) {
+        self.registry.counter(name, delta);
+    }
+
+    fn gauge(&self, name: &str, value: f64) {
+        self.registry.gauge(name, value);
+    }
+
+    fn info(&self, name: &str, labels: &[(String, String)]) {
+        self.registry.info(name, labels);
+    }
+}
+
+/// Encode the registry as an OTLP ExportMetricsServiceRequest (JSON).
+/// Counters are cumulative monotonic sums; gauges are gauges.
+fn encode(registry: &Registry, service_name: &str, start_nanos: u128, now_nanos: u128) -> Value {
+    let mut metrics: Vec<Value> = Vec::new();
+
+    for (name, value) in registry.counters() {
+        metrics.push(json!({
+            "name": name,
+            "sum": {
+                "aggregationTemporality": 2, // CUMULATIVE
+                "isMonotonic": true,
+                "dataPoints": [{
+                    "asInt": value.to_string(),
+                    "startTimeUnixNano": start_nanos.to_string(),
+                    "timeUnixNano": now_nanos.to_string(),
+                }],
+            },
+        }));
+    }
+    for (name, value) in registry.gauges() {
+        metrics.push(json!({
+            "name": name,
+            "gauge": {
+                "dataPoints": [{
+                    "asDouble": value,
+                    "timeUnixNano": now_nanos.to_string(),
+                }],
+            },
+        }));
+    }
+    for (name, labels) in registry.infos() {
+        let attributes: Vec<Value> = labels
+            .iter()
+            .map(|(k, v)| json!({ "key": k, "value": { "stringValue": v } }))
+            .collect();
+        metrics.push(json!({
+            "name": name,
+            "gauge": {
+                "dataPoints": [{
+                    "asDouble": 1.0,
+                    "timeUnixNano": now_nanos.to_string(),
+                    "attributes": attributes,
+                }],
+            },
+        }));
+    }
+
+    json!({
+        "resourceMetrics": [{
+            "resource": {
+                "attributes": [{
+                    "key": "service.name",
+                    "value": { "stringValue": service_name },
+                }],
+            },
+            "scopeMetrics": [{
+                "scope": { "name": "polybot-rs" },
+                "metrics": metrics,
+            }],
+        }],
+    })
+}
+
+fn now_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let r = Registry::default();
+        r.counter("polybot_fills", 4);
+        r.gauge("polybot_mid", 0.5);
+
+        let body = encode(&r, "polybot", 1, 2);
+        let metrics = &body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"];
+        assert_eq!(metrics[0]["name"], "polybot_fills");
+        assert_eq!(metrics[0]["sum"]["dataPoints"][0]["asInt"], "4");
+        assert_eq!(metrics[0]["sum"]["isMonotonic"], true);
+        assert_eq!(metrics[1]["gauge"]["dataPoints"][0]["asDouble"], 0.5);
+        assert_eq!(
+            body["resourceMetrics"][0]["resource"]["attributes"][0]["value"]["stringValue"],
+            "polybot"
+        );
+    }
+}
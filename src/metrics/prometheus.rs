@@ -0,0 +1,96 @@
+//! Prometheus pull backend: serves the text exposition format on `/metrics`.
+
+use std::sync::Arc;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use super::{MetricsBackend, Registry};
+use crate::tasks::{Stage, TaskRegistry};
+
+pub struct PrometheusExporter {
+    registry: Arc<Registry>,
+}
+
+impl PrometheusExporter {
+    /// Bind `addr` and serve metrics until the sinks stage stops.
+    pub async fn bind(addr: &str, tasks: &mut TaskRegistry) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let registry = Arc::new(Registry::default());
+
+        let served = registry.clone();
+        tasks.spawn(Stage::Sinks, "prometheus", async move {
+            loop {
+                let (mut socket, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(_) => continue,
+                };
+                let body = render(&served);
+                tokio::spawn(async move {
+                    // Every path gets the metrics; we only need to drain the request
+                    let mut buf = [0u8; 1024];
+                    let _ = socket.read(&mut buf).await;
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        Ok(Self { registry })
+    }
+}
+
+impl MetricsBackend for PrometheusExporter {
+    fn counter(&self, name: &str, delta: u64) {
+        self.registry.counter(name, delta);
+    }
+
+    fn gauge(&self, name: &str, value: f64) {
+        self.registry.gauge(name, value);
+    }
+
+    fn info(&self, name: &str, labels: &[(String, String)]) {
+        self.registry.info(name, labels);
+    }
+}
+
+/// Render the registry in Prometheus text format.
+fn render(registry: &Registry) -> String {
+    let mut out = String::new();
+    for (name, value) in registry.counters() {
+        out.push_str(&format!("# TYPE {0}_total counter\n{0}_total {1}\n", name, value));
+    }
+    for (name, value) in registry.gauges() {
+        out.push_str(&format!("# TYPE {0} gauge\n{0} {1}\n", name, value));
+    }
+    for (name, labels) in registry.infos() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(k, v)| format!("{}=\"{}\"", k, v.replace('\\', "\\\\").replace('"', "\\\"")))
+            .collect();
+        out.push_str(&format!("# TYPE {0} gauge\n{0}{{{1}}} 1\n", name, labels.join(",")));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let r = Registry::default();
+        r.counter("polybot_fills", 3);
+        r.gauge("polybot_mid", 0.52);
+        r.info("polybot_build_info", &[("commit".to_string(), "abc1234".to_string()), ("profile".to_string(), "release".to_string())]);
+
+        let text = render(&r);
+        assert!(text.contains("# TYPE polybot_fills_total counter\npolybot_fills_total 3\n"));
+        assert!(text.contains("# TYPE polybot_mid gauge\npolybot_mid 0.52\n"));
+        assert!(text.contains("polybot_build_info{commit=\"abc1234\",profile=\"release\"} 1\n"));
+    }
+}
@@ -0,0 +1,45 @@
+//! In-memory metric values, shared by the pull/export backends.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use super::MetricsBackend;
+
+/// Latest counter totals, gauge values and info labels.
+#[derive(Debug, Default)]
+pub struct Registry {
+    counters: Mutex<BTreeMap<String, u64>>,
+    gauges: Mutex<BTreeMap<String, f64>>,
+    infos: Mutex<BTreeMap<String, Vec<(String, String)>>>,
+}
+
+impl Registry {
+    /// Snapshot of counters.
+    pub fn counters(&self) -> BTreeMap<String, u64> {
+        self.counters.lock().unwrap().clone()
+    }
+
+    /// Snapshot of gauges.
+    pub fn gauges(&self) -> BTreeMap<String, f64> {
+        self.gauges.lock().unwrap().clone()
+    }
+
+    /// Snapshot of info metrics and their labels.
+    pub fn infos(&self) -> BTreeMap<String, Vec<(String, String)>> {
+        self.infos.lock().unwrap().clone()
+    }
+}
+
+impl MetricsBackend for Registry {
+    fn counter(&self, name: &str, delta: u64) {
+        *self.counters.lock().unwrap().entry(name.to_string()).or_insert(0) += delta;
+    }
+
+    fn gauge(&self, name: &str, value: f64) {
+        self.gauges.lock().unwrap().insert(name.to_string(), value);
+    }
+
+    fn info(&self, name: &str, labels: &[(String, String)]) {
+        self.infos.lock().unwrap().insert(name.to_string(), labels.to_vec());
+    }
+}
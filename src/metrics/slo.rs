@@ -0,0 +1,168 @@
+//! Latency SLOs.
+//!
+//! The objectives in `[metrics.slo]` are checked every tick against what
+//! the engine measured: the p99 of book-update-to-cancel latency over a
+//! rolling window, and how long each feed has been silent. A breach is
+//! reported once when it starts and once when it clears; while it lasts
+//! the engine doesn't quote.
+//!
+//! Halting stops the cancels the p99 is taken over, so a latency breach
+//! clears once its slow samples age out of the window.
+
+use std::collections::VecDeque;
+
+use crate::config::Slo;
+
+/// A change in SLO state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SloChange {
+    /// An objective was missed (the first one found)
+    Breached(String),
+    /// Every objective is met again
+    Cleared,
+}
+
+/// Measures latencies and checks them against the objectives.
+#[derive(Debug)]
+pub struct SloMonitor {
+    config: Slo,
+    /// Book-to-cancel samples: (time, ms)
+    cancels: VecDeque<(f64, f64)>,
+    /// Last event per feed. A feed is only judged once it's been seen.
+    feeds: Vec<(&'static str, f64)>,
+    breach: Option<String>,
+}
+
+impl SloMonitor {
+    pub fn new(config: Slo) -> Self {
+        Self { config, cancels: VecDeque::new(), feeds: Vec::new(), breach: None }
+    }
+
+    /// Whether any objective is set.
+    pub fn is_enabled(&self) -> bool {
+        self.config.book_to_cancel_p99_ms.is_some() || self.config.feed_gap_ms.is_some()
+    }
+
+    /// A cancel went out `latency_ms` after the book update it reacted to.
+    pub fn record_cancel(&mut self, now: f64, latency_ms: f64) {
+        self.cancels.push_back((now, latency_ms));
+    }
+
+    /// A feed delivered an event.
+    pub fn feed_event(&mut self, feed: &'static str, now: f64) {
+        match self.feeds.iter_mut().find(|(name, _)| *name == feed) {
+            Some((_, last)) => *last = now,
+            None => self.feeds.push((feed, now)),
+        }
+    }
+
+    /// Stop judging a feed until it delivers again (it's being restarted).
+    pub fn forget_feed(&mut self, feed: &str) {
+        self.feeds.retain(|(name, _)| *name != feed);
+    }
+
+    /// p99 book-to-cancel latency (ms) over the window, once it holds
+    /// `min_samples`.
+    pub fn cancel_p99(&self) -> Option<f64> {
+        if self.cancels.len() < self.config.min_samples.max(1) {
+            return None;
+        }
+        let mut latencies: Vec<f64> = self.cancels.iter().map(|&(_, ms)| ms).collect();
+        latencies.sort_by(f64::total_cmp);
+        let rank = (latencies.len() as f64 * 0.99).ceil() as usize;
+        latencies.get(rank.saturating_sub(1)).copied()
+    }
+
+    /// The objective currently missed, if any.
+    pub fn breach(&self) -> Option<&str> {
+        self.breach.as_deref()
+    }
+
+    /// Age out old samples and check every objective.
+    ///
+    /// # Returns
+    /// The change, if the state flipped.
+    pub fn check(&mut self, now: f64) -> Option<SloChange> {
+        while self.cancels.front().is_some_and(|&(t, _)| now - t > self.config.window_secs) {
+            self.cancels.pop_front();
+        }
+
+        let latency = self.config.book_to_cancel_p99_ms.and_then(|objective| {
+            let p99 = self.cancel_p99().filter(|&p99| p99 > objective)?;
+            Some(format!("book-to-cancel p99 {:.0}ms over {:.0}ms", p99, objective))
+        });
+        let gap = || {
+            let objective = self.config.feed_gap_ms?;
+            self.feeds.iter().find_map(|&(feed, last)| {
+                let gap_ms = (now - last) * 1000.0;
+                (gap_ms > objective).then(|| format!("{} silent {:.0}ms, over {:.0}ms", feed, gap_ms, objective))
+            })
+        };
+        let breach = latency.or_else(gap);
+
+        let change = match (&self.breach, &breach) {
+            (None, Some(reason)) => Some(SloChange::Breached(reason.clone())),
+            (Some(_), None) => Some(SloChange::Cleared),
+            _ => None,
+        };
+        self.breach = breach;
+        change
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor() -> SloMonitor {
+        SloMonitor::new(Slo {
+            book_to_cancel_p99_ms: Some(50.0),
+            feed_gap_ms: Some(2000.0),
+            window_secs: 60.0,
+            min_samples: 10,
+        })
+    }
+
+    #[test]
+    fn test_cancel_latency_breach_clears_as_samples_age_out() {
+        let mut slo = monitor();
+        for i in 0..99 {
+            slo.record_cancel(i as f64 * 0.1, 20.0);
+        }
+        slo.record_cancel(10.0, 80.0);
+        assert_eq!(slo.check(10.0), None);
+        assert_eq!(slo.cancel_p99(), Some(20.0));
+
+        // Two slow cancels in 100 push the p99 over
+        slo.record_cancel(10.0, 90.0);
+        assert!(matches!(slo.check(10.0), Some(SloChange::Breached(r)) if r.contains("p99 80ms")));
+        assert_eq!(slo.check(11.0), None);
+        assert!(slo.breach().is_some());
+
+        assert_eq!(slo.check(71.0), Some(SloChange::Cleared));
+        assert_eq!(slo.cancel_p99(), None);
+    }
+
+    #[test]
+    fn test_too_few_samples_not_judged() {
+        let mut slo = monitor();
+        for _ in 0..9 {
+            slo.record_cancel(0.0, 500.0);
+        }
+        assert_eq!(slo.check(0.0), None);
+    }
+
+    #[test]
+    fn test_feed_gap() {
+        let mut slo = monitor();
+        slo.feed_event("polymarket", 0.0);
+        slo.feed_event("binance", 0.0);
+        slo.feed_event("polymarket", 1.5);
+        assert_eq!(slo.check(2.0), None);
+        assert!(matches!(slo.check(2.5), Some(SloChange::Breached(r)) if r.starts_with("binance silent 2500ms")));
+
+        slo.feed_event("binance", 3.0);
+        slo.forget_feed("polymarket");
+        assert_eq!(slo.check(4.0), Some(SloChange::Cleared));
+    }
+}
@@ -0,0 +1,77 @@
+//! StatsD push backend: one UDP datagram per update, fire-and-forget.
+
+use std::net::UdpSocket;
+
+use super::MetricsBackend;
+
+pub struct StatsdSink {
+    socket: UdpSocket,
+}
+
+impl StatsdSink {
+    pub fn new(addr: &str) -> anyhow::Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(addr)?;
+        // Never block the tick loop on metrics
+        socket.set_nonblocking(true)?;
+        Ok(Self { socket })
+    }
+
+    fn send(&self, line: &str) {
+        let _ = self.socket.send(line.as_bytes());
+    }
+}
+
+impl MetricsBackend for StatsdSink {
+    fn counter(&self, name: &str, delta: u64) {
+        self.send(&format_counter(name, delta));
+    }
+
+    fn gauge(&self, name: &str, value: f64) {
+        self.send(&format_gauge(name, value));
+    }
+
+    fn info(&self, name: &str, labels: &[(String, String)]) {
+        self.send(&format_info(name, labels));
+    }
+}
+
+fn format_counter(name: &str, delta: u64) -> String {
+    format!("{}:{}|c", name, delta)
+}
+
+fn format_gauge(name: &str, value: f64) -> String {
+    format!("{}:{}|g", name, value)
+}
+
+/// Labels as DogStatsD tags (`|#k:v,...`).
+fn format_info(name: &str, labels: &[(String, String)]) -> String {
+    let tags: Vec<String> = labels.iter().map(|(k, v)| format!("{}:{}", k, v)).collect();
+    format!("{}:1|g|#{}", name, tags.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_format() {
+        assert_eq!(format_counter("polybot_fills", 2), "polybot_fills:2|c");
+        assert_eq!(format_gauge("polybot_mid", 0.5), "polybot_mid:0.5|g");
+        assert_eq!(
+            format_info("polybot_build_info", &[("commit".to_string(), "abc1234".to_string())]),
+            "polybot_build_info:1|g|#commit:abc1234"
+        );
+    }
+
+    #[test]
+    fn test_sends_datagram() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let sink = StatsdSink::new(&receiver.local_addr().unwrap().to_string()).unwrap();
+        sink.counter("polybot_fills", 1);
+
+        let mut buf = [0u8; 64];
+        let n = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"polybot_fills:1|c");
+    }
+}
@@ -0,0 +1,245 @@
+//! Paper executor: the live executor's order legs against a simulated
+//! venue.
+//!
+//! Places rest after a sampled ack delay, cancels take effect after theirs
+//! (fills can still land in between), and a resting bid fills in full when
+//! the market prints below it. Prints at our price don't fill: we'd be
+//! behind the queue. Fills go back through the event channel as OrderFill,
+//! so Position and OrderTracker are updated exactly as in live trading.
+//!
+//! Takes are IOC against a simulated ask book kept from the depth feed:
+//! each level at or under the limit fills at its own price (price
+//! improvement), what's taken is gone until the feed refills the level,
+//! and whatever the book can't cover is cancelled (partial fill).
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+
+use super::LatencyModel;
+use crate::config::Paper;
+use crate::events::Side;
+
+/// An order resting on the simulated venue.
+#[derive(Debug, Clone)]
+struct PaperOrder {
+    order_id: String,
+    side: Side,
+    price: u16,
+    size: Decimal,
+    /// When it starts resting (seconds)
+    live_at: f64,
+    /// When its cancel takes effect (seconds)
+    cancel_at: Option<f64>,
+}
+
+/// A simulated fill, reported after `delay`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaperFill {
+    pub order_id: String,
+    pub side: Side,
+    pub price: u16,
+    pub size: Decimal,
+    pub is_maker: bool,
+    pub delay: Duration,
+}
+
+/// Simulated venue behind the executor in paper mode.
+#[derive(Debug)]
+pub struct PaperExecutor {
+    latency: LatencyModel,
+    resting: Vec<PaperOrder>,
+    /// Simulated ask depth per token: price (ticks) → shares
+    asks: HashMap<Side, BTreeMap<u16, Decimal>>,
+    next_id: u64,
+}
+
+impl PaperExecutor {
+    pub fn new(config: &Paper) -> Self {
+        Self {
+            latency: LatencyModel::from_config(config),
+            resting: Vec::new(),
+            asks: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    fn order_id(&mut self, kind: &str) -> String {
+        self.next_id += 1;
+        format!("paper-{}-{}", kind, self.next_id)
+    }
+
+    /// Rest a bid; it can fill once the place ack delay has passed.
+    pub fn place(&mut self, now: f64, side: Side, price: u16, size: Decimal) -> String {
+        let order_id = self.order_id("order");
+        let live_at = now + self.latency.place_ack().as_secs_f64();
+        self.resting.push(PaperOrder { order_id: order_id.clone(), side, price, size, live_at, cancel_at: None });
+        order_id
+    }
+
+    /// Cancel a resting order. False if it isn't resting (already filled).
+    pub fn cancel(&mut self, now: f64, order_id: &str) -> bool {
+        let delay = self.latency.cancel_ack().as_secs_f64();
+        match self.resting.iter_mut().find(|o| o.order_id == order_id) {
+            Some(order) => {
+                order.cancel_at.get_or_insert(now + delay);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancel everything resting.
+    pub fn cancel_all(&mut self, now: f64) {
+        let ids: Vec<String> = self.resting.iter().map(|o| o.order_id.clone()).collect();
+        for id in &ids {
+            self.cancel(now, id);
+        }
+    }
+
+    /// Replace the ask book for `side` (snapshot from the feed).
+    pub fn set_asks(&mut self, side: Side, levels: impl IntoIterator<Item = (u16, Decimal)>) {
+        let book = self.asks.entry(side).or_default();
+        book.clear();
+        book.extend(levels.into_iter().filter(|(_, size)| *size > Decimal::ZERO));
+    }
+
+    /// One ask level's new size (0 = level gone).
+    pub fn set_ask(&mut self, side: Side, price: u16, size: Decimal) {
+        let book = self.asks.entry(side).or_default();
+        if size > Decimal::ZERO {
+            book.insert(price, size);
+        } else {
+            book.remove(&price);
+        }
+    }
+
+    /// Whether there's any simulated depth to take on `side`.
+    pub fn has_asks(&self, side: Side) -> bool {
+        self.asks.get(&side).is_some_and(|book| !book.is_empty())
+    }
+
+    /// IOC buy of up to `size` at `limit` or better: consumes the cheapest
+    /// asks first, one fill per level at that level's price. The rest is
+    /// cancelled.
+    pub fn take(&mut self, side: Side, size: Decimal, limit: u16) -> Vec<PaperFill> {
+        let mut legs = Vec::new();
+        let mut remaining = size;
+        if let Some(book) = self.asks.get_mut(&side) {
+            for (&price, available) in book.range_mut(..=limit) {
+                if remaining <= Decimal::ZERO {
+                    break;
+                }
+                let leg = remaining.min(*available);
+                *available -= leg;
+                remaining -= leg;
+                legs.push((price, leg));
+            }
+            book.retain(|_, available| *available > Decimal::ZERO);
+        }
+
+        let mut fills = Vec::with_capacity(legs.len());
+        for (price, size) in legs {
+            let order_id = self.order_id("take");
+            let delay = self.latency.fill_report();
+            fills.push(PaperFill { order_id, side, price, size, is_maker: false, delay });
+        }
+        fills
+    }
+
+    /// A market print on `side` at `price`: fill every live bid above it.
+    pub fn on_trade(&mut self, now: f64, side: Side, price: u16) -> Vec<PaperFill> {
+        // Cancels whose ack delay has passed are gone
+        self.resting.retain(|o| o.cancel_at.is_none_or(|at| now < at));
+
+        let (hit, rest): (Vec<PaperOrder>, Vec<PaperOrder>) = std::mem::take(&mut self.resting)
+            .into_iter()
+            .partition(|o| o.side == side && o.live_at <= now && price < o.price);
+        self.resting = rest;
+
+        hit.into_iter()
+            .map(|o| PaperFill {
+                order_id: o.order_id,
+                side,
+                price: o.price,
+                size: o.size,
+                is_maker: true,
+                delay: self.latency.fill_report(),
+            })
+            .collect()
+    }
+
+    /// Forget every order and the simulated book (on market switch).
+    pub fn reset(&mut self) {
+        self.resting.clear();
+        self.asks.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Latency;
+    use rust_decimal_macros::dec;
+
+    fn paper() -> PaperExecutor {
+        let fixed = Latency { min_ms: 100, max_ms: 100, spike_prob: 0.0, spike_ms: 0 };
+        PaperExecutor::new(&Paper { place_ack: fixed, cancel_ack: fixed, fill_report: fixed, ..Default::default() })
+    }
+
+    #[test]
+    fn test_fills_when_market_trades_through() {
+        let mut p = paper();
+        let id = p.place(0.0, Side::Yes, 480, dec!(5));
+
+        // Not resting yet, wrong token, and at-price prints don't fill
+        assert!(p.on_trade(0.05, Side::Yes, 470).is_empty());
+        assert!(p.on_trade(1.0, Side::No, 470).is_empty());
+        assert!(p.on_trade(1.0, Side::Yes, 480).is_empty());
+
+        let fills = p.on_trade(1.0, Side::Yes, 470);
+        assert_eq!(fills.len(), 1);
+        assert_eq!((fills[0].order_id.as_str(), fills[0].price, fills[0].size), (id.as_str(), 480, dec!(5)));
+        assert!(fills[0].is_maker);
+        assert_eq!(fills[0].delay, Duration::from_millis(100));
+        // Filled in full: gone
+        assert!(p.on_trade(2.0, Side::Yes, 400).is_empty());
+    }
+
+    #[test]
+    fn test_cancel_races_fill() {
+        let mut p = paper();
+        let a = p.place(0.0, Side::No, 500, dec!(5));
+        let b = p.place(0.0, Side::No, 490, dec!(5));
+        assert!(p.cancel(1.0, &a));
+        assert!(p.cancel(1.0, &b));
+
+        // Cancel not effective yet: a still fills
+        let fills = p.on_trade(1.05, Side::No, 495);
+        assert_eq!(fills.iter().map(|f| f.order_id.as_str()).collect::<Vec<_>>(), vec![a.as_str()]);
+        assert!(!p.cancel(1.05, &a));
+
+        // After the ack delay b is gone
+        assert!(p.on_trade(1.2, Side::No, 480).is_empty());
+    }
+
+    #[test]
+    fn test_take_walks_and_consumes_depth() {
+        let mut p = paper();
+        p.set_asks(Side::Yes, [(510, dec!(3)), (520, dec!(2)), (540, dec!(10))]);
+
+        // Limit 530: the 510 and 520 levels at their own prices, rest cancelled
+        let fills = p.take(Side::Yes, dec!(8), 530);
+        assert_eq!(fills.iter().map(|f| (f.price, f.size)).collect::<Vec<_>>(), vec![(510, dec!(3)), (520, dec!(2))]);
+        assert!(fills.iter().all(|f| !f.is_maker));
+
+        // That depth is gone until the feed refills it
+        assert!(p.take(Side::Yes, dec!(5), 530).is_empty());
+        p.set_ask(Side::Yes, 520, dec!(4));
+        let fills = p.take(Side::Yes, dec!(12), 540);
+        assert_eq!(fills.iter().map(|f| (f.price, f.size)).collect::<Vec<_>>(), vec![(520, dec!(4)), (540, dec!(8))]);
+        assert!(p.has_asks(Side::Yes));
+        assert!(!p.has_asks(Side::No));
+    }
+}
@@ -0,0 +1,297 @@
+//! Randomized fault injection for soak runs.
+//!
+//! A proxy sits between the feeds and the event loop and, per event, may
+//! take the feed down for a while, drop or garble the message, or hold a
+//! fill report back. The executor asks the same injector whether a batch
+//! should time out.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use super::rng::SplitMix64;
+use crate::config::Faults;
+use crate::events::Event;
+
+/// Where an event came from, for per-feed outages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Binance,
+    Polymarket,
+    User,
+}
+
+impl Source {
+    fn of(event: &Event) -> Option<Source> {
+        match event {
+            Event::BtcPrice { .. } | Event::PerpPrice { .. } => Some(Source::Binance),
+            Event::BookUpdate { .. }
+            | Event::BookDelta { .. }
+            | Event::BookSnapshot { .. }
+            | Event::Trade { .. } => Some(Source::Polymarket),
+            Event::OrderFill { .. } => Some(Source::User),
+            // Status, signals and lifecycle events aren't faulted
+            _ => None,
+        }
+    }
+
+    fn idx(self) -> usize {
+        match self {
+            Source::Binance => 0,
+            Source::Polymarket => 1,
+            Source::User => 2,
+        }
+    }
+}
+
+/// What happens to one event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fate {
+    Pass,
+    /// Lost: in an outage, or unparseable
+    Drop,
+    /// Delivered with garbage values that still parse
+    Corrupt,
+    /// Delivered late
+    Delay(Duration),
+}
+
+/// Injected fault counts, shared between the feed proxy and the executor.
+#[derive(Debug, Default)]
+pub struct FaultCounts {
+    pub disconnects: AtomicU64,
+    pub dropped: AtomicU64,
+    pub corrupted: AtomicU64,
+    pub delayed_fills: AtomicU64,
+    pub api_timeouts: AtomicU64,
+}
+
+impl FaultCounts {
+    fn bump(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// (name, count) pairs for the report.
+    pub fn summary(&self) -> Vec<(&'static str, u64)> {
+        vec![
+            ("feed disconnects", self.disconnects.load(Ordering::Relaxed)),
+            ("events dropped", self.dropped.load(Ordering::Relaxed)),
+            ("events corrupted", self.corrupted.load(Ordering::Relaxed)),
+            ("fills delayed", self.delayed_fills.load(Ordering::Relaxed)),
+            ("API timeouts", self.api_timeouts.load(Ordering::Relaxed)),
+        ]
+    }
+}
+
+/// Decides, per event, which fault (if any) to inject. Seeded from
+/// `[paper] seed`, so a run that breaks something can be replayed.
+pub struct FaultInjector {
+    config: Faults,
+    rng: SplitMix64,
+    /// Per source: outage ends at (seconds)
+    down_until: [f64; 3],
+    counts: Arc<FaultCounts>,
+}
+
+impl FaultInjector {
+    pub fn new(config: Faults, seed: u64, counts: Arc<FaultCounts>) -> Self {
+        Self {
+            config,
+            rng: SplitMix64::new(seed),
+            down_until: [0.0; 3],
+            counts,
+        }
+    }
+
+    /// Decide the fate of one feed event at `now` (seconds).
+    pub fn decide(&mut self, now: f64, event: &Event) -> Fate {
+        let Some(source) = Source::of(event) else {
+            return Fate::Pass;
+        };
+        let down_until = &mut self.down_until[source.idx()];
+        if now < *down_until {
+            FaultCounts::bump(&self.counts.dropped);
+            return Fate::Drop;
+        }
+        if self.rng.chance(self.config.disconnect_prob) {
+            *down_until = now + self.rng.range(1, self.config.disconnect_max_ms) as f64 / 1000.0;
+            FaultCounts::bump(&self.counts.disconnects);
+            FaultCounts::bump(&self.counts.dropped);
+            return Fate::Drop;
+        }
+        if self.rng.chance(self.config.malformed_prob) {
+            // Half never parse, half parse into garbage
+            return if self.rng.chance(0.5) {
+                FaultCounts::bump(&self.counts.dropped);
+                Fate::Drop
+            } else {
+                FaultCounts::bump(&self.counts.corrupted);
+                Fate::Corrupt
+            };
+        }
+        // Paper fills come off trade prints, so those are held back too
+        let fill_like = matches!(event, Event::OrderFill { .. } | Event::Trade { .. });
+        if fill_like && self.rng.chance(self.config.fill_delay_prob) {
+            FaultCounts::bump(&self.counts.delayed_fills);
+            return Fate::Delay(Duration::from_millis(self.rng.range(1, self.config.fill_delay_max_ms)));
+        }
+        Fate::Pass
+    }
+
+    /// Garble an event's values the way a bad message would.
+    pub fn corrupt(&mut self, event: Event) -> Event {
+        let pick = self.rng.range(0, 2);
+        let bad_price = [f64::NAN, 0.0, -1.0][pick as usize];
+        match event {
+            Event::BtcPrice { price } => Event::BtcPrice { price: if pick == 2 { price * 100.0 } else { bad_price } },
+            Event::PerpPrice { price } => Event::PerpPrice { price: if pick == 2 { price * 100.0 } else { bad_price } },
+            Event::BookUpdate { side, bid, ask } => match pick {
+                0 => Event::BookUpdate { side, bid: ask, ask: bid },
+                1 => Event::BookUpdate { side, bid, ask: 0 },
+                _ => Event::BookUpdate { side, bid: 1500, ask },
+            },
+            Event::BookDelta { side, is_bid, price, .. } => Event::BookDelta { side, is_bid, price, size: bad_price },
+            Event::BookSnapshot { side, bids, asks } => Event::BookSnapshot { side, bids: asks, asks: bids },
+            Event::Trade { side, taker_buy, .. } => Event::Trade { side, price: 1500, taker_buy },
+            Event::OrderFill { order_id, side, price, size, is_maker } => match pick {
+                0 => Event::OrderFill { order_id: "unknown".to_string(), side, price, size, is_maker },
+                1 => Event::OrderFill { order_id, side, price, size: -size, is_maker },
+                _ => Event::OrderFill { order_id, side, price: 1500, size, is_maker },
+            },
+            other => other,
+        }
+    }
+
+    /// Extra executor batch fault: Some(hang) if this one should time out.
+    pub fn api_timeout(&mut self) -> Option<Duration> {
+        if self.rng.chance(self.config.api_timeout_prob) {
+            FaultCounts::bump(&self.counts.api_timeouts);
+            Some(Duration::from_millis(self.config.api_timeout_ms))
+        } else {
+            None
+        }
+    }
+}
+
+/// Put a fault-injecting proxy in front of `tx`. Feeds send to the returned
+/// sender; events reach `tx` dropped, garbled, late or untouched.
+pub fn spawn_proxy(mut injector: FaultInjector, tx: mpsc::Sender<Event>) -> mpsc::Sender<Event> {
+    let (feed_tx, mut feed_rx) = mpsc::channel::<Event>(1000);
+    let start = tokio::time::Instant::now();
+    tokio::spawn(async move {
+        while let Some(event) = feed_rx.recv().await {
+            match injector.decide(start.elapsed().as_secs_f64(), &event) {
+                Fate::Pass => {
+                    if tx.send(event).await.is_err() {
+                        break;
+                    }
+                }
+                Fate::Drop => {}
+                Fate::Corrupt => {
+                    let event = injector.corrupt(event);
+                    let _ = tx.send(event).await;
+                }
+                Fate::Delay(delay) => {
+                    let tx = tx.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(delay).await;
+                        let _ = tx.send(event).await;
+                    });
+                }
+            }
+        }
+    });
+    feed_tx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Side;
+
+    fn injector(config: Faults) -> FaultInjector {
+        FaultInjector::new(config, 7, Arc::new(FaultCounts::default()))
+    }
+
+    fn quiet() -> Faults {
+        Faults {
+            disconnect_prob: 0.0,
+            malformed_prob: 0.0,
+            fill_delay_prob: 0.0,
+            api_timeout_prob: 0.0,
+            ..Default::default()
+        }
+    }
+
+    fn fill() -> Event {
+        Event::OrderFill { order_id: "o1".into(), side: Side::Yes, price: 480, size: 5.0, is_maker: true }
+    }
+
+    #[test]
+    fn test_no_faults_passes_everything() {
+        let mut f = injector(quiet());
+        for i in 0..1000 {
+            assert_eq!(f.decide(i as f64, &Event::BtcPrice { price: 1.0 }), Fate::Pass);
+            assert_eq!(f.decide(i as f64, &fill()), Fate::Pass);
+        }
+        assert_eq!(f.api_timeout(), None);
+        assert!(f.counts.summary().iter().all(|&(_, n)| n == 0));
+    }
+
+    #[test]
+    fn test_outage_is_per_feed() {
+        let mut f = injector(Faults { disconnect_prob: 1.0, disconnect_max_ms: 5000, ..quiet() });
+        assert_eq!(f.decide(0.0, &Event::BtcPrice { price: 1.0 }), Fate::Drop);
+        assert_eq!(f.counts.disconnects.load(Ordering::Relaxed), 1);
+        // Binance stays down without new disconnects...
+        f.config.disconnect_prob = 0.0;
+        assert_eq!(f.decide(0.0, &Event::BtcPrice { price: 1.0 }), Fate::Drop);
+        assert_eq!(f.counts.disconnects.load(Ordering::Relaxed), 1);
+        // ...while Polymarket is up, and Binance comes back after at most 5s
+        assert_eq!(f.decide(0.0, &Event::BookUpdate { side: Side::Yes, bid: 480, ask: 490 }), Fate::Pass);
+        assert_eq!(f.decide(5.0, &Event::BtcPrice { price: 1.0 }), Fate::Pass);
+    }
+
+    #[test]
+    fn test_only_fills_are_delayed() {
+        let mut f = injector(Faults { fill_delay_prob: 1.0, fill_delay_max_ms: 3000, ..quiet() });
+        assert_eq!(f.decide(0.0, &Event::BookUpdate { side: Side::No, bid: 500, ask: 510 }), Fate::Pass);
+        for event in [fill(), Event::Trade { side: Side::No, price: 500, taker_buy: None }] {
+            match f.decide(0.0, &event) {
+                Fate::Delay(d) => assert!(d >= Duration::from_millis(1) && d <= Duration::from_millis(3000)),
+                other => panic!("expected delay, got {:?}", other),
+            }
+        }
+        assert_eq!(f.decide(0.0, &Event::Tick), Fate::Pass);
+    }
+
+    #[test]
+    fn test_corrupt_changes_values() {
+        let mut f = injector(quiet());
+        for _ in 0..20 {
+            match f.corrupt(Event::BookUpdate { side: Side::Yes, bid: 480, ask: 490 }) {
+                Event::BookUpdate { bid, ask, .. } => assert_ne!((bid, ask), (480, 490)),
+                _ => panic!("variant changed"),
+            }
+            match f.corrupt(fill()) {
+                Event::OrderFill { order_id, price, size, .. } => {
+                    assert!(order_id != "o1" || price != 480 || size != 5.0)
+                }
+                _ => panic!("variant changed"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_deterministic() {
+        let config = Faults { disconnect_prob: 0.1, malformed_prob: 0.1, fill_delay_prob: 0.3, ..Default::default() };
+        let mut a = injector(config);
+        let mut b = injector(config);
+        for i in 0..500 {
+            let now = i as f64 * 0.1;
+            assert_eq!(a.decide(now, &fill()), b.decide(now, &fill()));
+        }
+    }
+}
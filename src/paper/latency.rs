@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use super::rng::SplitMix64;
+use crate::config::{Latency, Paper};
+
+/// Samples simulated exchange delays from the configured distributions.
+/// Seeded, so the same config replays the same delays.
+#[derive(Debug, Clone)]
+pub struct LatencyModel {
+    config: Paper,
+    rng: SplitMix64,
+}
+
+impl LatencyModel {
+    pub fn from_config(config: &Paper) -> Self {
+        Self {
+            config: config.clone(),
+            rng: SplitMix64::new(config.seed),
+        }
+    }
+
+    /// Delay before a placed order is live.
+    pub fn place_ack(&mut self) -> Duration {
+        let dist = self.config.place_ack;
+        self.sample(dist)
+    }
+
+    /// Delay before a cancel is effective.
+    pub fn cancel_ack(&mut self) -> Duration {
+        let dist = self.config.cancel_ack;
+        self.sample(dist)
+    }
+
+    /// Delay before a fill is reported.
+    pub fn fill_report(&mut self) -> Duration {
+        let dist = self.config.fill_report;
+        self.sample(dist)
+    }
+
+    fn sample(&mut self, dist: Latency) -> Duration {
+        if self.rng.chance(dist.spike_prob) {
+            return Duration::from_millis(dist.spike_ms);
+        }
+        Duration::from_millis(self.rng.range(dist.min_ms, dist.max_ms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_within_range() {
+        let config = Paper {
+            place_ack: Latency { min_ms: 40, max_ms: 120, spike_prob: 0.0, spike_ms: 0 },
+            ..Default::default()
+        };
+        let mut model = LatencyModel::from_config(&config);
+
+        for _ in 0..1000 {
+            let d = model.place_ack().as_millis() as u64;
+            assert!((40..=120).contains(&d), "{} out of range", d);
+        }
+    }
+
+    #[test]
+    fn test_spikes_and_determinism() {
+        let config = Paper {
+            fill_report: Latency { min_ms: 10, max_ms: 20, spike_prob: 0.5, spike_ms: 1000 },
+            ..Default::default()
+        };
+        let mut a = LatencyModel::from_config(&config);
+        let mut b = LatencyModel::from_config(&config);
+
+        let samples: Vec<Duration> = (0..200).map(|_| a.fill_report()).collect();
+        let spikes = samples.iter().filter(|d| d.as_millis() == 1000).count();
+        assert!(spikes > 50 && spikes < 150, "{} spikes", spikes);
+
+        // Same seed, same sequence
+        let replay: Vec<Duration> = (0..200).map(|_| b.fill_report()).collect();
+        assert_eq!(samples, replay);
+    }
+
+    #[test]
+    fn test_fixed_latency() {
+        let config = Paper {
+            cancel_ack: Latency { min_ms: 75, max_ms: 75, spike_prob: 0.0, spike_ms: 0 },
+            ..Default::default()
+        };
+        let mut model = LatencyModel::from_config(&config);
+        assert_eq!(model.cancel_ack(), Duration::from_millis(75));
+    }
+}
@@ -0,0 +1,24 @@
+//! Paper trading support.
+//!
+//! Instant acks and fills make paper results look better than live ones.
+//! [`LatencyModel`] samples realistic delays for order acks and fill
+//! reports so a simulated session sees the same races the exchange causes.
+//!
+//! [`PaperExecutor`] is the venue behind the executor when `[paper] enabled`
+//! is set: orders rest and fill against the live trade prints.
+//!
+//! Soak runs go further: [`FaultInjector`] randomly takes feeds down,
+//! garbles messages, delays fills and times out API calls, and
+//! [`SoakReport`] tallies the invariant violations that follow.
+
+mod executor;
+mod faults;
+mod latency;
+mod rng;
+mod soak;
+
+pub use executor::{PaperExecutor, PaperFill};
+pub use faults::{spawn_proxy, Fate, FaultCounts, FaultInjector, Source};
+pub use latency::LatencyModel;
+pub use rng::SplitMix64;
+pub use soak::{SoakReport, ViolationTally};
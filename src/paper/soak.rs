@@ -0,0 +1,145 @@
+//! Soak runs: hours of paper trading under injected faults, with every
+//! invariant violation tallied for a report at the end.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::Write as _;
+
+use super::faults::FaultCounts;
+use crate::risk::Violation;
+
+/// One kind of invariant violation over a soak run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViolationTally {
+    /// Separate incidents (a violation that persists across cycles is one)
+    pub incidents: u64,
+    /// When it first happened (seconds into the run)
+    pub first_at: f64,
+    /// The first one, as printed
+    pub example: String,
+}
+
+/// Aggregates invariant violations over a soak run instead of halting on
+/// the first one.
+pub struct SoakReport {
+    start: f64,
+    duration_secs: f64,
+    tallies: BTreeMap<&'static str, ViolationTally>,
+    /// Kinds violated on the previous check
+    active: HashSet<&'static str>,
+}
+
+impl SoakReport {
+    pub fn new(start: f64, duration_secs: f64) -> Self {
+        Self {
+            start,
+            duration_secs,
+            tallies: BTreeMap::new(),
+            active: HashSet::new(),
+        }
+    }
+
+    /// Whether the run has gone its full length.
+    pub fn is_done(&self, now: f64) -> bool {
+        now - self.start >= self.duration_secs
+    }
+
+    /// Record one invariant check's result. Returns the violations that
+    /// start a new incident.
+    pub fn record<'a>(&mut self, now: f64, violations: &'a [Violation]) -> Vec<&'a Violation> {
+        let mut new = Vec::new();
+        let kinds: HashSet<&'static str> = violations.iter().map(Violation::kind).collect();
+        for v in violations {
+            // Already active (last check, or earlier in this one): same incident
+            if !self.active.insert(v.kind()) {
+                continue;
+            }
+            let start = self.start;
+            let tally = self.tallies.entry(v.kind()).or_insert_with(|| ViolationTally {
+                incidents: 0,
+                first_at: now - start,
+                example: v.to_string(),
+            });
+            tally.incidents += 1;
+            new.push(v);
+        }
+        self.active.retain(|k| kinds.contains(k));
+        new
+    }
+
+    pub fn tallies(&self) -> &BTreeMap<&'static str, ViolationTally> {
+        &self.tallies
+    }
+
+    /// True if nothing was ever violated.
+    pub fn is_clean(&self) -> bool {
+        self.tallies.is_empty()
+    }
+
+    /// Human-readable summary: faults injected, then violations.
+    pub fn render(&self, now: f64, faults: &FaultCounts) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "Soak: {:.1}h", (now - self.start) / 3600.0);
+        let _ = writeln!(out, "Faults injected:");
+        for (name, count) in faults.summary() {
+            let _ = writeln!(out, "  {:<18} {}", name, count);
+        }
+        if self.is_clean() {
+            let _ = writeln!(out, "Invariant violations: none");
+        } else {
+            let _ = writeln!(out, "Invariant violations:");
+            for (kind, t) in &self.tallies {
+                let _ = writeln!(
+                    out,
+                    "  {:<22} {} incident(s), first at +{:.0}s: {}",
+                    kind, t.incidents, t.first_at, t.example
+                );
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn crossed() -> Violation {
+        Violation::BookCrossed { secs: 6.0 }
+    }
+
+    fn small(id: &str) -> Violation {
+        Violation::OrderBelowMinSize { order_id: id.into(), size: dec!(2) }
+    }
+
+    #[test]
+    fn test_persistent_violation_is_one_incident() {
+        let mut report = SoakReport::new(100.0, 3600.0);
+        assert_eq!(report.record(110.0, &[crossed()]).len(), 1);
+        assert!(report.record(110.1, &[crossed()]).is_empty());
+        let new = [crossed(), small("a"), small("b")];
+        assert_eq!(report.record(110.2, &new), vec![&new[1]]);
+        report.record(111.0, &[]);
+        report.record(120.0, &[crossed()]);
+
+        let t = &report.tallies()["book_crossed"];
+        assert_eq!(t.incidents, 2);
+        assert_eq!(t.first_at, 10.0);
+        assert_eq!(report.tallies()["order_below_min_size"].incidents, 1);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_duration_and_render() {
+        let mut report = SoakReport::new(0.0, 7200.0);
+        assert!(!report.is_done(7199.0));
+        assert!(report.is_done(7200.0));
+
+        let faults = FaultCounts::default();
+        assert!(report.render(3600.0, &faults).contains("Invariant violations: none"));
+        report.record(5.0, &[crossed()]);
+        let text = report.render(3600.0, &faults);
+        assert!(text.contains("book_crossed"), "{}", text);
+        assert!(text.contains("1 incident(s), first at +5s"), "{}", text);
+    }
+}
@@ -0,0 +1,312 @@
+//! Strategy plugins: a `decide(state) -> actions` boundary in WASM.
+//!
+//! A plugin sees the same frame the journal records (book, ladder,
+//! position, signals) and answers with actions. Everything crosses as JSON
+//! through the module's own memory, so a plugin can be written in anything
+//! that compiles to wasm32:
+//!
+//! ```text
+//! (export "memory")
+//! (export "alloc"  (func (param i32) (result i32)))          ;; len -> ptr
+//! (export "decide" (func (param i32 i32) (result i64)))      ;; ptr, len -> (out_ptr << 32) | out_len
+//! ```
+//!
+//! Input is a `journal::Snapshot`; output is a JSON array of
+//! `PluginAction`, e.g. `[{"op":"place","side":"YES","price":480,"size":5}]`.
+//!
+//! The runtime (feature `wasm`) links no host functions, so a plugin can't
+//! touch the network, files or clock. Each call gets a fuel budget and the
+//! memory is capped; running out of either is an error, not a hang.
+
+use anyhow::{bail, Context, Result};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::events::Side;
+use crate::state::OrderView;
+use crate::strategy::Action;
+
+/// Instructions a single `decide` call may execute
+pub const PLUGIN_FUEL: u64 = 10_000_000;
+
+/// Linear memory cap per plugin (bytes)
+pub const PLUGIN_MAX_MEMORY: usize = 16 << 20;
+
+/// Side as plugins spell it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PluginSide {
+    Yes,
+    No,
+}
+
+impl From<PluginSide> for Side {
+    fn from(side: PluginSide) -> Self {
+        match side {
+            PluginSide::Yes => Side::Yes,
+            PluginSide::No => Side::No,
+        }
+    }
+}
+
+/// What a plugin can ask for. Cancels are by price level: plugins never see
+/// order IDs.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PluginAction {
+    /// GTC bid (price in ticks, size in shares)
+    Place { side: PluginSide, price: u16, size: f64 },
+    /// Cancel everything resting at this price
+    Cancel { side: PluginSide, price: u16 },
+    /// Cancel both sides
+    CancelAll,
+}
+
+/// Parse a plugin's output.
+pub fn parse_actions(json: &[u8]) -> Result<Vec<PluginAction>> {
+    serde_json::from_slice(json).context("plugin output is not a JSON action list")
+}
+
+/// Turn plugin actions into executor actions against the resting ladder.
+///
+/// # Errors
+/// Any out-of-range price or non-positive size rejects the whole batch:
+/// a plugin that emits garbage shouldn't get half its orders through.
+pub fn to_actions(plugin_actions: &[PluginAction], orders: OrderView) -> Result<Vec<Action>> {
+    let mut actions = Vec::new();
+    for action in plugin_actions {
+        match *action {
+            PluginAction::Place { side, price, size } => {
+                if price == 0 || price >= 1000 {
+                    bail!("place price out of range: {}", price);
+                }
+                if !(size.is_finite() && size > 0.0) {
+                    bail!("place size must be positive: {}", size);
+                }
+                let size = Decimal::try_from(size).context("place size")?;
+                actions.push(Action::place(side.into(), price, size));
+            }
+            PluginAction::Cancel { side, price } => {
+                for order in orders.orders_at_price(side.into(), price) {
+                    actions.push(Action::Cancel { order_id: order.order_id.clone() });
+                }
+            }
+            PluginAction::CancelAll => actions.push(Action::CancelAll),
+        }
+    }
+    Ok(actions)
+}
+
+#[cfg(feature = "wasm")]
+pub use runtime::WasmPlugin;
+
+/// Stand-in when built without the `wasm` feature: loading always fails.
+#[cfg(not(feature = "wasm"))]
+pub struct WasmPlugin {
+    path: std::path::PathBuf,
+}
+
+#[cfg(not(feature = "wasm"))]
+impl WasmPlugin {
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        bail!("can't load {}: built without the `wasm` feature", path.as_ref().display())
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    pub fn decide(&mut self, _snapshot: &crate::journal::Snapshot) -> Result<Vec<PluginAction>> {
+        bail!("built without the `wasm` feature")
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod runtime {
+    use std::path::{Path, PathBuf};
+
+    use anyhow::{anyhow, Context, Result};
+    use wasmi::{Engine, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc};
+
+    use super::{parse_actions, PluginAction, PLUGIN_FUEL, PLUGIN_MAX_MEMORY};
+    use crate::journal::Snapshot;
+
+    /// A loaded plugin instance.
+    pub struct WasmPlugin {
+        path: PathBuf,
+        store: Store<StoreLimits>,
+        memory: Memory,
+        alloc: TypedFunc<i32, i32>,
+        decide: TypedFunc<(i32, i32), i64>,
+    }
+
+    impl WasmPlugin {
+        /// Load and instantiate a plugin from a `.wasm` file.
+        pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+            let path = path.as_ref();
+            let wasm = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+            Self::from_bytes(&wasm).map(|mut p| {
+                p.path = path.to_path_buf();
+                p
+            })
+        }
+
+        /// Instantiate from module bytes. Fails if the module imports anything.
+        pub fn from_bytes(wasm: &[u8]) -> Result<Self> {
+            let mut config = wasmi::Config::default();
+            config.consume_fuel(true);
+            let engine = Engine::new(&config);
+            let module = Module::new(&engine, wasm).context("invalid wasm module")?;
+
+            let limits = StoreLimitsBuilder::new().memory_size(PLUGIN_MAX_MEMORY).build();
+            let mut store = Store::new(&engine, limits);
+            store.limiter(|limits| limits);
+            store.set_fuel(PLUGIN_FUEL).map_err(|e| anyhow!("{}", e))?;
+
+            // No host functions: an import is a link error
+            let linker = <Linker<StoreLimits>>::new(&engine);
+            let instance: Instance = linker
+                .instantiate(&mut store, &module)
+                .context("plugin must not import anything")?
+                .start(&mut store)?;
+
+            let memory = instance.get_memory(&store, "memory").context("plugin exports no memory")?;
+            let alloc = instance.get_typed_func::<i32, i32>(&store, "alloc").context("plugin exports no alloc")?;
+            let decide = instance
+                .get_typed_func::<(i32, i32), i64>(&store, "decide")
+                .context("plugin exports no decide")?;
+
+            Ok(Self { path: PathBuf::new(), store, memory, alloc, decide })
+        }
+
+        /// Where this plugin was loaded from (empty for `from_bytes`).
+        pub fn path(&self) -> &Path {
+            &self.path
+        }
+
+        /// Run one decision.
+        pub fn decide(&mut self, snapshot: &Snapshot) -> Result<Vec<PluginAction>> {
+            let input = serde_json::to_vec(snapshot)?;
+            self.store.set_fuel(PLUGIN_FUEL).map_err(|e| anyhow!("{}", e))?;
+
+            let ptr = self.alloc.call(&mut self.store, input.len() as i32).context("plugin alloc")?;
+            self.memory
+                .write(&mut self.store, ptr as u32 as usize, &input)
+                .map_err(|e| anyhow!("writing plugin input: {}", e))?;
+
+            let packed = self.decide.call(&mut self.store, (ptr, input.len() as i32)).context("plugin decide")?;
+            let (out_ptr, out_len) = ((packed as u64 >> 32) as usize, (packed as u64 & 0xffff_ffff) as usize);
+            let mut output = vec![0u8; out_len];
+            self.memory
+                .read(&self.store, out_ptr, &mut output)
+                .map_err(|e| anyhow!("reading plugin output: {}", e))?;
+            parse_actions(&output)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::risk::Variant;
+    use crate::state::OrderTracker;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_actions() {
+        let json = br#"[
+            {"op":"place","side":"YES","price":480,"size":5},
+            {"op":"cancel","side":"NO","price":500},
+            {"op":"cancel_all"}
+        ]"#;
+        let actions = parse_actions(json).unwrap();
+        assert_eq!(
+            actions,
+            vec![
+                PluginAction::Place { side: PluginSide::Yes, price: 480, size: 5.0 },
+                PluginAction::Cancel { side: PluginSide::No, price: 500 },
+                PluginAction::CancelAll,
+            ]
+        );
+        assert!(parse_actions(br#"[{"op":"buy_everything"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_to_actions() {
+        let mut orders = OrderTracker::new();
+        orders.add(Side::No, "a".into(), 500, dec!(5));
+        orders.add(Side::No, "b".into(), 500, dec!(5));
+
+        let actions = to_actions(
+            &[
+                PluginAction::Place { side: PluginSide::Yes, price: 480, size: 5.0 },
+                PluginAction::Cancel { side: PluginSide::No, price: 500 },
+                PluginAction::Cancel { side: PluginSide::No, price: 490 },
+            ],
+            orders.of(Variant::Ladder),
+        )
+        .unwrap();
+        assert_eq!(
+            actions,
+            vec![
+                Action::place(Side::Yes, 480, dec!(5)),
+                Action::Cancel { order_id: "a".into() },
+                Action::Cancel { order_id: "b".into() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_actions_rejects_bad_batch() {
+        let orders = OrderTracker::new();
+        let good = PluginAction::Place { side: PluginSide::Yes, price: 480, size: 5.0 };
+        for bad in [
+            PluginAction::Place { side: PluginSide::Yes, price: 1000, size: 5.0 },
+            PluginAction::Place { side: PluginSide::No, price: 0, size: 5.0 },
+            PluginAction::Place { side: PluginSide::No, price: 480, size: 0.0 },
+            PluginAction::Place { side: PluginSide::No, price: 480, size: f64::NAN },
+        ] {
+            assert!(to_actions(&[good.clone(), bad], orders.of(Variant::Ladder)).is_err());
+        }
+    }
+
+    #[cfg(feature = "wasm")]
+    fn plugin(decide_body: &str) -> Result<WasmPlugin> {
+        let wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 0) "[{{\"op\":\"cancel_all\"}}]")
+                (func (export "alloc") (param i32) (result i32) i32.const 1024)
+                (func (export "decide") (param i32 i32) (result i64) {}))"#,
+            decide_body
+        );
+        WasmPlugin::from_bytes(&wat::parse_str(wat).unwrap())
+    }
+
+    #[cfg(feature = "wasm")]
+    fn snapshot() -> crate::journal::Snapshot {
+        use crate::journal::Signals;
+        use crate::state::{Book, Position};
+        crate::journal::Snapshot::capture(0.0, "m", 100.0, &Book::default(), &OrderTracker::new(), &Position::default(), Signals::default())
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_wasm_decide() {
+        // Output is the 21-byte data segment at offset 0
+        let mut p = plugin("i64.const 21").unwrap();
+        assert_eq!(p.decide(&snapshot()).unwrap(), vec![PluginAction::CancelAll]);
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn test_wasm_sandbox() {
+        // Runaway plugin runs out of fuel instead of hanging the loop
+        let mut p = plugin("(loop (br 0)) i64.const 0").unwrap();
+        assert!(p.decide(&snapshot()).is_err());
+
+        // Imports are refused
+        let wasm = wat::parse_str(r#"(module (import "env" "now" (func)))"#).unwrap();
+        assert!(WasmPlugin::from_bytes(&wasm).is_err());
+    }
+}
@@ -0,0 +1,287 @@
+//! Preflight checklist: everything live trading needs, checked up front.
+//!
+//! `polybot preflight` runs each check and prints a pass/warn/fail
+//! table: config, credentials, USDC balance and allowances, Gamma, the
+//! market WebSocket, clock skew and CLOB latency. A failure is a blocker (the bot would refuse to start, or start
+//! and have its orders rejected) and makes the command exit nonzero; a
+//! warning is worth a look but doesn't stop a run.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use polyfill_rs::ClobClient;
+
+use crate::api::account::{self, EXCHANGES};
+use crate::api::{gamma, CLOB_BASE};
+use crate::clock::{Clock, SystemClock};
+use crate::config::Config;
+use crate::feeds::polymarket::POLYMARKET_WS_URL;
+use crate::strategy::StrategyConfig;
+
+/// Less than one minimum order (5 shares at up to $1) can't quote at all
+pub const MIN_BALANCE_USD: f64 = 5.0;
+
+/// Local clock vs the CLOB's: signed requests carry a timestamp
+const SKEW_WARN_SECS: f64 = 2.0;
+const SKEW_FAIL_SECS: f64 = 10.0;
+
+/// CLOB round trip; the canary calls the order path degraded past 1.5s
+const LATENCY_WARN_MS: u64 = 300;
+const LATENCY_FAIL_MS: u64 = 1500;
+
+const CONFIG_PATH: &str = "config.toml";
+const TIMEOUT_SECS: u64 = 5;
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Warn,
+    /// A blocker
+    Fail,
+}
+
+impl Status {
+    fn label(&self) -> &'static str {
+        match self {
+            Status::Pass => "PASS",
+            Status::Warn => "WARN",
+            Status::Fail => "FAIL",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub name: String,
+    pub status: Status,
+    pub detail: String,
+}
+
+/// Checks in the order they ran.
+#[derive(Debug, Default)]
+pub struct Checklist {
+    checks: Vec<Check>,
+}
+
+impl Checklist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, name: impl Into<String>, status: Status, detail: impl Into<String>) {
+        self.checks.push(Check { name: name.into(), status, detail: detail.into() });
+    }
+
+    /// Pass with the value's description, or fail with the error.
+    pub fn result(&mut self, name: impl Into<String>, result: Result<String>) {
+        match result {
+            Ok(detail) => self.record(name, Status::Pass, detail),
+            Err(e) => self.record(name, Status::Fail, format!("{:#}", e)),
+        }
+    }
+
+    pub fn checks(&self) -> &[Check] {
+        &self.checks
+    }
+
+    /// Number of failed checks.
+    pub fn blockers(&self) -> usize {
+        self.checks.iter().filter(|c| c.status == Status::Fail).count()
+    }
+
+    /// The table, one check per row, and a summary line.
+    pub fn render(&self) -> String {
+        let width = self.checks.iter().map(|c| c.name.len()).max().unwrap_or(0);
+        let mut out = String::new();
+        for check in &self.checks {
+            out.push_str(&format!("{}  {:<width$}  {}\n", check.status.label(), check.name, check.detail, width = width));
+        }
+        let warnings = self.checks.iter().filter(|c| c.status == Status::Warn).count();
+        out.push_str(&format!(
+            "\n{} checks: {} passed, {} warnings, {} blockers\n",
+            self.checks.len(),
+            self.checks.len() - warnings - self.blockers(),
+            warnings,
+            self.blockers()
+        ));
+        out
+    }
+}
+
+/// Local clock minus the CLOB's, in seconds.
+pub fn clock_skew(skew_secs: f64) -> Status {
+    match skew_secs.abs() {
+        s if s >= SKEW_FAIL_SECS => Status::Fail,
+        s if s >= SKEW_WARN_SECS => Status::Warn,
+        _ => Status::Pass,
+    }
+}
+
+/// Round trip to the CLOB.
+pub fn latency(ms: u64) -> Status {
+    match ms {
+        ms if ms > LATENCY_FAIL_MS => Status::Fail,
+        ms if ms > LATENCY_WARN_MS => Status::Warn,
+        _ => Status::Pass,
+    }
+}
+
+/// USDC in the wallet.
+pub fn balance(usd: f64) -> Status {
+    if usd < MIN_BALANCE_USD {
+        Status::Fail
+    } else {
+        Status::Pass
+    }
+}
+
+/// An exchange's allowance against the balance it may need to move: below
+/// a minimum order blocks, below the balance strands the rest.
+pub fn allowance(allowance_usd: f64, balance_usd: f64) -> Status {
+    if allowance_usd < MIN_BALANCE_USD {
+        Status::Fail
+    } else if allowance_usd < balance_usd {
+        Status::Warn
+    } else {
+        Status::Pass
+    }
+}
+
+/// The CLOB's clock (unix seconds).
+async fn clob_time(http: &reqwest::Client) -> Result<f64> {
+    let text = http.get(format!("{}/time", CLOB_BASE)).send().await?.error_for_status()?.text().await?;
+    text.trim().parse().map_err(|e| anyhow!("bad /time response {:?}: {}", text, e))
+}
+
+/// Run every check, print the table, and exit nonzero on a blocker.
+pub async fn run() -> Result<()> {
+    println!("=== Preflight ===\n");
+
+    let mut checklist = Checklist::new();
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(TIMEOUT_SECS))
+        .build()?;
+
+    // Config: parses, and the strategy overrides are valid
+    if std::path::Path::new(CONFIG_PATH).exists() {
+        let checked = Config::load(CONFIG_PATH).and_then(|config| {
+            config.strategy.apply(&mut StrategyConfig::default())?;
+            Ok(format!("{} valid", CONFIG_PATH))
+        });
+        checklist.result("Config", checked);
+    } else {
+        checklist.record("Config", Status::Warn, format!("no {}, built-in settings", CONFIG_PATH));
+    }
+
+    // Credentials: derive the API key, as the bot does at startup
+    let private_key = std::env::var("POLY_PRIVATE_KEY").ok();
+    let wallet = std::env::var("POLY_PROXY_WALLET").ok();
+    match &private_key {
+        Some(key) => {
+            let client = ClobClient::with_l1_headers(CLOB_BASE, key, 137);
+            let derived = tokio::time::timeout(Duration::from_secs(TIMEOUT_SECS), client.create_or_derive_api_key(None))
+                .await
+                .map_err(|_| anyhow!("timed out"))
+                .and_then(|r| r.map_err(|e| anyhow!("{}", e)))
+                .map(|creds| format!("API key {}...", &creds.api_key[..8.min(creds.api_key.len())]));
+            checklist.result("Credentials", derived);
+        }
+        None => checklist.record("Credentials", Status::Fail, "POLY_PRIVATE_KEY not set"),
+    }
+
+    // Funding: USDC in the proxy wallet, and what the exchanges may move
+    match &wallet {
+        Some(wallet) => {
+            let rpc = account::polygon_rpc();
+            match account::usdc_balance(&http, &rpc, wallet).await {
+                Ok(usd) => {
+                    checklist.record("USDC balance", balance(usd), format!("${:.2}", usd));
+                    for (name, spender) in EXCHANGES {
+                        let check = format!("Allowance ({})", name);
+                        match account::usdc_allowance(&http, &rpc, wallet, spender).await {
+                            Ok(a) if a > 1e12 => checklist.record(check, allowance(a, usd), "unlimited"),
+                            Ok(a) => checklist.record(check, allowance(a, usd), format!("${:.2}", a)),
+                            Err(e) => checklist.record(check, Status::Fail, format!("{:#}", e)),
+                        }
+                    }
+                }
+                Err(e) => checklist.record("USDC balance", Status::Fail, format!("{:#}", e)),
+            }
+        }
+        None => checklist.record("USDC balance", Status::Fail, "POLY_PROXY_WALLET not set"),
+    }
+
+    // Gamma: the current market can be found
+    let market = tokio::time::timeout(Duration::from_secs(TIMEOUT_SECS), gamma::get_5m_market_at(&SystemClock))
+        .await
+        .map_err(|_| anyhow!("timed out"))
+        .and_then(|r| r)
+        .map(|m| m.slug);
+    checklist.result("Gamma", market);
+
+    // Market WebSocket: connects
+    let started = Instant::now();
+    let connected = tokio::time::timeout(Duration::from_secs(TIMEOUT_SECS), tokio_tungstenite::connect_async(POLYMARKET_WS_URL))
+        .await
+        .map_err(|_| anyhow!("timed out"))
+        .and_then(|r| r.map_err(|e| anyhow!("{}", e)))
+        .map(|_| format!("connected in {}ms", started.elapsed().as_millis()));
+    checklist.result("Market WebSocket", connected);
+
+    // CLOB: round trip, and our clock against its (the midpoint of the request)
+    let sent_ms = SystemClock.now_ms();
+    let started = Instant::now();
+    let server_time = clob_time(&http).await;
+    let rtt_ms = started.elapsed().as_millis() as u64;
+    match server_time {
+        Ok(server_secs) => {
+            checklist.record("CLOB latency", latency(rtt_ms), format!("{}ms", rtt_ms));
+            let local_secs = (sent_ms as f64 + rtt_ms as f64 / 2.0) / 1000.0;
+            let skew = local_secs - server_secs;
+            checklist.record("Clock skew", clock_skew(skew), format!("{:+.1}s vs CLOB", skew));
+        }
+        Err(e) => {
+            checklist.record("CLOB latency", Status::Fail, format!("{:#}", e));
+            checklist.record("Clock skew", Status::Fail, "no CLOB time");
+        }
+    }
+
+    print!("{}", checklist.render());
+    if checklist.blockers() > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_thresholds() {
+        assert_eq!(clock_skew(0.4), Status::Pass);
+        assert_eq!(clock_skew(-3.0), Status::Warn);
+        assert_eq!(clock_skew(12.0), Status::Fail);
+        assert_eq!(latency(120), Status::Pass);
+        assert_eq!(latency(800), Status::Warn);
+        assert_eq!(latency(2000), Status::Fail);
+        assert_eq!(balance(4.99), Status::Fail);
+        assert_eq!(allowance(50.0, 100.0), Status::Warn);
+        assert_eq!(allowance(0.0, 100.0), Status::Fail);
+    }
+
+    #[test]
+    fn test_render_counts_blockers() {
+        let mut checklist = Checklist::new();
+        checklist.result("Config", Ok("config.toml valid".to_string()));
+        checklist.record("Clock skew", Status::Warn, "+3.0s");
+        checklist.result("Gamma", Err(anyhow!("timed out")));
+
+        assert_eq!(checklist.blockers(), 1);
+        let table = checklist.render();
+        assert!(table.contains("FAIL  Gamma       timed out"));
+        assert!(table.ends_with("3 checks: 1 passed, 1 warnings, 1 blockers\n"));
+    }
+}
@@ -0,0 +1,317 @@
+//! `polybot replay`: step through a recorded session journal frame by frame.
+//!
+//! Shows what the bot saw (book, BTC, signals) and what it had out
+//! (ladder, position) at each snapshot. Useful for post-mortems.
+//!
+//! Usage:
+//!     cargo run -- replay logs/polybot_<ts>.journal.jsonl          # print all frames
+//!     cargo run -- replay logs/polybot_<ts>.journal.jsonl --tui    # interactive
+//!     cargo run -- replay logs/polybot_<ts>.journal.jsonl --fills  # fill model backtest
+//!     cargo run -- replay logs/state.jsonl --ledger                 # state ledger audit
+//!
+//! `--fills` scores the fill models against the rungs that actually
+//! filled: the default heuristic, and a model calibrated on the first half
+//! of the journal (scored on the second half only).
+//!
+//! `--ledger` reads the state ledger instead: every placement, cancel, fill
+//! and snapshot in order, then the position and session P&L a restart
+//! would recover.
+//!
+//! Interactive keys:
+//!     space       play / pause
+//!     → / l       step forward
+//!     ← / h       step back
+//!     ] / [       seek +/- 30s
+//!     g / G       jump to start / end
+//!     q / Esc     quit
+
+use std::collections::BTreeMap;
+
+use crate::journal::{read_journal, read_run_info, Rung, Snapshot};
+use crate::ledger::{read_ledger, recover, Entry};
+use crate::strategy::{fill_samples, CalibratedFillModel, FillModel, FillSample, HeuristicFillModel};
+
+/// Horizon the fill models are scored over (seconds)
+const FILL_HORIZON_SECS: f64 = 30.0;
+/// Distance bucket width for calibration and the report (ticks)
+const FILL_BUCKET_TICKS: u16 = 10;
+
+/// Run the subcommand with the arguments after `replay`.
+pub fn run(args: &[String]) -> anyhow::Result<()> {
+    let path = match args.iter().find(|a| !a.starts_with("--")) {
+        Some(p) => p.clone(),
+        None => anyhow::bail!("Usage: polybot replay <journal.jsonl> [--tui | --fills] | polybot replay <state.jsonl> --ledger"),
+    };
+    let tui = args.iter().any(|a| a == "--tui");
+    let fills = args.iter().any(|a| a == "--fills");
+    if args.iter().any(|a| a == "--ledger") {
+        return audit_ledger(&path);
+    }
+
+    let snapshots = read_journal(&path)?;
+    if snapshots.is_empty() {
+        anyhow::bail!("No snapshots in {}", path);
+    }
+
+    if !tui {
+        match read_run_info(&path)? {
+            Some(run) => println!("Run: {}\n", run.summary()),
+            None => println!("Run: not recorded (journal predates run headers)\n"),
+        }
+    }
+
+    if fills {
+        println!("{}", fill_backtest(&snapshots).join("\n"));
+        Ok(())
+    } else if tui {
+        tui::run_tui(&snapshots)
+    } else {
+        for (i, snap) in snapshots.iter().enumerate() {
+            println!("{}", render(snap, i, snapshots.len(), "").join("\n"));
+            println!();
+        }
+        Ok(())
+    }
+}
+
+/// Print a state ledger entry by entry, then what a restart would recover.
+fn audit_ledger(path: &str) -> anyhow::Result<()> {
+    let entries = read_ledger(path)?;
+    for entry in &entries {
+        let line = match entry {
+            Entry::Place { ts, market, order_id, side, price, size } => {
+                format!("{:.3} {} PLACE  {:?} {} @ {}c {}", ts, market, side, size, *price as f64 / 10.0, order_id)
+            }
+            Entry::Cancel { ts, market, order_id, remaining } => {
+                format!("{:.3} {} CANCEL {} ({} left)", ts, market, order_id, remaining)
+            }
+            Entry::Fill { ts, market, order_id, side, price, size } => {
+                format!("{:.3} {} FILL   {:?} {} @ {}c {}", ts, market, side, size, *price as f64 / 10.0, order_id)
+            }
+            Entry::Snapshot { ts, market, position, session_realized_usd } => format!(
+                "{:.3} {} SNAP   Y={} (${:.2}) N={} (${:.2}) session realized ${:+.2}",
+                ts,
+                market,
+                position.qty_yes,
+                position.cost_yes / 1000.0,
+                position.qty_no,
+                position.cost_no / 1000.0,
+                session_realized_usd
+            ),
+        };
+        println!("{}", line);
+    }
+
+    match recover(&entries) {
+        Some(recovered) => println!(
+            "\nRecovered: {} Y={} N={} | session realized ${:+.2} ({} fill(s) after the last snapshot)",
+            recovered.market,
+            recovered.position.qty_yes,
+            recovered.position.qty_no,
+            recovered.session_realized_usd,
+            recovered.fills_replayed
+        ),
+        None => println!("No entries in {}", path),
+    }
+    Ok(())
+}
+
+/// Score the heuristic and a calibrated fill model on the second half
+/// of the journal: realized vs predicted fill rate per distance bucket,
+/// and the Brier score of each.
+fn fill_backtest(snapshots: &[Snapshot]) -> Vec<String> {
+    let split = snapshots.len() / 2;
+    let calibrated = CalibratedFillModel::from_journal(&snapshots[..split], FILL_HORIZON_SECS, FILL_BUCKET_TICKS);
+    let heuristic = HeuristicFillModel::default();
+    let samples = fill_samples(&snapshots[split..], FILL_HORIZON_SECS);
+
+    let mut lines = vec![
+        format!(
+            "FILLS   {}s horizon, calibrated on {} rungs, scored on {}",
+            FILL_HORIZON_SECS,
+            calibrated.samples(),
+            samples.len()
+        ),
+        String::new(),
+        format!("{:>8} {:>7} {:>8} {:>9} {:>10}", "DIST", "RUNGS", "FILLED", "HEURISTIC", "CALIBRATED"),
+    ];
+
+    // Bucket → samples in it
+    let mut buckets: BTreeMap<u16, Vec<&FillSample>> = BTreeMap::new();
+    for sample in &samples {
+        buckets.entry(sample.query.distance_ticks / FILL_BUCKET_TICKS).or_default().push(sample);
+    }
+    let mean = |list: &[&FillSample], f: &dyn Fn(&FillSample) -> f64| {
+        list.iter().map(|s| f(s)).sum::<f64>() / list.len() as f64
+    };
+    for (bucket, list) in &buckets {
+        lines.push(format!(
+            "{:>7}¢ {:>7} {:>7.1}% {:>8.1}% {:>9.1}%",
+            (bucket * FILL_BUCKET_TICKS) as f64 / 10.0,
+            list.len(),
+            mean(list, &|s| s.filled as u8 as f64) * 100.0,
+            mean(list, &|s| heuristic.fill_prob(&s.query)) * 100.0,
+            mean(list, &|s| calibrated.fill_prob(&s.query)) * 100.0,
+        ));
+    }
+
+    let brier = |model: &dyn FillModel| {
+        let total: f64 = samples
+            .iter()
+            .map(|s| (model.fill_prob(&s.query) - s.filled as u8 as f64).powi(2))
+            .sum();
+        total / samples.len().max(1) as f64
+    };
+    lines.push(String::new());
+    lines.push(format!("BRIER   heuristic={:.4} calibrated={:.4}", brier(&heuristic), brier(&calibrated)));
+    lines
+}
+
+/// Interactive viewer (feature `tui`).
+#[cfg(feature = "tui")]
+mod tui {
+    use std::io::{stdout, Write};
+    use std::time::Duration;
+
+    use crossterm::cursor::MoveTo;
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+    use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+    use crossterm::{execute, queue};
+
+    use crate::journal::{seek_index, Snapshot};
+
+    use super::render;
+
+    const SEEK_SECS: f64 = 30.0;
+    const PLAY_INTERVAL_MS: u64 = 1000; // Real-time: snapshots are 1/sec
+
+    pub fn run_tui(snapshots: &[Snapshot]) -> anyhow::Result<()> {
+        let mut out = stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, EnterAlternateScreen)?;
+
+        let result = tui_loop(snapshots, &mut out);
+
+        // Always restore the terminal, even if drawing failed
+        execute!(out, LeaveAlternateScreen)?;
+        terminal::disable_raw_mode()?;
+        result
+    }
+
+    fn tui_loop(snapshots: &[Snapshot], out: &mut impl Write) -> anyhow::Result<()> {
+        let last = snapshots.len() - 1;
+        let mut idx = 0;
+        let mut playing = false;
+
+        loop {
+            queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+            for line in render(&snapshots[idx], idx, snapshots.len(), if playing { "PLAYING " } else { "PAUSED " }) {
+                // Raw mode needs explicit carriage returns
+                write!(out, "{}\r\n", line)?;
+            }
+            write!(out, "\r\n[space] play/pause  [←/→] step  [/] seek 30s  [g/G] start/end  [q] quit\r\n")?;
+            out.flush()?;
+
+            let timeout = if playing {
+                Duration::from_millis(PLAY_INTERVAL_MS)
+            } else {
+                Duration::from_secs(3600)
+            };
+
+            if !event::poll(timeout)? {
+                // No key within the frame interval: advance playback
+                if idx < last {
+                    idx += 1;
+                } else {
+                    playing = false;
+                }
+                continue;
+            }
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char(' ') => playing = !playing,
+                    KeyCode::Right | KeyCode::Char('l') => idx = (idx + 1).min(last),
+                    KeyCode::Left | KeyCode::Char('h') => idx = idx.saturating_sub(1),
+                    KeyCode::Char(']') => {
+                        idx = seek_index(snapshots, snapshots[idx].ts + SEEK_SECS).max(idx).min(last)
+                    }
+                    KeyCode::Char('[') => idx = seek_index(snapshots, snapshots[idx].ts - SEEK_SECS),
+                    KeyCode::Char('g') => idx = 0,
+                    KeyCode::Char('G') => idx = last,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "tui"))]
+mod tui {
+    use crate::journal::Snapshot;
+
+    pub fn run_tui(_snapshots: &[Snapshot]) -> anyhow::Result<()> {
+        anyhow::bail!("built without the `tui` feature; drop --tui to print frames")
+    }
+}
+
+/// Render one frame as lines of text.
+fn render(snap: &Snapshot, idx: usize, total: usize, state: &str) -> Vec<String> {
+    let time = chrono::DateTime::from_timestamp_millis((snap.ts * 1000.0) as i64)
+        .map(|t| t.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+        .unwrap_or_default();
+    let s = &snap.signals;
+    let p = &snap.position;
+
+    vec![
+        format!("[{}/{}] {}{} | {} T-{:.0}", idx + 1, total, state, time, snap.market, snap.time_left),
+        String::new(),
+        format!(
+            "BOOK    YES {} / {}    NO {} / {}",
+            fmt_price(snap.book.yes_bid),
+            fmt_price(snap.book.yes_ask),
+            fmt_price(snap.book.no_bid),
+            fmt_price(snap.book.no_ask),
+        ),
+        format!(
+            "SIGNAL  btc={:.2} mid={:.3} var={:.4} k={:.1}",
+            s.btc, s.mid, s.var, s.k
+        ),
+        match &s.halt {
+            Some(reason) => format!("STATE   HALT {}", reason),
+            None => format!("STATE   QUOTE Y={}¢ N={}¢", s.yes_target / 10, s.no_target / 10),
+        },
+        format!(
+            "POS     Y={:.0} N={:.0} cost Y=${:.2} N=${:.2}",
+            p.qty_yes,
+            p.qty_no,
+            p.cost_yes / 1000.0,
+            p.cost_no / 1000.0,
+        ),
+        String::new(),
+        format!("LADDER  YES: {}", fmt_ladder(&snap.ladder.yes)),
+        format!("        NO:  {}", fmt_ladder(&snap.ladder.no)),
+    ]
+}
+
+fn fmt_price(p: Option<u16>) -> String {
+    match p {
+        Some(t) => format!("{:.1}¢", t as f64 / 10.0),
+        None => "-".to_string(),
+    }
+}
+
+fn fmt_ladder(rungs: &[Rung]) -> String {
+    if rungs.is_empty() {
+        return "-".to_string();
+    }
+    rungs
+        .iter()
+        .map(|r| format!("{:.1}¢x{:.0}", r.price as f64 / 10.0, r.size))
+        .collect::<Vec<_>>()
+        .join("  ")
+}
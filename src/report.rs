@@ -0,0 +1,484 @@
+//! `polybot report`: portfolio stats across recorded sessions.
+//!
+//! Each session CSV (`logs/polybot_<ts>.csv`) is reduced to one
+//! `SessionSummary`: windows won, fills, guaranteed PnL (the `minpnl` of
+//! every `WINDOW_END`), edge per matched pair (fills paired FIFO per market)
+//! and fees, tagged with the commit and config hash from its `RUN` row.
+//! Liquidity rewards are paid to the wallet rather than logged, so they're
+//! read from the Data API and credited to the session running when each
+//! payout landed. `Portfolio` renders the sessions as a terminal table or a
+//! standalone HTML page with the cumulative PnL curve.
+//!
+//! Usage:
+//!     cargo run -- report                          # all sessions
+//!     cargo run -- report --range 30d              # last 30 days (also 12h, 90m)
+//!     cargo run -- report --html out.html          # default logs/report.html
+//!     cargo run -- report --taker-bps 100          # override the logged fee rates
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::api::data::{self, RewardPayout};
+use crate::run_info::summary_field;
+
+const LOG_DIR: &str = "logs";
+/// Makers pay nothing on crypto up/down
+const MAKER_FEE_BPS: u32 = 0;
+
+/// Run the subcommand with the arguments after `report`.
+pub async fn run(args: &[String]) -> anyhow::Result<()> {
+    let arg = |flag: &str| args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).cloned();
+
+    let range = arg("--range");
+    let html_path = arg("--html").unwrap_or_else(|| format!("{}/report.html", LOG_DIR));
+    let bps = |flag: &str| arg(flag).map(|v| v.parse::<u32>()).transpose();
+    let fees = FeeRates {
+        maker_bps: bps("--maker-bps")?,
+        taker_bps: bps("--taker-bps")?,
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+    let since = match &range {
+        Some(r) => now - parse_range(r)?,
+        None => 0,
+    };
+
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(LOG_DIR)? {
+        let path = entry?.path();
+        let Some(start) = path.file_name().and_then(|n| n.to_str()).and_then(session_start) else {
+            continue;
+        };
+        if start < since {
+            continue;
+        }
+        sessions.push(summarize(start, &fs::read_to_string(&path)?, &fees));
+    }
+    if sessions.is_empty() {
+        anyhow::bail!("No sessions in {} for range {}", LOG_DIR, range.as_deref().unwrap_or("all"));
+    }
+
+    let mut portfolio = Portfolio::new(sessions);
+    match std::env::var("POLY_PROXY_WALLET") {
+        Ok(wallet) => {
+            let first = portfolio.sessions[0].start;
+            match data::fetch_rewards(&reqwest::Client::new(), &wallet, first).await {
+                Ok(payouts) => portfolio.credit_rewards(&payouts),
+                Err(e) => println!("Reward payouts unavailable, not totalled: {:#}", e),
+            }
+        }
+        Err(_) => println!("POLY_PROXY_WALLET not set, rewards not totalled"),
+    }
+    print!("{}", portfolio.render_table());
+
+    let title = format!("polybot report ({})", range.as_deref().unwrap_or("all sessions"));
+    fs::write(&html_path, portfolio.render_html(&title))?;
+    println!("Wrote {}", html_path);
+    Ok(())
+}
+
+/// Fee rates to charge fills at. Unset, makers pay `MAKER_FEE_BPS` and
+/// takers the rate their market's orders were signed at (`fee_bps` on its
+/// `WINDOW_START`; nothing for logs that predate it).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FeeRates {
+    pub maker_bps: Option<u32>,
+    pub taker_bps: Option<u32>,
+}
+
+/// One session's totals.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionSummary {
+    /// Session start (unix seconds, from the file name)
+    pub start: i64,
+    pub windows: u32,
+    /// Windows that ended with positive guaranteed PnL
+    pub windows_won: u32,
+    pub maker_fills: u32,
+    pub taker_fills: u32,
+    /// Sum of each window's guaranteed PnL ($)
+    pub pnl: f64,
+    /// Edge of each matched pair: $1 minus pair cost (cents)
+    pub edges: Vec<f64>,
+    /// Fees on all fills at the report's rates ($)
+    pub fees: f64,
+    /// Liquidity rewards paid while this session was the latest ($); None
+    /// if payouts weren't fetched
+    pub rewards: Option<f64>,
+    /// Commit and config hash from the `RUN` row (older logs have none)
+    pub commit: Option<String>,
+    pub config_hash: Option<String>,
+}
+
+impl SessionSummary {
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.windows > 0).then(|| self.windows_won as f64 / self.windows as f64)
+    }
+
+    pub fn avg_edge_cents(&self) -> Option<f64> {
+        (!self.edges.is_empty()).then(|| self.edges.iter().sum::<f64>() / self.edges.len() as f64)
+    }
+
+    /// `commit/config` (config hash shortened), "-" when not recorded.
+    pub fn build(&self) -> String {
+        match (&self.commit, &self.config_hash) {
+            (None, None) => "-".to_string(),
+            (commit, config) => format!(
+                "{}/{}",
+                commit.as_deref().unwrap_or("?"),
+                config.as_deref().map_or("?", |c| &c[..c.len().min(8)])
+            ),
+        }
+    }
+
+    pub fn fills(&self) -> u32 {
+        self.maker_fills + self.taker_fills
+    }
+}
+
+/// Session start from a log file name (`polybot_<ts>.csv`).
+pub fn session_start(file_name: &str) -> Option<i64> {
+    file_name.strip_prefix("polybot_")?.strip_suffix(".csv")?.parse().ok()
+}
+
+/// Parse a range like `30d`, `12h` or `90m` into seconds.
+pub fn parse_range(range: &str) -> anyhow::Result<i64> {
+    let range = range.trim();
+    let (n, unit) = range.split_at(range.len().saturating_sub(1));
+    let n: i64 = n.parse().map_err(|_| anyhow::anyhow!("bad range {:?}, expected e.g. 30d", range))?;
+    let unit_secs = match unit {
+        "d" => 86_400,
+        "h" => 3_600,
+        "m" => 60,
+        _ => anyhow::bail!("bad range unit in {:?}, expected d, h or m", range),
+    };
+    Ok(n * unit_secs)
+}
+
+/// Reduce one session CSV to its summary.
+pub fn summarize(start: i64, csv: &str, fees: &FeeRates) -> SessionSummary {
+    let mut summary = SessionSummary { start, ..Default::default() };
+    // Per market: unpaired YES and NO fill prices (ticks)
+    let mut queues: HashMap<String, (VecDeque<u16>, VecDeque<u16>)> = HashMap::new();
+    // Per market: fee rate its orders were signed at
+    let mut signed_bps: HashMap<String, u32> = HashMap::new();
+
+    for line in csv.lines() {
+        let cols: Vec<&str> = line.split(',').collect();
+        match cols.first().copied() {
+            Some("FILL") if cols.len() > 18 => {
+                let (Ok(price), Ok(size)) = (cols[15].parse::<u16>(), cols[16].parse::<f64>()) else {
+                    continue;
+                };
+                let is_maker = cols[18] == "true";
+                let bps = if is_maker {
+                    summary.maker_fills += 1;
+                    fees.maker_bps.unwrap_or(MAKER_FEE_BPS)
+                } else {
+                    summary.taker_fills += 1;
+                    fees.taker_bps.or_else(|| signed_bps.get(cols[3]).copied()).unwrap_or(0)
+                };
+                summary.fees += price as f64 / 1000.0 * size * bps as f64 / 10_000.0;
+
+                let (yes, no) = queues.entry(cols[3].to_string()).or_default();
+                let (mine, other) = if cols[14] == "YES" { (yes, no) } else { (no, yes) };
+                match other.pop_front() {
+                    Some(other_price) => summary.edges.push((1000.0 - (price + other_price) as f64) / 10.0),
+                    None => mine.push_back(price),
+                }
+            }
+            Some("RUN") if cols.len() > 19 => {
+                summary.commit = summary_field(cols[19], "commit").map(str::to_string);
+                summary.config_hash = summary_field(cols[19], "config").map(str::to_string);
+            }
+            Some("WINDOW_START") if cols.len() > 4 => {
+                if let Some(bps) = cols[4].strip_prefix("fee_bps=").and_then(|v| v.parse().ok()) {
+                    signed_bps.insert(cols[3].to_string(), bps);
+                }
+            }
+            Some("WINDOW_END") => {
+                let pnl = cols
+                    .iter()
+                    .find_map(|c| c.strip_prefix("minpnl=$"))
+                    .and_then(|v| v.parse::<f64>().ok());
+                if let Some(pnl) = pnl {
+                    summary.windows += 1;
+                    if pnl > 0.0 {
+                        summary.windows_won += 1;
+                    }
+                    summary.pnl += pnl;
+                }
+            }
+            _ => {}
+        }
+    }
+    summary
+}
+
+/// Sessions in time order, with totals and rendering.
+pub struct Portfolio {
+    pub sessions: Vec<SessionSummary>,
+}
+
+impl Portfolio {
+    pub fn new(mut sessions: Vec<SessionSummary>) -> Self {
+        sessions.sort_by_key(|s| s.start);
+        Self { sessions }
+    }
+
+    pub fn total_pnl(&self) -> f64 {
+        self.sessions.iter().map(|s| s.pnl).sum()
+    }
+
+    pub fn total_fees(&self) -> f64 {
+        self.sessions.iter().map(|s| s.fees).sum()
+    }
+
+    /// Credit each payout to the last session started at or before it.
+    /// Payouts from before the first session are left out.
+    pub fn credit_rewards(&mut self, payouts: &[RewardPayout]) {
+        for s in &mut self.sessions {
+            s.rewards = Some(0.0);
+        }
+        for payout in payouts {
+            if let Some(s) = self.sessions.iter_mut().rev().find(|s| s.start <= payout.timestamp) {
+                *s.rewards.get_or_insert(0.0) += payout.usdc_size;
+            }
+        }
+    }
+
+    /// Rewards across sessions, None if payouts weren't fetched.
+    pub fn total_rewards(&self) -> Option<f64> {
+        self.sessions.iter().filter_map(|s| s.rewards).reduce(|a, b| a + b)
+    }
+
+    /// Average edge over every matched pair in the range (cents).
+    pub fn avg_edge_cents(&self) -> Option<f64> {
+        let edges: Vec<f64> = self.sessions.iter().flat_map(|s| s.edges.iter().copied()).collect();
+        (!edges.is_empty()).then(|| edges.iter().sum::<f64>() / edges.len() as f64)
+    }
+
+    /// Sessions with positive PnL, as a fraction of all sessions.
+    pub fn session_win_rate(&self) -> Option<f64> {
+        let won = self.sessions.iter().filter(|s| s.pnl > 0.0).count();
+        (!self.sessions.is_empty()).then(|| won as f64 / self.sessions.len() as f64)
+    }
+
+    /// Cumulative PnL after each session.
+    pub fn pnl_curve(&self) -> Vec<f64> {
+        self.sessions
+            .iter()
+            .scan(0.0, |total, s| {
+                *total += s.pnl;
+                Some(*total)
+            })
+            .collect()
+    }
+
+    /// Terminal table: one row per session, then totals.
+    pub fn render_table(&self) -> String {
+        let mut out = String::new();
+        let peak = self.pnl_curve().iter().fold(0.0_f64, |m, v| m.max(v.abs())).max(0.01);
+        writeln!(
+            out,
+            "{:<16} {:>4} {:>6} {:>6} {:>9} {:>8} {:>8} {:>8}  {:<23}  cumulative PnL",
+            "session", "win", "win%", "fills", "PnL", "edge", "fees", "rewards", "build"
+        )
+        .ok();
+        for (s, cumulative) in self.sessions.iter().zip(self.pnl_curve()) {
+            let bar = "#".repeat((cumulative.abs() / peak * 20.0).round() as usize);
+            writeln!(
+                out,
+                "{:<16} {:>4} {:>6} {:>6} {:>9} {:>8} {:>8} {:>8}  {:<23}  {}{} ${:.2}",
+                date(s.start),
+                format!("{}/{}", s.windows_won, s.windows),
+                pct(s.win_rate()),
+                s.fills(),
+                format!("${:.2}", s.pnl),
+                cents(s.avg_edge_cents()),
+                format!("${:.2}", s.fees),
+                dollars(s.rewards),
+                s.build(),
+                if cumulative < 0.0 { "-" } else { "" },
+                bar,
+                cumulative
+            )
+            .ok();
+        }
+        writeln!(
+            out,
+            "\n{} sessions | PnL ${:.2} | sessions won {} | avg edge {} | fees ${:.2} | rewards {}",
+            self.sessions.len(),
+            self.total_pnl(),
+            pct(self.session_win_rate()),
+            cents(self.avg_edge_cents()),
+            self.total_fees(),
+            dollars(self.total_rewards())
+        )
+        .ok();
+        out
+    }
+
+    /// Standalone HTML page: totals, PnL curve (inline SVG), session table.
+    pub fn render_html(&self, title: &str) -> String {
+        let mut rows = String::new();
+        for s in &self.sessions {
+            writeln!(
+                rows,
+                "<tr><td>{}</td><td>{}/{}</td><td>{}</td><td>{}</td><td>${:.2}</td><td>{}</td><td>${:.2}</td><td>{}</td><td>{}</td></tr>",
+                date(s.start),
+                s.windows_won,
+                s.windows,
+                pct(s.win_rate()),
+                s.fills(),
+                s.pnl,
+                cents(s.avg_edge_cents()),
+                s.fees,
+                dollars(s.rewards),
+                s.build()
+            )
+            .ok();
+        }
+        format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title>\n\
+             <style>body{{font-family:monospace;margin:2em}}td,th{{padding:2px 10px;text-align:right}}</style></head>\n\
+             <body><h1>{title}</h1>\n\
+             <p>{n} sessions | PnL ${pnl:.2} | sessions won {won} | avg edge {edge} | fees ${fees:.2} | rewards {rewards}</p>\n\
+             {svg}\n\
+             <table><tr><th>session</th><th>windows won</th><th>win%</th><th>fills</th><th>PnL</th><th>edge</th><th>fees</th><th>rewards</th><th>build</th></tr>\n\
+             {rows}</table></body></html>\n",
+            title = title,
+            n = self.sessions.len(),
+            pnl = self.total_pnl(),
+            won = pct(self.session_win_rate()),
+            edge = cents(self.avg_edge_cents()),
+            fees = self.total_fees(),
+            rewards = dollars(self.total_rewards()),
+            svg = svg_curve(&self.pnl_curve()),
+            rows = rows,
+        )
+    }
+}
+
+/// Cumulative PnL as an SVG polyline (600x200), zero line dashed.
+fn svg_curve(curve: &[f64]) -> String {
+    const W: f64 = 600.0;
+    const H: f64 = 200.0;
+    let lo = curve.iter().fold(0.0_f64, |m, &v| m.min(v));
+    let hi = curve.iter().fold(0.0_f64, |m, &v| m.max(v));
+    let span = (hi - lo).max(0.01);
+    let y = |v: f64| H - (v - lo) / span * H;
+    // Starts from zero before the first session
+    let step = W / curve.len().max(1) as f64;
+    let points: Vec<String> = std::iter::once(0.0)
+        .chain(curve.iter().copied())
+        .enumerate()
+        .map(|(i, v)| format!("{:.1},{:.1}", i as f64 * step, y(v)))
+        .collect();
+    format!(
+        "<svg width=\"{W}\" height=\"{H}\" style=\"border:1px solid #ccc\">\
+         <line x1=\"0\" y1=\"{z:.1}\" x2=\"{W}\" y2=\"{z:.1}\" stroke=\"#999\" stroke-dasharray=\"4\"/>\
+         <polyline fill=\"none\" stroke=\"#06c\" stroke-width=\"2\" points=\"{pts}\"/></svg>",
+        W = W,
+        H = H,
+        z = y(0.0),
+        pts = points.join(" ")
+    )
+}
+
+fn date(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| ts.to_string())
+}
+
+fn pct(v: Option<f64>) -> String {
+    v.map_or("-".to_string(), |v| format!("{:.0}%", v * 100.0))
+}
+
+fn cents(v: Option<f64>) -> String {
+    v.map_or("-".to_string(), |v| format!("{:.2}c", v))
+}
+
+fn dollars(v: Option<f64>) -> String {
+    v.map_or("-".to_string(), |v| format!("${:.2}", v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CSV: &str = "type,timestamp,time_left,market,mid,var,k,inventory,yes_target,no_target,yes_resting,no_resting,pair_cost,spread,side,price,size,order_id,is_maker,reason,error
+RUN,0.500,,,,,,,,,,,,,,,,,,version=0.1.0 commit=abc1234 profile=release config=0123456789abcdef margin_ticks=20,
+WINDOW_START,1.000,,m1,fee_bps=100,,,,,,,,,,,,,,,,,
+FILL,2.000,280.0,m1,,,,5.0,,,,,,,YES,480,5.0,o1,true,,,
+FILL,3.000,270.0,m1,,,,0.0,,,,,,,NO,500,5.0,o2,true,,,
+FILL,4.000,260.0,m1,,,,5.0,,,,,,,YES,470,5.0,o3,false,,,
+WINDOW_END,5.000,,m1,Y=10.0@47.5c,N=5.0@50.0c,minpnl=$-2.25,gamma=0.1000,halt=15,,,,,,,,,,,,
+WINDOW_START,6.000,,m2,,,,,,,,,,,,,,,,,,
+FILL,7.000,280.0,m2,,,,-5.0,,,,,,,NO,490,5.0,o4,true,,,
+FILL,8.000,270.0,m2,,,,0.0,,,,,,,YES,490,5.0,o5,true,,,
+WINDOW_END,9.000,,m2,Y=5.0@49.0c,N=5.0@49.0c,minpnl=$0.10,gamma=0.1000,halt=15,,,,,,,,,,,,
+";
+
+    #[test]
+    fn test_summarize() {
+        let s = summarize(1_772_000_000, CSV, &FeeRates::default());
+        assert_eq!((s.windows, s.windows_won), (2, 1));
+        assert_eq!((s.maker_fills, s.taker_fills), (4, 1));
+        assert!((s.pnl - -2.15).abs() < 1e-9);
+        // Pairs: 48+50 (2c edge), 49+49 (2c edge); the 47c YES is unpaired
+        assert_eq!(s.edges, vec![2.0, 2.0]);
+        // m1 signed at 1%: taker fee on 5 shares at 47c
+        assert!((s.fees - 0.0235).abs() < 1e-9);
+        assert_eq!(s.rewards, None);
+        assert_eq!(s.win_rate(), Some(0.5));
+        assert_eq!(s.build(), "abc1234/01234567");
+    }
+
+    #[test]
+    fn test_portfolio() {
+        let session = |start, pnl| SessionSummary { start, pnl, edges: vec![1.0], ..Default::default() };
+        let p = Portfolio::new(vec![session(200, -1.0), session(100, 3.0)]);
+        assert_eq!(p.pnl_curve(), vec![3.0, 2.0]);
+        assert_eq!(p.session_win_rate(), Some(0.5));
+        assert_eq!(p.avg_edge_cents(), Some(1.0));
+        assert!(p.render_table().contains("2 sessions | PnL $2.00"));
+        assert_eq!(p.sessions[0].build(), "-");
+        assert!(p.render_html("report").contains("<polyline"));
+    }
+
+    #[test]
+    fn test_fee_overrides() {
+        let fees = FeeRates { maker_bps: Some(100), taker_bps: Some(0) };
+        let s = summarize(0, CSV, &fees);
+        // 1% on the four maker fills: 48c, 50c, 49c, 49c × 5 shares
+        assert!((s.fees - 0.098).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_credit_rewards() {
+        let session = |start| SessionSummary { start, ..Default::default() };
+        let mut p = Portfolio::new(vec![session(100), session(200)]);
+        assert_eq!(p.total_rewards(), None);
+
+        let payout = |timestamp, usdc_size| RewardPayout { timestamp, usdc_size };
+        p.credit_rewards(&[payout(50, 9.0), payout(150, 1.5), payout(250, 2.0), payout(300, 0.5)]);
+        assert_eq!(p.sessions[0].rewards, Some(1.5));
+        assert_eq!(p.sessions[1].rewards, Some(2.5));
+        assert_eq!(p.total_rewards(), Some(4.0));
+        assert!(p.render_table().contains("rewards $4.00"));
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("30d").unwrap(), 30 * 86_400);
+        assert_eq!(parse_range("12h").unwrap(), 12 * 3_600);
+        assert!(parse_range("30x").is_err());
+        assert!(parse_range("d").is_err());
+        assert_eq!(session_start("polybot_1772000000.csv"), Some(1_772_000_000));
+        assert_eq!(session_start("polybot_1772000000.journal.jsonl"), None);
+    }
+}
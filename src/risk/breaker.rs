@@ -0,0 +1,190 @@
+//! Circuit breaker: stop quoting on anomalies the per-tick halts don't
+//! cover well.
+//!
+//! Four trips: BTC moving too fast in either direction, the book going
+//! silent, fills arriving faster than a market maker should ever be hit,
+//! and the window's worst-case P&L falling past a loss limit. Tripping
+//! cancels everything and starts a cooldown during which reconcile is
+//! suppressed; the cooldown restarts if an anomaly persists.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::config::Risk;
+use crate::strategy::Action;
+
+/// Why the breaker tripped.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trip {
+    /// BTC moved this many bps within the window
+    BtcVelocity { bps: f64 },
+    /// No book update for this long
+    StaleBook { age_ms: i64 },
+    /// This many fills within the window
+    FillRate { fills: usize },
+    /// Worst-case P&L for the window, in dollars
+    Loss { pnl_usd: f64 },
+}
+
+impl fmt::Display for Trip {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Trip::BtcVelocity { bps } => write!(f, "BTC moved {:.0}bps", bps),
+            Trip::StaleBook { age_ms } => write!(f, "book silent for {}ms", age_ms),
+            Trip::FillRate { fills } => write!(f, "{} fills in window", fills),
+            Trip::Loss { pnl_usd } => write!(f, "worst-case P&L ${:.2}", pnl_usd),
+        }
+    }
+}
+
+/// Watches for anomalies and holds quoting off after one.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: Risk,
+    /// (timestamp, price) within the BTC window
+    btc: VecDeque<(f64, f64)>,
+    /// Fill timestamps within the fill window
+    fills: VecDeque<f64>,
+    /// Cooldown end (seconds)
+    until: Option<f64>,
+    /// Most recent trip
+    trip: Option<Trip>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: Risk) -> Self {
+        Self { config, btc: VecDeque::new(), fills: VecDeque::new(), until: None, trip: None }
+    }
+
+    /// Swap in new thresholds (config reload). The BTC and fill windows
+    /// and any cooldown in progress carry over.
+    pub fn reconfigure(&mut self, config: Risk) {
+        self.config = config;
+    }
+
+    /// Call on each BTC price.
+    pub fn record_btc(&mut self, now: f64, price: f64) {
+        self.btc.push_back((now, price));
+        let cutoff = now - self.config.btc_window_secs;
+        while self.btc.front().is_some_and(|&(ts, _)| ts < cutoff) {
+            self.btc.pop_front();
+        }
+    }
+
+    /// Call on each of our fills.
+    pub fn record_fill(&mut self, now: f64) {
+        self.fills.push_back(now);
+        let cutoff = now - self.config.fill_window_secs;
+        while self.fills.front().is_some_and(|&ts| ts < cutoff) {
+            self.fills.pop_front();
+        }
+    }
+
+    fn detect(&self, book_age_ms: Option<i64>, pnl_usd: f64) -> Option<Trip> {
+        let (low, high) = self
+            .btc
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(low, high), &(_, p)| (low.min(p), high.max(p)));
+        if low > 0.0 && high > low {
+            let bps = (high - low) / low * 10_000.0;
+            if bps >= self.config.btc_move_bps {
+                return Some(Trip::BtcVelocity { bps });
+            }
+        }
+        if let Some(age_ms) = book_age_ms.filter(|&age| age >= self.config.max_book_age_ms) {
+            return Some(Trip::StaleBook { age_ms });
+        }
+        if self.fills.len() >= self.config.max_fills {
+            return Some(Trip::FillRate { fills: self.fills.len() });
+        }
+        if pnl_usd <= -self.config.max_loss_usd {
+            return Some(Trip::Loss { pnl_usd });
+        }
+        None
+    }
+
+    /// Check once per tick.
+    ///
+    /// # Arguments
+    /// * `book_age_ms` - Time since the last book update (None until synced)
+    /// * `pnl_usd` - The window's worst-case P&L (Position::min_pnl_usd)
+    ///
+    /// # Returns
+    /// `[CancelAll]` when the breaker trips (not while it stays tripped),
+    /// otherwise nothing.
+    pub fn check(&mut self, now: f64, book_age_ms: Option<i64>, pnl_usd: f64) -> Vec<Action> {
+        let Some(trip) = self.detect(book_age_ms, pnl_usd) else {
+            return Vec::new();
+        };
+        let was_tripped = self.in_cooldown(now);
+        self.until = Some(now + self.config.cooldown_secs);
+        self.trip = Some(trip);
+        if was_tripped {
+            Vec::new()
+        } else {
+            vec![Action::CancelAll]
+        }
+    }
+
+    /// Whether reconcile is suppressed.
+    pub fn in_cooldown(&self, now: f64) -> bool {
+        self.until.is_some_and(|until| now < until)
+    }
+
+    /// The most recent trip.
+    pub fn trip(&self) -> Option<&Trip> {
+        self.trip.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(Risk::default())
+    }
+
+    #[test]
+    fn test_trips_once_then_cools_down() {
+        let mut b = breaker();
+        b.record_btc(0.0, 100_000.0);
+        b.record_btc(1.0, 100_200.0);
+        assert!(b.check(1.0, Some(100), 0.0).is_empty());
+
+        // 60bps in the window: trip, cancel once
+        b.record_btc(2.0, 100_600.0);
+        assert_eq!(b.check(2.0, Some(100), 0.0), vec![Action::CancelAll]);
+        assert!(matches!(b.trip(), Some(Trip::BtcVelocity { .. })));
+        assert!(b.check(3.0, Some(100), 0.0).is_empty());
+        assert!(b.in_cooldown(3.0));
+
+        // Old prices age out; the cooldown runs from the last anomaly
+        b.record_btc(20.0, 100_600.0);
+        assert!(b.check(20.0, Some(100), 0.0).is_empty());
+        assert!(b.in_cooldown(20.0));
+        assert!(!b.in_cooldown(3.0 + Risk::default().cooldown_secs));
+    }
+
+    #[test]
+    fn test_book_fills_and_loss() {
+        let config = Risk::default();
+
+        let mut b = breaker();
+        assert!(b.check(0.0, None, 0.0).is_empty());
+        assert_eq!(b.check(0.0, Some(config.max_book_age_ms), 0.0), vec![Action::CancelAll]);
+        assert_eq!(b.trip(), Some(&Trip::StaleBook { age_ms: config.max_book_age_ms }));
+
+        let mut b = breaker();
+        for i in 0..config.max_fills {
+            b.record_fill(i as f64 * 0.1);
+        }
+        assert_eq!(b.check(1.0, Some(0), 0.0), vec![Action::CancelAll]);
+        assert_eq!(b.trip(), Some(&Trip::FillRate { fills: config.max_fills }));
+
+        let mut b = breaker();
+        assert!(b.check(0.0, Some(0), -config.max_loss_usd + 1.0).is_empty());
+        assert_eq!(b.check(0.0, Some(0), -config.max_loss_usd), vec![Action::CancelAll]);
+        assert_eq!(b.trip().unwrap().to_string(), format!("worst-case P&L ${:.2}", -config.max_loss_usd));
+    }
+}
@@ -0,0 +1,127 @@
+//! Execution health via a canary order.
+//!
+//! Every so often we place a tiny bid far below the market and cancel it
+//! right away. If that round trip fails or is slow, the order path is
+//! sick, and we'd rather find out on the canary than on a real quote.
+
+use std::time::Duration;
+
+/// Configuration for canary checks.
+#[derive(Debug, Clone)]
+pub struct CanaryConfig {
+    /// Seconds between canaries
+    pub interval_secs: f64,
+    /// Place + cancel round trip above this marks execution degraded
+    pub max_rtt: Duration,
+}
+
+impl Default for CanaryConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 300.0,                   // Every 5 minutes
+            max_rtt: Duration::from_millis(1500),   // Normal is ~100-300ms
+        }
+    }
+}
+
+/// Tracks canary schedule and the resulting health state.
+#[derive(Debug)]
+pub struct CanaryMonitor {
+    config: CanaryConfig,
+    /// When the last canary ran (None = never)
+    last_run: Option<f64>,
+    /// Why execution is degraded, if it is
+    degraded: Option<String>,
+}
+
+impl CanaryMonitor {
+    pub fn new(config: CanaryConfig) -> Self {
+        Self {
+            config,
+            last_run: None,
+            degraded: None,
+        }
+    }
+
+    /// Check if it's time for a canary. Degraded execution is rechecked
+    /// every interval like healthy execution.
+    pub fn is_due(&self, now: f64) -> bool {
+        match self.last_run {
+            Some(t) => now - t >= self.config.interval_secs,
+            None => true,
+        }
+    }
+
+    /// Mark a canary as sent, so it isn't due again while it's in flight.
+    pub fn start(&mut self, now: f64) {
+        self.last_run = Some(now);
+    }
+
+    /// Record a canary result.
+    ///
+    /// # Arguments
+    /// * `now` - When the canary finished
+    /// * `result` - Round trip time, or why the round trip failed
+    ///
+    /// # Returns
+    /// `true` if the health state changed (healthy ↔ degraded).
+    pub fn record(&mut self, now: f64, result: Result<Duration, String>) -> bool {
+        self.last_run = Some(now);
+        let degraded = match result {
+            Ok(rtt) if rtt > self.config.max_rtt => Some(format!(
+                "canary round trip {}ms over {}ms",
+                rtt.as_millis(),
+                self.config.max_rtt.as_millis()
+            )),
+            Ok(_) => None,
+            Err(e) => Some(format!("canary failed: {}", e)),
+        };
+        let changed = degraded.is_some() != self.degraded.is_some();
+        self.degraded = degraded;
+        changed
+    }
+
+    /// Reason execution is degraded, or None if healthy.
+    pub fn degraded(&self) -> Option<&str> {
+        self.degraded.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor() -> CanaryMonitor {
+        CanaryMonitor::new(CanaryConfig {
+            interval_secs: 60.0,
+            max_rtt: Duration::from_millis(500),
+        })
+    }
+
+    #[test]
+    fn test_schedule() {
+        let mut m = monitor();
+        assert!(m.is_due(0.0));
+        m.record(0.0, Ok(Duration::from_millis(100)));
+        assert!(!m.is_due(59.0));
+        assert!(m.is_due(60.0));
+    }
+
+    #[test]
+    fn test_slow_or_failed_round_trip_degrades() {
+        let mut m = monitor();
+        assert!(!m.record(0.0, Ok(Duration::from_millis(100))));
+        assert!(m.degraded().is_none());
+
+        assert!(m.record(60.0, Ok(Duration::from_millis(900))));
+        assert!(m.degraded().unwrap().contains("900ms"));
+
+        // Still degraded, different cause: no state change
+        assert!(!m.record(120.0, Err("post rejected".into())));
+        assert!(m.degraded().unwrap().contains("post rejected"));
+
+        // Recovery
+        assert!(m.record(180.0, Ok(Duration::from_millis(120))));
+        assert!(m.degraded().is_none());
+    }
+}
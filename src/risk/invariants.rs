@@ -0,0 +1,213 @@
+//! State invariants checked every cycle in paranoid mode.
+//!
+//! Any violation means our view of the world is wrong, so the caller
+//! should cancel everything and stop rather than keep trading on it.
+
+use std::fmt;
+
+use rust_decimal::Decimal;
+
+use crate::events::Side;
+use crate::state::{Book, OrderTracker, Position};
+
+/// Configuration for invariant checks.
+#[derive(Debug, Clone)]
+pub struct InvariantConfig {
+    /// Smallest order the exchange accepts (shares)
+    pub min_order_size: Decimal,
+    /// How long the book may stay locked/crossed before it's a violation (seconds)
+    pub max_crossed_secs: f64,
+}
+
+impl Default for InvariantConfig {
+    fn default() -> Self {
+        Self {
+            min_order_size: Decimal::from(5), // API minimum
+            max_crossed_secs: 5.0,            // Glitches clear well within this
+        }
+    }
+}
+
+/// A broken invariant.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// OrderTracker bookkeeping doesn't add up
+    TrackerInconsistent(String),
+    /// Negative share count or cost basis
+    NegativePosition { side: Side, qty: Decimal, cost: Decimal },
+    /// Book has been locked or crossed too long
+    BookCrossed { secs: f64 },
+    /// A resting order was placed below the exchange minimum
+    OrderBelowMinSize { order_id: String, size: Decimal },
+}
+
+impl Violation {
+    /// Short name of the invariant, for aggregating.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Violation::TrackerInconsistent(_) => "tracker_inconsistent",
+            Violation::NegativePosition { .. } => "negative_position",
+            Violation::BookCrossed { .. } => "book_crossed",
+            Violation::OrderBelowMinSize { .. } => "order_below_min_size",
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::TrackerInconsistent(msg) => write!(f, "order tracker inconsistent: {}", msg),
+            Violation::NegativePosition { side, qty, cost } => {
+                write!(f, "negative {:?} position: qty={} cost={}", side, qty, cost)
+            }
+            Violation::BookCrossed { secs } => write!(f, "book locked/crossed for {:.1}s", secs),
+            Violation::OrderBelowMinSize { order_id, size } => {
+                write!(f, "order {} placed with size {} below minimum", order_id, size)
+            }
+        }
+    }
+}
+
+/// Checks invariants, tracking how long the book has been crossed.
+pub struct InvariantChecker {
+    config: InvariantConfig,
+    crossed_since: Option<f64>,
+}
+
+impl InvariantChecker {
+    pub fn new(config: InvariantConfig) -> Self {
+        Self {
+            config,
+            crossed_since: None,
+        }
+    }
+
+    /// Check all invariants.
+    ///
+    /// # Returns
+    /// Every violation found (empty if state is sane).
+    pub fn check(
+        &mut self,
+        now: f64,
+        book: &Book,
+        orders: &OrderTracker,
+        position: &Position,
+    ) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        if let Err(msg) = orders.check_consistency() {
+            violations.push(Violation::TrackerInconsistent(msg));
+        }
+
+        for (side, qty, cost) in [
+            (Side::Yes, position.qty_yes, position.cost_yes),
+            (Side::No, position.qty_no, position.cost_no),
+        ] {
+            if qty < Decimal::ZERO || cost < Decimal::ZERO {
+                violations.push(Violation::NegativePosition { side, qty, cost });
+            }
+        }
+
+        if book.is_locked_or_crossed() {
+            let since = *self.crossed_since.get_or_insert(now);
+            if now - since > self.config.max_crossed_secs {
+                violations.push(Violation::BookCrossed { secs: now - since });
+            }
+        } else {
+            self.crossed_since = None;
+        }
+
+        // Partial fills legitimately leave less than the minimum resting,
+        // so check the size the order was placed with
+        for side in [Side::Yes, Side::No] {
+            for order in orders.all_orders(side) {
+                if order.original_size < self.config.min_order_size {
+                    violations.push(Violation::OrderBelowMinSize {
+                        order_id: order.order_id.clone(),
+                        size: order.original_size,
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// Reset state (call on market switch).
+    pub fn reset(&mut self) {
+        self.crossed_since = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn sane_book() -> Book {
+        let mut book = Book::default();
+        book.update(Side::Yes, 480, 490, 0);
+        book.update(Side::No, 500, 510, 0);
+        book
+    }
+
+    #[test]
+    fn test_sane_state_passes() {
+        let mut checker = InvariantChecker::new(InvariantConfig::default());
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "y1".into(), 470, dec!(5));
+        orders.update_fill(Side::Yes, "y1", dec!(3)); // 2 left is fine
+        let mut position = Position::default();
+        position.apply_fill(Side::Yes, 470, dec!(3));
+
+        assert!(checker.check(0.0, &sane_book(), &orders, &position).is_empty());
+    }
+
+    #[test]
+    fn test_negative_position() {
+        let mut checker = InvariantChecker::new(InvariantConfig::default());
+        let position = Position {
+            qty_no: dec!(-1),
+            ..Default::default()
+        };
+
+        let v = checker.check(0.0, &sane_book(), &OrderTracker::new(), &position);
+        assert!(matches!(v[..], [Violation::NegativePosition { side: Side::No, .. }]));
+    }
+
+    #[test]
+    fn test_crossed_book_needs_to_persist() {
+        let mut checker = InvariantChecker::new(InvariantConfig {
+            max_crossed_secs: 2.0,
+            ..Default::default()
+        });
+        let mut book = sane_book();
+        book.update(Side::Yes, 495, 490, 0);
+        let orders = OrderTracker::new();
+        let position = Position::default();
+
+        assert!(checker.check(10.0, &book, &orders, &position).is_empty());
+        assert!(checker.check(11.5, &book, &orders, &position).is_empty());
+        assert_eq!(
+            checker.check(12.5, &book, &orders, &position),
+            vec![Violation::BookCrossed { secs: 2.5 }]
+        );
+
+        // Clearing resets the clock
+        assert!(checker.check(13.0, &sane_book(), &orders, &position).is_empty());
+        assert!(checker.check(14.0, &book, &orders, &position).is_empty());
+    }
+
+    #[test]
+    fn test_order_below_min_size() {
+        let mut checker = InvariantChecker::new(InvariantConfig::default());
+        let mut orders = OrderTracker::new();
+        orders.add(Side::No, "n1".into(), 500, dec!(2));
+
+        let v = checker.check(0.0, &sane_book(), &orders, &Position::default());
+        assert_eq!(
+            v,
+            vec![Violation::OrderBelowMinSize { order_id: "n1".into(), size: dec!(2) }]
+        );
+    }
+}
@@ -0,0 +1,104 @@
+//! Hard limits on resting orders.
+//!
+//! The strategy trims its ladder to stay inside these; the executor
+//! checks them again before every placement as a backstop. Placements
+//! still in flight count as resting, so a batch (or a slow ack) can't
+//! overshoot the caps.
+
+use rust_decimal::Decimal;
+
+use crate::events::Side;
+use crate::state::OrderTracker;
+
+/// Per-side caps on resting orders.
+#[derive(Debug, Clone)]
+pub struct RiskLimits {
+    /// Maximum number of resting orders per side
+    pub max_orders_per_side: usize,
+    /// Maximum total resting size per side (shares)
+    pub max_exposure_per_side: Decimal,
+}
+
+impl Default for RiskLimits {
+    fn default() -> Self {
+        Self {
+            max_orders_per_side: 5,                      // 5 resting orders
+            max_exposure_per_side: Decimal::from(60),    // 60 shares resting
+        }
+    }
+}
+
+impl RiskLimits {
+    /// Check if placing `size` more on `side` stays within limits,
+    /// counting placements not yet acknowledged.
+    pub fn allows_place(&self, side: Side, size: Decimal, orders: &OrderTracker) -> bool {
+        orders.count(side) + orders.pending_count(side) < self.max_orders_per_side
+            && orders.total_exposure(side) + orders.pending_exposure(side) + size <= self.max_exposure_per_side
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::risk::Variant;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_allows_place_under_limits() {
+        let limits = RiskLimits {
+            max_orders_per_side: 2,
+            max_exposure_per_side: dec!(20),
+        };
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "o1".to_string(), 450, dec!(10));
+
+        assert!(limits.allows_place(Side::Yes, dec!(10), &orders));
+        assert!(limits.allows_place(Side::No, dec!(20), &orders));
+    }
+
+    #[test]
+    fn test_rejects_over_exposure() {
+        let limits = RiskLimits {
+            max_orders_per_side: 5,
+            max_exposure_per_side: dec!(20),
+        };
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "o1".to_string(), 450, dec!(15));
+
+        assert!(!limits.allows_place(Side::Yes, dec!(6), &orders));
+        assert!(limits.allows_place(Side::Yes, dec!(5), &orders));
+    }
+
+    #[test]
+    fn test_rejects_over_order_count() {
+        let limits = RiskLimits {
+            max_orders_per_side: 2,
+            max_exposure_per_side: dec!(100),
+        };
+        let mut orders = OrderTracker::new();
+        orders.add(Side::No, "o1".to_string(), 540, dec!(5));
+        orders.add(Side::No, "o2".to_string(), 530, dec!(5));
+
+        assert!(!limits.allows_place(Side::No, dec!(5), &orders));
+        assert!(limits.allows_place(Side::Yes, dec!(5), &orders));
+    }
+
+    #[test]
+    fn test_counts_pending_places() {
+        let limits = RiskLimits {
+            max_orders_per_side: 2,
+            max_exposure_per_side: dec!(20),
+        };
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "o1".to_string(), 450, dec!(5));
+        orders.add_pending(Side::Yes, "c1".to_string(), 460, dec!(10), Variant::Ladder, 0.0);
+
+        // One live plus one in flight fills the order count
+        assert!(!limits.allows_place(Side::Yes, dec!(1), &orders));
+
+        let mut orders = OrderTracker::new();
+        orders.add_pending(Side::Yes, "c1".to_string(), 460, dec!(15), Variant::Ladder, 0.0);
+        assert!(!limits.allows_place(Side::Yes, dec!(6), &orders));
+        assert!(limits.allows_place(Side::Yes, dec!(5), &orders));
+    }
+}
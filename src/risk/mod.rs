@@ -0,0 +1,19 @@
+mod breaker;
+mod canary;
+mod invariants;
+mod limits;
+mod prevalidate;
+mod recovery;
+mod schedule;
+mod supervisor;
+mod variants;
+
+pub use breaker::{CircuitBreaker, Trip};
+pub use canary::{CanaryConfig, CanaryMonitor};
+pub use invariants::{InvariantChecker, InvariantConfig, Violation};
+pub use limits::RiskLimits;
+pub use prevalidate::{OrderRules, Rejection};
+pub use recovery::{Recovery, RecoveryStage};
+pub use schedule::PositionSchedule;
+pub use supervisor::{Gated, Supervisor};
+pub use variants::{Variant, VariantBudget, VariantFills, VariantRisk};
@@ -0,0 +1,172 @@
+//! Local pre-validation of orders before they're signed.
+//!
+//! Tick grid, minimum size, price band, our own resting orders and the
+//! balance are all known locally; rejecting here takes microseconds, where
+//! a rejected order costs a signature and an API round trip.
+
+use std::fmt;
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::events::Side;
+use crate::state::OrderTracker;
+
+/// Exchange order rules for the current market.
+#[derive(Debug, Clone)]
+pub struct OrderRules {
+    /// Price grid (ticks, 10 = 1c)
+    pub tick: u16,
+    /// Minimum order size (shares)
+    pub min_size: Decimal,
+    /// Lowest accepted price (ticks)
+    pub min_price: u16,
+    /// Highest accepted price (ticks)
+    pub max_price: u16,
+    /// USDC available for resting bids. None = unknown, not checked
+    pub balance: Option<Decimal>,
+    /// Our YES and NO bids must sum to under $1 plus this (ticks). A YES
+    /// bid at 48c is an offer to sell NO at 52c, so our NO bid at 52c would
+    /// trade against it through any arbitrageur.
+    pub self_trade_overlap_ticks: u16,
+}
+
+impl Default for OrderRules {
+    fn default() -> Self {
+        Self {
+            tick: 10,                                    // 1c grid
+            min_size: Decimal::from(5),                  // API minimum
+            min_price: 10,                               // 1c
+            max_price: 990,                              // 99c
+            balance: None,                               // not checked
+            self_trade_overlap_ticks: 0,                 // Never lock or cross
+        }
+    }
+}
+
+/// Why an order was rejected locally.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejection {
+    OffTick { price: u16, tick: u16 },
+    BelowMinSize { size: Decimal, min: Decimal },
+    OutsideBand { price: u16, min: u16, max: u16 },
+    /// Would match our own bid on the other token (prices sum to $1 or
+    /// more, plus any allowed overlap)
+    CrossesOwn { price: u16, own: u16 },
+    OverBalance { needed: Decimal, available: Decimal },
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::OffTick { price, tick } => write!(f, "price {} not on {}-tick grid", price, tick),
+            Rejection::BelowMinSize { size, min } => write!(f, "size {} below minimum {}", size, min),
+            Rejection::OutsideBand { price, min, max } => write!(f, "price {} outside {}-{}", price, min, max),
+            Rejection::CrossesOwn { price, own } => write!(f, "price {} crosses own bid {} on other token", price, own),
+            Rejection::OverBalance { needed, available } => write!(f, "needs ${} with ${} available", needed, available),
+        }
+    }
+}
+
+impl OrderRules {
+    /// Set the grid from the market's tick size in dollars (0.01 or 0.001).
+    /// The price band is one tick in from either end.
+    pub fn set_tick_size(&mut self, tick_size: Decimal) {
+        self.tick = (tick_size * Decimal::from(1000)).round().to_u16().unwrap_or(10).max(1);
+        self.min_price = self.tick;
+        self.max_price = 1000 - self.tick;
+    }
+
+    /// Check a bid for `size` at `price` on `side` against the rules and
+    /// the orders we already have resting.
+    pub fn check_place(&self, side: Side, price: u16, size: Decimal, orders: &OrderTracker) -> Result<(), Rejection> {
+        if !price.is_multiple_of(self.tick) {
+            return Err(Rejection::OffTick { price, tick: self.tick });
+        }
+        if size < self.min_size {
+            return Err(Rejection::BelowMinSize { size, min: self.min_size });
+        }
+        if price < self.min_price || price > self.max_price {
+            return Err(Rejection::OutsideBand { price, min: self.min_price, max: self.max_price });
+        }
+        // Placements in flight count: one may already be resting
+        if let Some(own) = orders.highest_bid(side.opposite()) {
+            if price + own >= 1000 + self.self_trade_overlap_ticks {
+                return Err(Rejection::CrossesOwn { price, own });
+            }
+        }
+        if let Some(available) = self.balance {
+            let needed = (orders.locked_notional() + Decimal::from(price) * size) / Decimal::from(1000);
+            if needed > available {
+                return Err(Rejection::OverBalance { needed, available });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::risk::Variant;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_check_place_rules() {
+        let rules = OrderRules::default();
+        let orders = OrderTracker::new();
+        assert_eq!(rules.check_place(Side::Yes, 480, dec!(5), &orders), Ok(()));
+        assert_eq!(
+            rules.check_place(Side::Yes, 485, dec!(5), &orders),
+            Err(Rejection::OffTick { price: 485, tick: 10 })
+        );
+        assert_eq!(
+            rules.check_place(Side::Yes, 480, dec!(4), &orders),
+            Err(Rejection::BelowMinSize { size: dec!(4), min: dec!(5) })
+        );
+        assert_eq!(
+            rules.check_place(Side::No, 0, dec!(5), &orders),
+            Err(Rejection::OutsideBand { price: 0, min: 10, max: 990 })
+        );
+    }
+
+    #[test]
+    fn test_rejects_self_cross_and_over_balance() {
+        let mut rules = OrderRules { balance: Some(dec!(5)), ..Default::default() };
+        let mut orders = OrderTracker::new();
+        orders.add(Side::No, "n1".into(), 500, dec!(5));
+
+        // YES 500 + our NO 500 = $1: we'd trade with ourselves
+        assert_eq!(
+            rules.check_place(Side::Yes, 500, dec!(5), &orders),
+            Err(Rejection::CrossesOwn { price: 500, own: 500 })
+        );
+        // $2.50 locked + $2.45 = $4.95 fits in $5, $3 more doesn't
+        assert_eq!(rules.check_place(Side::Yes, 490, dec!(5), &orders), Ok(()));
+        assert!(matches!(
+            rules.check_place(Side::No, 500, dec!(6), &orders),
+            Err(Rejection::OverBalance { .. })
+        ));
+
+        rules.set_tick_size(dec!(0.001));
+        assert_eq!((rules.tick, rules.min_price, rules.max_price), (1, 1, 999));
+        assert_eq!(rules.check_place(Side::Yes, 495, dec!(5), &orders), Ok(()));
+    }
+
+    #[test]
+    fn test_self_cross_counts_pending_and_overlap() {
+        let mut rules = OrderRules::default();
+        let mut orders = OrderTracker::new();
+        orders.add_pending(Side::Yes, "c1".into(), 480, dec!(5), Variant::Ladder, 0.0);
+
+        // The YES bid is still in flight: NO at 52c would lock against it
+        assert_eq!(
+            rules.check_place(Side::No, 520, dec!(5), &orders),
+            Err(Rejection::CrossesOwn { price: 520, own: 480 })
+        );
+        assert_eq!(rules.check_place(Side::No, 510, dec!(5), &orders), Ok(()));
+
+        rules.self_trade_overlap_ticks = 20;
+        assert_eq!(rules.check_place(Side::No, 530, dec!(5), &orders), Ok(()));
+    }
+}
@@ -0,0 +1,152 @@
+//! Resuming after a circuit breaker trip.
+//!
+//! The breaker's cooldown waits out the anomaly (it restarts while the
+//! anomaly lasts). Quoting doesn't snap back after it: the market first
+//! has to pass the warm-up checks (synced and fresh book, no lock, no BTC
+//! cooldown, healthy execution) for `resume_warmup_secs` straight, then
+//! size ramps from `resume_size_start` back to full over
+//! `resume_ramp_secs`. A new trip at any point starts over.
+
+use std::fmt;
+
+use crate::config::Risk;
+
+/// Where recovery is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RecoveryStage {
+    /// Quoting at full size
+    Normal,
+    /// Breaker in cooldown
+    Tripped,
+    /// Cooldown over; checks passing since `since`
+    WarmingUp { since: f64 },
+    /// Quoting again, size ramping up since `since`
+    Ramping { since: f64 },
+}
+
+impl fmt::Display for RecoveryStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RecoveryStage::Normal => "normal",
+            RecoveryStage::Tripped => "tripped",
+            RecoveryStage::WarmingUp { .. } => "warming_up",
+            RecoveryStage::Ramping { .. } => "ramping",
+        })
+    }
+}
+
+/// Steps from a breaker trip back to normal quoting.
+#[derive(Debug)]
+pub struct Recovery {
+    warmup_secs: f64,
+    ramp_secs: f64,
+    size_start: f64,
+    stage: RecoveryStage,
+}
+
+impl Recovery {
+    pub fn new(config: &Risk) -> Self {
+        Self {
+            warmup_secs: config.resume_warmup_secs,
+            ramp_secs: config.resume_ramp_secs,
+            size_start: config.resume_size_start.clamp(0.0, 1.0),
+            stage: RecoveryStage::Normal,
+        }
+    }
+
+    /// Swap in new timings (config reload), keeping the stage.
+    pub fn reconfigure(&mut self, config: &Risk) {
+        let stage = self.stage;
+        *self = Self { stage, ..Self::new(config) };
+    }
+
+    /// Advance once per tick.
+    ///
+    /// # Arguments
+    /// * `tripped` - Breaker in cooldown
+    /// * `healthy` - Warm-up checks pass this tick
+    ///
+    /// # Returns
+    /// The new stage when it changed.
+    pub fn update(&mut self, now: f64, tripped: bool, healthy: bool) -> Option<RecoveryStage> {
+        let next = match self.stage {
+            _ if tripped => RecoveryStage::Tripped,
+            RecoveryStage::Tripped => RecoveryStage::WarmingUp { since: now },
+            // A failed check restarts the warm-up, quietly
+            RecoveryStage::WarmingUp { .. } if !healthy => {
+                self.stage = RecoveryStage::WarmingUp { since: now };
+                return None;
+            }
+            RecoveryStage::WarmingUp { since } if now - since >= self.warmup_secs => {
+                RecoveryStage::Ramping { since: now }
+            }
+            RecoveryStage::Ramping { since } if now - since >= self.ramp_secs => RecoveryStage::Normal,
+            stage => stage,
+        };
+        if std::mem::discriminant(&next) == std::mem::discriminant(&self.stage) {
+            return None;
+        }
+        self.stage = next;
+        Some(next)
+    }
+
+    /// Whether quoting is held off (tripped or warming up).
+    pub fn is_halted(&self) -> bool {
+        matches!(self.stage, RecoveryStage::Tripped | RecoveryStage::WarmingUp { .. })
+    }
+
+    /// Fraction of normal size to quote: ramps linearly while ramping.
+    pub fn size_fraction(&self, now: f64) -> f64 {
+        match self.stage {
+            RecoveryStage::Ramping { since } if self.ramp_secs > 0.0 => {
+                let progress = ((now - since) / self.ramp_secs).clamp(0.0, 1.0);
+                self.size_start + (1.0 - self.size_start) * progress
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recovery() -> Recovery {
+        Recovery::new(&Risk { resume_warmup_secs: 10.0, resume_ramp_secs: 60.0, resume_size_start: 0.25, ..Default::default() })
+    }
+
+    #[test]
+    fn test_trip_warmup_ramp_normal() {
+        let mut r = recovery();
+        assert_eq!(r.update(0.0, false, true), None);
+        assert_eq!(r.update(1.0, true, false), Some(RecoveryStage::Tripped));
+        assert_eq!(r.update(30.0, true, true), None);
+        assert!(r.is_halted());
+
+        // Cooldown over: warm up, and a failed check restarts it
+        assert_eq!(r.update(61.0, false, true), Some(RecoveryStage::WarmingUp { since: 61.0 }));
+        assert_eq!(r.update(65.0, false, false), None);
+        assert_eq!(r.update(74.0, false, true), None);
+        assert!(r.is_halted());
+        assert_eq!(r.update(75.0, false, true), Some(RecoveryStage::Ramping { since: 75.0 }));
+        assert!(!r.is_halted());
+
+        assert_eq!(r.size_fraction(75.0), 0.25);
+        assert_eq!(r.size_fraction(105.0), 0.625);
+        assert_eq!(r.update(134.0, false, false), None);
+        assert_eq!(r.update(135.0, false, true), Some(RecoveryStage::Normal));
+        assert_eq!(r.size_fraction(135.0), 1.0);
+    }
+
+    #[test]
+    fn test_trip_while_ramping_starts_over() {
+        let mut r = recovery();
+        r.update(0.0, true, true);
+        r.update(1.0, false, true);
+        r.update(11.0, false, true);
+        assert!(matches!(r.stage, RecoveryStage::Ramping { .. }));
+        assert_eq!(r.update(20.0, true, true), Some(RecoveryStage::Tripped));
+        assert_eq!(r.size_fraction(20.0), 1.0);
+        assert!(r.is_halted());
+    }
+}
@@ -0,0 +1,104 @@
+//! Max position by time remaining.
+//!
+//! Unmatched shares with minutes left can still be paired off; with
+//! seconds left they settle as a straight bet on BTC. The schedule scales
+//! max_position down in steps as expiry approaches. Sizing quotes zero on
+//! a side over its scaled limit, and the trading loop pulls that side.
+
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+
+use crate::events::Side;
+use crate::state::Position;
+
+/// Steps of (seconds left, fraction of max_position).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionSchedule {
+    /// A step applies at or under its seconds left
+    steps: Vec<(f64, Decimal)>,
+}
+
+impl Default for PositionSchedule {
+    fn default() -> Self {
+        Self::new(&[
+            (120.0, 0.5),   // Last 2 min: half
+            (60.0, 0.25),   // Last minute: a quarter
+            (20.0, 0.1),    // Last 20s: a tenth
+        ])
+    }
+}
+
+impl PositionSchedule {
+    /// Fractions are clamped to 0..=1; out-of-order steps are fine.
+    pub fn new(steps: &[(f64, f64)]) -> Self {
+        let steps = steps
+            .iter()
+            .filter_map(|&(secs, fraction)| Some((secs, Decimal::from_f64(fraction.clamp(0.0, 1.0))?)))
+            .collect();
+        Self { steps }
+    }
+
+    /// No scaling: max_position holds to the end.
+    pub fn flat() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    /// Max position with `time_remaining` seconds left: the tightest step
+    /// that applies, full size before the first.
+    pub fn max_position(&self, base: Decimal, time_remaining: f64) -> Decimal {
+        let fraction = self
+            .steps
+            .iter()
+            .filter(|&&(secs, _)| time_remaining <= secs)
+            .map(|&(_, fraction)| fraction)
+            .min()
+            .unwrap_or(Decimal::ONE);
+        base * fraction
+    }
+
+    /// Whether `side` is under its scaled limit (net shares on that side).
+    pub fn allows(&self, side: Side, position: &Position, base: Decimal, time_remaining: f64) -> bool {
+        let net = position.net_position();
+        let exposure = match side {
+            Side::Yes => net,
+            Side::No => -net,
+        };
+        exposure < self.max_position(base, time_remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_steps_at_boundaries() {
+        let s = PositionSchedule::default();
+        assert_eq!(s.max_position(dec!(150), 300.0), dec!(150));
+        assert_eq!(s.max_position(dec!(150), 120.1), dec!(150));
+        assert_eq!(s.max_position(dec!(150), 120.0), dec!(75));
+        assert_eq!(s.max_position(dec!(150), 60.1), dec!(75));
+        assert_eq!(s.max_position(dec!(150), 60.0), dec!(37.5));
+        assert_eq!(s.max_position(dec!(150), 20.0), dec!(15));
+        assert_eq!(s.max_position(dec!(150), 0.0), dec!(15));
+        assert_eq!(PositionSchedule::flat().max_position(dec!(150), 0.0), dec!(150));
+
+        // Unsorted, and a looser late step doesn't loosen the limit
+        let s = PositionSchedule::new(&[(30.0, 0.8), (60.0, 0.5), (300.0, 2.0)]);
+        assert_eq!(s.max_position(dec!(100), 200.0), dec!(100));
+        assert_eq!(s.max_position(dec!(100), 10.0), dec!(50));
+    }
+
+    #[test]
+    fn test_allows_only_the_light_side() {
+        let s = PositionSchedule::default();
+        let mut position = Position::default();
+        position.apply_fill(Side::Yes, 500, dec!(20));
+
+        // 20 net YES: fine with 2 min left, over the limit in the last 20s
+        assert!(s.allows(Side::Yes, &position, dec!(150), 120.0));
+        assert!(!s.allows(Side::Yes, &position, dec!(150), 20.0));
+        assert!(s.allows(Side::No, &position, dec!(150), 20.0));
+    }
+}
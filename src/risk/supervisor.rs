@@ -0,0 +1,232 @@
+//! Supervised mode: a human approves large order batches.
+//!
+//! With `[risk] supervised = true`, a reconcile batch buying more than
+//! `supervised_min_shares` is held instead of executed and previewed on
+//! the control stream. Approving it by id executes it as previewed;
+//! rejecting it, or leaving it past `approval_timeout_secs`, drops it.
+//! Cancels always go straight through, since they only reduce exposure.
+//!
+//! Nothing queues behind a held batch: while one waits, and for the
+//! timeout after one is rejected or expires, further large batches are
+//! dropped. Reconcile proposes again from the live book afterwards.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::config::Risk;
+use crate::control::BatchPreview;
+use crate::events::Side;
+use crate::strategy::Action;
+
+/// A batch waiting on approval.
+#[derive(Debug, Clone)]
+struct Held {
+    id: u64,
+    actions: Vec<Action>,
+    expires_at: f64,
+}
+
+/// What to do with a reconcile batch.
+#[derive(Debug, Default)]
+pub struct Gated {
+    /// Execute now
+    pub run: Vec<Action>,
+    /// Just held, to publish
+    pub held: Option<BatchPreview>,
+}
+
+/// Holds large batches for approval.
+#[derive(Debug)]
+pub struct Supervisor {
+    enabled: bool,
+    min_shares: Decimal,
+    timeout_secs: f64,
+    held: Option<Held>,
+    /// No new proposals until then, after a rejection or expiry
+    quiet_until: f64,
+    next_id: u64,
+}
+
+impl Supervisor {
+    pub fn new(config: &Risk) -> Self {
+        Self {
+            enabled: config.supervised,
+            min_shares: Decimal::try_from(config.supervised_min_shares).unwrap_or(Decimal::ZERO),
+            timeout_secs: config.approval_timeout_secs,
+            held: None,
+            quiet_until: 0.0,
+            next_id: 1,
+        }
+    }
+
+    /// Swap in new settings (config reload). A held batch stays held,
+    /// unless supervision was switched off.
+    pub fn reconfigure(&mut self, config: &Risk) {
+        let (held, quiet_until, next_id) = (self.held.take(), self.quiet_until, self.next_id);
+        *self = Self { held, quiet_until, next_id, ..Self::new(config) };
+        if !self.enabled {
+            self.held = None;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Drop a held batch past its timeout.
+    ///
+    /// # Returns
+    /// The dropped batch's id.
+    pub fn expire(&mut self, now: f64) -> Option<u64> {
+        let held = self.held.take_if(|h| now >= h.expires_at)?;
+        self.quiet_until = now + self.timeout_secs;
+        Some(held.id)
+    }
+
+    /// Drop any held batch without a hold-off (market switch: its orders
+    /// are for the old tokens).
+    pub fn clear(&mut self) -> Option<u64> {
+        self.held.take().map(|h| h.id)
+    }
+
+    /// Split a reconcile batch into what runs now and what waits.
+    pub fn gate(&mut self, now: f64, actions: Vec<Action>) -> Gated {
+        if !self.enabled {
+            return Gated { run: actions, held: None };
+        }
+        let (run, buys): (Vec<Action>, Vec<Action>) = actions.into_iter().partition(Action::is_cancel);
+        if shares(&buys) <= self.min_shares {
+            return Gated { run: run.into_iter().chain(buys).collect(), held: None };
+        }
+        if self.held.is_some() || now < self.quiet_until {
+            return Gated { run, held: None };
+        }
+
+        let held = Held { id: self.next_id, actions: buys, expires_at: now + self.timeout_secs };
+        self.next_id += 1;
+        let preview = preview(&held, now);
+        self.held = Some(held);
+        Gated { run, held: Some(preview) }
+    }
+
+    /// Release a held batch for execution.
+    ///
+    /// # Errors
+    /// No batch with that id is waiting.
+    pub fn approve(&mut self, id: u64) -> Result<Vec<Action>, String> {
+        match self.held.take_if(|h| h.id == id) {
+            Some(held) => Ok(held.actions),
+            None => Err(self.missing(id)),
+        }
+    }
+
+    /// Drop a held batch.
+    ///
+    /// # Errors
+    /// No batch with that id is waiting.
+    pub fn reject(&mut self, id: u64, now: f64) -> Result<(), String> {
+        match self.held.take_if(|h| h.id == id) {
+            Some(_) => {
+                self.quiet_until = now + self.timeout_secs;
+                Ok(())
+            }
+            None => Err(self.missing(id)),
+        }
+    }
+
+    fn missing(&self, id: u64) -> String {
+        match &self.held {
+            Some(held) => format!("no batch {} waiting (batch {} is)", id, held.id),
+            None => format!("no batch {} waiting", id),
+        }
+    }
+}
+
+/// Shares a batch buys.
+fn shares(actions: &[Action]) -> Decimal {
+    actions
+        .iter()
+        .map(|a| match a {
+            Action::Place { size, .. } | Action::Take { size, .. } => *size,
+            _ => Decimal::ZERO,
+        })
+        .sum()
+}
+
+fn preview(held: &Held, now: f64) -> BatchPreview {
+    let side = |s: &Side| if *s == Side::Yes { "YES" } else { "NO" };
+    let actions = held
+        .actions
+        .iter()
+        .map(|a| match a {
+            Action::Place { side: s, price, size, .. } => format!("place {} {} @ {}", side(s), size, price),
+            Action::Take { side: s, size, max_price } => format!("take {} {} up to {}", side(s), size, max_price),
+            other => format!("{:?}", other),
+        })
+        .collect();
+    BatchPreview {
+        id: held.id,
+        actions,
+        shares: shares(&held.actions).to_f64().unwrap_or(0.0),
+        expires_in_secs: held.expires_at - now,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn supervisor() -> Supervisor {
+        Supervisor::new(&Risk { supervised: true, supervised_min_shares: 10.0, approval_timeout_secs: 30.0, ..Default::default() })
+    }
+
+    fn batch() -> Vec<Action> {
+        vec![
+            Action::cancel("o1"),
+            Action::place(Side::Yes, 480, dec!(10)),
+            Action::place(Side::No, 500, dec!(5)),
+        ]
+    }
+
+    #[test]
+    fn test_holds_large_batches_until_approved() {
+        let mut s = supervisor();
+        // Small batches and cancels run straight away
+        let small = s.gate(0.0, vec![Action::cancel("o1"), Action::place(Side::Yes, 480, dec!(10))]);
+        assert_eq!((small.run.len(), small.held), (2, None));
+
+        let gated = s.gate(1.0, batch());
+        assert_eq!(gated.run, vec![Action::cancel("o1")]);
+        let preview = gated.held.unwrap();
+        assert_eq!(preview.actions, vec!["place YES 10 @ 480", "place NO 5 @ 500"]);
+        assert_eq!((preview.shares, preview.expires_in_secs), (15.0, 30.0));
+
+        // Nothing queues behind it
+        let next = s.gate(2.0, batch());
+        assert_eq!((next.run.len(), next.held), (1, None));
+
+        assert!(s.approve(preview.id + 1).unwrap_err().contains("is"));
+        assert_eq!(s.approve(preview.id).unwrap().len(), 2);
+        assert!(s.approve(preview.id).is_err());
+        assert_eq!(s.gate(3.0, batch()).held.unwrap().id, preview.id + 1);
+    }
+
+    #[test]
+    fn test_reject_and_expiry_hold_off() {
+        let mut s = supervisor();
+        let id = s.gate(0.0, batch()).held.unwrap().id;
+        s.reject(id, 5.0).unwrap();
+        assert!(s.gate(20.0, batch()).held.is_none());
+
+        let id = s.gate(35.0, batch()).held.unwrap().id;
+        assert_eq!(s.expire(64.0), None);
+        assert_eq!(s.expire(65.0), Some(id));
+        assert!(s.gate(80.0, batch()).held.is_none());
+        assert!(s.gate(95.0, batch()).held.is_some());
+
+        // Off: everything runs
+        s.reconfigure(&Risk::default());
+        assert_eq!(s.gate(96.0, batch()).run.len(), 3);
+    }
+}
@@ -0,0 +1,195 @@
+//! Per-variant risk budgets.
+//!
+//! Several strategy variants can trade the same market (the maker ladder,
+//! an arb taker, an end-game module). Orders are tagged with the variant
+//! that placed them, and each variant gets its own position and notional
+//! budget so an experimental module can't use up the whole book's risk.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use rust_decimal::Decimal;
+
+use crate::events::Side;
+use crate::state::OrderTracker;
+
+/// Which strategy variant an order or fill belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Variant {
+    /// The maker ladder (everything untagged)
+    #[default]
+    Ladder,
+    /// Taker arbitrage / rebalancing
+    ArbTaker,
+    /// Near-expiry module
+    EndGame,
+    /// An extra strategy instance quoting alongside the ladder
+    /// (`[[instances]]` in config.toml, in order)
+    Instance(u8),
+}
+
+impl Variant {
+    pub const ALL: [Variant; 3] = [Variant::Ladder, Variant::ArbTaker, Variant::EndGame];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Variant::Ladder => "ladder",
+            Variant::ArbTaker => "arb_taker",
+            Variant::EndGame => "end_game",
+            Variant::Instance(_) => "instance",
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Variant::Instance(i) => write!(f, "instance_{}", i),
+            _ => f.write_str(self.as_str()),
+        }
+    }
+}
+
+/// Risk budget for one variant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VariantBudget {
+    /// Max shares per side, filled plus resting
+    pub max_position: Decimal,
+    /// Max USDC, filled cost plus resting notional, both sides
+    pub max_notional: Decimal,
+}
+
+impl VariantBudget {
+    /// Default budgets: experiments get a slice. The ladder is left to its
+    /// own limits (RiskLimits, max_position) and has no variant budget.
+    pub fn defaults() -> HashMap<Variant, VariantBudget> {
+        HashMap::from([
+            (Variant::ArbTaker, VariantBudget {
+                max_position: Decimal::from(30),    // 30 shares
+                max_notional: Decimal::from(20),    // $20
+            }),
+            (Variant::EndGame, VariantBudget {
+                max_position: Decimal::from(20),    // 20 shares
+                max_notional: Decimal::from(15),    // $15
+            }),
+        ])
+    }
+}
+
+/// Filled shares and cost for one variant.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VariantFills {
+    /// Shares bought per side (YES, NO)
+    pub qty: [Decimal; 2],
+    /// Total cost (ticks × shares)
+    pub cost_ticks: Decimal,
+}
+
+impl VariantFills {
+    /// Total cost in USDC.
+    pub fn cost_usd(&self) -> Decimal {
+        self.cost_ticks / Decimal::from(1000)
+    }
+}
+
+fn idx(side: Side) -> usize {
+    match side {
+        Side::Yes => 0,
+        Side::No => 1,
+    }
+}
+
+/// Budgets plus per-variant fills for the current market.
+#[derive(Debug, Default)]
+pub struct VariantRisk {
+    budgets: HashMap<Variant, VariantBudget>,
+    fills: HashMap<Variant, VariantFills>,
+}
+
+impl VariantRisk {
+    pub fn new(budgets: HashMap<Variant, VariantBudget>) -> Self {
+        Self { budgets, fills: HashMap::new() }
+    }
+
+    /// Check if `variant` can add `size` on `side` at `price` without going
+    /// over its budget. Variants without a budget are unrestricted.
+    pub fn allows(&self, variant: Variant, side: Side, price: u16, size: Decimal, orders: &OrderTracker) -> bool {
+        let Some(budget) = self.budgets.get(&variant) else {
+            return true;
+        };
+        let fills = self.fills.get(&variant).cloned().unwrap_or_default();
+
+        let position = fills.qty[idx(side)] + orders.variant_exposure(variant, side) + size;
+        let notional_ticks = fills.cost_ticks + orders.variant_notional(variant) + Decimal::from(price) * size;
+        position <= budget.max_position && notional_ticks <= budget.max_notional * Decimal::from(1000)
+    }
+
+    /// Record a fill against a variant.
+    pub fn record_fill(&mut self, variant: Variant, side: Side, price: u16, size: Decimal) {
+        let fills = self.fills.entry(variant).or_default();
+        fills.qty[idx(side)] += size;
+        fills.cost_ticks += Decimal::from(price) * size;
+    }
+
+    /// Fills so far for a variant.
+    pub fn fills(&self, variant: Variant) -> VariantFills {
+        self.fills.get(&variant).cloned().unwrap_or_default()
+    }
+
+    /// Reset fills for a new market (budgets stay).
+    pub fn reset(&mut self) {
+        self.fills.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn risk() -> VariantRisk {
+        VariantRisk::new(HashMap::from([(Variant::ArbTaker, VariantBudget {
+            max_position: dec!(20),
+            max_notional: dec!(10),
+        })]))
+    }
+
+    #[test]
+    fn test_position_budget_counts_fills_and_resting() {
+        let mut risk = risk();
+        let mut orders = OrderTracker::new();
+        orders.add_tagged(Side::Yes, "arb1".into(), 400, dec!(5), Variant::ArbTaker);
+        risk.record_fill(Variant::ArbTaker, Side::Yes, 400, dec!(10));
+
+        assert!(risk.allows(Variant::ArbTaker, Side::Yes, 100, dec!(5), &orders));
+        assert!(!risk.allows(Variant::ArbTaker, Side::Yes, 100, dec!(6), &orders));
+        // Other side has its own position count
+        assert!(risk.allows(Variant::ArbTaker, Side::No, 100, dec!(6), &orders));
+    }
+
+    #[test]
+    fn test_notional_budget() {
+        let mut risk = risk();
+        let orders = OrderTracker::new();
+        risk.record_fill(Variant::ArbTaker, Side::Yes, 500, dec!(10)); // $5
+
+        assert!(risk.allows(Variant::ArbTaker, Side::No, 500, dec!(10), &orders)); // $10 total
+        assert!(!risk.allows(Variant::ArbTaker, Side::No, 510, dec!(10), &orders));
+    }
+
+    #[test]
+    fn test_other_variants_unaffected() {
+        let mut risk = risk();
+        let mut orders = OrderTracker::new();
+        // Ladder orders don't count against the arb budget
+        orders.add(Side::Yes, "l1".into(), 500, dec!(100));
+        risk.record_fill(Variant::Ladder, Side::Yes, 500, dec!(100));
+
+        assert!(risk.allows(Variant::ArbTaker, Side::Yes, 500, dec!(20), &orders));
+        // Unbudgeted variant is unrestricted
+        assert!(risk.allows(Variant::Ladder, Side::Yes, 500, dec!(1000), &orders));
+
+        risk.reset();
+        assert_eq!(risk.fills(Variant::Ladder), VariantFills::default());
+    }
+}
@@ -0,0 +1,146 @@
+//! Which code and configuration produced a run.
+//!
+//! Captured once at startup and stamped into the journal header, the
+//! session CSV (a `RUN` row), the `build_info` metric and the session
+//! summary, so any result can be traced back to the commit, build profile,
+//! config file and strategy parameters behind it.
+
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::strategy::StrategyConfig;
+
+/// Strategy parameters in effect at startup.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyParams {
+    pub margin_ticks: u16,
+    pub max_position: f64,
+    pub ladder_rungs: u16,
+    pub rung_spacing: u16,
+    /// Per-rung margins, when they replace uniform spacing
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rung_margins: Vec<u16>,
+    pub rebalance_threshold: f64,
+    pub max_take_size: f64,
+    pub duration: String,
+    pub min_fill_prob: f64,
+}
+
+impl StrategyParams {
+    pub fn from_config(strategy: &StrategyConfig) -> Self {
+        let f = |d: rust_decimal::Decimal| d.to_f64().unwrap_or(0.0);
+        Self {
+            margin_ticks: strategy.margin_ticks,
+            max_position: f(strategy.max_position),
+            ladder_rungs: strategy.ladder_rungs,
+            rung_spacing: strategy.rung_spacing,
+            rung_margins: strategy.rung_margins.clone(),
+            rebalance_threshold: f(strategy.rebalance_threshold),
+            max_take_size: f(strategy.max_take_size),
+            duration: strategy.duration.label().to_string(),
+            min_fill_prob: strategy.min_fill_prob,
+        }
+    }
+}
+
+/// Build and configuration identity of a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunInfo {
+    pub version: String,
+    /// Short git hash, `-dirty` with uncommitted changes
+    pub commit: String,
+    /// Cargo profile (debug/release)
+    pub profile: String,
+    /// FNV-1a of the config file, "none" without one
+    pub config_hash: String,
+    pub strategy: StrategyParams,
+}
+
+impl RunInfo {
+    /// Capture this build with the config file at `config_path`.
+    pub fn capture(config_path: &str, strategy: &StrategyConfig) -> Self {
+        let config = std::fs::read_to_string(config_path).ok();
+        Self::new(config.as_deref(), strategy)
+    }
+
+    pub fn new(config: Option<&str>, strategy: &StrategyConfig) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            commit: option_env!("GIT_COMMIT").unwrap_or("unknown").to_string(),
+            profile: option_env!("BUILD_PROFILE").unwrap_or("unknown").to_string(),
+            config_hash: config.map_or("none".to_string(), config_hash),
+            strategy: StrategyParams::from_config(strategy),
+        }
+    }
+
+    /// Space-separated `key=value` pairs (safe inside a CSV column).
+    pub fn summary(&self) -> String {
+        let s = &self.strategy;
+        let mut summary = format!(
+            "version={} commit={} profile={} config={} margin_ticks={} max_position={} ladder_rungs={} rung_spacing={} rebalance_threshold={} max_take_size={} duration={} min_fill_prob={}",
+            self.version,
+            self.commit,
+            self.profile,
+            self.config_hash,
+            s.margin_ticks,
+            s.max_position,
+            s.ladder_rungs,
+            s.rung_spacing,
+            s.rebalance_threshold,
+            s.max_take_size,
+            s.duration,
+            s.min_fill_prob
+        );
+        if !s.rung_margins.is_empty() {
+            let margins: Vec<String> = s.rung_margins.iter().map(|m| m.to_string()).collect();
+            summary.push_str(&format!(" rung_margins={}", margins.join("/")));
+        }
+        summary
+    }
+
+    /// Labels for the `build_info` metric.
+    pub fn labels(&self) -> Vec<(String, String)> {
+        vec![
+            ("version".to_string(), self.version.clone()),
+            ("commit".to_string(), self.commit.clone()),
+            ("profile".to_string(), self.profile.clone()),
+            ("config_hash".to_string(), self.config_hash.clone()),
+        ]
+    }
+}
+
+/// 64-bit FNV-1a, hex. Stable across builds and platforms, unlike
+/// `DefaultHasher`.
+pub fn config_hash(text: &str) -> String {
+    let hash = text.bytes().fold(0xcbf29ce484222325_u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+    format!("{:016x}", hash)
+}
+
+/// Value of `key=` in a `summary` string.
+pub fn summary_field<'a>(summary: &'a str, key: &str) -> Option<&'a str> {
+    summary
+        .split_whitespace()
+        .find_map(|kv| kv.strip_prefix(key).and_then(|v| v.strip_prefix('=')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_and_hash() {
+        let strategy = StrategyConfig { margin_ticks: 20, ladder_rungs: 1, ..Default::default() };
+        let run = RunInfo::new(Some("[strategy]\nmargin_ticks = 20\n"), &strategy);
+        assert_eq!(run.config_hash.len(), 16);
+        assert_ne!(run.config_hash, config_hash("[strategy]\nmargin_ticks = 25\n"));
+        assert_eq!(config_hash(""), "cbf29ce484222325");
+        assert_eq!(RunInfo::new(None, &strategy).config_hash, "none");
+
+        let summary = run.summary();
+        assert!(!summary.contains(','));
+        assert_eq!(summary_field(&summary, "config"), Some(run.config_hash.as_str()));
+        assert_eq!(summary_field(&summary, "margin_ticks"), Some("20"));
+        assert_eq!(summary_field(&summary, "duration"), Some("5m"));
+        assert_eq!(summary_field(&summary, "missing"), None);
+    }
+}
@@ -0,0 +1,40 @@
+//! Kafka backend (feature `kafka`).
+
+use std::time::Duration;
+
+use futures_util::future::{join_all, BoxFuture};
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+
+use super::Publisher;
+
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+}
+
+impl KafkaPublisher {
+    /// `brokers` is a comma-separated bootstrap list.
+    pub fn new(brokers: &str) -> anyhow::Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("linger.ms", "50")
+            .set("queue.buffering.max.messages", "100000")
+            .create()?;
+        Ok(Self { producer })
+    }
+}
+
+impl Publisher for KafkaPublisher {
+    fn publish<'a>(&'a mut self, topic: &'a str, payloads: &'a [Vec<u8>]) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let sends = payloads.iter().map(|payload| {
+                let record = FutureRecord::<(), _>::to(topic).payload(payload);
+                self.producer.send(record, Duration::from_secs(0))
+            });
+            for result in join_all(sends).await {
+                result.map_err(|(e, _)| e)?;
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,390 @@
+//! Event publishing to a message bus (Kafka, NATS).
+//!
+//! The trading loop hands records to [`EventSink`], which only ever
+//! `try_send`s onto a bounded queue: when the bus is slow or down, records
+//! are dropped and counted, never waited on. A worker task batches them per
+//! topic and publishes on size or on a timer, and flushes what it holds
+//! when the sinks stage shuts down.
+//!
+//! Topics are `<prefix>.fills`, `<prefix>.quotes` and `<prefix>.snapshots`;
+//! payloads are one JSON record each.
+
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "nats")]
+mod nats;
+
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaPublisher;
+#[cfg(feature = "nats")]
+pub use nats::NatsPublisher;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio_util::sync::CancellationToken;
+
+use crate::config;
+use crate::events::Side;
+use crate::journal::Snapshot;
+use crate::tasks::{Stage, TaskRegistry};
+
+/// One published record.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Record {
+    Fill {
+        ts: f64,
+        market: String,
+        side: &'static str,
+        price: u16,
+        size: f64,
+        order_id: String,
+        is_maker: bool,
+    },
+    Quote {
+        ts: f64,
+        market: String,
+        yes_target: u16,
+        no_target: u16,
+    },
+    Snapshot(Snapshot),
+}
+
+/// One of our fills, as handed to [`EventSink::fill`].
+#[derive(Debug, Clone, Copy)]
+pub struct FillInfo<'a> {
+    pub side: Side,
+    pub price: u16,
+    pub size: f64,
+    pub order_id: &'a str,
+    pub is_maker: bool,
+}
+
+impl Record {
+    /// Topic suffix.
+    pub fn topic(&self) -> &'static str {
+        match self {
+            Record::Fill { .. } => "fills",
+            Record::Quote { .. } => "quotes",
+            Record::Snapshot(_) => "snapshots",
+        }
+    }
+}
+
+/// A bus to publish batches to.
+pub trait Publisher: Send + 'static {
+    /// Publish `payloads` to `topic`. Called from the worker task only.
+    fn publish<'a>(&'a mut self, topic: &'a str, payloads: &'a [Vec<u8>]) -> BoxFuture<'a, anyhow::Result<()>>;
+}
+
+/// Batching and queueing.
+#[derive(Debug, Clone)]
+pub struct SinkConfig {
+    /// Topic prefix
+    pub prefix: String,
+    /// Publish a topic's batch once it has this many records
+    pub batch_size: usize,
+    /// ...or after this long, whichever comes first
+    pub flush_interval: Duration,
+    /// Records queued between the loop and the worker; beyond this they're dropped
+    pub queue_size: usize,
+    /// Give up on a batch after this long
+    pub publish_timeout: Duration,
+}
+
+impl Default for SinkConfig {
+    fn default() -> Self {
+        Self {
+            prefix: "polybot".to_string(),
+            batch_size: 100,
+            flush_interval: Duration::from_millis(250),
+            queue_size: 10_000,
+            publish_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl From<&config::Sink> for SinkConfig {
+    fn from(cfg: &config::Sink) -> Self {
+        Self {
+            prefix: cfg.topic_prefix.clone(),
+            batch_size: cfg.batch_size.max(1),
+            flush_interval: Duration::from_millis(cfg.flush_ms.max(1)),
+            queue_size: cfg.queue_size.max(1),
+            ..Default::default()
+        }
+    }
+}
+
+/// Cheap-to-clone handle the trading loop publishes through. Disabled
+/// (every call a no-op) unless a bus is configured.
+#[derive(Clone, Default)]
+pub struct EventSink {
+    tx: Option<mpsc::Sender<Record>>,
+    dropped: Arc<AtomicU64>,
+    failed: Arc<AtomicU64>,
+}
+
+impl EventSink {
+    /// Sink that publishes nothing.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    /// Build from the `[sink]` config section.
+    /// Must be called inside a tokio runtime (the worker is a task).
+    #[cfg_attr(not(any(feature = "nats", feature = "kafka")), allow(unused_variables))]
+    pub async fn from_config(cfg: &config::Sink, tasks: &mut TaskRegistry) -> anyhow::Result<Self> {
+        if let Some(url) = &cfg.nats_url {
+            #[cfg(feature = "nats")]
+            {
+                let publisher = NatsPublisher::connect(url).await?;
+                println!("[sink] NATS at {} ({}.*)", url, cfg.topic_prefix);
+                return Ok(Self::spawn(publisher, SinkConfig::from(cfg), tasks));
+            }
+            #[cfg(not(feature = "nats"))]
+            anyhow::bail!("sink.nats_url = {} but built without the `nats` feature", url);
+        }
+        if let Some(brokers) = &cfg.kafka_brokers {
+            #[cfg(feature = "kafka")]
+            {
+                let publisher = KafkaPublisher::new(brokers)?;
+                println!("[sink] Kafka at {} ({}.*)", brokers, cfg.topic_prefix);
+                return Ok(Self::spawn(publisher, SinkConfig::from(cfg), tasks));
+            }
+            #[cfg(not(feature = "kafka"))]
+            anyhow::bail!("sink.kafka_brokers = {} but built without the `kafka` feature", brokers);
+        }
+        Ok(Self::disabled())
+    }
+
+    /// Start a worker publishing to `publisher`, registered as a sink.
+    pub fn spawn(publisher: impl Publisher, config: SinkConfig, tasks: &mut TaskRegistry) -> Self {
+        let (tx, rx) = mpsc::channel(config.queue_size);
+        let sink = Self {
+            tx: Some(tx),
+            ..Default::default()
+        };
+        let failed = sink.failed.clone();
+        tasks.spawn_with(Stage::Sinks, "sink", move |stop| run(rx, publisher, config, failed, stop));
+        sink
+    }
+
+    /// Whether a bus is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.tx.is_some()
+    }
+
+    /// Queue a record. Never blocks: a full queue drops it.
+    pub fn send(&self, record: Record) {
+        let Some(tx) = &self.tx else {
+            return;
+        };
+        if let Err(TrySendError::Full(_) | TrySendError::Closed(_)) = tx.try_send(record) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn fill(&self, ts: f64, market: &str, fill: &FillInfo) {
+        if self.is_enabled() {
+            self.send(Record::Fill {
+                ts,
+                market: market.to_string(),
+                side: if fill.side == Side::Yes { "YES" } else { "NO" },
+                price: fill.price,
+                size: fill.size,
+                order_id: fill.order_id.to_string(),
+                is_maker: fill.is_maker,
+            });
+        }
+    }
+
+    pub fn quote(&self, ts: f64, market: &str, yes_target: u16, no_target: u16) {
+        if self.is_enabled() {
+            self.send(Record::Quote { ts, market: market.to_string(), yes_target, no_target });
+        }
+    }
+
+    pub fn snapshot(&self, snapshot: &Snapshot) {
+        if self.is_enabled() {
+            self.send(Record::Snapshot(snapshot.clone()));
+        }
+    }
+
+    /// Records dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Records in batches the bus rejected or timed out on.
+    pub fn failed(&self) -> u64 {
+        self.failed.load(Ordering::Relaxed)
+    }
+}
+
+/// Worker: batch per topic, publish on size or timer, flush what's left
+/// when every handle is gone or on `stop`.
+async fn run(
+    mut rx: mpsc::Receiver<Record>,
+    mut publisher: impl Publisher,
+    config: SinkConfig,
+    failed: Arc<AtomicU64>,
+    stop: CancellationToken,
+) {
+    let mut batches: HashMap<&'static str, Vec<Vec<u8>>> = HashMap::new();
+    let mut ticker = tokio::time::interval(config.flush_interval);
+
+    loop {
+        tokio::select! {
+            record = rx.recv() => {
+                let Some(record) = record else {
+                    break;
+                };
+                let topic = record.topic();
+                let Ok(payload) = serde_json::to_vec(&record) else {
+                    continue;
+                };
+                let batch = batches.entry(topic).or_default();
+                batch.push(payload);
+                if batch.len() >= config.batch_size {
+                    let batch = std::mem::take(batch);
+                    flush(&mut publisher, &config, topic, batch, &failed).await;
+                }
+            }
+            _ = stop.cancelled() => {
+                // Take what's already queued, then flush below
+                rx.close();
+                while let Ok(record) = rx.try_recv() {
+                    if let Ok(payload) = serde_json::to_vec(&record) {
+                        batches.entry(record.topic()).or_default().push(payload);
+                    }
+                }
+                break;
+            }
+            _ = ticker.tick() => {
+                for (topic, batch) in batches.iter_mut() {
+                    if !batch.is_empty() {
+                        let batch = std::mem::take(batch);
+                        flush(&mut publisher, &config, topic, batch, &failed).await;
+                    }
+                }
+            }
+        }
+    }
+
+    for (topic, batch) in batches {
+        if !batch.is_empty() {
+            flush(&mut publisher, &config, topic, batch, &failed).await;
+        }
+    }
+}
+
+async fn flush(publisher: &mut impl Publisher, config: &SinkConfig, topic: &str, batch: Vec<Vec<u8>>, failed: &AtomicU64) {
+    let full_topic = format!("{}.{}", config.prefix, topic);
+    let result = tokio::time::timeout(config.publish_timeout, publisher.publish(&full_topic, &batch)).await;
+    let error = match result {
+        Ok(Ok(())) => return,
+        Ok(Err(e)) => e.to_string(),
+        Err(_) => "timed out".to_string(),
+    };
+    failed.fetch_add(batch.len() as u64, Ordering::Relaxed);
+    tracing::warn!("sink: dropped {} records for {}: {}", batch.len(), full_topic, error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records every batch it's given.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<(String, usize)>>>);
+
+    impl Publisher for Recorder {
+        fn publish<'a>(&'a mut self, topic: &'a str, payloads: &'a [Vec<u8>]) -> BoxFuture<'a, anyhow::Result<()>> {
+            self.0.lock().unwrap().push((topic.to_string(), payloads.len()));
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    /// Never finishes publishing.
+    struct Stuck;
+
+    impl Publisher for Stuck {
+        fn publish<'a>(&'a mut self, _topic: &'a str, _payloads: &'a [Vec<u8>]) -> BoxFuture<'a, anyhow::Result<()>> {
+            Box::pin(std::future::pending())
+        }
+    }
+
+    fn config(batch_size: usize, queue_size: usize) -> SinkConfig {
+        SinkConfig {
+            batch_size,
+            queue_size,
+            flush_interval: Duration::from_millis(50),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batches_by_size_then_timer() {
+        let recorder = Recorder::default();
+        let sink = EventSink::spawn(recorder.clone(), config(3, 100), &mut TaskRegistry::new());
+        for i in 0..7 {
+            let fill = FillInfo { side: Side::Yes, price: 480, size: 5.0, order_id: "id", is_maker: true };
+            sink.fill(i as f64, "m", &fill);
+        }
+        sink.quote(0.0, "m", 480, 500);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let batches = recorder.0.lock().unwrap().clone();
+        let fills: Vec<usize> = batches.iter().filter(|(t, _)| t == "polybot.fills").map(|(_, n)| *n).collect();
+        assert_eq!(fills, vec![3, 3, 1]);
+        assert!(batches.contains(&("polybot.quotes".to_string(), 1)));
+        assert_eq!(sink.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_stuck_bus_never_blocks() {
+        let sink = EventSink::spawn(Stuck, config(1, 4), &mut TaskRegistry::new());
+        let start = std::time::Instant::now();
+        for _ in 0..1000 {
+            sink.quote(0.0, "m", 480, 500);
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+        assert!(sink.dropped() >= 990);
+    }
+
+    #[tokio::test]
+    async fn test_flushes_on_shutdown() {
+        let recorder = Recorder::default();
+        let mut tasks = TaskRegistry::new();
+        let sink = EventSink::spawn(recorder.clone(), SinkConfig { flush_interval: Duration::from_secs(60), ..config(100, 100) }, &mut tasks);
+        sink.quote(0.0, "m", 480, 500);
+        sink.quote(1.0, "m", 490, 500);
+
+        assert!(tasks.stop(Stage::Sinks, Duration::from_secs(1)).await.is_empty());
+        assert_eq!(*recorder.0.lock().unwrap(), vec![("polybot.quotes".to_string(), 2)]);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let sink = EventSink::disabled();
+        sink.quote(0.0, "m", 480, 500);
+        assert!(!sink.is_enabled());
+        assert_eq!(sink.dropped(), 0);
+    }
+
+    #[test]
+    fn test_record_json() {
+        let record = Record::Quote { ts: 1.0, market: "m".into(), yes_target: 480, no_target: 500 };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(json, r#"{"kind":"quote","ts":1.0,"market":"m","yes_target":480,"no_target":500}"#);
+    }
+}
@@ -0,0 +1,28 @@
+//! NATS backend (feature `nats`).
+
+use futures_util::future::BoxFuture;
+
+use super::Publisher;
+
+pub struct NatsPublisher {
+    client: async_nats::Client,
+}
+
+impl NatsPublisher {
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        let client = async_nats::connect(url).await?;
+        Ok(Self { client })
+    }
+}
+
+impl Publisher for NatsPublisher {
+    fn publish<'a>(&'a mut self, topic: &'a str, payloads: &'a [Vec<u8>]) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            for payload in payloads {
+                self.client.publish(topic.to_string(), payload.clone().into()).await?;
+            }
+            self.client.flush().await?;
+            Ok(())
+        })
+    }
+}
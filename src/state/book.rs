@@ -62,6 +62,30 @@ impl Book {
         }
     }
 
+    /// Check if either side is locked (bid == ask) or crossed (bid > ask),
+    /// or the two sides cross each other (yes_bid + no_bid > $1).
+    /// Quoting into a book like this can take instantly; wait for it to clear.
+    pub fn is_locked_or_crossed(&self) -> bool {
+        let side_crossed = |bid: Option<u16>, ask: Option<u16>| match (bid, ask) {
+            (Some(b), Some(a)) => b >= a,
+            _ => false,
+        };
+        let pair_crossed = match (self.yes_bid, self.no_bid) {
+            (Some(y), Some(n)) => y + n > 1000,
+            _ => false,
+        };
+
+        side_crossed(self.yes_bid, self.yes_ask) || side_crossed(self.no_bid, self.no_ask) || pair_crossed
+    }
+
+    /// Spread in ticks for a side. None if either price is missing.
+    pub fn spread(&self, side: Side) -> Option<i32> {
+        match (self.best_bid(side), self.best_ask(side)) {
+            (Some(b), Some(a)) => Some(a as i32 - b as i32),
+            _ => None,
+        }
+    }
+
     /// Reset book state (e.g., on market switch).
     pub fn reset(&mut self) {
         self.yes_bid = None;
@@ -120,6 +144,52 @@ mod tests {
         assert_eq!(book.opposite_ask(Side::No), Some(490));
     }
 
+    #[test]
+    fn test_normal_book_not_locked() {
+        let mut book = Book::default();
+        book.update(Side::Yes, 480, 490, 1000);
+        book.update(Side::No, 510, 520, 1001);
+        assert!(!book.is_locked_or_crossed());
+
+        // One-cent-wide is tight but not locked
+        assert_eq!(book.spread(Side::Yes), Some(10));
+    }
+
+    #[test]
+    fn test_locked_side() {
+        let mut book = Book::default();
+        book.update(Side::Yes, 490, 490, 1000);
+        book.update(Side::No, 500, 510, 1001);
+        assert!(book.is_locked_or_crossed());
+        assert_eq!(book.spread(Side::Yes), Some(0));
+    }
+
+    #[test]
+    fn test_crossed_side() {
+        let mut book = Book::default();
+        book.update(Side::Yes, 480, 490, 1000);
+        book.update(Side::No, 520, 510, 1001);
+        assert!(book.is_locked_or_crossed());
+        assert_eq!(book.spread(Side::No), Some(-10));
+    }
+
+    #[test]
+    fn test_crossed_pair() {
+        let mut book = Book::default();
+        // Each side fine on its own, but YES bid + NO bid > $1
+        book.update(Side::Yes, 500, 520, 1000);
+        book.update(Side::No, 510, 530, 1001);
+        assert!(book.is_locked_or_crossed());
+    }
+
+    #[test]
+    fn test_partial_book_not_locked() {
+        let mut book = Book::default();
+        book.update(Side::Yes, 490, 490, 1000);
+        book.yes_ask = None;
+        assert!(!book.is_locked_or_crossed());
+    }
+
     #[test]
     fn test_reset() {
         let mut book = Book::default();
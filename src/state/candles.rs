@@ -0,0 +1,236 @@
+//! BTC OHLC candles at several resolutions, built from the trade feed.
+//!
+//! Each resolution keeps its candle in progress and the last `history`
+//! closed ones. A candle closes when a trade (or `roll`) lands past its
+//! end; a period without trades has no candle. Momentum and volatility
+//! features are taken over the closed candles, and the latest of each
+//! resolution goes into the journal's signals, where plugins see it too.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+/// Resolutions built from the BTC feed (seconds)
+pub const CANDLE_PERIODS: [u32; 3] = [1, 5, 60];
+
+/// Closed candles kept per resolution
+pub const CANDLE_HISTORY: usize = 120;
+
+/// Candles the momentum and volatility features look back over
+const FEATURE_CANDLES: usize = 10;
+
+/// One OHLC candle. `start` is the unix time its period began.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Candle {
+    pub start: f64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub trades: u32,
+}
+
+impl Candle {
+    fn open_at(start: f64, price: f64) -> Self {
+        Self { start, open: price, high: price, low: price, close: price, trades: 1 }
+    }
+
+    fn update(&mut self, price: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.trades += 1;
+    }
+}
+
+/// One resolution's latest closed candle and the features over its history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandleSignal {
+    pub period_secs: u32,
+    pub last: Candle,
+    /// Log return from the open of the last `FEATURE_CANDLES` closed
+    /// candles to the latest close
+    pub momentum: f64,
+    /// Parkinson (high-low) volatility per candle over the same candles
+    pub volatility: f64,
+}
+
+#[derive(Debug)]
+struct Series {
+    period: u32,
+    current: Option<Candle>,
+    closed: VecDeque<Candle>,
+}
+
+impl Series {
+    fn start_of(&self, ts: f64) -> f64 {
+        let period = self.period as f64;
+        (ts / period).floor() * period
+    }
+
+    /// Close the current candle if `ts` is past its end.
+    fn roll(&mut self, ts: f64, history: usize) -> Option<Candle> {
+        let current = self.current?;
+        if ts < current.start + self.period as f64 {
+            return None;
+        }
+        self.current = None;
+        self.closed.push_back(current);
+        if self.closed.len() > history {
+            self.closed.pop_front();
+        }
+        Some(current)
+    }
+}
+
+/// Candles at each of several resolutions.
+#[derive(Debug)]
+pub struct Candles {
+    series: Vec<Series>,
+    history: usize,
+}
+
+impl Default for Candles {
+    fn default() -> Self {
+        Self::new(&CANDLE_PERIODS, CANDLE_HISTORY)
+    }
+}
+
+impl Candles {
+    pub fn new(periods: &[u32], history: usize) -> Self {
+        let series = periods
+            .iter()
+            .map(|&period| Series { period: period.max(1), current: None, closed: VecDeque::new() })
+            .collect();
+        Self { series, history: history.max(1) }
+    }
+
+    /// Add a trade.
+    ///
+    /// # Returns
+    /// Candles it closed, as (period, candle).
+    pub fn record(&mut self, ts: f64, price: f64) -> Vec<(u32, Candle)> {
+        let closed = self.roll(ts);
+        for series in &mut self.series {
+            match series.current.as_mut() {
+                Some(candle) => candle.update(price),
+                None => series.current = Some(Candle::open_at(series.start_of(ts), price)),
+            }
+        }
+        closed
+    }
+
+    /// Close candles whose period is over, without a trade.
+    ///
+    /// # Returns
+    /// Candles it closed, as (period, candle).
+    pub fn roll(&mut self, now: f64) -> Vec<(u32, Candle)> {
+        let history = self.history;
+        self.series
+            .iter_mut()
+            .filter_map(|series| series.roll(now, history).map(|candle| (series.period, candle)))
+            .collect()
+    }
+
+    fn series(&self, period: u32) -> Option<&Series> {
+        self.series.iter().find(|s| s.period == period)
+    }
+
+    /// Closed candles at `period`, oldest first.
+    pub fn closed(&self, period: u32) -> impl Iterator<Item = &Candle> {
+        self.series(period).into_iter().flat_map(|s| s.closed.iter())
+    }
+
+    /// Latest closed candle at `period`.
+    pub fn last(&self, period: u32) -> Option<&Candle> {
+        self.series(period)?.closed.back()
+    }
+
+    /// Log return over the last `n` closed candles at `period`.
+    pub fn momentum(&self, period: u32, n: usize) -> Option<f64> {
+        let closed = &self.series(period)?.closed;
+        let first = closed.get(closed.len().checked_sub(n.max(1))?)?;
+        let last = closed.back()?;
+        (first.open > 0.0 && last.close > 0.0).then(|| (last.close / first.open).ln())
+    }
+
+    /// Parkinson volatility per candle over the last `n` closed candles at
+    /// `period`: sqrt(mean(ln(high/low)²) / 4 ln 2).
+    pub fn volatility(&self, period: u32, n: usize) -> Option<f64> {
+        let closed = &self.series(period)?.closed;
+        let recent: Vec<&Candle> = closed.iter().rev().take(n).filter(|c| c.low > 0.0).collect();
+        if recent.is_empty() {
+            return None;
+        }
+        let mean_sq = recent.iter().map(|c| (c.high / c.low).ln().powi(2)).sum::<f64>() / recent.len() as f64;
+        Some((mean_sq / (4.0 * std::f64::consts::LN_2)).sqrt())
+    }
+
+    /// Latest candle and features per resolution, for the journal.
+    pub fn signals(&self) -> Vec<CandleSignal> {
+        self.series
+            .iter()
+            .filter_map(|series| {
+                let last = *series.closed.back()?;
+                Some(CandleSignal {
+                    period_secs: series.period,
+                    last,
+                    momentum: self.momentum(series.period, FEATURE_CANDLES).unwrap_or(0.0),
+                    volatility: self.volatility(series.period, FEATURE_CANDLES).unwrap_or(0.0),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_candles_close_per_resolution() {
+        let mut candles = Candles::new(&[1, 5], 10);
+        assert!(candles.record(100.2, 50_000.0).is_empty());
+        candles.record(100.5, 50_010.0);
+        candles.record(100.9, 49_990.0);
+
+        // Next second closes the 1s candle, not the 5s one
+        let closed = candles.record(101.1, 50_005.0);
+        assert_eq!(
+            closed,
+            vec![(1, Candle { start: 100.0, open: 50_000.0, high: 50_010.0, low: 49_990.0, close: 49_990.0, trades: 3 })]
+        );
+
+        // A quiet stretch: roll closes both, and the empty seconds get no candle
+        let closed = candles.roll(106.0);
+        assert_eq!(closed.len(), 2);
+        assert_eq!(closed[1], (5, Candle { start: 100.0, open: 50_000.0, high: 50_010.0, low: 49_990.0, close: 50_005.0, trades: 4 }));
+        assert_eq!(candles.closed(1).count(), 2);
+        assert_eq!(candles.last(1).unwrap().start, 101.0);
+        assert!(candles.roll(107.0).is_empty());
+    }
+
+    #[test]
+    fn test_features() {
+        let mut candles = Candles::new(&[1], 3);
+        for (i, price) in [100.0, 101.0, 102.0, 104.0].into_iter().enumerate() {
+            candles.record(i as f64, price);
+            candles.record(i as f64 + 0.5, price * 1.01);
+        }
+        candles.roll(10.0);
+
+        // History is capped: the first candle is gone
+        assert_eq!(candles.closed(1).count(), 3);
+        let momentum = candles.momentum(1, 3).unwrap();
+        assert!((momentum - (104.0 * 1.01 / 101.0_f64).ln()).abs() < 1e-12);
+        assert!(candles.momentum(1, 4).is_none());
+
+        let expected = (1.01_f64.ln().powi(2) / (4.0 * std::f64::consts::LN_2)).sqrt();
+        assert!((candles.volatility(1, 3).unwrap() - expected).abs() < 1e-12);
+
+        let signals = candles.signals();
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].last.close, 104.0 * 1.01);
+        assert!(candles.momentum(60, 1).is_none());
+    }
+}
@@ -0,0 +1,56 @@
+//! Client order IDs.
+//!
+//! Every placement gets an ID of ours before it's sent,
+//! `<market>-<session>-<seq>`: the market's end time, the process's start
+//! time (so a restart never reuses an ID) and a counter. The tracker keys
+//! an order by it until the exchange's ID is known, and keeps it on the
+//! order afterwards, so a fill or user channel event can be traced back to
+//! the placement that caused it.
+
+/// Generates client order IDs, in sequence.
+#[derive(Debug)]
+pub struct ClientOrderIds {
+    market: String,
+    session: String,
+    seq: u64,
+}
+
+impl ClientOrderIds {
+    /// `session_ms` is the process start (ms since epoch).
+    pub fn new(session_ms: u64) -> Self {
+        Self { market: "0".to_string(), session: format!("{:x}", session_ms), seq: 0 }
+    }
+
+    /// IDs from now on belong to the market ending at `end_secs`.
+    pub fn set_market(&mut self, end_secs: i64) {
+        self.market = end_secs.to_string();
+    }
+
+    /// The next ID.
+    pub fn next_id(&mut self) -> String {
+        self.seq += 1;
+        format!("{}-{}-{}", self.market, self.session, self.seq)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_unique_and_tagged_with_market() {
+        let mut ids = ClientOrderIds::new(0x1234);
+        ids.set_market(1772242800);
+        let first = ids.next_id();
+        assert_eq!(first, "1772242800-1234-1");
+
+        // The sequence carries on across markets
+        ids.set_market(1772243100);
+        assert_eq!(ids.next_id(), "1772243100-1234-2");
+
+        // Another session never collides
+        let mut restarted = ClientOrderIds::new(0x1235);
+        restarted.set_market(1772243100);
+        assert_eq!(restarted.next_id(), "1772243100-1235-1");
+    }
+}
@@ -1,3 +1,5 @@
+use crate::clock::Clock;
+
 /// Market identity - set once when switching to a new market.
 #[derive(Debug, Clone)]
 pub struct Market {
@@ -35,4 +37,35 @@ impl Market {
     pub fn time_remaining_secs(&self, now_ms: i64) -> i64 {
         ((self.end_timestamp_ms - now_ms) / 1000).max(0)
     }
+
+    /// Time remaining according to `clock`.
+    pub fn time_remaining(&self, clock: &dyn Clock) -> i64 {
+        self.time_remaining_secs(clock.now_ms())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+
+    #[test]
+    fn test_time_remaining_follows_clock() {
+        let market = Market::new(
+            "0x1".into(),
+            "yes".into(),
+            "no".into(),
+            "btc-updown-5m-1000".into(),
+            1_300_000,
+        );
+        let clock = ManualClock::new(1_000_000);
+        assert_eq!(market.time_remaining(&clock), 300);
+
+        clock.advance_secs(299.5);
+        assert_eq!(market.time_remaining(&clock), 0);
+
+        // Past the end clamps to zero
+        clock.advance_secs(60.0);
+        assert_eq!(market.time_remaining(&clock), 0);
+    }
 }
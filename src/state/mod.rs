@@ -1,9 +1,19 @@
 mod market;
 mod book;
+mod candles;
+mod client_id;
 mod position;
 mod orders;
+mod pnl;
+mod shadow;
+mod startup;
 
 pub use market::Market;
 pub use book::Book;
+pub use candles::{Candle, CandleSignal, Candles};
+pub use client_id::ClientOrderIds;
 pub use position::Position;
-pub use orders::{OrderTracker, StandingOrder};
+pub use orders::{FillMatch, OrderState, OrderTracker, OrderView, PendingCancel, PendingPlace, RestingLadder, StandingOrder};
+pub use pnl::{Pnl, PnlReport};
+pub use shadow::{Divergence, ShadowPosition};
+pub use startup::{adopt_orders, position_from_holdings, ExchangeOrder};
@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use rust_decimal::Decimal;
 use crate::events::Side;
+use crate::risk::Variant;
 
 /// A standing order in the book.
 #[derive(Debug, Clone)]
@@ -12,6 +13,63 @@ pub struct StandingOrder {
     pub remaining_size: Decimal,
     /// Original size when placed
     pub original_size: Decimal,
+    /// Strategy variant that placed it
+    pub variant: Variant,
+    /// Our ID for the placement (None for orders we didn't place this run)
+    pub client_id: Option<String>,
+}
+
+/// An order we asked the exchange to cancel but haven't seen go away.
+#[derive(Debug, Clone)]
+pub struct PendingCancel {
+    pub side: Side,
+    pub order: StandingOrder,
+    /// When the last cancel attempt (or status query) was sent (seconds)
+    pub sent_at: f64,
+    /// Number of retries sent after the first attempt
+    pub retries: u32,
+    /// True once we've queried the exchange for the order's status
+    pub queried: bool,
+}
+
+/// A placement sent to the exchange whose response hasn't arrived (or was
+/// lost). Keyed by client order ID until the exchange's ID is known.
+#[derive(Debug, Clone)]
+pub struct PendingPlace {
+    pub side: Side,
+    /// `order_id` is the client order ID until it's confirmed
+    pub order: StandingOrder,
+    /// When it was sent (seconds)
+    pub sent_at: f64,
+}
+
+/// Where an order is in its life.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderState {
+    /// Sent, not acknowledged (known by client order ID)
+    Pending,
+    /// Acknowledged and resting
+    Live,
+    /// Cancel sent, not acknowledged
+    CancelPending,
+    /// Gone (recently; older IDs are forgotten)
+    Cancelled,
+}
+
+/// How many cancelled order IDs to remember for classifying late fills.
+const CANCELLED_MEMORY: usize = 512;
+
+/// What a fill matched in the tracker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillMatch {
+    /// A live order on the ladder
+    Live,
+    /// An order whose cancel hasn't been acknowledged yet
+    PendingCancel,
+    /// An order we already saw cancelled (the fill raced the cancel)
+    AfterCancel,
+    /// An order we never tracked
+    Unknown,
 }
 
 /// Tracks standing orders for both YES and NO sides.
@@ -22,6 +80,20 @@ pub struct OrderTracker {
     yes_orders: HashMap<u16, Vec<StandingOrder>>,
     /// NO orders: price → list of orders at that price
     no_orders: HashMap<u16, Vec<StandingOrder>>,
+    /// order_id → (side, price), kept in sync with the price maps
+    /// so lookups by ID don't scan every level.
+    index: HashMap<String, (Side, u16)>,
+    /// Orders whose cancel hasn't been acknowledged yet.
+    /// Not part of the ladder, but still live exposure until acked.
+    pending_cancels: HashMap<String, PendingCancel>,
+    /// Placements in flight, by client order ID. Not part of the ladder
+    /// (they can't be cancelled without an exchange ID), but counted at
+    /// their price so reconcile doesn't place the same rung twice.
+    pending_places: HashMap<String, PendingPlace>,
+    /// Recently cancelled order IDs, oldest first, so a fill that races
+    /// its cancel can be told apart from one on an order we never knew.
+    cancelled: VecDeque<String>,
+    cancelled_set: HashSet<String>,
 }
 
 impl OrderTracker {
@@ -49,73 +121,319 @@ impl OrderTracker {
 
     /// Add a new order. Appends to list at this price (stacking).
     pub fn add(&mut self, side: Side, order_id: String, price: u16, size: Decimal) {
-        let orders = self.orders_mut(side);
-        orders.entry(price).or_default().push(StandingOrder {
+        self.add_tagged(side, order_id, price, size, Variant::default());
+    }
+
+    /// Add a new order placed by a specific strategy variant.
+    pub fn add_tagged(&mut self, side: Side, order_id: String, price: u16, size: Decimal, variant: Variant) {
+        self.insert(side, StandingOrder {
             order_id,
             price,
             remaining_size: size,
             original_size: size,
+            variant,
+            client_id: None,
         });
     }
 
+    fn insert(&mut self, side: Side, order: StandingOrder) {
+        self.index.insert(order.order_id.clone(), (side, order.price));
+        self.orders_mut(side).entry(order.price).or_default().push(order);
+    }
+
     /// Remove a specific order by ID. Returns the removed order or None.
     pub fn remove_by_id(&mut self, side: Side, order_id: &str) -> Option<StandingOrder> {
+        let price = match self.index.get(order_id) {
+            Some(&(s, price)) if s == side => price,
+            _ => return None,
+        };
+        self.index.remove(order_id);
+
         let orders = self.orders_mut(side);
-        for (price, order_list) in orders.iter_mut() {
-            if let Some(idx) = order_list.iter().position(|o| o.order_id == order_id) {
-                let removed = order_list.remove(idx);
-                let price = *price;
-                // Clean up empty price levels
-                if order_list.is_empty() {
-                    self.orders_mut(side).remove(&price);
-                }
-                return Some(removed);
-            }
+        let order_list = orders.get_mut(&price)?;
+        let idx = order_list.iter().position(|o| o.order_id == order_id)?;
+        let removed = order_list.remove(idx);
+        // Clean up empty price levels
+        if order_list.is_empty() {
+            orders.remove(&price);
         }
-        None
+        Some(removed)
     }
 
     /// Remove all orders at a price. Returns removed orders.
     pub fn remove_at_price(&mut self, side: Side, price: u16) -> Vec<StandingOrder> {
-        self.orders_mut(side).remove(&price).unwrap_or_default()
+        let removed = self.orders_mut(side).remove(&price).unwrap_or_default();
+        for order in &removed {
+            self.index.remove(&order.order_id);
+        }
+        removed
     }
 
     /// Update remaining size after a fill. Removes order if fully filled.
-    pub fn update_fill(&mut self, side: Side, order_id: &str, filled_size: Decimal) {
-        let orders = self.orders_mut(side);
+    /// The fill is never dropped by the caller: even late or unknown fills
+    /// change position. The return value says what it matched.
+    pub fn update_fill(&mut self, side: Side, order_id: &str, filled_size: Decimal) -> FillMatch {
+        // Fill on an order we're trying to cancel
+        if let Some(pending) = self.pending_cancels.get_mut(order_id) {
+            pending.order.remaining_size -= filled_size;
+            if pending.order.remaining_size <= Decimal::ZERO {
+                self.pending_cancels.remove(order_id);
+            }
+            return FillMatch::PendingCancel;
+        }
 
-        // Find the order by ID
-        let mut price_to_check: Option<u16> = None;
-        for (price, order_list) in orders.iter_mut() {
-            if let Some(order) = order_list.iter_mut().find(|o| o.order_id == order_id) {
+        let price = match self.index.get(order_id) {
+            Some(&(s, price)) if s == side => price,
+            _ if self.cancelled_set.contains(order_id) => return FillMatch::AfterCancel,
+            _ => return FillMatch::Unknown,
+        };
+
+        let fully_filled = match self
+            .orders_mut(side)
+            .get_mut(&price)
+            .and_then(|list| list.iter_mut().find(|o| o.order_id == order_id))
+        {
+            Some(order) => {
                 order.remaining_size -= filled_size;
-                if order.remaining_size <= Decimal::ZERO {
-                    price_to_check = Some(*price);
-                }
-                break;
+                order.remaining_size <= Decimal::ZERO
             }
-        }
+            None => false,
+        };
 
         // Remove fully filled order
-        if let Some(price) = price_to_check {
-            if let Some(order_list) = orders.get_mut(&price) {
-                order_list.retain(|o| o.order_id != order_id);
-                if order_list.is_empty() {
-                    orders.remove(&price);
-                }
+        if fully_filled {
+            self.remove_by_id(side, order_id);
+        }
+        FillMatch::Live
+    }
+
+    /// Cancel acknowledged for a live order: stop tracking it, but remember
+    /// it so a fill that raced the cancel is recognized.
+    pub fn confirm_cancel(&mut self, order_id: &str) -> Option<StandingOrder> {
+        let side = self.find_side_by_id(order_id)?;
+        let removed = self.remove_by_id(side, order_id);
+        self.remember_cancelled(order_id);
+        removed
+    }
+
+    /// Check if an order was recently cancelled.
+    pub fn was_cancelled(&self, order_id: &str) -> bool {
+        self.cancelled_set.contains(order_id)
+    }
+
+    fn remember_cancelled(&mut self, order_id: &str) {
+        if !self.cancelled_set.insert(order_id.to_string()) {
+            return;
+        }
+        self.cancelled.push_back(order_id.to_string());
+        if self.cancelled.len() > CANCELLED_MEMORY {
+            if let Some(oldest) = self.cancelled.pop_front() {
+                self.cancelled_set.remove(&oldest);
             }
         }
     }
 
-    /// Clear all orders for a side.
+    /// Clear all orders for a side (after a cancel-all).
     pub fn clear(&mut self, side: Side) {
+        let ids: Vec<String> = self.all_order_ids(side).into_iter().map(String::from).collect();
+        for id in &ids {
+            self.remember_cancelled(id);
+        }
         self.orders_mut(side).clear();
+        self.index.retain(|_, (s, _)| *s != side);
     }
 
-    /// Clear all orders for both sides.
+    /// Clear all orders for both sides (after a cancel-all). Placements
+    /// still in flight are forgotten too: one that did go live shows up
+    /// on the user channel as untracked.
     pub fn clear_all(&mut self) {
+        let ids: Vec<String> = self.index.keys().chain(self.pending_cancels.keys()).cloned().collect();
+        for id in &ids {
+            self.remember_cancelled(id);
+        }
         self.yes_orders.clear();
         self.no_orders.clear();
+        self.index.clear();
+        self.pending_cancels.clear();
+        self.pending_places.clear();
+    }
+
+    // =========================================================================
+    // PENDING PLACES
+    // =========================================================================
+
+    /// Record a placement about to be sent, under its client order ID.
+    pub fn add_pending(&mut self, side: Side, client_id: String, price: u16, size: Decimal, variant: Variant, now: f64) {
+        let order = StandingOrder {
+            order_id: client_id.clone(),
+            price,
+            remaining_size: size,
+            original_size: size,
+            variant,
+            client_id: Some(client_id.clone()),
+        };
+        self.pending_places.insert(client_id, PendingPlace { side, order, sent_at: now });
+    }
+
+    /// Placement acknowledged: the order goes live under the exchange's ID,
+    /// keeping its client order ID. Returns false if `client_id` wasn't
+    /// pending (expired or cleared).
+    pub fn confirm_place(&mut self, client_id: &str, order_id: String) -> bool {
+        let Some(pending) = self.pending_places.remove(client_id) else {
+            return false;
+        };
+        self.insert(pending.side, StandingOrder { order_id, ..pending.order });
+        true
+    }
+
+    /// Placement refused: stop tracking it.
+    pub fn fail_place(&mut self, client_id: &str) -> Option<PendingPlace> {
+        self.pending_places.remove(client_id)
+    }
+
+    /// An order we don't know appeared on the exchange (user channel or a
+    /// fill) at `side`/`price`, and `size` if known: if a placement like
+    /// that is pending, its response was lost, so adopt the oldest as
+    /// `order_id`.
+    pub fn adopt_pending(&mut self, side: Side, price: u16, size: Option<Decimal>, order_id: &str) -> bool {
+        if self.state(order_id).is_some() {
+            return false;
+        }
+        let client_id = self
+            .pending_places
+            .iter()
+            .filter(|(_, p)| p.side == side && p.order.price == price)
+            .filter(|(_, p)| size.is_none_or(|size| p.order.original_size == size))
+            .min_by(|(_, a), (_, b)| a.sent_at.total_cmp(&b.sent_at))
+            .map(|(id, _)| id.clone());
+        match client_id {
+            Some(client_id) => self.confirm_place(&client_id, order_id.to_string()),
+            None => false,
+        }
+    }
+
+    /// Drop placements sent more than `timeout_secs` ago that were never
+    /// confirmed: the exchange refused them, or they'd have shown up.
+    pub fn expire_pending_places(&mut self, now: f64, timeout_secs: f64) -> Vec<PendingPlace> {
+        let expired: Vec<String> = self
+            .pending_places
+            .iter()
+            .filter(|(_, p)| now - p.sent_at >= timeout_secs)
+            .map(|(id, _)| id.clone())
+            .collect();
+        expired.iter().filter_map(|id| self.pending_places.remove(id)).collect()
+    }
+
+    /// Size of placements in flight at a price.
+    pub fn pending_place_size(&self, side: Side, price: u16) -> Decimal {
+        self.pending_places
+            .values()
+            .filter(|p| p.side == side && p.order.price == price)
+            .map(|p| p.order.remaining_size)
+            .sum()
+    }
+
+    /// Count placements in flight.
+    pub fn pending_place_count(&self) -> usize {
+        self.pending_places.len()
+    }
+
+    /// Count placements in flight on a side.
+    pub fn pending_count(&self, side: Side) -> usize {
+        self.pending_places.values().filter(|p| p.side == side).count()
+    }
+
+    /// Total size of placements in flight on a side.
+    pub fn pending_exposure(&self, side: Side) -> Decimal {
+        self.pending_places
+            .values()
+            .filter(|p| p.side == side)
+            .map(|p| p.order.remaining_size)
+            .sum()
+    }
+
+    /// Client order ID of a live order (None if we didn't place it this run).
+    pub fn client_id_of(&self, order_id: &str) -> Option<&str> {
+        let &(side, price) = self.index.get(order_id)?;
+        self.orders_at_price(side, price)
+            .iter()
+            .find(|o| o.order_id == order_id)?
+            .client_id
+            .as_deref()
+    }
+
+    /// Where an order is, by exchange or client order ID.
+    pub fn state(&self, order_id: &str) -> Option<OrderState> {
+        if self.pending_places.contains_key(order_id) {
+            Some(OrderState::Pending)
+        } else if self.index.contains_key(order_id) {
+            Some(OrderState::Live)
+        } else if self.pending_cancels.contains_key(order_id) {
+            Some(OrderState::CancelPending)
+        } else if self.cancelled_set.contains(order_id) {
+            Some(OrderState::Cancelled)
+        } else {
+            None
+        }
+    }
+
+    // =========================================================================
+    // PENDING CANCELS
+    // =========================================================================
+
+    /// Move a live order to pending-cancel (its cancel wasn't acknowledged).
+    /// Returns false if the order isn't live.
+    pub fn mark_cancel_pending(&mut self, order_id: &str, now: f64) -> bool {
+        let Some(side) = self.find_side_by_id(order_id) else {
+            return false;
+        };
+        let Some(order) = self.remove_by_id(side, order_id) else {
+            return false;
+        };
+        self.pending_cancels.insert(
+            order_id.to_string(),
+            PendingCancel {
+                side,
+                order,
+                sent_at: now,
+                retries: 0,
+                queried: false,
+            },
+        );
+        true
+    }
+
+    /// Cancel confirmed (ack, removal, or order found dead). Stops tracking it.
+    pub fn ack_cancel(&mut self, order_id: &str) -> Option<PendingCancel> {
+        let removed = self.pending_cancels.remove(order_id);
+        if removed.is_some() {
+            self.remember_cancelled(order_id);
+        }
+        removed
+    }
+
+    /// Get a pending cancel by order ID.
+    pub fn pending_cancel(&self, order_id: &str) -> Option<&PendingCancel> {
+        self.pending_cancels.get(order_id)
+    }
+
+    /// Get a pending cancel by order ID for escalation bookkeeping.
+    pub fn pending_cancel_mut(&mut self, order_id: &str) -> Option<&mut PendingCancel> {
+        self.pending_cancels.get_mut(order_id)
+    }
+
+    /// Order IDs whose last cancel attempt is older than `timeout_secs`.
+    pub fn timed_out_cancels(&self, now: f64, timeout_secs: f64) -> Vec<String> {
+        self.pending_cancels
+            .iter()
+            .filter(|(_, p)| now - p.sent_at >= timeout_secs)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    /// Count cancels awaiting acknowledgement.
+    pub fn pending_cancel_count(&self) -> usize {
+        self.pending_cancels.len()
     }
 
     // =========================================================================
@@ -171,6 +489,12 @@ impl OrderTracker {
         self.orders(side).keys().max().copied()
     }
 
+    /// Highest price we bid on a side, counting placements in flight.
+    pub fn highest_bid(&self, side: Side) -> Option<u16> {
+        let pending = self.pending_places.values().filter(|p| p.side == side).map(|p| p.order.price);
+        self.top_price(side).into_iter().chain(pending).max()
+    }
+
     /// Get the lowest price with a standing order.
     pub fn bottom_price(&self, side: Side) -> Option<u16> {
         self.orders(side).keys().min().copied()
@@ -185,6 +509,95 @@ impl OrderTracker {
             .sum()
     }
 
+    /// Resting size on a side placed by one variant, including cancels
+    /// and placements not yet acknowledged.
+    pub fn variant_exposure(&self, variant: Variant, side: Side) -> Decimal {
+        let live: Decimal = self
+            .orders(side)
+            .values()
+            .flatten()
+            .filter(|o| o.variant == variant)
+            .map(|o| o.remaining_size)
+            .sum();
+        let pending: Decimal = self
+            .pending_cancels
+            .values()
+            .filter(|p| p.side == side && p.order.variant == variant)
+            .map(|p| p.order.remaining_size)
+            .sum();
+        let placing: Decimal = self
+            .pending_places
+            .values()
+            .filter(|p| p.side == side && p.order.variant == variant)
+            .map(|p| p.order.remaining_size)
+            .sum();
+        live + pending + placing
+    }
+
+    /// Resting notional (ticks) on both sides placed by one variant,
+    /// including cancels and placements not yet acknowledged.
+    pub fn variant_notional(&self, variant: Variant) -> Decimal {
+        let live: Decimal = [Side::Yes, Side::No]
+            .iter()
+            .flat_map(|&side| self.orders(side).values().flatten())
+            .filter(|o| o.variant == variant)
+            .map(|o| Decimal::from(o.price) * o.remaining_size)
+            .sum();
+        let pending: Decimal = self
+            .pending_cancels
+            .values()
+            .filter(|p| p.order.variant == variant)
+            .map(|p| Decimal::from(p.order.price) * p.order.remaining_size)
+            .sum();
+        let placing: Decimal = self
+            .pending_places
+            .values()
+            .filter(|p| p.order.variant == variant)
+            .map(|p| Decimal::from(p.order.price) * p.order.remaining_size)
+            .sum();
+        live + pending + placing
+    }
+
+    /// One variant's live orders and placements in flight, borrowed: what
+    /// the per-tick reconcile reads, without copying the tracker.
+    pub fn of(&self, variant: Variant) -> OrderView<'_> {
+        OrderView { tracker: self, variant }
+    }
+
+    /// Variant that placed an order, if it's still tracked (live or
+    /// pending cancel).
+    pub fn variant_of(&self, order_id: &str) -> Option<Variant> {
+        if let Some(pending) = self.pending_cancels.get(order_id) {
+            return Some(pending.order.variant);
+        }
+        let &(side, price) = self.index.get(order_id)?;
+        self.orders_at_price(side, price)
+            .iter()
+            .find(|o| o.order_id == order_id)
+            .map(|o| o.variant)
+    }
+
+    /// Notional (price × remaining, in ticks) tied up in resting orders on
+    /// both sides, including cancels and placements not yet acknowledged.
+    pub fn locked_notional(&self) -> Decimal {
+        let live: Decimal = [Side::Yes, Side::No]
+            .iter()
+            .flat_map(|&side| self.orders(side).values().flatten())
+            .map(|o| Decimal::from(o.price) * o.remaining_size)
+            .sum();
+        let cancelling: Decimal = self
+            .pending_cancels
+            .values()
+            .map(|p| Decimal::from(p.order.price) * p.order.remaining_size)
+            .sum();
+        let placing: Decimal = self
+            .pending_places
+            .values()
+            .map(|p| Decimal::from(p.order.price) * p.order.remaining_size)
+            .sum();
+        live + cancelling + placing
+    }
+
     /// Check if there are any orders for a side.
     pub fn has_orders(&self, side: Side) -> bool {
         !self.orders(side).is_empty()
@@ -192,12 +605,157 @@ impl OrderTracker {
 
     /// Find price for an order_id. Returns None if not found.
     pub fn find_price_by_id(&self, side: Side, order_id: &str) -> Option<u16> {
-        for (price, order_list) in self.orders(side) {
-            if order_list.iter().any(|o| o.order_id == order_id) {
-                return Some(*price);
+        match self.index.get(order_id) {
+            Some(&(s, price)) if s == side => Some(price),
+            _ => None,
+        }
+    }
+
+    /// Find which side an order_id rests on. Returns None if not found.
+    pub fn find_side_by_id(&self, order_id: &str) -> Option<Side> {
+        self.index.get(order_id).map(|&(side, _)| side)
+    }
+
+    /// Verify internal bookkeeping: every order is indexed at its side and
+    /// price, the index has no strays, and remaining sizes are in range.
+    /// Exposure is derived from these orders, so this also checks it.
+    pub fn check_consistency(&self) -> Result<(), String> {
+        let mut seen = 0;
+        for side in [Side::Yes, Side::No] {
+            for (&price, list) in self.orders(side) {
+                if list.is_empty() {
+                    return Err(format!("{:?} empty level at {}", side, price));
+                }
+                for order in list {
+                    seen += 1;
+                    match self.index.get(&order.order_id) {
+                        Some(&(s, p)) if s == side && p == price => {}
+                        other => {
+                            return Err(format!(
+                                "{} at {:?} {} indexed as {:?}",
+                                order.order_id, side, price, other
+                            ))
+                        }
+                    }
+                    if order.remaining_size <= Decimal::ZERO
+                        || order.remaining_size > order.original_size
+                    {
+                        return Err(format!(
+                            "{} remaining {} of {}",
+                            order.order_id, order.remaining_size, order.original_size
+                        ));
+                    }
+                }
             }
         }
-        None
+        if seen != self.index.len() {
+            return Err(format!("{} orders but {} index entries", seen, self.index.len()));
+        }
+        Ok(())
+    }
+}
+
+/// Read-only view of the orders one variant placed (see `OrderTracker::of`).
+#[derive(Debug, Clone, Copy)]
+pub struct OrderView<'a> {
+    tracker: &'a OrderTracker,
+    variant: Variant,
+}
+
+impl<'a> OrderView<'a> {
+    /// This variant's orders at a price, oldest first.
+    pub fn orders_at_price(&self, side: Side, price: u16) -> Vec<&'a StandingOrder> {
+        self.tracker
+            .orders_at_price(side, price)
+            .iter()
+            .filter(|o| o.variant == self.variant)
+            .collect()
+    }
+
+    /// Total remaining size of this variant's orders at a price.
+    pub fn total_size_at_price(&self, side: Side, price: u16) -> Decimal {
+        self.orders_at_price(side, price).iter().map(|o| o.remaining_size).sum()
+    }
+
+    /// Prices where this variant has a standing order.
+    pub fn prices(&self, side: Side) -> Vec<u16> {
+        self.tracker
+            .orders(side)
+            .iter()
+            .filter(|(_, list)| list.iter().any(|o| o.variant == self.variant))
+            .map(|(&price, _)| price)
+            .collect()
+    }
+
+    /// This variant's standing orders on a side (flattened).
+    pub fn all_orders(&self, side: Side) -> Vec<&'a StandingOrder> {
+        self.tracker
+            .all_orders(side)
+            .into_iter()
+            .filter(|o| o.variant == self.variant)
+            .collect()
+    }
+
+    /// Order IDs of this variant's standing orders on a side.
+    pub fn all_order_ids(&self, side: Side) -> Vec<&'a str> {
+        self.all_orders(side).into_iter().map(|o| o.order_id.as_str()).collect()
+    }
+
+    /// Count this variant's standing orders on a side.
+    pub fn count(&self, side: Side) -> usize {
+        self.all_orders(side).len()
+    }
+
+    /// Highest price where this variant has a standing order.
+    pub fn top_price(&self, side: Side) -> Option<u16> {
+        self.prices(side).into_iter().max()
+    }
+
+    /// Price of one of this variant's orders on a side. None if it isn't
+    /// resting there or another variant placed it.
+    pub fn find_price_by_id(&self, side: Side, order_id: &str) -> Option<u16> {
+        let price = self.tracker.find_price_by_id(side, order_id)?;
+        self.orders_at_price(side, price)
+            .iter()
+            .any(|o| o.order_id == order_id)
+            .then_some(price)
+    }
+
+    /// Size of this variant's placements in flight at a price.
+    pub fn pending_place_size(&self, side: Side, price: u16) -> Decimal {
+        self.tracker
+            .pending_places
+            .values()
+            .filter(|p| p.side == side && p.order.price == price && p.order.variant == self.variant)
+            .map(|p| p.order.remaining_size)
+            .sum()
+    }
+}
+
+/// Resting orders by price, as a journal snapshot reads them: the whole
+/// tracker, or one variant's view of it.
+pub trait RestingLadder {
+    fn prices(&self, side: Side) -> Vec<u16>;
+    fn total_size_at_price(&self, side: Side, price: u16) -> Decimal;
+}
+
+impl RestingLadder for OrderTracker {
+    fn prices(&self, side: Side) -> Vec<u16> {
+        OrderTracker::prices(self, side)
+    }
+
+    fn total_size_at_price(&self, side: Side, price: u16) -> Decimal {
+        OrderTracker::total_size_at_price(self, side, price)
+    }
+}
+
+impl RestingLadder for OrderView<'_> {
+    fn prices(&self, side: Side) -> Vec<u16> {
+        OrderView::prices(self, side)
+    }
+
+    fn total_size_at_price(&self, side: Side, price: u16) -> Decimal {
+        OrderView::total_size_at_price(self, side, price)
     }
 }
 
@@ -206,6 +764,95 @@ mod tests {
     use super::*;
     use rust_decimal_macros::dec;
 
+    #[test]
+    fn test_view_by_variant() {
+        let mut tracker = OrderTracker::new();
+        tracker.add(Side::Yes, "ladder".to_string(), 480, dec!(5));
+        tracker.add_tagged(Side::Yes, "tight".to_string(), 490, dec!(5), Variant::Instance(0));
+        tracker.add_tagged(Side::No, "tight_no".to_string(), 500, dec!(5), Variant::Instance(0));
+        tracker.update_fill(Side::Yes, "tight", dec!(2));
+
+        let view = tracker.of(Variant::Instance(0));
+        assert_eq!(view.all_order_ids(Side::Yes), vec!["tight"]);
+        assert_eq!(view.total_size_at_price(Side::Yes, 490), dec!(3));
+        assert_eq!(view.count(Side::No), 1);
+        assert_eq!(view.find_price_by_id(Side::No, "tight_no"), Some(500));
+        assert_eq!(view.find_price_by_id(Side::Yes, "ladder"), None);
+
+        let ladder = tracker.of(Variant::Ladder);
+        assert_eq!(ladder.top_price(Side::Yes), Some(480));
+        assert_eq!(ladder.count(Side::No), 0);
+    }
+
+    #[test]
+    fn test_borrowed_view_by_variant() {
+        let mut tracker = OrderTracker::new();
+        tracker.add(Side::Yes, "ladder".to_string(), 490, dec!(5));
+        tracker.add_tagged(Side::Yes, "tight".to_string(), 490, dec!(4), Variant::Instance(0));
+        tracker.add_tagged(Side::Yes, "deep".to_string(), 470, dec!(4), Variant::Instance(0));
+        tracker.add_pending(Side::Yes, "c1".to_string(), 480, dec!(5), Variant::Ladder, 10.0);
+
+        let view = tracker.of(Variant::Instance(0));
+        let mut prices = view.prices(Side::Yes);
+        prices.sort();
+        assert_eq!(prices, vec![470, 490]);
+        assert_eq!(view.total_size_at_price(Side::Yes, 490), dec!(4));
+        assert_eq!(view.pending_place_size(Side::Yes, 480), Decimal::ZERO);
+
+        let ladder = tracker.of(Variant::Ladder);
+        assert_eq!(ladder.all_order_ids(Side::Yes), vec!["ladder"]);
+        assert_eq!(ladder.pending_place_size(Side::Yes, 480), dec!(5));
+    }
+
+    #[test]
+    fn test_pending_place_lifecycle() {
+        let mut tracker = OrderTracker::new();
+        tracker.add_pending(Side::Yes, "c1".to_string(), 480, dec!(5), Variant::Ladder, 10.0);
+        assert_eq!(tracker.state("c1"), Some(OrderState::Pending));
+        assert_eq!(tracker.pending_place_size(Side::Yes, 480), dec!(5));
+        assert_eq!(tracker.count(Side::Yes), 0);
+        assert_eq!(tracker.variant_exposure(Variant::Ladder, Side::Yes), dec!(5));
+
+        // Ack: live under the exchange ID
+        assert!(tracker.confirm_place("c1", "0xabc".to_string()));
+        assert_eq!(tracker.state("c1"), None);
+        assert_eq!(tracker.state("0xabc"), Some(OrderState::Live));
+        assert_eq!(tracker.client_id_of("0xabc"), Some("c1"));
+        assert_eq!(tracker.pending_place_size(Side::Yes, 480), dec!(0));
+        assert_eq!(tracker.total_size_at_price(Side::Yes, 480), dec!(5));
+
+        // Cancel unacknowledged, then acknowledged
+        tracker.mark_cancel_pending("0xabc", 11.0);
+        assert_eq!(tracker.state("0xabc"), Some(OrderState::CancelPending));
+        tracker.ack_cancel("0xabc");
+        assert_eq!(tracker.state("0xabc"), Some(OrderState::Cancelled));
+        assert!(tracker.check_consistency().is_ok());
+    }
+
+    #[test]
+    fn test_pending_place_adopted_or_expired() {
+        let mut tracker = OrderTracker::new();
+        tracker.add_pending(Side::No, "c1".to_string(), 500, dec!(5), Variant::Ladder, 10.0);
+        tracker.add_pending(Side::No, "c2".to_string(), 500, dec!(5), Variant::Ladder, 11.0);
+        tracker.add_pending(Side::No, "c3".to_string(), 490, dec!(5), Variant::Ladder, 11.0);
+
+        // Response lost: the exchange's ID arrives first, oldest at the price is adopted
+        assert!(!tracker.adopt_pending(Side::No, 510, None, "0x1"));
+        assert!(!tracker.adopt_pending(Side::No, 500, Some(dec!(6)), "0x1"));
+        assert!(tracker.adopt_pending(Side::No, 500, Some(dec!(5)), "0x1"));
+        assert!(!tracker.adopt_pending(Side::No, 500, None, "0x1"));
+        assert_eq!(tracker.state("c1"), None);
+        assert_eq!(tracker.client_id_of("0x1"), Some("c1"));
+        assert_eq!(tracker.state("c2"), Some(OrderState::Pending));
+
+        assert!(tracker.fail_place("c3").is_some());
+        let expired = tracker.expire_pending_places(14.0, 3.0);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].order.order_id, "c2");
+        assert_eq!(tracker.pending_place_count(), 0);
+        assert!(!tracker.confirm_place("c2", "0x2".to_string()));
+    }
+
     #[test]
     fn test_add_and_query() {
         let mut tracker = OrderTracker::new();
@@ -304,6 +951,151 @@ mod tests {
         assert_eq!(tracker.total_exposure(Side::Yes), dec!(30));
     }
 
+    #[test]
+    fn test_locked_notional() {
+        let mut tracker = OrderTracker::new();
+        tracker.add(Side::Yes, "y".into(), 450, dec!(10));
+        tracker.add(Side::No, "n".into(), 500, dec!(5));
+        tracker.update_fill(Side::Yes, "y", dec!(4));
+        assert_eq!(tracker.locked_notional(), dec!(5200)); // 450×6 + 500×5
+
+        // Unacked cancel still locks its margin
+        tracker.mark_cancel_pending("n", 0.0);
+        assert_eq!(tracker.locked_notional(), dec!(5200));
+
+        // So does a placement not yet acked
+        tracker.add_pending(Side::No, "c1".into(), 400, dec!(2), Variant::Ladder, 0.0);
+        assert_eq!(tracker.locked_notional(), dec!(6000));
+    }
+
+    #[test]
+    fn test_check_consistency() {
+        let mut tracker = OrderTracker::new();
+        tracker.add(Side::Yes, "y1".into(), 450, dec!(5));
+        tracker.add(Side::No, "n1".into(), 500, dec!(5));
+        tracker.update_fill(Side::Yes, "y1", dec!(2));
+        assert!(tracker.check_consistency().is_ok());
+
+        // Stray index entry
+        tracker.index.insert("ghost".into(), (Side::Yes, 440));
+        assert!(tracker.check_consistency().is_err());
+    }
+
+    #[test]
+    fn test_index_tracks_lookups() {
+        let mut tracker = OrderTracker::new();
+
+        tracker.add(Side::Yes, "o1".to_string(), 450, dec!(10));
+        tracker.add(Side::No, "o2".to_string(), 540, dec!(10));
+
+        assert_eq!(tracker.find_price_by_id(Side::Yes, "o1"), Some(450));
+        assert_eq!(tracker.find_price_by_id(Side::No, "o1"), None);
+        assert_eq!(tracker.find_side_by_id("o2"), Some(Side::No));
+
+        // Wrong side is a no-op
+        assert!(tracker.remove_by_id(Side::No, "o1").is_none());
+        assert_eq!(tracker.count(Side::Yes), 1);
+
+        // Full fill drops the order from the index
+        tracker.update_fill(Side::Yes, "o1", dec!(10));
+        assert_eq!(tracker.find_side_by_id("o1"), None);
+
+        // Price-level removal drops the order from the index
+        tracker.remove_at_price(Side::No, 540);
+        assert_eq!(tracker.find_side_by_id("o2"), None);
+    }
+
+    #[test]
+    fn test_mark_cancel_pending() {
+        let mut tracker = OrderTracker::new();
+
+        tracker.add(Side::Yes, "o1".to_string(), 450, dec!(10));
+        assert!(tracker.mark_cancel_pending("o1", 100.0));
+
+        // Out of the ladder, but still tracked
+        assert_eq!(tracker.count(Side::Yes), 0);
+        assert_eq!(tracker.pending_cancel_count(), 1);
+        assert_eq!(tracker.pending_cancel("o1").unwrap().side, Side::Yes);
+
+        // Unknown orders can't be marked
+        assert!(!tracker.mark_cancel_pending("nope", 100.0));
+    }
+
+    #[test]
+    fn test_timed_out_cancels_and_ack() {
+        let mut tracker = OrderTracker::new();
+
+        tracker.add(Side::Yes, "o1".to_string(), 450, dec!(10));
+        tracker.add(Side::No, "o2".to_string(), 540, dec!(10));
+        tracker.mark_cancel_pending("o1", 100.0);
+        tracker.mark_cancel_pending("o2", 102.0);
+
+        assert_eq!(tracker.timed_out_cancels(102.5, 2.0), vec!["o1".to_string()]);
+
+        assert!(tracker.ack_cancel("o1").is_some());
+        assert!(tracker.timed_out_cancels(102.5, 2.0).is_empty());
+        assert_eq!(tracker.pending_cancel_count(), 1);
+    }
+
+    #[test]
+    fn test_fill_on_pending_cancel() {
+        let mut tracker = OrderTracker::new();
+
+        tracker.add(Side::Yes, "o1".to_string(), 450, dec!(10));
+        tracker.mark_cancel_pending("o1", 100.0);
+
+        tracker.update_fill(Side::Yes, "o1", dec!(4));
+        assert_eq!(tracker.pending_cancel("o1").unwrap().order.remaining_size, dec!(6));
+
+        // Fully filled → nothing left to cancel
+        tracker.update_fill(Side::Yes, "o1", dec!(6));
+        assert_eq!(tracker.pending_cancel_count(), 0);
+    }
+
+    #[test]
+    fn test_fill_match_classification() {
+        let mut tracker = OrderTracker::new();
+        tracker.add(Side::Yes, "live".into(), 450, dec!(10));
+        tracker.add(Side::Yes, "pending".into(), 440, dec!(10));
+        tracker.add(Side::No, "gone".into(), 540, dec!(10));
+        tracker.mark_cancel_pending("pending", 100.0);
+        assert!(tracker.confirm_cancel("gone").is_some());
+
+        assert_eq!(tracker.update_fill(Side::Yes, "live", dec!(5)), FillMatch::Live);
+        assert_eq!(tracker.update_fill(Side::Yes, "pending", dec!(5)), FillMatch::PendingCancel);
+        assert_eq!(tracker.update_fill(Side::No, "gone", dec!(5)), FillMatch::AfterCancel);
+        assert_eq!(tracker.update_fill(Side::No, "never", dec!(5)), FillMatch::Unknown);
+
+        // Late fill doesn't resurrect the cancelled order
+        assert_eq!(tracker.count(Side::No), 0);
+        assert!(tracker.check_consistency().is_ok());
+    }
+
+    #[test]
+    fn test_cancel_all_remembers_ids() {
+        let mut tracker = OrderTracker::new();
+        tracker.add(Side::Yes, "y".into(), 450, dec!(10));
+        tracker.add(Side::No, "n".into(), 540, dec!(10));
+
+        tracker.clear(Side::Yes);
+        assert!(tracker.was_cancelled("y"));
+        tracker.clear_all();
+        assert!(tracker.was_cancelled("n"));
+    }
+
+    #[test]
+    fn test_cancelled_memory_is_bounded() {
+        let mut tracker = OrderTracker::new();
+        for i in 0..CANCELLED_MEMORY + 10 {
+            let id = format!("o{}", i);
+            tracker.add(Side::Yes, id.clone(), 450, dec!(5));
+            tracker.confirm_cancel(&id);
+        }
+        assert!(!tracker.was_cancelled("o0"));
+        assert!(tracker.was_cancelled(&format!("o{}", CANCELLED_MEMORY + 9)));
+        assert_eq!(tracker.cancelled.len(), CANCELLED_MEMORY);
+    }
+
     #[test]
     fn test_clear() {
         let mut tracker = OrderTracker::new();
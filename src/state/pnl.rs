@@ -0,0 +1,245 @@
+//! Realized and unrealized P&L across market windows.
+//!
+//! `Position::min_pnl_ticks` only counts what the pairs lock in. This
+//! follows the money: merges, sells and redemptions realize P&L against
+//! the average cost of the shares they remove, and whatever is still held
+//! is marked to the YES mid (NO at the complement).
+//!
+//! At window end the paired shares are realized as a merge (they pay $1
+//! either way, merged on chain or redeemed). Unpaired shares realize as a
+//! redemption once the final mark says the market is decided; until then
+//! they stay open, marked at the last mid, and count as session unrealized.
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use super::Position;
+use crate::events::Side;
+
+/// A final YES mid this close to 0 or $1 is taken as the outcome (ticks)
+const DECIDED_TICKS: u16 = 10;
+
+/// P&L totals in dollars, for the log, metrics and the event stream.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PnlReport {
+    pub market: String,
+    pub realized_usd: f64,
+    pub unrealized_usd: f64,
+    /// Every window so far, including this one
+    pub session_realized_usd: f64,
+    pub session_unrealized_usd: f64,
+}
+
+/// A closed window's unpaired shares, waiting on the outcome.
+#[derive(Debug, Clone)]
+struct Unsettled {
+    market: String,
+    position: Position,
+    /// Last YES mid (ticks)
+    mark: u16,
+}
+
+/// Tracks P&L for the current market and the session (all in ticks × shares).
+#[derive(Debug, Default)]
+pub struct Pnl {
+    market: String,
+    /// Realized in the current market
+    realized: Decimal,
+    /// Realized in closed markets
+    closed_realized: Decimal,
+    unsettled: Vec<Unsettled>,
+}
+
+impl Pnl {
+    pub fn new(market: &str) -> Self {
+        Self { market: market.to_string(), ..Default::default() }
+    }
+
+    /// Merge `size` pairs back into USDC: $1 each against their cost.
+    ///
+    /// # Returns
+    /// P&L realized (ticks)
+    pub fn merge(&mut self, position: &mut Position, size: Decimal) -> Decimal {
+        let pairs = size.min(position.qty_yes).min(position.qty_no).max(Decimal::ZERO);
+        let (_, yes_cost) = position.remove(Side::Yes, pairs);
+        let (_, no_cost) = position.remove(Side::No, pairs);
+        let pnl = pairs * Decimal::from(1000) - yes_cost - no_cost;
+        self.realized += pnl;
+        pnl
+    }
+
+    /// Sell `size` shares of `side` at `price` ticks.
+    ///
+    /// # Returns
+    /// P&L realized (ticks)
+    pub fn sell(&mut self, position: &mut Position, side: Side, price: u16, size: Decimal) -> Decimal {
+        let (sold, cost) = position.remove(side, size);
+        let pnl = sold * Decimal::from(price) - cost;
+        self.realized += pnl;
+        pnl
+    }
+
+    /// Mark-to-market P&L of `position` at YES mid `yes_mid` ticks.
+    pub fn unrealized(position: &Position, yes_mid: u16) -> Decimal {
+        position.nav_usd(yes_mid) * Decimal::from(1000) - position.cost_yes - position.cost_no
+    }
+
+    /// Close the current window: merge the pairs, redeem the rest if the
+    /// final mark decides it, and carry anything left as unsettled.
+    ///
+    /// # Returns
+    /// The closed window's report.
+    pub fn close_market(&mut self, position: &Position, yes_mid: u16) -> PnlReport {
+        let mut position = position.clone();
+        let pairs = position.qty_yes.min(position.qty_no);
+        self.merge(&mut position, pairs);
+
+        let unrealized = match decided(yes_mid) {
+            Some(winner) => {
+                self.realized += redemption(&position, winner);
+                Decimal::ZERO
+            }
+            None => {
+                let unrealized = Self::unrealized(&position, yes_mid);
+                if !position.is_empty() {
+                    self.unsettled.push(Unsettled { market: self.market.clone(), position, mark: yes_mid });
+                }
+                unrealized
+            }
+        };
+
+        self.closed_realized += self.realized;
+        let report = PnlReport {
+            market: self.market.clone(),
+            realized_usd: usd(self.realized),
+            unrealized_usd: usd(unrealized),
+            session_realized_usd: usd(self.closed_realized),
+            session_unrealized_usd: usd(self.unsettled_ticks()),
+        };
+        self.realized = Decimal::ZERO;
+        report
+    }
+
+    /// Start tracking a new window.
+    pub fn open_market(&mut self, market: &str) {
+        self.market = market.to_string();
+        self.realized = Decimal::ZERO;
+    }
+
+    /// Redeem a closed window's unsettled shares once its outcome is known.
+    ///
+    /// # Returns
+    /// P&L realized (ticks), None if nothing was waiting on that market.
+    pub fn redeem(&mut self, market: &str, winner: Side) -> Option<Decimal> {
+        let i = self.unsettled.iter().position(|u| u.market == market)?;
+        let pnl = redemption(&self.unsettled.remove(i).position, winner);
+        self.closed_realized += pnl;
+        Some(pnl)
+    }
+
+    /// Current market and session totals, marking `position` at `yes_mid`.
+    pub fn report(&self, position: &Position, yes_mid: u16) -> PnlReport {
+        let unrealized = Self::unrealized(position, yes_mid);
+        PnlReport {
+            market: self.market.clone(),
+            realized_usd: usd(self.realized),
+            unrealized_usd: usd(unrealized),
+            session_realized_usd: usd(self.closed_realized + self.realized),
+            session_unrealized_usd: usd(self.unsettled_ticks() + unrealized),
+        }
+    }
+
+    /// Carry session realized P&L over from before a restart.
+    pub fn resume(&mut self, session_realized_usd: f64) {
+        self.closed_realized = Decimal::try_from(session_realized_usd * 1000.0).unwrap_or_default();
+    }
+
+    /// Closed windows still waiting on an outcome.
+    pub fn unsettled_count(&self) -> usize {
+        self.unsettled.len()
+    }
+
+    fn unsettled_ticks(&self) -> Decimal {
+        self.unsettled.iter().map(|u| Self::unrealized(&u.position, u.mark)).sum()
+    }
+}
+
+/// Winner implied by a final YES mid, if it's that close to 0 or $1.
+fn decided(yes_mid: u16) -> Option<Side> {
+    if yes_mid >= 1000 - DECIDED_TICKS {
+        Some(Side::Yes)
+    } else if yes_mid <= DECIDED_TICKS {
+        Some(Side::No)
+    } else {
+        None
+    }
+}
+
+/// Winning shares pay $1, losing ones nothing, against all the cost.
+fn redemption(position: &Position, winner: Side) -> Decimal {
+    position.qty(winner) * Decimal::from(1000) - position.cost_yes - position.cost_no
+}
+
+fn usd(ticks: Decimal) -> f64 {
+    (ticks / Decimal::from(1000)).to_f64().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_merge_sell_and_mark() {
+        let mut pnl = Pnl::new("btc-updown-5m-0");
+        let mut position = Position::default();
+        position.apply_fill(Side::Yes, 450, dec!(20));
+        position.apply_fill(Side::No, 500, dec!(10));
+
+        // 10 pairs at 95c: +50c
+        assert_eq!(pnl.merge(&mut position, dec!(100)), dec!(500));
+        assert_eq!((position.qty_yes, position.qty_no), (dec!(10), dec!(0)));
+        assert_eq!(position.cost_yes, dec!(4500));
+
+        // Sell 4 YES at 50c against a 45c cost: +20c
+        assert_eq!(pnl.sell(&mut position, Side::Yes, 500, dec!(4)), dec!(200));
+
+        // 6 YES left, marked at 40c: -30c
+        let report = pnl.report(&position, 400);
+        assert_eq!(report.realized_usd, 0.7);
+        assert_eq!(report.unrealized_usd, -0.3);
+        assert_eq!(report.session_unrealized_usd, -0.3);
+    }
+
+    #[test]
+    fn test_close_market_settles_or_carries() {
+        let mut pnl = Pnl::new("m1");
+        let mut position = Position::default();
+        position.apply_fill(Side::Yes, 450, dec!(15));
+        position.apply_fill(Side::No, 500, dec!(10));
+
+        // Undecided at close: pairs realized, 5 YES carried at the mark
+        let closed = pnl.close_market(&position, 600);
+        assert_eq!(closed.market, "m1");
+        assert_eq!(closed.realized_usd, 0.5);
+        assert_eq!(closed.unrealized_usd, 0.75);
+        assert_eq!((closed.session_realized_usd, closed.session_unrealized_usd), (0.5, 0.75));
+        assert_eq!(pnl.unsettled_count(), 1);
+
+        // Next window: NO fills decided at $0 YES
+        pnl.open_market("m2");
+        let mut position = Position::default();
+        position.apply_fill(Side::No, 700, dec!(5));
+        let closed = pnl.close_market(&position, 0);
+        assert_eq!(closed.realized_usd, 1.5);
+        assert_eq!(closed.unrealized_usd, 0.0);
+        assert_eq!(closed.session_realized_usd, 2.0);
+        assert_eq!(pnl.unsettled_count(), 1);
+
+        // m1 resolves NO: its 5 YES are worth nothing
+        assert_eq!(pnl.redeem("m1", Side::No), Some(dec!(-2250)));
+        assert_eq!(pnl.redeem("m1", Side::No), None);
+        let session = pnl.report(&Position::default(), 500);
+        assert_eq!((session.session_realized_usd, session.session_unrealized_usd), (-0.25, 0.0));
+    }
+}
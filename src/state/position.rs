@@ -69,6 +69,22 @@ impl Position {
         self.min_pnl_ticks() / Decimal::from(1000)
     }
 
+    /// Capital in inventory plus resting orders, in dollars.
+    ///
+    /// # Arguments
+    /// * `locked_ticks` - Notional of resting orders (OrderTracker::locked_notional)
+    pub fn capital_employed_usd(&self, locked_ticks: Decimal) -> Decimal {
+        (self.cost_yes + self.cost_no + locked_ticks) / Decimal::from(1000)
+    }
+
+    /// Mark-to-market value of inventory in dollars, at YES mid `yes_mid`
+    /// ticks (NO marked at the complement).
+    pub fn nav_usd(&self, yes_mid: u16) -> Decimal {
+        let yes = Decimal::from(yes_mid);
+        let no = Decimal::from(1000u16.saturating_sub(yes_mid));
+        (self.qty_yes * yes + self.qty_no * no) / Decimal::from(1000)
+    }
+
     /// Apply a fill to the position.
     pub fn apply_fill(&mut self, side: Side, price_ticks: u16, size: Decimal) {
         let cost = Decimal::from(price_ticks) * size;
@@ -84,6 +100,25 @@ impl Position {
         }
     }
 
+    /// Remove up to `size` shares from a side at average cost.
+    ///
+    /// # Returns
+    /// (shares removed, cost basis removed in ticks)
+    pub fn remove(&mut self, side: Side, size: Decimal) -> (Decimal, Decimal) {
+        let (qty, cost) = match side {
+            Side::Yes => (&mut self.qty_yes, &mut self.cost_yes),
+            Side::No => (&mut self.qty_no, &mut self.cost_no),
+        };
+        let removed = size.min(*qty).max(Decimal::ZERO);
+        if removed == Decimal::ZERO {
+            return (Decimal::ZERO, Decimal::ZERO);
+        }
+        let basis = *cost * removed / *qty;
+        *qty -= removed;
+        *cost -= basis;
+        (removed, basis)
+    }
+
     /// Get quantity for a side.
     pub fn qty(&self, side: Side) -> Decimal {
         match side {
@@ -186,4 +221,17 @@ mod tests {
         assert_eq!(pos.min_pnl_ticks(), dec!(-4200));
         assert_eq!(pos.min_pnl_usd(), dec!(-4.2));
     }
+
+    #[test]
+    fn test_capital_and_nav() {
+        let mut pos = Position::default();
+        pos.apply_fill(Side::Yes, 450, dec!(10));
+        pos.apply_fill(Side::No, 500, dec!(4));
+
+        // $4.50 + $2.00 inventory, $1.00 resting
+        assert_eq!(pos.capital_employed_usd(dec!(1000)), dec!(7.5));
+
+        // YES marked at 60c, NO at 40c
+        assert_eq!(pos.nav_usd(600), dec!(7.6));
+    }
 }
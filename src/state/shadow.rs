@@ -0,0 +1,93 @@
+//! Shadow accounting: our fill-built Position against what the exchange
+//! says we hold.
+//!
+//! The Data API lags fills by a few seconds, so one mismatched snapshot
+//! is expected around a fill. A gap that survives the next check too is
+//! an accounting bug (a dropped, doubled or misattributed fill).
+
+use rust_decimal::prelude::ToPrimitive;
+
+use super::Position;
+use crate::events::Side;
+
+/// Shares of slack per side before a gap counts (API rounding)
+const TOLERANCE: f64 = 0.01;
+
+/// One side's disagreement between local and reported holdings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Divergence {
+    pub side: Side,
+    pub local: f64,
+    pub reported: f64,
+}
+
+/// Compares each reported snapshot with the local position.
+#[derive(Debug, Default)]
+pub struct ShadowPosition {
+    /// Gaps seen at the previous check
+    pending: Vec<Divergence>,
+}
+
+impl ShadowPosition {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check a reported (YES, NO) snapshot. Returns the sides still off
+    /// after two checks in a row; empty means in sync (or not yet sure).
+    pub fn check(&mut self, local: &Position, reported: (f64, f64)) -> Vec<Divergence> {
+        let gaps: Vec<Divergence> = [
+            (Side::Yes, &local.qty_yes, reported.0),
+            (Side::No, &local.qty_no, reported.1),
+        ]
+        .into_iter()
+        .map(|(side, qty, reported)| Divergence { side, local: qty.to_f64().unwrap_or(0.0), reported })
+        .filter(|d| (d.local - d.reported).abs() > TOLERANCE)
+        .collect();
+
+        let persistent = gaps
+            .iter()
+            .filter(|d| self.pending.iter().any(|p| p.side == d.side))
+            .cloned()
+            .collect();
+        self.pending = gaps;
+        persistent
+    }
+
+    /// Forget pending gaps (on market switch).
+    pub fn reset(&mut self) {
+        self.pending.clear();
+    }
+
+    /// One line describing the divergences, for the alert.
+    pub fn describe(divergences: &[Divergence]) -> String {
+        divergences
+            .iter()
+            .map(|d| format!("{:?} local {:.2} vs exchange {:.2} ({:+.2})", d.side, d.local, d.reported, d.reported - d.local))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_alarms_only_on_persistent_gap() {
+        let mut shadow = ShadowPosition::new();
+        let local = Position { qty_yes: dec!(10), ..Default::default() };
+
+        assert!(shadow.check(&local, (10.0, 0.0)).is_empty());
+        // A fill the API hasn't caught up on yet
+        assert!(shadow.check(&local, (5.0, 0.0)).is_empty());
+        assert!(shadow.check(&local, (10.0, 0.0)).is_empty());
+
+        // Off twice in a row: alarm, with details
+        assert!(shadow.check(&local, (10.0, 5.0)).is_empty());
+        let gaps = shadow.check(&local, (10.0, 5.0));
+        assert_eq!(gaps, vec![Divergence { side: Side::No, local: 0.0, reported: 5.0 }]);
+        assert_eq!(ShadowPosition::describe(&gaps), "No local 0.00 vs exchange 5.00 (+5.00)");
+    }
+}
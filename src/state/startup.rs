@@ -0,0 +1,100 @@
+//! Startup reconciliation.
+//!
+//! A restart mid-market would otherwise begin with an empty tracker and a
+//! flat position while our orders still rest and our shares are still held.
+//! Before the first tick the exchange's open orders and the Data API's
+//! holdings are read once: the position is rebuilt from the holdings (cost
+//! basis from their average price), and the orders are either adopted into
+//! the tracker, where reconcile keeps or reprices them like its own, or
+//! cancelled.
+
+use rust_decimal::Decimal;
+
+use super::{OrderTracker, Position};
+use crate::api::data::HeldPosition;
+use crate::events::Side;
+use crate::strategy::TICKS_PER_DOLLAR;
+
+/// One of our orders resting on the exchange.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExchangeOrder {
+    pub order_id: String,
+    pub side: Side,
+    /// Price in ticks
+    pub price: u16,
+    /// Size not yet matched
+    pub remaining: Decimal,
+}
+
+/// Position rebuilt from the wallet's holdings in one market.
+pub fn position_from_holdings(holdings: &[HeldPosition]) -> Position {
+    let mut position = Position::default();
+    for held in holdings {
+        let (Ok(qty), Ok(avg)) = (Decimal::try_from(held.size), Decimal::try_from(held.avg_price)) else {
+            continue;
+        };
+        let cost = (qty * avg * Decimal::from(TICKS_PER_DOLLAR)).round_dp(4);
+        match held.outcome_index {
+            0 => {
+                position.qty_yes += qty;
+                position.cost_yes += cost;
+            }
+            1 => {
+                position.qty_no += qty;
+                position.cost_no += cost;
+            }
+            _ => {}
+        }
+    }
+    position
+}
+
+/// Track resting orders as the main ladder's. Orders already tracked or
+/// fully matched are skipped.
+///
+/// # Returns
+/// How many were adopted.
+pub fn adopt_orders(orders: &mut OrderTracker, open: &[ExchangeOrder]) -> usize {
+    let mut adopted = 0;
+    for order in open {
+        if order.remaining <= Decimal::ZERO || orders.find_side_by_id(&order.order_id).is_some() {
+            continue;
+        }
+        orders.add(order.side, order.order_id.clone(), order.price, order.remaining);
+        adopted += 1;
+    }
+    adopted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_position_from_holdings() {
+        let holdings = vec![
+            HeldPosition { size: 40.0, avg_price: 0.48, outcome_index: 0 },
+            HeldPosition { size: 25.0, avg_price: 0.5, outcome_index: 1 },
+        ];
+        let position = position_from_holdings(&holdings);
+        assert_eq!((position.qty_yes, position.qty_no), (dec!(40), dec!(25)));
+        assert_eq!(position.cost_yes, dec!(19200));
+        assert_eq!(position.avg_price_no(), Some(dec!(500)));
+    }
+
+    #[test]
+    fn test_adopt_orders() {
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "known".to_string(), 480, dec!(12));
+        let open = vec![
+            ExchangeOrder { order_id: "known".to_string(), side: Side::Yes, price: 480, remaining: dec!(12) },
+            ExchangeOrder { order_id: "rest".to_string(), side: Side::No, price: 500, remaining: dec!(7) },
+            ExchangeOrder { order_id: "done".to_string(), side: Side::No, price: 490, remaining: dec!(0) },
+        ];
+
+        assert_eq!(adopt_orders(&mut orders, &open), 1);
+        assert_eq!(orders.total_count(), 2);
+        assert_eq!(orders.total_size_at_price(Side::No, 500), dec!(7));
+    }
+}
@@ -1,6 +1,19 @@
 use crate::events::Side;
 use rust_decimal::Decimal;
 
+/// How long a placed order rests on the exchange.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeInForce {
+    /// Good-til-cancelled: rests until filled or explicitly cancelled.
+    #[default]
+    Gtc,
+    /// Good-til-date: the exchange expires it after this many seconds.
+    Gtd { expires_in_secs: u64 },
+    /// Good-til-date: the exchange expires it this many seconds before the
+    /// market's end, so an order we lose track of can't outlive its market.
+    GtdMarketEnd { buffer_secs: u64 },
+}
+
 /// Actions that the strategy can request.
 /// The executor turns these into API calls.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -13,6 +26,8 @@ pub enum Action {
         price: u16,
         /// Size in shares
         size: Decimal,
+        /// Time in force (GTC unless the rung is configured otherwise)
+        tif: TimeInForce,
     },
 
     /// Cancel an existing order by ID.
@@ -36,9 +51,24 @@ pub enum Action {
 }
 
 impl Action {
-    /// Create a Place action.
+    /// Create a Place action (GTC).
     pub fn place(side: Side, price: u16, size: Decimal) -> Self {
-        Self::Place { side, price, size }
+        Self::Place {
+            side,
+            price,
+            size,
+            tif: TimeInForce::Gtc,
+        }
+    }
+
+    /// Create a Place action with an explicit time in force.
+    pub fn place_with_tif(side: Side, price: u16, size: Decimal, tif: TimeInForce) -> Self {
+        Self::Place {
+            side,
+            price,
+            size,
+            tif,
+        }
     }
 
     /// Create a Cancel action.
@@ -90,15 +120,30 @@ mod tests {
         assert!(!action.is_cancel());
         assert!(!action.is_take());
 
-        if let Action::Place { side, price, size } = action {
+        if let Action::Place {
+            side,
+            price,
+            size,
+            tif,
+        } = action
+        {
             assert_eq!(side, Side::Yes);
             assert_eq!(price, 450);
             assert_eq!(size, dec!(12));
+            assert_eq!(tif, TimeInForce::Gtc);
         } else {
             panic!("Expected Place action");
         }
     }
 
+    #[test]
+    fn test_place_with_tif() {
+        let tif = TimeInForce::Gtd { expires_in_secs: 60 };
+        let action = Action::place_with_tif(Side::No, 530, dec!(5), tif);
+
+        assert!(matches!(action, Action::Place { tif: TimeInForce::Gtd { expires_in_secs: 60 }, .. }));
+    }
+
     #[test]
     fn test_cancel_action() {
         let action = Action::cancel("order123");
@@ -0,0 +1,240 @@
+//! In-session margin annealing.
+//!
+//! Widen the margin a step after a toxic fill, tighten it back a step after
+//! a stretch of clean trading. A fill is toxic if, some seconds later, the
+//! bid on the side we bought has dropped well below what we paid.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::events::Side;
+use crate::state::Book;
+
+/// Configuration for margin annealing.
+#[derive(Debug, Clone)]
+pub struct AnnealConfig {
+    /// Size of one adjustment (ticks)
+    pub step_ticks: u16,
+    /// Never tighten below this extra margin (ticks)
+    pub min_extra: u16,
+    /// Never widen beyond this extra margin (ticks)
+    pub max_extra: u16,
+    /// How long after a fill to check its markout (seconds)
+    pub markout_secs: f64,
+    /// Bid drop from fill price that makes a fill toxic (ticks)
+    pub toxic_ticks: u16,
+    /// Tighten a step after this long with no toxic fill (seconds)
+    pub clean_secs: f64,
+}
+
+impl Default for AnnealConfig {
+    fn default() -> Self {
+        Self {
+            step_ticks: 10,        // 1c per adjustment
+            min_extra: 0,          // Back to the base margin
+            max_extra: 30,         // At most 3c wider
+            markout_secs: 5.0,     // 5s markout
+            toxic_ticks: 20,       // Bid 2c below our fill
+            clean_secs: 120.0,     // 2 clean minutes
+        }
+    }
+}
+
+/// Why the margin moved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdjustReason {
+    /// A fill marked out badly
+    ToxicFill { side: Side, price: u16, bid_after: u16 },
+    /// No toxic fills for `clean_secs`
+    Clean,
+}
+
+/// One margin adjustment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adjustment {
+    pub at: f64,
+    pub from: u16,
+    pub to: u16,
+    pub reason: AdjustReason,
+}
+
+impl fmt::Display for Adjustment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "extra margin {}→{} ticks: ", self.from, self.to)?;
+        match self.reason {
+            AdjustReason::ToxicFill { side, price, bid_after } => {
+                write!(f, "toxic {:?} fill @{} (bid now {})", side, price, bid_after)
+            }
+            AdjustReason::Clean => write!(f, "clean"),
+        }
+    }
+}
+
+/// Tracks fill markouts and the resulting extra margin.
+#[derive(Debug)]
+pub struct MarginAnnealer {
+    config: AnnealConfig,
+    /// Fills waiting for their markout: (time, side, price)
+    pending: VecDeque<(f64, Side, u16)>,
+    /// Current extra margin (ticks)
+    extra: u16,
+    /// Start of the current clean stretch
+    clean_since: f64,
+}
+
+impl MarginAnnealer {
+    pub fn new(config: AnnealConfig, now: f64) -> Self {
+        let extra = config.min_extra;
+        Self {
+            config,
+            pending: VecDeque::new(),
+            extra,
+            clean_since: now,
+        }
+    }
+
+    /// Current extra margin (ticks), added on top of the base margin.
+    pub fn extra(&self) -> u16 {
+        self.extra
+    }
+
+    /// Queue one of our fills for a markout check.
+    pub fn record_fill(&mut self, now: f64, side: Side, price: u16) {
+        self.pending.push_back((now, side, price));
+    }
+
+    /// Check matured markouts and the clean timer.
+    ///
+    /// # Returns
+    /// The adjustment made, if any (at most one per call).
+    pub fn update(&mut self, now: f64, book: &Book) -> Option<Adjustment> {
+        let mut toxic = None;
+        while let Some(&(t, side, price)) = self.pending.front() {
+            if now - t < self.config.markout_secs {
+                break;
+            }
+            self.pending.pop_front();
+            let Some(bid) = book.best_bid(side) else { continue };
+            if bid + self.config.toxic_ticks <= price && toxic.is_none() {
+                toxic = Some(AdjustReason::ToxicFill { side, price, bid_after: bid });
+            }
+        }
+
+        let (to, reason) = if let Some(reason) = toxic {
+            self.clean_since = now;
+            ((self.extra + self.config.step_ticks).min(self.config.max_extra), reason)
+        } else if now - self.clean_since >= self.config.clean_secs {
+            self.clean_since = now;
+            (
+                self.extra.saturating_sub(self.config.step_ticks).max(self.config.min_extra),
+                AdjustReason::Clean,
+            )
+        } else {
+            return None;
+        };
+
+        if to == self.extra {
+            return None;
+        }
+        let adjustment = Adjustment { at: now, from: self.extra, to, reason };
+        self.extra = to;
+        Some(adjustment)
+    }
+
+    /// Reset for a new market: back to the minimum, forget pending fills.
+    pub fn reset(&mut self, now: f64) {
+        self.pending.clear();
+        self.extra = self.config.min_extra;
+        self.clean_since = now;
+    }
+
+    /// Start a new market keeping part of the margin earned by toxic fills:
+    /// `decay` (0-1) of the steps over the minimum, rounded down. Pending
+    /// fills are forgotten; their markouts are on the old market's book.
+    pub fn carry_over(&mut self, now: f64, decay: f64) {
+        let step = self.config.step_ticks.max(1);
+        let steps = (self.extra.saturating_sub(self.config.min_extra) / step) as f64 * decay.clamp(0.0, 1.0);
+        self.reset(now);
+        self.extra = self.config.min_extra + steps.floor() as u16 * step;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book_with_yes_bid(bid: u16) -> Book {
+        let mut book = Book::default();
+        book.update(Side::Yes, bid, bid + 10, 0);
+        book.update(Side::No, 1000 - bid - 20, 1000 - bid - 10, 0);
+        book
+    }
+
+    #[test]
+    fn test_toxic_fill_widens() {
+        let mut annealer = MarginAnnealer::new(AnnealConfig::default(), 0.0);
+        annealer.record_fill(10.0, Side::Yes, 500);
+
+        // Not matured yet
+        assert_eq!(annealer.update(12.0, &book_with_yes_bid(450)), None);
+
+        let adj = annealer.update(15.0, &book_with_yes_bid(470)).unwrap();
+        assert_eq!((adj.from, adj.to), (0, 10));
+        assert!(matches!(adj.reason, AdjustReason::ToxicFill { side: Side::Yes, price: 500, bid_after: 470 }));
+        assert_eq!(annealer.extra(), 10);
+    }
+
+    #[test]
+    fn test_clean_fill_no_change() {
+        let mut annealer = MarginAnnealer::new(AnnealConfig::default(), 0.0);
+        annealer.record_fill(10.0, Side::Yes, 500);
+        assert_eq!(annealer.update(15.0, &book_with_yes_bid(490)), None);
+        assert_eq!(annealer.extra(), 0);
+    }
+
+    #[test]
+    fn test_bounded_by_max() {
+        let mut annealer = MarginAnnealer::new(AnnealConfig::default(), 0.0);
+        let book = book_with_yes_bid(400);
+        for i in 0..5 {
+            annealer.record_fill(i as f64, Side::Yes, 500);
+            annealer.update(i as f64 + 5.0, &book);
+        }
+        assert_eq!(annealer.extra(), 30);
+    }
+
+    #[test]
+    fn test_tightens_after_clean_stretch() {
+        let mut annealer = MarginAnnealer::new(AnnealConfig::default(), 0.0);
+        annealer.record_fill(0.0, Side::Yes, 500);
+        annealer.update(5.0, &book_with_yes_bid(450));
+        assert_eq!(annealer.extra(), 10);
+
+        let book = book_with_yes_bid(500);
+        assert_eq!(annealer.update(100.0, &book), None);
+        let adj = annealer.update(125.0, &book).unwrap();
+        assert_eq!((adj.from, adj.to, adj.reason), (10, 0, AdjustReason::Clean));
+
+        // Already at the minimum: nothing more to tighten
+        assert_eq!(annealer.update(300.0, &book), None);
+    }
+
+    #[test]
+    fn test_carry_over_keeps_part_of_the_margin() {
+        let mut annealer = MarginAnnealer::new(AnnealConfig::default(), 0.0);
+        let book = book_with_yes_bid(400);
+        for i in 0..3 {
+            annealer.record_fill(i as f64, Side::Yes, 500);
+            annealer.update(i as f64 + 5.0, &book);
+        }
+        assert_eq!(annealer.extra(), 30);
+
+        // 3 steps, half kept, rounded down
+        annealer.record_fill(10.0, Side::Yes, 500);
+        annealer.carry_over(10.0, 0.5);
+        assert_eq!(annealer.extra(), 10);
+        assert_eq!(annealer.update(20.0, &book), None);
+        annealer.carry_over(20.0, 0.5);
+        assert_eq!(annealer.extra(), 0);
+    }
+}
@@ -0,0 +1,224 @@
+//! Perp-spot basis as a slow directional prior.
+//!
+//! When the BTCUSDT perp trades rich to spot, spot tends to drift up toward
+//! it (and down when it's cheap). Up/Down markets resolve on spot, so a
+//! positive basis nudges the pricing mid toward YES.
+//!
+//! The perp carries a standing premium from funding that says nothing about
+//! the next five minutes, so the signal is a fast EWMA of the basis minus a
+//! slow one: only basis that opened up recently counts.
+
+/// Basis prior settings.
+#[derive(Debug, Clone, Copy)]
+pub struct BasisConfig {
+    /// Weight of the prior (0 = log only, 1 = full shift)
+    pub weight: f64,
+    /// Smoothing of the basis (seconds)
+    pub fast_secs: f64,
+    /// Standing premium estimate (seconds)
+    pub slow_secs: f64,
+    /// Signal (bps) at which the shift saturates
+    pub full_bps: f64,
+    /// Largest mid shift at weight 1 (probability)
+    pub max_shift: f64,
+}
+
+impl Default for BasisConfig {
+    fn default() -> Self {
+        Self {
+            weight: 0.0,       // Log only until evaluated
+            fast_secs: 30.0,
+            slow_secs: 900.0,  // 15 minutes
+            full_bps: 5.0,
+            max_shift: 0.02,   // 2c
+        }
+    }
+}
+
+/// Snapshot of the basis for logging.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BasisReading {
+    pub spot: f64,
+    pub perp: f64,
+    /// Latest raw basis (bps of spot)
+    pub basis_bps: f64,
+    /// Fast minus slow EWMA (bps)
+    pub signal_bps: f64,
+    /// Mid shift applied (probability, + = toward YES)
+    pub shift: f64,
+}
+
+/// Time-decayed EWMA: samples arrive irregularly, so the weight of the new
+/// sample depends on the gap since the last one.
+#[derive(Debug, Clone, Copy)]
+struct Ewma {
+    tau: f64,
+    value: Option<f64>,
+    last_ts: f64,
+}
+
+impl Ewma {
+    fn new(secs: f64) -> Self {
+        Self { tau: secs, value: None, last_ts: 0.0 }
+    }
+
+    fn update(&mut self, now: f64, x: f64) {
+        self.value = Some(match self.value {
+            None => x,
+            Some(v) => {
+                let alpha = 1.0 - (-(now - self.last_ts).max(0.0) / self.tau).exp();
+                v + (x - v) * alpha
+            }
+        });
+        self.last_ts = now;
+    }
+}
+
+/// Tracks spot and perp prices and turns their basis into a mid shift.
+pub struct BasisTracker {
+    config: BasisConfig,
+    spot: Option<f64>,
+    perp: Option<f64>,
+    fast: Ewma,
+    slow: Ewma,
+}
+
+impl BasisTracker {
+    pub fn new(config: BasisConfig) -> Self {
+        Self {
+            config,
+            spot: None,
+            perp: None,
+            fast: Ewma::new(config.fast_secs),
+            slow: Ewma::new(config.slow_secs),
+        }
+    }
+
+    pub fn config(&self) -> &BasisConfig {
+        &self.config
+    }
+
+    /// Latest spot print (from the spot feed, after filtering).
+    pub fn update_spot(&mut self, price: f64) {
+        self.spot = Some(price);
+    }
+
+    /// Latest perp price. Samples the basis against the last spot print.
+    pub fn update_perp(&mut self, now: f64, price: f64) {
+        self.perp = Some(price);
+        if let Some(bps) = self.basis_bps() {
+            self.fast.update(now, bps);
+            self.slow.update(now, bps);
+        }
+    }
+
+    /// Raw basis (bps of spot), once both legs have printed.
+    pub fn basis_bps(&self) -> Option<f64> {
+        match (self.spot, self.perp) {
+            (Some(spot), Some(perp)) if spot > 0.0 => Some((perp - spot) / spot * 10_000.0),
+            _ => None,
+        }
+    }
+
+    /// Recent change in basis: fast minus slow EWMA (bps).
+    pub fn signal_bps(&self) -> f64 {
+        match (self.fast.value, self.slow.value) {
+            (Some(fast), Some(slow)) => fast - slow,
+            _ => 0.0,
+        }
+    }
+
+    /// Mid shift (probability, + = toward YES).
+    pub fn mid_shift(&self) -> f64 {
+        if self.config.full_bps <= 0.0 {
+            return 0.0;
+        }
+        let strength = (self.signal_bps() / self.config.full_bps).clamp(-1.0, 1.0);
+        self.config.weight * strength * self.config.max_shift
+    }
+
+    /// Pricing mid with the prior applied, kept inside (0, 1).
+    pub fn adjust_mid(&self, mid: f64) -> f64 {
+        (mid + self.mid_shift()).clamp(0.001, 0.999)
+    }
+
+    /// Current values, once both legs have printed.
+    pub fn reading(&self) -> Option<BasisReading> {
+        Some(BasisReading {
+            spot: self.spot?,
+            perp: self.perp?,
+            basis_bps: self.basis_bps()?,
+            signal_bps: self.signal_bps(),
+            shift: self.mid_shift(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(weight: f64) -> BasisTracker {
+        BasisTracker::new(BasisConfig { weight, ..Default::default() })
+    }
+
+    #[test]
+    fn test_basis_bps() {
+        let mut t = tracker(1.0);
+        assert_eq!(t.basis_bps(), None);
+        t.update_perp(0.0, 100_050.0);
+        assert_eq!(t.basis_bps(), None);
+        t.update_spot(100_000.0);
+        assert!((t.basis_bps().unwrap() - 5.0).abs() < 1e-9);
+        assert!(t.reading().is_some());
+    }
+
+    #[test]
+    fn test_standing_premium_is_no_signal() {
+        let mut t = tracker(1.0);
+        t.update_spot(100_000.0);
+        for i in 0..600 {
+            t.update_perp(i as f64, 100_050.0);
+        }
+        assert!(t.signal_bps().abs() < 1e-9);
+        assert_eq!(t.mid_shift(), 0.0);
+    }
+
+    #[test]
+    fn test_widening_basis_shifts_toward_yes() {
+        let mut t = tracker(1.0);
+        t.update_spot(100_000.0);
+        for i in 0..600 {
+            t.update_perp(i as f64, 100_000.0);
+        }
+        // Perp jumps 10 bps rich and stays there for a minute
+        for i in 600..660 {
+            t.update_perp(i as f64, 100_100.0);
+        }
+        assert!(t.signal_bps() > 5.0);
+        // Saturated at max_shift
+        assert!((t.mid_shift() - 0.02).abs() < 1e-9);
+        assert!((t.adjust_mid(0.5) - 0.52).abs() < 1e-9);
+
+        // Cheap perp pushes the other way, scaled by weight
+        let mut t = tracker(0.5);
+        t.update_spot(100_000.0);
+        for i in 0..600 {
+            t.update_perp(i as f64, 100_000.0);
+        }
+        for i in 600..660 {
+            t.update_perp(i as f64, 99_900.0);
+        }
+        assert!((t.mid_shift() + 0.01).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_zero_weight_logs_only() {
+        let mut t = tracker(0.0);
+        t.update_spot(100_000.0);
+        t.update_perp(0.0, 100_000.0);
+        t.update_perp(60.0, 100_200.0);
+        assert!(t.reading().unwrap().signal_bps > 0.0);
+        assert_eq!(t.adjust_mid(0.5), 0.5);
+    }
+}
@@ -0,0 +1,35 @@
+//! Everything the strategy sees on one cycle, bundled so new signals
+//! don't change every function signature.
+
+use super::TapeSignal;
+use crate::state::{Book, Market, OrderTracker, Position};
+
+/// Derived market signals for this cycle.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Signals {
+    /// Mid probability (0.0 to 1.0)
+    pub p_mid: f64,
+    /// Price variance estimate
+    pub var: f64,
+    /// Order flow intensity (A-S k)
+    pub k: f64,
+    /// Latest BTC price
+    pub btc_price: f64,
+    /// Trade tape rate and aggressor direction
+    pub tape: TapeSignal,
+}
+
+/// Read-only view of bot state passed to the strategy.
+#[derive(Debug, Clone, Copy)]
+pub struct BotState<'a> {
+    pub book: &'a Book,
+    pub position: &'a Position,
+    pub orders: &'a OrderTracker,
+    /// Market identity, if known
+    pub market: Option<&'a Market>,
+    pub signals: Signals,
+    /// Current time (unix seconds)
+    pub now: f64,
+    /// Seconds until market settlement
+    pub time_remaining: f64,
+}
@@ -0,0 +1,175 @@
+//! External model signals as an optional pricing input.
+//!
+//! An outside model can push a fair YES probability, a direction, or both,
+//! each with a TTL. While the latest signal is live, the fair value pulls
+//! the pricing mid toward it and the direction widens the side it's
+//! against. Once it expires the strategy prices exactly as without it.
+
+use crate::events::Side;
+
+/// How much an external signal may move the quotes.
+#[derive(Debug, Clone, Copy)]
+pub struct ExternalSignalConfig {
+    /// Weight of the external fair value in the pricing mid (0 = ignore, 1 = replace)
+    pub fair_weight: f64,
+    /// Extra margin on the side a full-strength direction is against (ticks)
+    pub against_ticks: u16,
+    /// Longest TTL accepted; longer ones are clamped (seconds)
+    pub max_ttl_secs: f64,
+}
+
+impl Default for ExternalSignalConfig {
+    fn default() -> Self {
+        Self {
+            fair_weight: 0.5,
+            against_ticks: 10, // 1c
+            max_ttl_secs: 60.0,
+        }
+    }
+}
+
+/// A signal as received.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExternalSignal {
+    /// Fair YES probability (0, 1)
+    pub fair: Option<f64>,
+    /// Side expected to gain
+    pub direction: Option<Side>,
+    /// Confidence in the direction (0..=1)
+    pub strength: f64,
+    /// How long it stays valid (seconds)
+    pub ttl_secs: f64,
+}
+
+impl ExternalSignal {
+    /// Check ranges before it gets anywhere near pricing.
+    ///
+    /// # Errors
+    /// Missing both fair and direction, fair outside (0, 1), strength
+    /// outside [0, 1], or a non-positive TTL.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.fair.is_none() && self.direction.is_none() {
+            return Err("signal needs fair or direction".to_string());
+        }
+        if let Some(fair) = self.fair {
+            if !(fair > 0.0 && fair < 1.0) {
+                return Err(format!("fair must be in (0, 1): {}", fair));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.strength) {
+            return Err(format!("strength must be in [0, 1]: {}", self.strength));
+        }
+        if !(self.ttl_secs > 0.0 && self.ttl_secs.is_finite()) {
+            return Err(format!("ttl must be positive: {}", self.ttl_secs));
+        }
+        Ok(())
+    }
+}
+
+/// Latest live external signal.
+pub struct ExternalInput {
+    config: ExternalSignalConfig,
+    latest: Option<ExternalSignal>,
+    expires_at: f64,
+}
+
+impl ExternalInput {
+    pub fn new(config: ExternalSignalConfig) -> Self {
+        Self {
+            config,
+            latest: None,
+            expires_at: 0.0,
+        }
+    }
+
+    /// Replace the current signal. Invalid signals are rejected and leave
+    /// the previous one in place.
+    pub fn update(&mut self, now: f64, signal: ExternalSignal) -> Result<(), String> {
+        signal.validate()?;
+        self.expires_at = now + signal.ttl_secs.min(self.config.max_ttl_secs);
+        self.latest = Some(signal);
+        Ok(())
+    }
+
+    /// The signal, if it hasn't expired.
+    pub fn current(&self, now: f64) -> Option<&ExternalSignal> {
+        self.latest.as_ref().filter(|_| now < self.expires_at)
+    }
+
+    /// Pricing mid: the book mid blended toward a live fair value.
+    pub fn adjust_mid(&self, now: f64, mid: f64) -> f64 {
+        match self.current(now).and_then(|s| s.fair) {
+            Some(fair) => mid + (fair - mid) * self.config.fair_weight,
+            None => mid,
+        }
+    }
+
+    /// Extra margin for `side`: non-zero only when a live direction is
+    /// against it, scaled by strength.
+    pub fn extra_margin(&self, now: f64, side: Side) -> u16 {
+        match self.current(now) {
+            Some(s) if s.direction == Some(side.opposite()) => {
+                (self.config.against_ticks as f64 * s.strength).round() as u16
+            }
+            _ => 0,
+        }
+    }
+
+    /// Drop the current signal (market switch: it was about the old one).
+    pub fn reset(&mut self) {
+        self.latest = None;
+        self.expires_at = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(fair: Option<f64>, direction: Option<Side>, strength: f64, ttl_secs: f64) -> ExternalSignal {
+        ExternalSignal { fair, direction, strength, ttl_secs }
+    }
+
+    #[test]
+    fn test_expiry() {
+        let mut input = ExternalInput::new(ExternalSignalConfig::default());
+        input.update(100.0, signal(Some(0.6), None, 1.0, 5.0)).unwrap();
+        assert!(input.current(104.9).is_some());
+        assert!(input.current(105.0).is_none());
+        assert_eq!(input.adjust_mid(106.0, 0.5), 0.5);
+
+        // TTL clamped to max_ttl_secs
+        input.update(200.0, signal(Some(0.6), None, 1.0, 3600.0)).unwrap();
+        assert!(input.current(259.0).is_some());
+        assert!(input.current(261.0).is_none());
+    }
+
+    #[test]
+    fn test_adjust_mid() {
+        let mut input = ExternalInput::new(ExternalSignalConfig::default());
+        assert_eq!(input.adjust_mid(0.0, 0.5), 0.5);
+        input.update(0.0, signal(Some(0.7), None, 1.0, 10.0)).unwrap();
+        assert!((input.adjust_mid(1.0, 0.5) - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_direction_widens_other_side() {
+        let mut input = ExternalInput::new(ExternalSignalConfig::default());
+        input.update(0.0, signal(None, Some(Side::Yes), 0.5, 10.0)).unwrap();
+        // YES expected up: bidding NO is the bad trade
+        assert_eq!(input.extra_margin(1.0, Side::No), 5);
+        assert_eq!(input.extra_margin(1.0, Side::Yes), 0);
+        assert_eq!(input.extra_margin(11.0, Side::No), 0);
+    }
+
+    #[test]
+    fn test_invalid_keeps_previous() {
+        let mut input = ExternalInput::new(ExternalSignalConfig::default());
+        input.update(0.0, signal(Some(0.6), None, 1.0, 10.0)).unwrap();
+        assert!(input.update(1.0, signal(None, None, 1.0, 10.0)).is_err());
+        assert!(input.update(1.0, signal(Some(1.2), None, 1.0, 10.0)).is_err());
+        assert!(input.update(1.0, signal(Some(0.4), None, 1.0, 0.0)).is_err());
+        assert!(input.update(1.0, signal(None, Some(Side::No), 2.0, 10.0)).is_err());
+        assert_eq!(input.current(2.0).and_then(|s| s.fair), Some(0.6));
+    }
+}
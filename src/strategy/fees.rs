@@ -0,0 +1,112 @@
+//! Fee breakeven math.
+//!
+//! A pair (YES + NO both filled) earns the margin; every fill pays a fee
+//! whether or not its other leg ever fills. So the margin only covers fees
+//! if enough fills complete a pair.
+
+/// Exchange fees in basis points of notional.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FeeSchedule {
+    /// Fee on resting orders that get filled
+    pub maker_bps: u32,
+    /// Fee on orders that cross the spread (Takes)
+    pub taker_bps: u32,
+}
+
+impl FeeSchedule {
+    /// Maker fee in ticks for one share bought at `price` ticks.
+    pub fn maker_fee_ticks(&self, price: u16) -> f64 {
+        price as f64 * self.maker_bps as f64 / 10_000.0
+    }
+
+    /// Taker fee in ticks for one share bought at `price` ticks.
+    pub fn taker_fee_ticks(&self, price: u16) -> f64 {
+        price as f64 * self.taker_bps as f64 / 10_000.0
+    }
+}
+
+/// What a margin is worth after fees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Breakeven {
+    /// Maker fee per share, in ticks, for both legs of a pair combined
+    pub pair_fee_ticks: f64,
+    /// Fraction of fills that must complete a pair to break even.
+    /// Above 1.0 the margin can't cover fees at all.
+    pub pair_rate: f64,
+    /// Smallest margin (ticks) that breaks even with every fill paired
+    pub min_margin_ticks: u16,
+}
+
+impl Breakeven {
+    /// True if the margin covers fees with every fill paired.
+    pub fn is_viable(&self) -> bool {
+        self.pair_rate <= 1.0
+    }
+}
+
+/// Breakeven for quoting `margin_ticks` below a dollar per pair.
+///
+/// Legs are assumed to fill near the pair's average price (500 ticks
+/// each at the midpoint). Each fill pays its maker fee; each paired
+/// share earns margin / 2.
+///
+/// # Arguments
+/// * `fees` - Current fee schedule
+/// * `margin_ticks` - Profit per pair before fees (1000 - yes_bid - no_bid)
+///
+/// # Returns
+/// Breakeven with the required pair rate and minimum viable margin
+pub fn breakeven(fees: &FeeSchedule, margin_ticks: u16) -> Breakeven {
+    let leg_price = (1000 - margin_ticks.min(1000)) / 2;
+    let leg_fee = fees.maker_fee_ticks(leg_price);
+    let pair_fee_ticks = 2.0 * leg_fee;
+
+    let pair_rate = if pair_fee_ticks == 0.0 {
+        0.0
+    } else if margin_ticks == 0 {
+        f64::INFINITY
+    } else {
+        pair_fee_ticks / margin_ticks as f64
+    };
+
+    Breakeven {
+        pair_fee_ticks,
+        pair_rate,
+        min_margin_ticks: pair_fee_ticks.ceil() as u16,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_fees_always_viable() {
+        let b = breakeven(&FeeSchedule::default(), 5);
+        assert_eq!(b.pair_rate, 0.0);
+        assert_eq!(b.min_margin_ticks, 0);
+        assert!(b.is_viable());
+    }
+
+    #[test]
+    fn test_breakeven_pair_rate() {
+        // 10 bps on ~495-tick legs ≈ 0.495 ticks each, ~0.99 per pair
+        let fees = FeeSchedule { maker_bps: 10, taker_bps: 0 };
+        let b = breakeven(&fees, 10);
+        assert!((b.pair_fee_ticks - 0.99).abs() < 1e-9);
+        assert!((b.pair_rate - 0.099).abs() < 1e-9);
+        assert_eq!(b.min_margin_ticks, 1);
+        assert!(b.is_viable());
+    }
+
+    #[test]
+    fn test_margin_too_thin() {
+        // 100 bps ≈ 10 ticks per pair; a 5-tick margin can't pay for it
+        let fees = FeeSchedule { maker_bps: 100, taker_bps: 0 };
+        let b = breakeven(&fees, 5);
+        assert!(!b.is_viable());
+        assert_eq!(b.min_margin_ticks, 10);
+
+        assert!(!breakeven(&fees, 0).is_viable());
+    }
+}
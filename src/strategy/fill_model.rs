@@ -0,0 +1,323 @@
+//! Fill models: how likely a resting bid is to fill within a horizon.
+//!
+//! A rung fills once the price comes down to it and the queue in front of
+//! it (other bids at better or equal prices, and our own higher rungs) has
+//! traded through. [`HeuristicFillModel`] estimates that from the distance
+//! to the best bid, the depth ahead and the current volatility;
+//! [`CalibratedFillModel`] replaces the guess with fill rates measured from
+//! a session journal. Both sit behind [`FillModel`], so ladder construction
+//! (`StrategyConfig::fill_model`) and the replay backtest take either.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::events::Side;
+use crate::journal::{Rung, Snapshot};
+
+/// One resting bid, as the fill model sees it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FillQuery {
+    /// Ticks under the side's best bid (0 = at or above it)
+    pub distance_ticks: u16,
+    /// Shares that fill before this one
+    pub depth_ahead: f64,
+    /// Price volatility in ticks per √second
+    pub volatility: f64,
+    pub horizon_secs: f64,
+}
+
+/// Probability that a bid fills within the query's horizon.
+pub trait FillModel: fmt::Debug + Send + Sync {
+    /// Fill probability, 0.0 to 1.0.
+    fn fill_prob(&self, query: &FillQuery) -> f64;
+}
+
+/// Price volatility in ticks per √second from the pricer's logit variance
+/// at mid `p_mid` (dp = p(1-p)·dx).
+pub fn tick_volatility(p_mid: f64, var: f64) -> f64 {
+    p_mid * (1.0 - p_mid) * var.max(0.0).sqrt() * 1000.0
+}
+
+/// Default model: the chance a random walk with the given volatility
+/// touches the rung within the horizon, discounted for the queue ahead.
+#[derive(Debug, Clone)]
+pub struct HeuristicFillModel {
+    /// Shares ahead that cut the fill chance by a factor of e
+    pub queue_scale: f64,
+    /// Fill chance at the best bid with an empty queue
+    pub touch_prob: f64,
+}
+
+impl Default for HeuristicFillModel {
+    fn default() -> Self {
+        Self {
+            queue_scale: 200.0,   // A typical top-of-book level
+            touch_prob: 0.9,      // Not every touch trades through us
+        }
+    }
+}
+
+impl FillModel for HeuristicFillModel {
+    fn fill_prob(&self, query: &FillQuery) -> f64 {
+        // Reflection principle: P(min over [0, h] ≤ -d) = 2·(1 - Φ(d / σ√h))
+        let reach = if query.distance_ticks == 0 {
+            1.0
+        } else {
+            let spread = query.volatility * query.horizon_secs.max(0.0).sqrt();
+            if spread <= 0.0 {
+                0.0
+            } else {
+                2.0 * (1.0 - normal_cdf(query.distance_ticks as f64 / spread))
+            }
+        };
+        let queue = (-query.depth_ahead.max(0.0) / self.queue_scale.max(1.0)).exp();
+        (reach * queue * self.touch_prob).clamp(0.0, 1.0)
+    }
+}
+
+/// Fill rates by distance measured from a journal, at the journal's
+/// horizon. Buckets with too few samples fall back to the heuristic.
+#[derive(Debug, Clone)]
+pub struct CalibratedFillModel {
+    /// Horizon the rates were measured over (seconds)
+    horizon_secs: f64,
+    /// Distance bucket width (ticks)
+    bucket_ticks: u16,
+    /// Bucket → (filled, seen)
+    buckets: HashMap<u16, (u32, u32)>,
+    fallback: HeuristicFillModel,
+}
+
+/// Samples a bucket needs before its rate is trusted
+const MIN_SAMPLES: u32 = 20;
+
+impl CalibratedFillModel {
+    /// Measure fill rates from journal snapshots (see [`fill_samples`]).
+    pub fn from_journal(snapshots: &[Snapshot], horizon_secs: f64, bucket_ticks: u16) -> Self {
+        let bucket_ticks = bucket_ticks.max(1);
+        let mut buckets: HashMap<u16, (u32, u32)> = HashMap::new();
+        for sample in fill_samples(snapshots, horizon_secs) {
+            let entry = buckets.entry(sample.query.distance_ticks / bucket_ticks).or_default();
+            entry.0 += sample.filled as u32;
+            entry.1 += 1;
+        }
+
+        Self {
+            horizon_secs,
+            bucket_ticks,
+            buckets,
+            fallback: HeuristicFillModel::default(),
+        }
+    }
+
+    /// Total rungs sampled.
+    pub fn samples(&self) -> u32 {
+        self.buckets.values().map(|&(_, seen)| seen).sum()
+    }
+
+    /// Measured rate for a distance at the calibration horizon, if the
+    /// bucket has enough samples (Laplace-smoothed).
+    fn rate(&self, distance_ticks: u16) -> Option<f64> {
+        let &(filled, seen) = self.buckets.get(&(distance_ticks / self.bucket_ticks))?;
+        (seen >= MIN_SAMPLES).then(|| (filled as f64 + 1.0) / (seen as f64 + 2.0))
+    }
+}
+
+impl FillModel for CalibratedFillModel {
+    /// Depth and volatility are averaged into the measured rates. Other
+    /// horizons assume a constant hazard: 1 - (1 - p)^(h / H).
+    fn fill_prob(&self, query: &FillQuery) -> f64 {
+        match self.rate(query.distance_ticks) {
+            Some(p) if self.horizon_secs > 0.0 => {
+                1.0 - (1.0 - p).powf(query.horizon_secs.max(0.0) / self.horizon_secs)
+            }
+            _ => self.fallback.fill_prob(query),
+        }
+    }
+}
+
+/// A rung from the journal and whether it filled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FillSample {
+    pub ts: f64,
+    pub side: Side,
+    pub query: FillQuery,
+    pub filled: bool,
+}
+
+/// Every rung of every snapshot as a sample over `horizon_secs`. A rung
+/// counts as filled when, within the horizon, that side's inventory grew
+/// and the rung at that price shrank or went away. Frames whose horizon
+/// runs past the journal (or the market) are skipped.
+pub fn fill_samples(snapshots: &[Snapshot], horizon_secs: f64) -> Vec<FillSample> {
+    let mut samples = Vec::new();
+    for (i, snap) in snapshots.iter().enumerate() {
+        let Some(later) = snapshots[i + 1..]
+            .iter()
+            .take_while(|s| s.ts - snap.ts <= horizon_secs && s.market == snap.market)
+            .last()
+        else {
+            continue;
+        };
+        let volatility = tick_volatility(snap.signals.mid, snap.signals.var);
+        for side in [Side::Yes, Side::No] {
+            let Some(best_bid) = best_bid(snap, side) else {
+                continue;
+            };
+            let bought = qty(later, side) > qty(snap, side);
+            // Rungs are best first: our higher ones are queued ahead
+            let mut depth_ahead = 0.0;
+            for rung in rungs(snap, side) {
+                let gone = rungs(later, side)
+                    .iter()
+                    .find(|r| r.price == rung.price)
+                    .is_none_or(|r| r.size < rung.size);
+                samples.push(FillSample {
+                    ts: snap.ts,
+                    side,
+                    query: FillQuery {
+                        distance_ticks: best_bid.saturating_sub(rung.price),
+                        depth_ahead,
+                        volatility,
+                        horizon_secs,
+                    },
+                    filled: bought && gone,
+                });
+                depth_ahead += rung.size;
+            }
+        }
+    }
+    samples
+}
+
+/// Drop rungs unlikely to fill within `horizon_secs`, top-down: each
+/// rung's queue includes our own higher rungs. A `min_prob` of 0 keeps
+/// everything.
+pub fn retain_fillable(
+    model: &dyn FillModel,
+    min_prob: f64,
+    ladder: &mut HashMap<u16, Decimal>,
+    best_bid: Option<u16>,
+    volatility: f64,
+    horizon_secs: f64,
+) {
+    let Some(best_bid) = best_bid.filter(|_| min_prob > 0.0) else {
+        return;
+    };
+    let mut prices: Vec<u16> = ladder.keys().copied().collect();
+    prices.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut depth_ahead = 0.0;
+    for price in prices {
+        let size = ladder[&price].to_f64().unwrap_or(0.0);
+        let query = FillQuery {
+            distance_ticks: best_bid.saturating_sub(price),
+            depth_ahead,
+            volatility,
+            horizon_secs,
+        };
+        if model.fill_prob(&query) < min_prob {
+            ladder.remove(&price);
+        } else {
+            depth_ahead += size;
+        }
+    }
+}
+
+fn best_bid(snap: &Snapshot, side: Side) -> Option<u16> {
+    match side {
+        Side::Yes => snap.book.yes_bid,
+        Side::No => snap.book.no_bid,
+    }
+}
+
+fn qty(snap: &Snapshot, side: Side) -> f64 {
+    match side {
+        Side::Yes => snap.position.qty_yes,
+        Side::No => snap.position.qty_no,
+    }
+}
+
+fn rungs(snap: &Snapshot, side: Side) -> &[Rung] {
+    match side {
+        Side::Yes => &snap.ladder.yes,
+        Side::No => &snap.ladder.no,
+    }
+}
+
+/// Standard normal CDF (Abramowitz & Stegun 7.1.26, error < 1.5e-7).
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.3275911 * z);
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::journal::{BookSnapshot, LadderSnapshot, PositionSnapshot, Signals};
+    use rust_decimal_macros::dec;
+
+    fn query(distance_ticks: u16, depth_ahead: f64) -> FillQuery {
+        FillQuery { distance_ticks, depth_ahead, volatility: 5.0, horizon_secs: 10.0 }
+    }
+
+    #[test]
+    fn test_heuristic_falls_with_distance_and_depth() {
+        let model = HeuristicFillModel::default();
+        let top = model.fill_prob(&query(0, 0.0));
+        assert!((top - 0.9).abs() < 1e-9);
+        assert!(model.fill_prob(&query(10, 0.0)) < top);
+        assert!(model.fill_prob(&query(40, 0.0)) < model.fill_prob(&query(10, 0.0)));
+        assert!(model.fill_prob(&query(0, 200.0)) < top);
+        assert_eq!(model.fill_prob(&FillQuery { volatility: 0.0, ..query(10, 0.0) }), 0.0);
+
+        // Keep the top rungs, drop the one too deep to fill
+        let mut ladder = HashMap::from([(500, dec!(10)), (490, dec!(10)), (400, dec!(10))]);
+        retain_fillable(&model, 0.05, &mut ladder, Some(500), 5.0, 10.0);
+        let mut kept: Vec<u16> = ladder.into_keys().collect();
+        kept.sort_unstable();
+        assert_eq!(kept, vec![490, 500]);
+    }
+
+    #[test]
+    fn test_calibrated_from_journal() {
+        // Top rung at the bid fills every other frame; the deep one never does
+        let frame = |ts: f64, qty_yes: f64, top: f64| Snapshot {
+            ts,
+            market: "btc-updown-5m-0".into(),
+            time_left: 300.0 - ts,
+            book: BookSnapshot { yes_bid: Some(500), ..Default::default() },
+            ladder: LadderSnapshot {
+                yes: vec![Rung { price: 500, size: top }, Rung { price: 450, size: 5.0 }],
+                no: Vec::new(),
+            },
+            position: PositionSnapshot { qty_yes, ..Default::default() },
+            signals: Signals::default(),
+        };
+        let snaps: Vec<Snapshot> = (0..60)
+            .map(|i| frame(i as f64, ((i + 1) / 2) as f64 * 5.0, if i % 2 == 0 { 5.0 } else { 0.0 }))
+            .collect();
+
+        let model = CalibratedFillModel::from_journal(&snaps, 1.0, 10);
+        assert_eq!(model.samples(), 118);
+        let at_bid = model.fill_prob(&FillQuery { horizon_secs: 1.0, ..query(0, 0.0) });
+        let deep = model.fill_prob(&FillQuery { horizon_secs: 1.0, ..query(50, 0.0) });
+        assert!((at_bid - 31.0 / 61.0).abs() < 1e-9);
+        assert!((deep - 1.0 / 61.0).abs() < 1e-9);
+        // Longer horizon at a constant hazard
+        let two = model.fill_prob(&FillQuery { horizon_secs: 2.0, ..query(0, 0.0) });
+        assert!((two - (1.0 - (30.0f64 / 61.0).powi(2))).abs() < 1e-9);
+        // Unsampled distance: heuristic
+        assert_eq!(model.fill_prob(&query(25, 0.0)), HeuristicFillModel::default().fill_prob(&query(25, 0.0)));
+    }
+}
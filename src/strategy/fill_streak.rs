@@ -0,0 +1,144 @@
+//! Consecutive same-side fill detection.
+//!
+//! Getting hit several times in a row on one side, with nothing on the
+//! other, usually means someone knows where price is going. Pause that
+//! side for a while instead of feeding them more size.
+
+use crate::events::Side;
+
+/// Configuration for the same-side fill guard.
+#[derive(Debug, Clone)]
+pub struct FillStreakConfig {
+    /// Consecutive fills on one side that trigger a pause
+    pub max_consecutive: u32,
+    /// The streak only counts if it happens within this window (seconds)
+    pub window_secs: f64,
+    /// How long to stop quoting the hit side (seconds)
+    pub cooldown_secs: f64,
+}
+
+impl Default for FillStreakConfig {
+    fn default() -> Self {
+        Self {
+            max_consecutive: 5,    // 5 in a row
+            window_secs: 10.0,     // Within 10 seconds
+            cooldown_secs: 15.0,   // Pause that side for 15s
+        }
+    }
+}
+
+/// Counts fills on the same side without an opposite-side fill in between.
+#[derive(Debug)]
+pub struct FillStreakGuard {
+    config: FillStreakConfig,
+    /// Side of the current streak
+    side: Option<Side>,
+    /// Fills in the current streak
+    count: u32,
+    /// Time of the first fill in the current streak
+    started_at: f64,
+    /// Pause deadline per side (YES, NO)
+    paused_until: [f64; 2],
+}
+
+fn idx(side: Side) -> usize {
+    match side {
+        Side::Yes => 0,
+        Side::No => 1,
+    }
+}
+
+impl FillStreakGuard {
+    pub fn new(config: FillStreakConfig) -> Self {
+        Self {
+            config,
+            side: None,
+            count: 0,
+            started_at: 0.0,
+            paused_until: [0.0; 2],
+        }
+    }
+
+    /// Record a fill. Call on each Event::OrderFill.
+    ///
+    /// # Returns
+    /// `true` if this fill completed a streak and `side` is now paused.
+    pub fn record_fill(&mut self, side: Side, now: f64) -> bool {
+        let continues = self.side == Some(side) && now - self.started_at <= self.config.window_secs;
+        if continues {
+            self.count += 1;
+        } else {
+            self.side = Some(side);
+            self.count = 1;
+            self.started_at = now;
+        }
+
+        if self.count >= self.config.max_consecutive {
+            self.paused_until[idx(side)] = now + self.config.cooldown_secs;
+            self.side = None;
+            self.count = 0;
+            return true;
+        }
+        false
+    }
+
+    /// Check if quoting on `side` is paused.
+    pub fn is_paused(&self, side: Side, now: f64) -> bool {
+        now < self.paused_until[idx(side)]
+    }
+
+    /// Reset state (call on market switch).
+    pub fn reset(&mut self) {
+        self.side = None;
+        self.count = 0;
+        self.started_at = 0.0;
+        self.paused_until = [0.0; 2];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard() -> FillStreakGuard {
+        FillStreakGuard::new(FillStreakConfig {
+            max_consecutive: 3,
+            window_secs: 5.0,
+            cooldown_secs: 10.0,
+        })
+    }
+
+    #[test]
+    fn test_streak_pauses_side() {
+        let mut g = guard();
+        assert!(!g.record_fill(Side::Yes, 0.0));
+        assert!(!g.record_fill(Side::Yes, 1.0));
+        assert!(g.record_fill(Side::Yes, 2.0));
+
+        assert!(g.is_paused(Side::Yes, 5.0));
+        assert!(!g.is_paused(Side::No, 5.0));
+        assert!(!g.is_paused(Side::Yes, 12.0));
+    }
+
+    #[test]
+    fn test_opposite_fill_breaks_streak() {
+        let mut g = guard();
+        g.record_fill(Side::Yes, 0.0);
+        g.record_fill(Side::Yes, 1.0);
+        g.record_fill(Side::No, 1.5);
+        assert!(!g.record_fill(Side::Yes, 2.0));
+        assert!(!g.is_paused(Side::Yes, 2.0));
+    }
+
+    #[test]
+    fn test_slow_streak_ignored() {
+        let mut g = guard();
+        g.record_fill(Side::No, 0.0);
+        g.record_fill(Side::No, 3.0);
+        // Third fill outside the window starts a new streak
+        assert!(!g.record_fill(Side::No, 6.0));
+
+        g.reset();
+        assert!(!g.is_paused(Side::No, 6.0));
+    }
+}
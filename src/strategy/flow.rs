@@ -55,6 +55,15 @@ impl FlowEstimator {
     pub fn reset(&mut self) {
         self.trade_timestamps.clear();
     }
+
+    /// Start a new market window from the previous one's flow: keep the
+    /// most recent `decay` (0-1) fraction of trades, which age out of the
+    /// window as the new market trades.
+    pub fn carry_over(&mut self, decay: f64) {
+        let keep = (self.trade_timestamps.len() as f64 * decay.clamp(0.0, 1.0)).round() as usize;
+        let drop = self.trade_timestamps.len() - keep;
+        self.trade_timestamps.drain(..drop);
+    }
 }
 
 #[cfg(test)]
@@ -118,4 +127,18 @@ mod tests {
         est.reset();
         assert_eq!(est.trade_count(), 0);
     }
+
+    #[test]
+    fn test_carry_over() {
+        let mut est = FlowEstimator::new(30.0, 0.1);
+        for i in 0..30 {
+            est.record_trade(i as f64);
+        }
+        // Half the flow, most recent first, then it ages out
+        est.carry_over(0.5);
+        assert_eq!(est.trade_count(), 15);
+        assert!((est.current_k() - 0.5).abs() < 0.01);
+        est.record_trade(50.0);
+        assert_eq!(est.trade_count(), 11); // t=20..29, t=50
+    }
 }
@@ -0,0 +1,124 @@
+//! Extra strategy instances quoting alongside the main ladder.
+//!
+//! Each instance takes the main loop's per-side targets, so it shares the
+//! book, the pricer and every widening, and quotes its own ladder shape
+//! under them: e.g. one tight rung at the target next to a deep ladder a
+//! few cents back. Orders are tagged `Variant::Instance(i)`, so every
+//! instance manages only its own orders, has its own variant budget in the
+//! executor, and has its fills counted separately.
+
+use rust_decimal::Decimal;
+
+use super::{reconcile_ladder, Action, StrategyConfig};
+use crate::events::Side;
+use crate::risk::{Variant, VariantBudget};
+use crate::state::OrderTracker;
+
+/// Shape and budget of one instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstanceConfig {
+    pub name: String,
+    /// Top rung this far under the main target (ticks)
+    pub offset_ticks: u16,
+    pub rungs: u16,
+    /// Between rungs (ticks)
+    pub spacing_ticks: u16,
+    /// Shares per rung
+    pub size: Decimal,
+    /// Shares per side, filled plus resting
+    pub max_position: Decimal,
+    /// USDC, filled cost plus resting notional, both sides
+    pub max_notional: Decimal,
+}
+
+/// One running instance.
+pub struct StrategyInstance {
+    pub variant: Variant,
+    pub config: InstanceConfig,
+    ladder: StrategyConfig,
+}
+
+impl StrategyInstance {
+    /// Instance `index` (its position in config, tags its orders).
+    pub fn new(index: u8, config: InstanceConfig) -> Self {
+        let ladder = StrategyConfig {
+            ladder_rungs: config.rungs,
+            rung_spacing: config.spacing_ticks.max(1),
+            ..Default::default()
+        };
+        Self { variant: Variant::Instance(index), config, ladder }
+    }
+
+    /// Budget the executor enforces for this instance.
+    pub fn budget(&self) -> VariantBudget {
+        VariantBudget {
+            max_position: self.config.max_position,
+            max_notional: self.config.max_notional,
+        }
+    }
+
+    /// Actions to move this instance's orders on `side` to its ladder under
+    /// `target` (0 = the main loop isn't quoting that side: pull).
+    /// `orders` is the shared tracker; only this instance's orders are touched.
+    pub fn reconcile(&self, side: Side, target: u16, orders: &OrderTracker) -> Vec<Action> {
+        let top = if target == 0 { 0 } else { target.saturating_sub(self.config.offset_ticks) };
+        reconcile_ladder(side, top, self.config.size, orders.of(self.variant), &self.ladder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn deep() -> StrategyInstance {
+        StrategyInstance::new(1, InstanceConfig {
+            name: "deep".to_string(),
+            offset_ticks: 20,
+            rungs: 3,
+            spacing_ticks: 10,
+            size: dec!(5),
+            max_position: dec!(50),
+            max_notional: dec!(25),
+        })
+    }
+
+    fn placed(actions: &[Action]) -> Vec<u16> {
+        let mut prices: Vec<u16> = actions
+            .iter()
+            .filter_map(|a| match a {
+                Action::Place { price, .. } => Some(*price),
+                _ => None,
+            })
+            .collect();
+        prices.sort();
+        prices
+    }
+
+    #[test]
+    fn test_ladder_under_target() {
+        let inst = deep();
+        let orders = OrderTracker::new();
+        assert_eq!(placed(&inst.reconcile(Side::Yes, 480, &orders)), vec![440, 450, 460]);
+    }
+
+    #[test]
+    fn test_only_touches_own_orders() {
+        let inst = deep();
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "main".to_string(), 480, dec!(5));
+        orders.add_tagged(Side::Yes, "other".to_string(), 300, dec!(5), Variant::Instance(0));
+        orders.add_tagged(Side::Yes, "mine_top".to_string(), 460, dec!(5), Variant::Instance(1));
+        orders.add_tagged(Side::Yes, "mine_stale".to_string(), 400, dec!(5), Variant::Instance(1));
+
+        let actions = inst.reconcile(Side::Yes, 480, &orders);
+        assert!(actions.contains(&Action::cancel("mine_stale")));
+        assert_eq!(actions.iter().filter(|a| matches!(a, Action::Cancel { .. })).count(), 1);
+        assert_eq!(placed(&actions), vec![440, 450]);
+
+        // Main side pulled: pull ours, nobody else's
+        let actions = inst.reconcile(Side::Yes, 0, &orders);
+        assert_eq!(actions.len(), 2);
+        assert!(actions.contains(&Action::cancel("mine_top")));
+    }
+}
@@ -0,0 +1,128 @@
+//! Inventory target: how much imbalance to tolerate as the window runs down.
+//!
+//! Early on, an imbalance has minutes to pair off through the ladder, so
+//! the tolerance is wide (`rebalance_threshold`). It narrows linearly to
+//! `final_tolerance` at `flat_secs` before expiry and stays there. Shares
+//! beyond the tolerance are the deviation the strategy leans against: the
+//! heavy side's bid drops `lean_ticks_per_share` per share over (up to
+//! `max_lean_ticks`), its size shrinks in proportion, and the excess is
+//! bought on the light side as taker.
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+use crate::events::Side;
+use crate::state::Position;
+
+/// Inventory trajectory and how hard to lean against it.
+#[derive(Debug, Clone)]
+pub struct InventoryConfig {
+    /// Imbalance tolerated from `flat_secs` before expiry on (shares)
+    pub final_tolerance: Decimal,
+    pub flat_secs: f64,
+    /// Heavy-side widening per share over the tolerance
+    pub lean_ticks_per_share: f64,
+    pub max_lean_ticks: u16,
+}
+
+impl Default for InventoryConfig {
+    fn default() -> Self {
+        Self {
+            final_tolerance: Decimal::from(5),   // One minimum order
+            flat_secs: 30.0,                     // Near flat by T-30s
+            lean_ticks_per_share: 0.5,           // 1c per 20 shares over
+            max_lean_ticks: 30,                  // At most 3c
+        }
+    }
+}
+
+impl InventoryConfig {
+    /// Imbalance tolerated with `time_remaining` left of a `total_secs`
+    /// window, narrowing from `start` at the open.
+    pub fn tolerance(&self, start: Decimal, time_remaining: f64, total_secs: f64) -> Decimal {
+        let end = self.final_tolerance.min(start);
+        if time_remaining <= self.flat_secs || total_secs <= self.flat_secs {
+            return end;
+        }
+        let progress = ((total_secs - time_remaining) / (total_secs - self.flat_secs)).clamp(0.0, 1.0);
+        let progress = Decimal::from_f64(progress).unwrap_or(Decimal::ONE);
+        start - (start - end) * progress
+    }
+}
+
+/// Imbalance beyond the tolerance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deviation {
+    /// Side holding more shares
+    pub heavy: Side,
+    /// Shares over the tolerance
+    pub excess: Decimal,
+    pub tolerance: Decimal,
+}
+
+impl Deviation {
+    /// None while the imbalance is within `tolerance`.
+    pub fn measure(position: &Position, tolerance: Decimal) -> Option<Self> {
+        let net = position.net_position();
+        let excess = net.abs() - tolerance;
+        if excess <= Decimal::ZERO {
+            return None;
+        }
+        let heavy = if net > Decimal::ZERO { Side::Yes } else { Side::No };
+        Some(Self { heavy, excess, tolerance })
+    }
+
+    /// Ticks to lower the heavy side's bid.
+    pub fn lean_ticks(&self, config: &InventoryConfig) -> u16 {
+        let ticks = self.excess.to_f64().unwrap_or(0.0) * config.lean_ticks_per_share;
+        (ticks.round() as u16).min(config.max_lean_ticks)
+    }
+
+    /// Heavy-side order size: shrinks as the excess grows (halved once
+    /// the imbalance is twice the tolerance), whole shares, zero under
+    /// `min_size`.
+    pub fn heavy_size(&self, size: Decimal, min_size: Decimal) -> Decimal {
+        let total = self.tolerance + self.excess;
+        let scaled = (size * self.tolerance / total).floor();
+        if scaled < min_size {
+            Decimal::ZERO
+        } else {
+            scaled
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_tolerance_narrows_to_flat() {
+        let config = InventoryConfig::default();
+        // 5m window, 30 shares at the open → 5 at T-30s
+        assert_eq!(config.tolerance(dec!(30), 300.0, 300.0), dec!(30));
+        assert_eq!(config.tolerance(dec!(30), 165.0, 300.0), dec!(17.5));
+        assert_eq!(config.tolerance(dec!(30), 30.0, 300.0), dec!(5));
+        assert_eq!(config.tolerance(dec!(30), 5.0, 300.0), dec!(5));
+        // Never wider than the start
+        assert_eq!(config.tolerance(dec!(3), 10.0, 300.0), dec!(3));
+    }
+
+    #[test]
+    fn test_leans_against_excess() {
+        let config = InventoryConfig::default();
+        let mut position = Position::default();
+        position.apply_fill(Side::No, 500, dec!(25));
+        assert_eq!(Deviation::measure(&position, dec!(30)), None);
+
+        let d = Deviation::measure(&position, dec!(5)).unwrap();
+        assert_eq!((d.heavy, d.excess), (Side::No, dec!(20)));
+        assert_eq!(d.lean_ticks(&config), 10);
+        assert_eq!(d.heavy_size(dec!(12), dec!(5)), dec!(0)); // 12 × 5/25 = 2.4
+        assert_eq!(Deviation::measure(&position, dec!(20)).unwrap().heavy_size(dec!(12), dec!(5)), dec!(9));
+
+        position.apply_fill(Side::No, 500, dec!(200));
+        assert_eq!(Deviation::measure(&position, dec!(5)).unwrap().lean_ticks(&config), 30);
+    }
+}
@@ -1,21 +1,57 @@
 mod actions;
+mod anneal;
+mod basis;
+mod bot_state;
 mod btc_guard;
+mod external;
+mod fees;
+mod fill_model;
+mod fill_streak;
 mod flow;
+mod instances;
+mod inventory;
+mod ofi;
+mod price_filter;
 mod pricing;
+mod rewards;
 mod sizing;
+mod staleness;
+mod tape;
+mod ticks;
 mod variance;
 
-pub use actions::Action;
+pub use actions::{Action, TimeInForce};
+pub use anneal::{AdjustReason, Adjustment, AnnealConfig, MarginAnnealer};
+pub use basis::{BasisConfig, BasisReading, BasisTracker};
+pub use bot_state::{BotState, Signals};
 pub use btc_guard::{BtcGuard, BtcGuardConfig};
+pub use external::{ExternalInput, ExternalSignal, ExternalSignalConfig};
+pub use fees::{breakeven, Breakeven, FeeSchedule};
+pub use fill_model::{
+    fill_samples, retain_fillable, tick_volatility, CalibratedFillModel, FillModel, FillQuery, FillSample,
+    HeuristicFillModel,
+};
+pub use fill_streak::{FillStreakConfig, FillStreakGuard};
 pub use flow::FlowEstimator;
+pub use instances::{InstanceConfig, StrategyInstance};
+pub use inventory::{Deviation, InventoryConfig};
+pub use ofi::{DepthBook, OfiConfig, OfiTracker};
+pub use price_filter::{PriceFilter, PriceFilterConfig};
 pub use pricing::{calc_max_bid, AvellanedaStoikov, Quotes, P_MAX, P_MIN};
-pub use sizing::{calc_size, calc_size_with_limit, can_place, MarketDuration};
+pub use rewards::RewardBand;
+pub use sizing::{calc_size, calc_size_with_limit, can_place, resolve_duration, MarketDuration};
+pub use staleness::StalenessConfig;
+pub use tape::{TapeConfig, TapeSignal, TapeVelocity};
+pub use ticks::{cents_to_ticks, TICKS_PER_DOLLAR};
 pub use variance::VarianceEstimator;
 
 use crate::events::Side;
-use crate::state::{OrderTracker, Position};
+use crate::risk::{PositionSchedule, RiskLimits, Variant};
+use crate::state::{Book, OrderView, Position};
+use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Strategy configuration.
 #[derive(Debug, Clone)]
@@ -30,12 +66,45 @@ pub struct StrategyConfig {
     pub ladder_rungs: u16,
     /// Spacing between ladder rungs in ticks (10 = 1c)
     pub rung_spacing: u16,
+    /// Pair margin per rung in ticks, top rung first (e.g. [5, 15, 30]).
+    /// When set it replaces `ladder_rungs` and `rung_spacing`: rung i sits
+    /// `rung_margins[i] - rung_margins[0]` under the top. Empty = uniform.
+    pub rung_margins: Vec<u16>,
     /// Market duration (5m or 15m)
     pub duration: MarketDuration,
-    /// Imbalance threshold before rebalancing (shares)
+    /// Imbalance tolerated at the open (shares); narrows toward expiry
     pub rebalance_threshold: Decimal,
+    /// Tolerance trajectory and the lean against imbalance beyond it
+    pub inventory: InventoryConfig,
     /// Maximum size to take when rebalancing
     pub max_take_size: Decimal,
+    /// Most a rebalancing Take pays over the light side's fair value, in ticks
+    pub take_premium_ticks: u16,
+    /// Hysteresis for rebalancing Takes (shares): one fires this far past
+    /// the tolerance and buys back to this far under it
+    pub take_band: Decimal,
+    /// Per-side caps on resting orders (ladder depth is trimmed to fit)
+    pub limits: RiskLimits,
+    /// Time in force per rung, top rung first. Rungs past the end use GTC,
+    /// or `expire_before_end_secs` when set.
+    pub rung_tif: Vec<TimeInForce>,
+    /// Post GTC rungs as GTD expiring this long before the market ends
+    pub expire_before_end_secs: Option<u64>,
+    /// Exchange fees, for checking the margin pays for them
+    pub fees: FeeSchedule,
+    /// Pause a side after too many fills in a row on it
+    pub fill_streak: FillStreakConfig,
+    /// Widen quotes as the book ages; stop quoting when it's dead
+    pub staleness: StalenessConfig,
+    /// Widen or pull the side the trade tape is running over
+    pub tape: TapeConfig,
+    /// Only quote inside the market's liquidity-reward band (set per market)
+    pub reward_band: Option<RewardBand>,
+    /// Fill probability per rung (heuristic, or calibrated from a journal)
+    pub fill_model: Arc<dyn FillModel>,
+    /// Drop rungs less likely than this to fill within `fill_horizon_secs`
+    pub min_fill_prob: f64,
+    pub fill_horizon_secs: f64,
 }
 
 impl Default for StrategyConfig {
@@ -46,9 +115,105 @@ impl Default for StrategyConfig {
             min_order_size: Decimal::from(5),            // API minimum
             ladder_rungs: 3,                             // 3 price levels
             rung_spacing: 10,                            // 1c spacing
+            rung_margins: Vec::new(),                    // Uniform spacing
             duration: MarketDuration::FiveMin,           // Default to 5m
             rebalance_threshold: Decimal::from(30),      // 30 share imbalance
+            inventory: InventoryConfig::default(),       // 5 shares by T-30s
             max_take_size: Decimal::from(12),            // Max 12 shares per take
+            take_premium_ticks: 30,                      // Fair + 3c
+            take_band: Decimal::from(3),                 // Fire at +3, buy back to -3
+            limits: RiskLimits::default(),
+            rung_tif: Vec::new(),                        // All rungs GTC
+            expire_before_end_secs: None,                // ...resting until cancelled
+            fees: FeeSchedule::default(),                // Makers pay no fee
+            fill_streak: FillStreakConfig::default(),    // 5 fills in 10s → 15s pause
+            staleness: StalenessConfig::default(),       // +1c at 2s, off at 5s
+            tape: TapeConfig::default(),                 // +1c at 80/20, pull at 95/5
+            reward_band: None,                           // Quote wherever pricing says
+            fill_model: Arc::new(HeuristicFillModel::default()),
+            min_fill_prob: 0.0,                          // Keep every rung
+            fill_horizon_secs: 30.0,                     // Half a minute
+        }
+    }
+}
+
+impl StrategyConfig {
+    /// Set margin and rung spacing from cents, converted for the market's
+    /// tick size. Leaves the config untouched if either isn't representable.
+    /// Per-rung margins, when set, keep their top rung's margin.
+    pub fn set_cents(
+        &mut self,
+        margin_cents: Decimal,
+        rung_spacing_cents: Decimal,
+        venue_tick: Decimal,
+    ) -> anyhow::Result<()> {
+        let margin = cents_to_ticks(margin_cents, venue_tick)?;
+        let spacing = cents_to_ticks(rung_spacing_cents, venue_tick)?;
+        if spacing == 0 {
+            anyhow::bail!("rung spacing must be positive");
+        }
+        if self.rung_margins.is_empty() {
+            self.margin_ticks = margin;
+        }
+        self.rung_spacing = spacing;
+        Ok(())
+    }
+
+    /// Set the per-rung margins (ticks, top rung first). The top rung's
+    /// becomes `margin_ticks`; an empty list goes back to uniform spacing.
+    ///
+    /// # Errors
+    /// Margins that don't increase down the ladder.
+    pub fn set_rung_margins(&mut self, margins: Vec<u16>) -> anyhow::Result<()> {
+        if margins.windows(2).any(|w| w[1] <= w[0]) {
+            anyhow::bail!("rung margins must increase down the ladder, got {:?}", margins);
+        }
+        if let Some(&top) = margins.first() {
+            self.margin_ticks = top;
+        }
+        self.rung_margins = margins;
+        Ok(())
+    }
+
+    /// Number of rungs in the ladder.
+    pub fn rung_count(&self) -> u16 {
+        if self.rung_margins.is_empty() {
+            self.ladder_rungs
+        } else {
+            self.rung_margins.len() as u16
+        }
+    }
+
+    /// Ticks a rung (0 = top of ladder) sits under the top rung.
+    pub fn rung_offset(&self, rung: u16) -> u16 {
+        match self.rung_margins.first() {
+            Some(&top) => self.rung_margins.get(rung as usize).map_or(u16::MAX, |&m| m - top),
+            None => rung.saturating_mul(self.rung_spacing),
+        }
+    }
+
+    /// Rung for a price `offset` ticks under the top rung: the deepest
+    /// rung at or above it.
+    pub fn rung_at(&self, offset: u16) -> u16 {
+        match self.rung_margins.first() {
+            Some(&top) => self.rung_margins.iter().rposition(|&m| m - top <= offset).unwrap_or(0) as u16,
+            None => offset / self.rung_spacing.max(1),
+        }
+    }
+
+    /// Fee breakeven for the configured margin.
+    pub fn breakeven(&self) -> Breakeven {
+        breakeven(&self.fees, self.margin_ticks)
+    }
+
+    /// Time in force for a rung (0 = top of ladder). With
+    /// `expire_before_end_secs` set, GTC becomes GTD at the market's end.
+    pub fn tif_for_rung(&self, rung: u16) -> TimeInForce {
+        match self.rung_tif.get(rung as usize).copied().unwrap_or_default() {
+            TimeInForce::Gtc => self
+                .expire_before_end_secs
+                .map_or(TimeInForce::Gtc, |buffer_secs| TimeInForce::GtdMarketEnd { buffer_secs }),
+            tif => tif,
         }
     }
 }
@@ -59,72 +224,302 @@ impl Default for StrategyConfig {
 ///
 /// # Arguments
 /// * `quotes` - A-S computed quotes (yes_bid, no_bid in probability space)
-/// * `p_mid` - Current mid probability (for should_quote check)
-/// * `position` - Current inventory
-/// * `orders` - Current standing orders
-/// * `time_remaining` - Seconds until market settlement
+/// * `state` - Book, position, orders, signals (p_mid for should_quote), and time left
 /// * `config` - Strategy configuration
+/// * `schedule` - Max position by time left (`[risk] position_schedule`)
 ///
 /// # Returns
-/// Vec of actions to execute (Place, Cancel, CancelAll)
+/// Vec of actions to execute (Place, Cancel, CancelAll, Take)
 pub fn reconcile(
     quotes: &Quotes,
-    p_mid: f64,
-    position: &Position,
-    orders: &OrderTracker,
-    time_remaining: f64,
+    state: &BotState,
     config: &StrategyConfig,
+    schedule: &PositionSchedule,
 ) -> Vec<Action> {
     let mut actions = Vec::new();
+    let p_mid = state.signals.p_mid;
+    let position = state.position;
+    let orders = state.orders.of(Variant::Ladder);
+    let time_remaining = state.time_remaining;
+
+    // Book age (a book that never updated counts as fresh; callers
+    // only reconcile once it's synced)
+    let book_age_ms = if state.book.last_update_ms > 0 {
+        (state.now * 1000.0) as i64 - state.book.last_update_ms
+    } else {
+        0
+    };
+    let stale_extra = config.staleness.extra_margin(book_age_ms);
 
     // Check if we should quote at all
-    if !Quotes::should_quote(p_mid) {
-        // Outside valid range - cancel all orders
+    let Some(stale_extra) = stale_extra.filter(|_| Quotes::should_quote(p_mid)) else {
+        // Outside valid range or book dead - cancel all orders
         if orders.total_count() > 0 {
             actions.push(Action::CancelAll);
         }
         return actions;
-    }
+    };
 
-    // Convert A-S quotes to ticks
-    let yes_top_tick = AvellanedaStoikov::to_ticks(quotes.yes_bid);
-    let no_top_tick = AvellanedaStoikov::to_ticks(quotes.no_bid);
+    // Convert A-S quotes to ticks, widened for book staleness and tape
+    // pressure. A side the tape says to pull gets an empty ladder.
+    let top_tick = |side: Side, bid: f64| match state.signals.tape.extra_margin(side, &config.tape) {
+        Some(extra) => AvellanedaStoikov::to_ticks(bid).saturating_sub(stale_extra + extra),
+        None => 0,
+    };
 
-    // Calculate size for each side
+    // Imbalance beyond the inventory target: lean the heavy side's price and size
+    let tolerance = config.inventory.tolerance(
+        config.rebalance_threshold,
+        time_remaining,
+        config.duration.total_secs() as f64,
+    );
+    let deviation = Deviation::measure(position, tolerance);
+    let heavy = |side: Side| deviation.filter(|d| d.heavy == side);
+    let lean = |side: Side| heavy(side).map_or(0, |d| d.lean_ticks(&config.inventory));
+
+    let yes_top_tick = top_tick(Side::Yes, quotes.yes_bid).saturating_sub(lean(Side::Yes));
+    let no_top_tick = top_tick(Side::No, quotes.no_bid).saturating_sub(lean(Side::No));
+
+    // Calculate size for each side, under the max position for the time left
     let time_remaining_secs = time_remaining as i64;
+    let max_position = schedule.max_position(config.max_position, time_remaining);
     let yes_size = calc_size_with_limit(
         Side::Yes,
         position,
         time_remaining_secs,
         config.duration,
-        config.max_position,
+        max_position,
     );
     let no_size = calc_size_with_limit(
         Side::No,
         position,
         time_remaining_secs,
         config.duration,
-        config.max_position,
+        max_position,
     );
 
-    // Build ideal ladders
-    let yes_ideal = build_ladder(yes_top_tick, yes_size, config);
-    let no_ideal = build_ladder(no_top_tick, no_size, config);
+    let yes_size = heavy(Side::Yes).map_or(yes_size, |d| d.heavy_size(yes_size, config.min_order_size));
+    let no_size = heavy(Side::No).map_or(no_size, |d| d.heavy_size(no_size, config.min_order_size));
+
+    // Build ideal ladders, dropping rungs that wouldn't earn rewards
+    let mut yes_ideal = build_ladder(yes_top_tick, yes_size, config);
+    let mut no_ideal = build_ladder(no_top_tick, no_size, config);
+    if let Some(band) = &config.reward_band {
+        let yes_mid = (p_mid * 1000.0).round() as u16;
+        band.retain(Side::Yes, &mut yes_ideal, yes_mid);
+        band.retain(Side::No, &mut no_ideal, yes_mid);
+    }
+
+    // ...and rungs too deep to fill before the horizon (or expiry)
+    let volatility = tick_volatility(p_mid, state.signals.var);
+    let horizon = config.fill_horizon_secs.min(time_remaining);
+    for (side, ideal) in [(Side::Yes, &mut yes_ideal), (Side::No, &mut no_ideal)] {
+        let best_bid = state.book.best_bid(side);
+        retain_fillable(config.fill_model.as_ref(), config.min_fill_prob, ideal, best_bid, volatility, horizon);
+    }
 
     // Reconcile YES side
-    reconcile_side(Side::Yes, &yes_ideal, orders, config, &mut actions);
+    reconcile_side(Side::Yes, yes_top_tick, &yes_ideal, orders, config, &mut actions);
 
     // Reconcile NO side
-    reconcile_side(Side::No, &no_ideal, orders, config, &mut actions);
+    reconcile_side(Side::No, no_top_tick, &no_ideal, orders, config, &mut actions);
+
+    // Cross the spread on the light side for what's over the target
+    actions.extend(check_rebalance(position, state.book, p_mid, tolerance, config));
+
+    actions
+}
+
+/// Rebalance check: once one side holds more than `tolerance` shares over
+/// the other (the inventory target for the time left), buy the excess on
+/// the light side as taker, up to `max_take_size`.
+///
+/// With hysteresis: a Take only fires once the excess reaches `take_band`,
+/// and then buys `take_band` past the tolerance, so the next one needs
+/// twice the band of fresh heavy fills rather than a single share.
+///
+/// The max price keeps the pairs we complete profitable: the heavy side's
+/// average cost plus the take must leave `margin_ticks` under $1. It's also
+/// capped at the light side's fair value (from `p_mid`, the YES fair
+/// probability) plus `take_premium_ticks`, so a cheap heavy fill doesn't
+/// license overpaying for the light side. Nothing is taken if the light
+/// side's ask is already above the cap.
+pub fn check_rebalance(
+    position: &Position,
+    book: &Book,
+    p_mid: f64,
+    tolerance: Decimal,
+    config: &StrategyConfig,
+) -> Option<Action> {
+    let deviation = Deviation::measure(position, tolerance)?;
+    if deviation.excess < config.take_band {
+        return None;
+    }
+    let (light, heavy_avg) = match deviation.heavy {
+        Side::Yes => (Side::No, position.avg_price_yes()?),
+        Side::No => (Side::Yes, position.avg_price_no()?),
+    };
+
+    let pair_cap = (Decimal::from(1000) - heavy_avg - Decimal::from(config.margin_ticks))
+        .floor()
+        .to_u16()
+        .unwrap_or(0);
+    let yes_fair = (p_mid.clamp(0.0, 1.0) * 1000.0).round() as u16;
+    let light_fair = match light {
+        Side::Yes => yes_fair,
+        Side::No => 1000 - yes_fair,
+    };
+    let max_price = pair_cap.min(light_fair.saturating_add(config.take_premium_ticks));
+    let ask = book.best_ask(light)?;
+    if max_price == 0 || ask > max_price {
+        return None;
+    }
+
+    let size = (deviation.excess + config.take_band).min(config.max_take_size);
+    if size < config.min_order_size {
+        return None;
+    }
+    Some(Action::take(light, size, max_price))
+}
+
+/// What the live loop does with one side's ladder this tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SideQuote {
+    /// Cancel everything resting on the side
+    Pull,
+    /// Leave the side as it is (fill cooldown)
+    Hold,
+    /// Reconcile the ladder to this top price
+    Ladder(u16),
+}
+
+/// The live loop's decision for one tick.
+#[derive(Debug, Clone, Copy)]
+pub struct TickQuotes {
+    pub yes: SideQuote,
+    pub no: SideQuote,
+    /// Shares per rung
+    pub size: Decimal,
+    /// Inventory target for the time left (shares)
+    pub tolerance: Decimal,
+    /// YES fair probability, for pricing a rebalancing Take
+    pub p_mid: f64,
+    /// Price volatility in ticks per √second (see `tick_volatility`)
+    pub volatility: f64,
+    /// Seconds to expiry, which caps the fill horizon
+    pub time_left: f64,
+}
+
+/// Actions for one live tick: each side's ladder, less rungs the fill model
+/// doesn't expect to fill in time, then a rebalancing Take on the light side
+/// once the imbalance is past the target (see `check_rebalance`; the
+/// executor throttles Takes per side).
+pub fn tick_actions(
+    quotes: &TickQuotes,
+    position: &Position,
+    book: &Book,
+    orders: OrderView,
+    config: &StrategyConfig,
+) -> Vec<Action> {
+    let mut actions = Vec::new();
+    let horizon = config.fill_horizon_secs.min(quotes.time_left);
+    for (side, quote) in [(Side::Yes, quotes.yes), (Side::No, quotes.no)] {
+        match quote {
+            SideQuote::Pull => actions.extend(orders.all_order_ids(side).into_iter().map(Action::cancel)),
+            SideQuote::Hold => {}
+            SideQuote::Ladder(top) => {
+                let mut ideal = build_ladder(top, quotes.size, config);
+                let model = config.fill_model.as_ref();
+                retain_fillable(model, config.min_fill_prob, &mut ideal, book.best_bid(side), quotes.volatility, horizon);
+                reconcile_side(side, top, &ideal, orders, config, &mut actions);
+            }
+        }
+    }
+    actions.extend(check_rebalance(position, book, quotes.p_mid, quotes.tolerance, config));
+    actions
+}
 
+/// Reconcile one side's ladder from a top price: cancel what isn't on it,
+/// place what's missing. A top price of 0 pulls the side.
+pub fn reconcile_ladder(
+    side: Side,
+    top_price: u16,
+    size: Decimal,
+    orders: OrderView,
+    config: &StrategyConfig,
+) -> Vec<Action> {
+    let ideal = build_ladder(top_price, size, config);
+    let mut actions = Vec::new();
+    reconcile_side(side, top_price, &ideal, orders, config, &mut actions);
     actions
 }
 
+/// Fill fast path: when our top rung on a side gets hit, pull the deeper
+/// rungs right away instead of waiting for the next reconcile. Getting swept
+/// down the whole ladder in one move is the most common toxic pattern.
+///
+/// Call before applying the fill to the tracker.
+///
+/// # Returns
+/// Cancel actions for every other order on that side (empty if the fill
+/// wasn't on the top rung).
+pub fn pull_on_top_fill(side: Side, order_id: &str, orders: OrderView) -> Vec<Action> {
+    let filled_price = match orders.find_price_by_id(side, order_id) {
+        Some(price) => price,
+        None => return Vec::new(),
+    };
+    if orders.top_price(side) != Some(filled_price) {
+        return Vec::new();
+    }
+
+    orders
+        .all_orders(side)
+        .into_iter()
+        .filter(|o| o.order_id != order_id && o.price < filled_price)
+        .map(|o| Action::cancel(o.order_id.clone()))
+        .collect()
+}
+
+/// Find duplicate exposure at one price, e.g. from a retried placement that
+/// actually went through twice: orders placed with the same size as an older
+/// order at that price, beyond what the ladder wants resting there.
+///
+/// The oldest order is always kept (best queue position); extras are
+/// dropped newest first while the remaining size still covers `target_size`.
+///
+/// # Returns
+/// Order IDs to cancel (empty if nothing is duplicated).
+pub fn duplicate_orders(
+    side: Side,
+    price: u16,
+    target_size: Decimal,
+    orders: OrderView,
+) -> Vec<String> {
+    let list = orders.orders_at_price(side, price);
+    if list.len() < 2 {
+        return Vec::new();
+    }
+
+    let mut kept = orders.total_size_at_price(side, price);
+    let mut extras = Vec::new();
+    for (i, order) in list.iter().enumerate().skip(1).rev() {
+        let is_duplicate = list[..i]
+            .iter()
+            .any(|o| o.original_size == order.original_size);
+        if is_duplicate && kept - order.remaining_size >= target_size {
+            kept -= order.remaining_size;
+            extras.push(order.order_id.clone());
+        }
+    }
+    extras
+}
+
 /// Reconcile a single side: cancel stale orders, place missing orders.
 fn reconcile_side(
     side: Side,
+    top_price: u16,
     ideal: &HashMap<u16, Decimal>,
-    orders: &OrderTracker,
+    orders: OrderView,
     config: &StrategyConfig,
     actions: &mut Vec<Action>,
 ) {
@@ -139,23 +534,42 @@ fn reconcile_side(
         }
     }
 
-    // 2. Place orders at ideal prices where we're short
+    // 2. Cancel duplicate exposure at ideal prices
     for (&price, &target_size) in ideal {
-        let current_size = orders.total_size_at_price(side, price);
+        let extras = duplicate_orders(side, price, target_size, orders);
+        if !extras.is_empty() {
+            tracing::warn!(
+                "Duplicate orders at {:?} {}: cancelling {} extra",
+                side, price, extras.len()
+            );
+        }
+        actions.extend(extras.into_iter().map(Action::cancel));
+    }
+
+    // 3. Place orders at ideal prices where we're short (counting
+    // placements still in flight)
+    for (&price, &target_size) in ideal {
+        let current_size = orders.total_size_at_price(side, price) + orders.pending_place_size(side, price);
         if current_size < target_size {
             let needed = target_size - current_size;
             if needed >= config.min_order_size {
-                actions.push(Action::Place {
+                let rung = config.rung_at(top_price.saturating_sub(price));
+                actions.push(Action::place_with_tif(
                     side,
                     price,
-                    size: needed,
-                });
+                    needed,
+                    config.tif_for_rung(rung),
+                ));
             }
         }
     }
 }
 
-/// Build ideal ladder: {price: size, price-spacing: size, ...}
+/// Build ideal ladder: {price: size, price-spacing: size, ...}, or with
+/// per-rung margins each rung at its own offset under the top.
+///
+/// Rungs are added top-down, so when the per-side order count or
+/// exposure limit binds, the deepest rungs are the ones dropped.
 fn build_ladder(
     top_price: u16,
     size: Decimal,
@@ -167,9 +581,17 @@ fn build_ladder(
         return ladder; // Empty ladder
     }
 
-    for i in 0..config.ladder_rungs {
-        let price = top_price.saturating_sub(i * config.rung_spacing);
+    let mut exposure = Decimal::ZERO;
+    for i in 0..config.rung_count() {
+        if ladder.len() >= config.limits.max_orders_per_side
+            || exposure + size > config.limits.max_exposure_per_side
+        {
+            break;
+        }
+
+        let price = top_price.saturating_sub(config.rung_offset(i));
         if price >= 100 {
+            exposure += size;
             // Min 10c
             ladder.insert(price, size);
         }
@@ -178,12 +600,69 @@ fn build_ladder(
     ladder
 }
 
+/// Notional (ticks × shares) the full ladder from `top_price` would lock.
+pub fn ladder_notional(top_price: u16, size: Decimal, config: &StrategyConfig) -> Decimal {
+    build_ladder(top_price, size, config)
+        .iter()
+        .map(|(price, size)| Decimal::from(*price) * size)
+        .sum()
+}
+
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::OrderTracker;
     use rust_decimal_macros::dec;
 
+    #[test]
+    fn test_duplicate_orders_cancels_newest_extra() {
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "first".into(), 480, dec!(12));
+        orders.add(Side::Yes, "retry".into(), 480, dec!(12));
+
+        let extras = duplicate_orders(Side::Yes, 480, dec!(12), orders.of(Variant::Ladder));
+        assert_eq!(extras, vec!["retry".to_string()]);
+    }
+
+    #[test]
+    fn test_duplicate_orders_ignores_top_up_stack() {
+        let mut orders = OrderTracker::new();
+        // Partially filled order topped up back to target: not a duplicate
+        orders.add(Side::Yes, "orig".into(), 480, dec!(12));
+        orders.update_fill(Side::Yes, "orig", dec!(5));
+        orders.add(Side::Yes, "topup".into(), 480, dec!(5));
+
+        assert!(duplicate_orders(Side::Yes, 480, dec!(12), orders.of(Variant::Ladder)).is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_orders_keeps_enough_for_target() {
+        let mut orders = OrderTracker::new();
+        orders.add(Side::No, "a".into(), 500, dec!(6));
+        orders.add(Side::No, "b".into(), 500, dec!(6));
+        orders.add(Side::No, "c".into(), 500, dec!(6));
+
+        // Ladder wants 12: only one of the three is extra
+        let extras = duplicate_orders(Side::No, 500, dec!(12), orders.of(Variant::Ladder));
+        assert_eq!(extras, vec!["c".to_string()]);
+    }
+
+    #[test]
+    fn test_reconcile_cancels_duplicate() {
+        let config = StrategyConfig::default();
+        let mut ideal = HashMap::new();
+        ideal.insert(480, dec!(12));
+
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "first".into(), 480, dec!(12));
+        orders.add(Side::Yes, "retry".into(), 480, dec!(12));
+
+        let mut actions = Vec::new();
+        reconcile_side(Side::Yes, 480, &ideal, orders.of(Variant::Ladder), &config, &mut actions);
+        assert_eq!(actions, vec![Action::cancel("retry".to_string())]);
+    }
+
     #[test]
     fn test_build_ladder_basic() {
         let config = StrategyConfig {
@@ -232,6 +711,484 @@ mod tests {
         assert!(!ladder.contains_key(&90));
     }
 
+    #[test]
+    fn test_build_ladder_per_rung_margins() {
+        let mut config = StrategyConfig { ladder_rungs: 1, rung_spacing: 10, ..Default::default() };
+        // 0.5c, 1.5c, 3c: rungs 0, 10 and 25 ticks under the top
+        config.set_rung_margins(vec![5, 15, 30]).unwrap();
+        assert_eq!(config.margin_ticks, 5);
+
+        let ladder = build_ladder(495, dec!(12), &config);
+        let mut prices: Vec<u16> = ladder.keys().copied().collect();
+        prices.sort_unstable();
+        assert_eq!(prices, vec![470, 485, 495]);
+        assert_eq!(ladder_notional(495, dec!(12), &config), dec!(17400));
+
+        // TIF lookups map prices back to their rung
+        assert_eq!(config.rung_at(0), 0);
+        assert_eq!(config.rung_at(10), 1);
+        assert_eq!(config.rung_at(25), 2);
+
+        // Cents keep the top rung's margin
+        config.set_cents(dec!(2), dec!(1), dec!(0.01)).unwrap();
+        assert_eq!(config.margin_ticks, 5);
+
+        assert!(config.set_rung_margins(vec![15, 5]).is_err());
+        config.set_rung_margins(Vec::new()).unwrap();
+        assert_eq!(build_ladder(495, dec!(12), &config).len(), 1);
+    }
+
+    #[test]
+    fn test_build_ladder_trims_to_order_limit() {
+        let config = StrategyConfig {
+            ladder_rungs: 5,
+            rung_spacing: 10,
+            limits: RiskLimits {
+                max_orders_per_side: 2,
+                max_exposure_per_side: dec!(100),
+            },
+            ..Default::default()
+        };
+
+        let ladder = build_ladder(480, dec!(12), &config);
+
+        // Deepest rungs dropped first
+        assert_eq!(ladder.len(), 2);
+        assert!(ladder.contains_key(&480));
+        assert!(ladder.contains_key(&470));
+    }
+
+    #[test]
+    fn test_build_ladder_trims_to_exposure_limit() {
+        let config = StrategyConfig {
+            ladder_rungs: 5,
+            rung_spacing: 10,
+            limits: RiskLimits {
+                max_orders_per_side: 10,
+                max_exposure_per_side: dec!(30),
+            },
+            ..Default::default()
+        };
+
+        // 12 + 12 = 24 fits, a third rung would make 36 > 30
+        let ladder = build_ladder(480, dec!(12), &config);
+
+        assert_eq!(ladder.len(), 2);
+        assert!(!ladder.contains_key(&460));
+    }
+
+    #[test]
+    fn test_reconcile_ladder_counts_pending_places() {
+        let config = StrategyConfig { ladder_rungs: 2, rung_spacing: 10, ..Default::default() };
+        let mut orders = OrderTracker::new();
+        orders.add_pending(Side::Yes, "c1".to_string(), 480, dec!(12), Variant::Ladder, 0.0);
+
+        // In flight at the top rung: only the second rung is placed, and nothing is cancelled
+        let actions = reconcile_ladder(Side::Yes, 480, dec!(12), &orders, &config);
+        assert_eq!(actions, vec![Action::place_with_tif(Side::Yes, 470, dec!(12), config.tif_for_rung(1))]);
+
+        // Off the ladder, it can't be cancelled until it's acknowledged
+        let actions = reconcile_ladder(Side::Yes, 0, dec!(12), &orders, &config);
+        assert!(actions.is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_cancels_all_outside_range() {
+        let book = Book::default();
+        let position = Position::default();
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "o1".into(), 950, dec!(5));
+
+        let state = BotState {
+            book: &book,
+            position: &position,
+            orders: &orders,
+            market: None,
+            signals: Signals {
+                p_mid: 0.97,
+                ..Default::default()
+            },
+            now: 0.0,
+            time_remaining: 300.0,
+        };
+        let quotes = Quotes { yes_bid: 0.95, no_bid: 0.02 };
+
+        let actions = reconcile(&quotes, &state, &StrategyConfig::default(), &PositionSchedule::default());
+        assert!(matches!(actions.as_slice(), [Action::CancelAll]));
+    }
+
+    #[test]
+    fn test_reconcile_widens_on_stale_book() {
+        let mut book = Book::default();
+        book.update(Side::Yes, 480, 490, 100_000);
+        book.update(Side::No, 500, 510, 100_000);
+        let position = Position::default();
+        let orders = OrderTracker::new();
+        let quotes = Quotes { yes_bid: 0.48, no_bid: 0.50 };
+        let config = StrategyConfig::default();
+
+        let top_yes = |now: f64| {
+            let state = BotState {
+                book: &book,
+                position: &position,
+                orders: &orders,
+                market: None,
+                signals: Signals { p_mid: 0.5, ..Default::default() },
+                now,
+                time_remaining: 300.0,
+            };
+            reconcile(&quotes, &state, &config, &PositionSchedule::default())
+                .iter()
+                .filter_map(|a| match a {
+                    Action::Place { side: Side::Yes, price, .. } => Some(*price),
+                    _ => None,
+                })
+                .max()
+        };
+
+        assert_eq!(top_yes(100.0), Some(480));
+        assert_eq!(top_yes(102.0), Some(470)); // 2s stale: +1c
+        assert_eq!(top_yes(106.0), None); // Dead book: no quotes
+    }
+
+    #[test]
+    fn test_reconcile_pulls_side_under_tape_pressure() {
+        let book = Book::default();
+        let position = Position::default();
+        let mut orders = OrderTracker::new();
+        orders.add(Side::No, "no1".into(), 490, dec!(5));
+        let quotes = Quotes { yes_bid: 0.48, no_bid: 0.50 };
+
+        // Takers one-way pushing YES up: NO bids are getting run over
+        let state = BotState {
+            book: &book,
+            position: &position,
+            orders: &orders,
+            market: None,
+            signals: Signals {
+                p_mid: 0.5,
+                tape: TapeSignal { rate: 3.0, pressure: 1.0 },
+                ..Default::default()
+            },
+            now: 0.0,
+            time_remaining: 300.0,
+        };
+        let actions = reconcile(&quotes, &state, &StrategyConfig::default(), &PositionSchedule::default());
+
+        assert!(actions.contains(&Action::cancel("no1")));
+        assert!(!actions.iter().any(|a| matches!(a, Action::Place { side: Side::No, .. })));
+        assert!(actions.iter().any(|a| matches!(a, Action::Place { side: Side::Yes, .. })));
+    }
+
+    #[test]
+    fn test_reconcile_shrinks_max_position_near_expiry() {
+        let book = Book::default();
+        let mut position = Position::default();
+        position.apply_fill(Side::Yes, 480, dec!(40));
+        let orders = OrderTracker::new();
+        let quotes = Quotes { yes_bid: 0.48, no_bid: 0.50 };
+        // Inventory target out of the way: only the position schedule limits YES
+        let config = StrategyConfig {
+            rebalance_threshold: dec!(1000),
+            inventory: InventoryConfig { final_tolerance: dec!(1000), ..Default::default() },
+            ..Default::default()
+        };
+        let places = |time_remaining: f64, side: Side| {
+            let state = BotState {
+                book: &book,
+                position: &position,
+                orders: &orders,
+                market: None,
+                signals: Signals { p_mid: 0.5, ..Default::default() },
+                now: 0.0,
+                time_remaining,
+            };
+            reconcile(&quotes, &state, &config, &PositionSchedule::default())
+                .iter()
+                .any(|a| matches!(a, Action::Place { side: s, .. } if *s == side))
+        };
+
+        // 40 net YES is under 150 and under half of it, not under a quarter
+        assert!(places(61.0, Side::Yes));
+        assert!(!places(60.0, Side::Yes));
+        assert!(!places(10.0, Side::Yes));
+        // The light side keeps quoting
+        assert!(places(10.0, Side::No));
+    }
+
+    #[test]
+    fn test_reconcile_drops_rungs_unlikely_to_fill() {
+        let mut book = Book::default();
+        book.update(Side::Yes, 480, 490, 0);
+        book.update(Side::No, 500, 510, 0);
+        let position = Position::default();
+        let orders = OrderTracker::new();
+        let quotes = Quotes { yes_bid: 0.48, no_bid: 0.50 };
+        let config = StrategyConfig { min_fill_prob: 0.2, ..Default::default() };
+        let yes_prices = |var: f64| {
+            let state = BotState {
+                book: &book,
+                position: &position,
+                orders: &orders,
+                market: None,
+                signals: Signals { p_mid: 0.5, var, ..Default::default() },
+                now: 0.0,
+                time_remaining: 300.0,
+            };
+            let mut prices: Vec<u16> = reconcile(&quotes, &state, &config, &PositionSchedule::default())
+                .iter()
+                .filter_map(|a| match a {
+                    Action::Place { side: Side::Yes, price, .. } => Some(*price),
+                    _ => None,
+                })
+                .collect();
+            prices.sort_unstable();
+            prices
+        };
+
+        // Volatile: the whole ladder can fill. Calm: only the top rung.
+        assert_eq!(yes_prices(0.01), vec![460, 470, 480]);
+        assert_eq!(yes_prices(1e-6), vec![480]);
+    }
+
+    #[test]
+    fn test_tif_for_rung() {
+        let config = StrategyConfig {
+            rung_tif: vec![
+                TimeInForce::Gtc,
+                TimeInForce::Gtd { expires_in_secs: 60 },
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(config.tif_for_rung(0), TimeInForce::Gtc);
+        assert_eq!(config.tif_for_rung(1), TimeInForce::Gtd { expires_in_secs: 60 });
+        // Past the configured rungs → GTC
+        assert_eq!(config.tif_for_rung(2), TimeInForce::Gtc);
+
+        // Expiring at the market's end replaces GTC, not a shorter GTD
+        let config = StrategyConfig { expire_before_end_secs: Some(10), ..config };
+        assert_eq!(config.tif_for_rung(0), TimeInForce::GtdMarketEnd { buffer_secs: 10 });
+        assert_eq!(config.tif_for_rung(1), TimeInForce::Gtd { expires_in_secs: 60 });
+        assert_eq!(config.tif_for_rung(2), TimeInForce::GtdMarketEnd { buffer_secs: 10 });
+    }
+
+    #[test]
+    fn test_reconcile_side_uses_rung_tif() {
+        let config = StrategyConfig {
+            rung_tif: vec![
+                TimeInForce::Gtc,
+                TimeInForce::Gtd { expires_in_secs: 60 },
+            ],
+            ..Default::default()
+        };
+        let orders = OrderTracker::new();
+        let ideal = build_ladder(480, dec!(12), &config);
+
+        let mut actions = Vec::new();
+        reconcile_side(Side::Yes, 480, &ideal, orders.of(Variant::Ladder), &config, &mut actions);
+
+        for action in &actions {
+            if let Action::Place { price, tif, .. } = action {
+                let expected = if *price == 470 {
+                    TimeInForce::Gtd { expires_in_secs: 60 }
+                } else {
+                    TimeInForce::Gtc
+                };
+                assert_eq!(*tif, expected);
+            }
+        }
+        assert_eq!(actions.len(), 3);
+    }
+
+    #[test]
+    fn test_pull_on_top_fill() {
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "top".to_string(), 480, dec!(12));
+        orders.add(Side::Yes, "mid".to_string(), 470, dec!(12));
+        orders.add(Side::Yes, "deep".to_string(), 460, dec!(12));
+        orders.add(Side::No, "other".to_string(), 500, dec!(12));
+
+        let mut actions = pull_on_top_fill(Side::Yes, "top", orders.of(Variant::Ladder));
+        actions.sort_by_key(|a| format!("{:?}", a));
+
+        assert_eq!(actions, vec![Action::cancel("deep"), Action::cancel("mid")]);
+    }
+
+    #[test]
+    fn test_pull_on_top_fill_ignores_deep_fill() {
+        let mut orders = OrderTracker::new();
+        orders.add(Side::Yes, "top".to_string(), 480, dec!(12));
+        orders.add(Side::Yes, "deep".to_string(), 460, dec!(12));
+
+        // Deeper rung hit: no fast pull
+        assert!(pull_on_top_fill(Side::Yes, "deep", orders.of(Variant::Ladder)).is_empty());
+        // Unknown order: no fast pull
+        assert!(pull_on_top_fill(Side::Yes, "gone", orders.of(Variant::Ladder)).is_empty());
+    }
+
+    fn lopsided(yes: Decimal, yes_price: u16, no: Decimal, no_price: u16) -> Position {
+        let mut position = Position::default();
+        position.apply_fill(Side::Yes, yes_price, yes);
+        position.apply_fill(Side::No, no_price, no);
+        position
+    }
+
+    #[test]
+    fn test_check_rebalance() {
+        let config = StrategyConfig::default();
+        let tolerance = config.rebalance_threshold;
+        let mut book = Book::default();
+        book.update(Side::Yes, 470, 480, 0);
+        book.update(Side::No, 500, 510, 0);
+
+        // Within tolerance: nothing
+        let position = lopsided(dec!(40), 480, dec!(20), 500);
+        assert_eq!(check_rebalance(&position, &book, 0.48, tolerance, &config), None);
+
+        // 50 YES over, 20 past tolerance: buy NO, capped at max_take_size, priced so the pair
+        // with YES at 48c still clears the 0.5c margin
+        let position = lopsided(dec!(60), 480, dec!(10), 500);
+        assert_eq!(
+            check_rebalance(&position, &book, 0.48, tolerance, &config),
+            Some(Action::take(Side::No, dec!(12), 515))
+        );
+
+        // Light side's ask above the cap: don't chase it
+        book.update(Side::No, 510, 520, 0);
+        assert_eq!(check_rebalance(&position, &book, 0.48, tolerance, &config), None);
+
+        // Heavy NO: buy YES, the 5 past tolerance plus the band
+        let position = lopsided(dec!(0), 480, dec!(35), 500);
+        assert_eq!(
+            check_rebalance(&position, &book, 0.48, tolerance, &config),
+            Some(Action::take(Side::Yes, dec!(8), 495))
+        );
+        // Near expiry the same imbalance is mostly excess
+        assert_eq!(
+            check_rebalance(&position, &book, 0.48, config.inventory.final_tolerance, &config),
+            Some(Action::take(Side::Yes, dec!(12), 495))
+        );
+
+        // Fair value caps it wherever the light side trades: YES fair at 40c
+        // won't pay 48c, NO fair at 48c pays at most 51c
+        assert_eq!(check_rebalance(&position, &book, 0.40, tolerance, &config), None);
+        let position = lopsided(dec!(60), 480, dec!(10), 500);
+        book.update(Side::No, 500, 510, 0);
+        assert_eq!(
+            check_rebalance(&position, &book, 0.52, tolerance, &config),
+            Some(Action::take(Side::No, dec!(12), 510))
+        );
+    }
+
+    #[test]
+    fn test_check_rebalance_hysteresis() {
+        let config = StrategyConfig::default();
+        let tolerance = config.rebalance_threshold;
+        let mut book = Book::default();
+        book.update(Side::Yes, 470, 480, 0);
+        book.update(Side::No, 500, 510, 0);
+
+        // Just past tolerance, inside the band: hold
+        let position = lopsided(dec!(32), 480, dec!(0), 500);
+        assert_eq!(check_rebalance(&position, &book, 0.48, tolerance, &config), None);
+
+        // At the band: buy back to 3 under the tolerance
+        let mut position = lopsided(dec!(33), 480, dec!(0), 500);
+        assert_eq!(
+            check_rebalance(&position, &book, 0.48, tolerance, &config),
+            Some(Action::take(Side::No, dec!(6), 515))
+        );
+
+        // Once filled, a few more heavy fills don't fire it again
+        position.apply_fill(Side::No, 510, dec!(6));
+        position.apply_fill(Side::Yes, 480, dec!(5));
+        assert_eq!(check_rebalance(&position, &book, 0.48, tolerance, &config), None);
+    }
+
+    #[test]
+    fn test_tick_actions_ladders_and_rebalances() {
+        let config = StrategyConfig { ladder_rungs: 1, ..Default::default() };
+        let mut book = Book::default();
+        book.update(Side::Yes, 470, 480, 0);
+        book.update(Side::No, 500, 510, 0);
+        let mut orders = OrderTracker::new();
+        orders.add(Side::No, "n1".into(), 490, dec!(5));
+        let mut quotes = TickQuotes {
+            yes: SideQuote::Ladder(470),
+            no: SideQuote::Hold,
+            size: dec!(5),
+            tolerance: config.rebalance_threshold,
+            p_mid: 0.48,
+            volatility: 0.0,
+            time_left: 200.0,
+        };
+
+        // Balanced: the YES ladder goes up, NO is left alone
+        let position = lopsided(dec!(10), 480, dec!(10), 500);
+        assert_eq!(
+            tick_actions(&quotes, &position, &book, orders.of(Variant::Ladder), &config),
+            [Action::place_with_tif(Side::Yes, 470, dec!(5), config.tif_for_rung(0))]
+        );
+
+        // 50 YES over: pulling YES still buys NO back as taker
+        quotes.yes = SideQuote::Pull;
+        let position = lopsided(dec!(60), 480, dec!(10), 500);
+        orders.add(Side::Yes, "y1".into(), 470, dec!(5));
+        assert_eq!(
+            tick_actions(&quotes, &position, &book, orders.of(Variant::Ladder), &config),
+            [Action::cancel("y1".to_string()), Action::take(Side::No, dec!(12), 515)]
+        );
+
+        // A still market won't come down a cent to a rung in time: it's dropped
+        let config = StrategyConfig { min_fill_prob: 0.5, ..config };
+        quotes.yes = SideQuote::Ladder(460);
+        let position = lopsided(dec!(10), 480, dec!(10), 500);
+        assert_eq!(
+            tick_actions(&quotes, &position, &book, orders.of(Variant::Ladder), &config),
+            [Action::cancel("y1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_reconcile_emits_rebalance_take() {
+        let mut book = Book::default();
+        book.update(Side::Yes, 470, 480, 100_000);
+        book.update(Side::No, 500, 510, 100_000);
+        let position = lopsided(dec!(60), 480, dec!(10), 500);
+        let orders = OrderTracker::new();
+        let state = BotState {
+            book: &book,
+            position: &position,
+            orders: &orders,
+            market: None,
+            signals: Signals { p_mid: 0.48, ..Default::default() },
+            now: 100.0,
+            time_remaining: 200.0,
+        };
+        let quotes = Quotes { yes_bid: 0.46, no_bid: 0.50 };
+
+        let actions = reconcile(&quotes, &state, &StrategyConfig::default(), &PositionSchedule::default());
+        assert_eq!(actions.iter().filter(|a| a.is_take()).count(), 1);
+        assert!(actions.iter().any(|a| matches!(a, Action::Place { .. })));
+    }
+
+    #[test]
+    fn test_set_cents() {
+        let mut config = StrategyConfig::default();
+
+        config.set_cents(dec!(1), dec!(2), dec!(0.01)).unwrap();
+        assert_eq!(config.margin_ticks, 10);
+        assert_eq!(config.rung_spacing, 20);
+
+        // 0.5c margin isn't on a 1c grid: rejected, config unchanged
+        assert!(config.set_cents(dec!(0.5), dec!(1), dec!(0.01)).is_err());
+        assert_eq!(config.margin_ticks, 10);
+
+        // Zero spacing would stack every rung on one price
+        assert!(config.set_cents(dec!(1), dec!(0), dec!(0.01)).is_err());
+    }
+
     #[test]
     fn test_strategy_config_default() {
         let config = StrategyConfig::default();
@@ -0,0 +1,242 @@
+//! Order flow imbalance (OFI) from depth deltas.
+//!
+//! Every level update near the touch is a change in resting interest:
+//! bids added or asks pulled are buying pressure, bids pulled or asks added
+//! are selling pressure. Summed over a rolling window, per token, that's OFI.
+//! Buying NO is selling YES, so the combined YES pressure is
+//! `ofi(YES) - ofi(NO)`.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::events::Side;
+
+#[derive(Debug, Clone, Copy)]
+pub struct OfiConfig {
+    /// Rolling window (seconds)
+    pub window_secs: f64,
+    /// Only levels within this many ticks of the touch count
+    pub depth_ticks: u16,
+    /// |YES pressure| (shares) at which the side it's against widens
+    pub widen_threshold: f64,
+    /// Extra margin on that side (ticks)
+    pub widen_ticks: u16,
+}
+
+impl Default for OfiConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 5.0,
+            depth_ticks: 20,       // 2c either side of the touch
+            widen_threshold: 500.0,
+            widen_ticks: 10,       // 1c
+        }
+    }
+}
+
+/// Resting size per price level for one token.
+#[derive(Debug, Clone, Default)]
+pub struct DepthBook {
+    bids: BTreeMap<u16, f64>,
+    asks: BTreeMap<u16, f64>,
+}
+
+impl DepthBook {
+    pub fn best_bid(&self) -> Option<u16> {
+        self.bids.keys().next_back().copied()
+    }
+
+    pub fn best_ask(&self) -> Option<u16> {
+        self.asks.keys().next().copied()
+    }
+
+    /// Set a level's size (0 removes it). Returns the change in size.
+    pub fn set_level(&mut self, is_bid: bool, price: u16, size: f64) -> f64 {
+        let levels = if is_bid { &mut self.bids } else { &mut self.asks };
+        let old = if size > 0.0 {
+            levels.insert(price, size)
+        } else {
+            levels.remove(&price)
+        };
+        size.max(0.0) - old.unwrap_or(0.0)
+    }
+
+    /// Replace the whole book.
+    pub fn replace(&mut self, bids: &[(u16, f64)], asks: &[(u16, f64)]) {
+        self.bids = bids.iter().copied().filter(|&(_, s)| s > 0.0).collect();
+        self.asks = asks.iter().copied().filter(|&(_, s)| s > 0.0).collect();
+    }
+
+    /// Whether `price` is within `depth` ticks of the touch on that side.
+    /// With no touch yet, everything counts.
+    fn near_touch(&self, is_bid: bool, price: u16, depth: u16) -> bool {
+        if is_bid {
+            self.best_bid().is_none_or(|best| price + depth >= best)
+        } else {
+            self.best_ask().is_none_or(|best| price <= best.saturating_add(depth))
+        }
+    }
+}
+
+/// Rolling OFI for both tokens.
+pub struct OfiTracker {
+    config: OfiConfig,
+    books: [DepthBook; 2],
+    /// (timestamp, token, signed contribution in shares)
+    events: VecDeque<(f64, Side, f64)>,
+}
+
+fn idx(side: Side) -> usize {
+    match side {
+        Side::Yes => 0,
+        Side::No => 1,
+    }
+}
+
+impl OfiTracker {
+    pub fn new(config: OfiConfig) -> Self {
+        Self {
+            config,
+            books: Default::default(),
+            events: VecDeque::new(),
+        }
+    }
+
+    pub fn book(&self, side: Side) -> &DepthBook {
+        &self.books[idx(side)]
+    }
+
+    /// Apply one level update for `side`'s token. Contributes to OFI if the
+    /// level was near the touch before the update.
+    pub fn apply_delta(&mut self, now: f64, side: Side, is_bid: bool, price: u16, size: f64) {
+        let book = &mut self.books[idx(side)];
+        let counts = book.near_touch(is_bid, price, self.config.depth_ticks);
+        let change = book.set_level(is_bid, price, size);
+        if counts && change != 0.0 {
+            let signed = if is_bid { change } else { -change };
+            self.events.push_back((now, side, signed));
+        }
+        self.prune(now);
+    }
+
+    /// Replace `side`'s book from a snapshot. Snapshots aren't flow: they
+    /// resync after a reconnect, so they don't contribute.
+    pub fn apply_snapshot(&mut self, side: Side, bids: &[(u16, f64)], asks: &[(u16, f64)]) {
+        self.books[idx(side)].replace(bids, asks);
+    }
+
+    /// OFI for one token over the window (shares, + = buying).
+    pub fn ofi(&self, now: f64, side: Side) -> f64 {
+        let cutoff = now - self.config.window_secs;
+        self.events
+            .iter()
+            .filter(|&&(ts, s, _)| s == side && ts > cutoff)
+            .map(|&(_, _, v)| v)
+            .sum()
+    }
+
+    /// Net pressure on YES: buying YES plus selling NO.
+    pub fn yes_pressure(&self, now: f64) -> f64 {
+        self.ofi(now, Side::Yes) - self.ofi(now, Side::No)
+    }
+
+    /// Extra margin for `side`: widen the side the pressure is against.
+    pub fn extra_margin(&self, now: f64, side: Side) -> u16 {
+        let pressure = self.yes_pressure(now);
+        let against = if pressure >= self.config.widen_threshold {
+            Some(Side::No)
+        } else if pressure <= -self.config.widen_threshold {
+            Some(Side::Yes)
+        } else {
+            None
+        };
+        if against == Some(side) {
+            self.config.widen_ticks
+        } else {
+            0
+        }
+    }
+
+    /// Clear books and window (market switch).
+    pub fn reset(&mut self) {
+        self.books = Default::default();
+        self.events.clear();
+    }
+
+    fn prune(&mut self, now: f64) {
+        let cutoff = now - self.config.window_secs;
+        while self.events.front().is_some_and(|&(ts, _, _)| ts <= cutoff) {
+            self.events.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> OfiTracker {
+        let mut t = OfiTracker::new(OfiConfig::default());
+        t.apply_snapshot(Side::Yes, &[(480, 100.0), (470, 100.0)], &[(500, 100.0), (510, 100.0)]);
+        t.apply_snapshot(Side::No, &[(500, 100.0)], &[(520, 100.0)]);
+        t
+    }
+
+    #[test]
+    fn test_snapshot_is_not_flow() {
+        let t = tracker();
+        assert_eq!(t.ofi(0.0, Side::Yes), 0.0);
+        assert_eq!(t.book(Side::Yes).best_bid(), Some(480));
+        assert_eq!(t.book(Side::Yes).best_ask(), Some(500));
+    }
+
+    #[test]
+    fn test_signs() {
+        let mut t = tracker();
+        // Bid added (+50), bid pulled (-30)
+        t.apply_delta(1.0, Side::Yes, true, 480, 150.0);
+        t.apply_delta(1.0, Side::Yes, true, 470, 70.0);
+        assert_eq!(t.ofi(1.0, Side::Yes), 20.0);
+
+        // Ask pulled entirely (+100), ask added (-40)
+        t.apply_delta(1.5, Side::Yes, false, 500, 0.0);
+        t.apply_delta(1.5, Side::Yes, false, 510, 140.0);
+        assert_eq!(t.ofi(1.5, Side::Yes), 80.0);
+        assert_eq!(t.book(Side::Yes).best_ask(), Some(510));
+    }
+
+    #[test]
+    fn test_far_levels_ignored() {
+        let mut t = tracker();
+        // 5c below best bid, 5c above best ask: outside 2c depth
+        t.apply_delta(1.0, Side::Yes, true, 430, 1000.0);
+        t.apply_delta(1.0, Side::Yes, false, 550, 1000.0);
+        assert_eq!(t.ofi(1.0, Side::Yes), 0.0);
+        // New best bid improving the touch counts
+        t.apply_delta(1.0, Side::Yes, true, 490, 25.0);
+        assert_eq!(t.ofi(1.0, Side::Yes), 25.0);
+    }
+
+    #[test]
+    fn test_window_expiry() {
+        let mut t = tracker();
+        t.apply_delta(0.0, Side::Yes, true, 480, 300.0);
+        assert_eq!(t.ofi(4.9, Side::Yes), 200.0);
+        assert_eq!(t.ofi(5.0, Side::Yes), 0.0);
+        t.apply_delta(6.0, Side::Yes, true, 480, 250.0);
+        assert_eq!(t.ofi(6.0, Side::Yes), -50.0);
+    }
+
+    #[test]
+    fn test_yes_pressure_and_margin() {
+        let mut t = tracker();
+        // YES bids stacking (+400), NO bids pulled (-100): both push YES up
+        t.apply_delta(1.0, Side::Yes, true, 480, 500.0);
+        t.apply_delta(1.0, Side::No, true, 500, 0.0);
+        assert_eq!(t.yes_pressure(1.0), 500.0);
+        assert_eq!(t.extra_margin(1.0, Side::No), 10);
+        assert_eq!(t.extra_margin(1.0, Side::Yes), 0);
+
+        // Outside the window nothing is widened
+        assert_eq!(t.extra_margin(7.0, Side::No), 0);
+    }
+}
@@ -0,0 +1,180 @@
+//! Outlier filter for the BTC price feed.
+//!
+//! A single bad print (flash wick, bad parse) can look like a crash to the
+//! BTC guard and trigger mass cancels. This rejects ticks that deviate too
+//! far from the median of the last N accepted prices.
+
+use std::collections::VecDeque;
+
+/// Configuration for the outlier filter.
+#[derive(Debug, Clone)]
+pub struct PriceFilterConfig {
+    /// Number of recent accepted prices to take the median over
+    pub window: usize,
+    /// Maximum deviation from the median before rejecting (e.g., 0.01 = 1%)
+    pub max_deviation_pct: f64,
+    /// Consecutive rejects before accepting anyway (price really moved)
+    pub max_consecutive_rejects: u32,
+}
+
+impl Default for PriceFilterConfig {
+    fn default() -> Self {
+        Self {
+            window: 21,                  // Median of last 21 trades
+            max_deviation_pct: 0.01,     // 1% off median is a bad print
+            max_consecutive_rejects: 10, // 10 in a row means it's real
+        }
+    }
+}
+
+/// Rejects outlier prices before they reach the strategy.
+pub struct PriceFilter {
+    config: PriceFilterConfig,
+    /// Recent accepted prices
+    prices: VecDeque<f64>,
+    /// Rejects since the last accepted price
+    consecutive_rejects: u32,
+    /// Total rejected ticks (for metrics)
+    rejected: u64,
+}
+
+impl PriceFilter {
+    pub fn new(config: PriceFilterConfig) -> Self {
+        Self {
+            prices: VecDeque::with_capacity(config.window),
+            config,
+            consecutive_rejects: 0,
+            rejected: 0,
+        }
+    }
+
+    /// Check a new price. Accepted prices are added to the window.
+    ///
+    /// # Returns
+    /// `true` if the price should be passed on, `false` if it's an outlier.
+    pub fn accept(&mut self, price: f64) -> bool {
+        if !price.is_finite() || price <= 0.0 {
+            self.rejected += 1;
+            return false;
+        }
+
+        if let Some(median) = self.median() {
+            let deviation = (price - median).abs() / median;
+            if deviation > self.config.max_deviation_pct {
+                self.consecutive_rejects += 1;
+                if self.consecutive_rejects <= self.config.max_consecutive_rejects {
+                    self.rejected += 1;
+                    return false;
+                }
+                // Sustained move: re-anchor on the new level
+                self.prices.clear();
+            }
+        }
+
+        self.consecutive_rejects = 0;
+        if self.prices.len() >= self.config.window {
+            self.prices.pop_front();
+        }
+        self.prices.push_back(price);
+        true
+    }
+
+    /// Median of the current window. None if empty.
+    pub fn median(&self) -> Option<f64> {
+        if self.prices.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<f64> = self.prices.iter().copied().collect();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let mid = sorted.len() / 2;
+        if sorted.len().is_multiple_of(2) {
+            Some((sorted[mid - 1] + sorted[mid]) / 2.0)
+        } else {
+            Some(sorted[mid])
+        }
+    }
+
+    /// Total ticks rejected since creation.
+    pub fn rejected(&self) -> u64 {
+        self.rejected
+    }
+
+    /// Reset state (call on feed reconnect or market switch).
+    pub fn reset(&mut self) {
+        self.prices.clear();
+        self.consecutive_rejects = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter() -> PriceFilter {
+        PriceFilter::new(PriceFilterConfig {
+            window: 5,
+            max_deviation_pct: 0.01,
+            max_consecutive_rejects: 3,
+        })
+    }
+
+    #[test]
+    fn test_accepts_normal_ticks() {
+        let mut f = filter();
+        for p in [50000.0, 50010.0, 49990.0, 50020.0] {
+            assert!(f.accept(p));
+        }
+        assert_eq!(f.rejected(), 0);
+    }
+
+    #[test]
+    fn test_rejects_single_bad_print() {
+        let mut f = filter();
+        f.accept(50000.0);
+        f.accept(50010.0);
+        f.accept(50005.0);
+
+        // Flash wick 5% down
+        assert!(!f.accept(47500.0));
+        assert_eq!(f.rejected(), 1);
+
+        // Next normal tick is fine, median unaffected
+        assert!(f.accept(50000.0));
+        assert_eq!(f.median(), Some(50002.5));
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        let mut f = filter();
+        assert!(!f.accept(0.0));
+        assert!(!f.accept(f64::NAN));
+        assert!(!f.accept(-1.0));
+        assert_eq!(f.rejected(), 3);
+        assert!(f.median().is_none());
+    }
+
+    #[test]
+    fn test_sustained_move_reanchors() {
+        let mut f = filter();
+        f.accept(50000.0);
+        f.accept(50000.0);
+
+        // Real 3% move: rejected up to the limit, then accepted
+        assert!(!f.accept(48500.0));
+        assert!(!f.accept(48500.0));
+        assert!(!f.accept(48500.0));
+        assert!(f.accept(48500.0));
+        assert_eq!(f.median(), Some(48500.0));
+        assert_eq!(f.rejected(), 3);
+    }
+
+    #[test]
+    fn test_window_is_bounded() {
+        let mut f = filter();
+        for i in 0..10 {
+            f.accept(50000.0 + i as f64);
+        }
+        // Only the last 5 (50005..=50009) remain
+        assert_eq!(f.median(), Some(50007.0));
+    }
+}
@@ -141,9 +141,17 @@ impl AvellanedaStoikov {
 /// * `book` - Current order book state
 /// * `margin_ticks` - Minimum profit margin in ticks (e.g., 5 = 0.5c)
 ///
+/// Not clamped under this side's ask: the caller keeps the quote maker,
+/// a whole venue tick under the ask, since only it knows the market's grid.
+///
 /// # Returns
 /// Max bid price in ticks (0-1000), or 0 if shouldn't bid
 pub fn calc_max_bid(side: Side, book: &Book, margin_ticks: u16) -> u16 {
+    // Locked/crossed book (glitch or mid-update): anything we post may take
+    if book.is_locked_or_crossed() {
+        return 0;
+    }
+
     // Get opposite side's ask
     let opposite_ask = match book.opposite_ask(side) {
         Some(ask) => ask,
@@ -228,6 +236,23 @@ mod tests {
         assert_eq!(calc_max_bid(Side::Yes, &book, 0), 490);
     }
 
+    #[test]
+    fn test_calc_max_bid_locked_or_crossed() {
+        let mut book = Book::default();
+
+        // Locked YES
+        book.update(Side::Yes, 490, 490, 1000);
+        book.update(Side::No, 500, 510, 1001);
+        assert_eq!(calc_max_bid(Side::Yes, &book, 5), 0);
+        assert_eq!(calc_max_bid(Side::No, &book, 5), 0);
+
+        // Crossed NO
+        book.update(Side::Yes, 480, 490, 1002);
+        book.update(Side::No, 520, 510, 1003);
+        assert_eq!(calc_max_bid(Side::Yes, &book, 5), 0);
+        assert_eq!(calc_max_bid(Side::No, &book, 5), 0);
+    }
+
     #[test]
     fn test_calc_max_bid_extreme_prices() {
         let mut book = Book::default();
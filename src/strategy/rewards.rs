@@ -0,0 +1,81 @@
+//! Liquidity-reward band.
+//!
+//! Polymarket pays liquidity rewards only on orders within a max spread of
+//! the midpoint (set per market). With the band enabled, rungs outside it
+//! are dropped so every order we rest earns, and the rest of the ladder
+//! isn't spent on prices that don't.
+
+use std::collections::HashMap;
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use super::TICKS_PER_DOLLAR;
+use crate::events::Side;
+
+/// Reward band around the midpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RewardBand {
+    /// Max distance below the side's mid (ticks)
+    pub max_spread: u16,
+}
+
+impl RewardBand {
+    /// Band from the market's max spread in cents (e.g. 3.5).
+    /// None if the market has no rewards.
+    pub fn from_cents(max_spread_cents: Decimal) -> Option<Self> {
+        let ticks = (max_spread_cents * Decimal::from(TICKS_PER_DOLLAR / 100)).floor().to_u16()?;
+        (ticks > 0).then_some(Self { max_spread: ticks })
+    }
+
+    /// Whether a bid on `side` at `price` is inside the band.
+    ///
+    /// # Arguments
+    /// * `yes_mid` - YES midpoint in ticks; the NO mid is its complement
+    pub fn contains(&self, side: Side, price: u16, yes_mid: u16) -> bool {
+        let mid = match side {
+            Side::Yes => yes_mid,
+            Side::No => (TICKS_PER_DOLLAR as u16).saturating_sub(yes_mid),
+        };
+        price <= mid && mid - price <= self.max_spread
+    }
+
+    /// Drop ladder rungs outside the band.
+    pub fn retain(&self, side: Side, ladder: &mut HashMap<u16, Decimal>, yes_mid: u16) {
+        ladder.retain(|&price, _| self.contains(side, price, yes_mid));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_from_cents() {
+        assert_eq!(RewardBand::from_cents(dec!(3.5)), Some(RewardBand { max_spread: 35 }));
+        assert_eq!(RewardBand::from_cents(dec!(0)), None);
+    }
+
+    #[test]
+    fn test_contains_both_sides() {
+        let band = RewardBand { max_spread: 30 };
+        // YES mid 52c, NO mid 48c
+        assert!(band.contains(Side::Yes, 490, 520));
+        assert!(!band.contains(Side::Yes, 480, 520));
+        assert!(band.contains(Side::No, 450, 520));
+        assert!(!band.contains(Side::No, 440, 520));
+        // Above mid isn't a resting bid we'd be paid for
+        assert!(!band.contains(Side::Yes, 530, 520));
+    }
+
+    #[test]
+    fn test_retain_drops_outside_rungs() {
+        let band = RewardBand { max_spread: 25 };
+        let mut ladder: HashMap<u16, Decimal> = [(500, dec!(5)), (490, dec!(5)), (470, dec!(5))].into();
+        band.retain(Side::Yes, &mut ladder, 510);
+        let mut prices: Vec<u16> = ladder.into_keys().collect();
+        prices.sort();
+        assert_eq!(prices, vec![490, 500]);
+    }
+}
@@ -18,6 +18,53 @@ impl MarketDuration {
             Self::FifteenMin => 900,
         }
     }
+
+    /// From a label like "5m" or "15m" (slug segment or config value).
+    pub fn from_label(label: &str) -> Option<Self> {
+        match label.trim().to_ascii_lowercase().as_str() {
+            "5m" => Some(Self::FiveMin),
+            "15m" => Some(Self::FifteenMin),
+            _ => None,
+        }
+    }
+
+    /// From a market length in seconds.
+    pub fn from_secs(secs: i64) -> Option<Self> {
+        match secs {
+            300 => Some(Self::FiveMin),
+            900 => Some(Self::FifteenMin),
+            _ => None,
+        }
+    }
+
+    /// From a market slug, e.g. "btc-updown-15m-1772242500".
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        slug.split('-').find_map(Self::from_label)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::FiveMin => "5m",
+            Self::FifteenMin => "15m",
+        }
+    }
+}
+
+/// Pick the sizing schedule for a market: what the market says it is wins
+/// over config. Returns a warning when the two disagree.
+pub fn resolve_duration(
+    detected: Option<MarketDuration>,
+    configured: Option<MarketDuration>,
+) -> (MarketDuration, Option<String>) {
+    match (detected, configured) {
+        (Some(d), Some(c)) if d != c => (
+            d,
+            Some(format!("market is {} but config says {}, using {}", d.label(), c.label(), d.label())),
+        ),
+        (Some(d), _) => (d, None),
+        (None, Some(c)) => (c, None),
+        (None, None) => (MarketDuration::FiveMin, Some("market duration unknown, assuming 5m".to_string())),
+    }
 }
 
 /// Calculate order size based on time remaining.
@@ -137,6 +184,28 @@ mod tests {
         assert_eq!(calc_size_5m(1), dec!(7));
     }
 
+    #[test]
+    fn test_duration_from_slug() {
+        assert_eq!(MarketDuration::from_slug("btc-updown-5m-1772242500"), Some(MarketDuration::FiveMin));
+        assert_eq!(MarketDuration::from_slug("btc-updown-15m-1772242200"), Some(MarketDuration::FifteenMin));
+        assert_eq!(MarketDuration::from_slug("will-it-rain-tomorrow"), None);
+        assert_eq!(MarketDuration::from_secs(900), Some(MarketDuration::FifteenMin));
+        assert_eq!(MarketDuration::from_secs(600), None);
+    }
+
+    #[test]
+    fn test_resolve_duration() {
+        use MarketDuration::*;
+        assert_eq!(resolve_duration(Some(FiveMin), None), (FiveMin, None));
+        assert_eq!(resolve_duration(Some(FiveMin), Some(FiveMin)), (FiveMin, None));
+        assert_eq!(resolve_duration(None, Some(FifteenMin)), (FifteenMin, None));
+        // Market wins, with a warning
+        let (d, warning) = resolve_duration(Some(FifteenMin), Some(FiveMin));
+        assert_eq!(d, FifteenMin);
+        assert!(warning.unwrap().contains("config says 5m"));
+        assert!(resolve_duration(None, None).1.is_some());
+    }
+
     #[test]
     fn test_15m_sizing() {
         // >9 min
@@ -0,0 +1,71 @@
+//! Margin widening as the book goes stale.
+//!
+//! A book that hasn't updated in a while may no longer be where we think
+//! it is. Rather than quote normally until a hard cutoff and then vanish,
+//! widen the margin as the book ages and only stop quoting when it's
+//! clearly dead.
+
+/// Configuration for staleness widening.
+#[derive(Debug, Clone)]
+pub struct StalenessConfig {
+    /// Book younger than this is fresh: no widening (ms)
+    pub grace_ms: i64,
+    /// Age at which the margin has widened by `widen_ticks` (ms)
+    pub widen_ms: i64,
+    /// Extra margin per side at `widen_ms` (ticks); keeps growing past it
+    pub widen_ticks: u16,
+    /// Stop quoting at this age (ms)
+    pub halt_ms: i64,
+}
+
+impl Default for StalenessConfig {
+    fn default() -> Self {
+        Self {
+            grace_ms: 500,       // Normal gap between book updates
+            widen_ms: 2000,      // 2s stale...
+            widen_ticks: 10,     // ...costs an extra 1c per side
+            halt_ms: 5000,       // 5s stale → no quotes
+        }
+    }
+}
+
+impl StalenessConfig {
+    /// Extra margin per side for a book `age_ms` old.
+    ///
+    /// # Returns
+    /// Ticks to add to the margin, or None if the book is too stale to quote.
+    pub fn extra_margin(&self, age_ms: i64) -> Option<u16> {
+        if age_ms >= self.halt_ms {
+            return None;
+        }
+        if age_ms <= self.grace_ms {
+            return Some(0);
+        }
+        let span = (self.widen_ms - self.grace_ms).max(1) as f64;
+        let frac = (age_ms - self.grace_ms) as f64 / span;
+        Some((frac * self.widen_ticks as f64).ceil() as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extra_margin_interpolates() {
+        let config = StalenessConfig::default();
+        assert_eq!(config.extra_margin(0), Some(0));
+        assert_eq!(config.extra_margin(500), Some(0));
+        assert_eq!(config.extra_margin(1250), Some(5));
+        assert_eq!(config.extra_margin(2000), Some(10));
+        // Keeps widening past widen_ms
+        assert_eq!(config.extra_margin(3500), Some(20));
+    }
+
+    #[test]
+    fn test_halts_when_dead() {
+        let config = StalenessConfig::default();
+        assert!(config.extra_margin(4999).is_some());
+        assert_eq!(config.extra_margin(5000), None);
+    }
+}
@@ -0,0 +1,194 @@
+//! Trade tape velocity: how fast trades are printing and which way.
+//!
+//! The flow estimator only counts trades. This also tracks who was the
+//! aggressor, so a burst of takers pushing YES up (buying YES or selling
+//! NO) shows up as pressure against our NO bids, and vice versa.
+
+use std::collections::VecDeque;
+
+use crate::events::Side;
+
+/// Configuration for the tape signal.
+#[derive(Debug, Clone)]
+pub struct TapeConfig {
+    /// Rolling window (seconds)
+    pub window_secs: f64,
+    /// Ignore direction below this many trades per second
+    pub min_rate: f64,
+    /// |pressure| at which to start widening the pressured side
+    pub widen_pressure: f64,
+    /// Extra margin on the pressured side (ticks)
+    pub widen_ticks: u16,
+    /// |pressure| at which to pull the pressured side entirely
+    pub pull_pressure: f64,
+}
+
+impl Default for TapeConfig {
+    fn default() -> Self {
+        Self {
+            window_secs: 5.0,      // Short: this is about bursts
+            min_rate: 1.0,         // 5 trades in 5s before direction means anything
+            widen_pressure: 0.6,   // 80/20 split
+            widen_ticks: 10,       // 1c
+            pull_pressure: 0.9,    // 95/5 split
+        }
+    }
+}
+
+/// Snapshot of the tape over the window.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TapeSignal {
+    /// Trades per second
+    pub rate: f64,
+    /// Net aggressor direction in YES terms, -1 to +1.
+    /// +1 = every taker pushed YES up (bought YES / sold NO).
+    pub pressure: f64,
+}
+
+impl TapeSignal {
+    /// The side the tape is running over, if any: YES pushed up means NO
+    /// is falling into our NO bids.
+    pub fn pressured_side(&self, config: &TapeConfig) -> Option<Side> {
+        if self.rate < config.min_rate || self.pressure.abs() < config.widen_pressure {
+            return None;
+        }
+        Some(if self.pressure > 0.0 { Side::No } else { Side::Yes })
+    }
+
+    /// Extra margin for one side.
+    ///
+    /// # Returns
+    /// Ticks to add (0 if the side isn't pressured), or None to pull it.
+    pub fn extra_margin(&self, side: Side, config: &TapeConfig) -> Option<u16> {
+        if self.pressured_side(config) != Some(side) {
+            return Some(0);
+        }
+        if self.pressure.abs() >= config.pull_pressure {
+            None
+        } else {
+            Some(config.widen_ticks)
+        }
+    }
+}
+
+/// Rolling trade tape.
+pub struct TapeVelocity {
+    config: TapeConfig,
+    /// (timestamp, +1 pushes YES up / -1 pushes YES down / 0 unknown)
+    trades: VecDeque<(f64, f64)>,
+}
+
+impl TapeVelocity {
+    pub fn new(config: TapeConfig) -> Self {
+        Self {
+            config,
+            trades: VecDeque::with_capacity(256),
+        }
+    }
+
+    pub fn config(&self) -> &TapeConfig {
+        &self.config
+    }
+
+    /// Record a trade print. `taker_buy` is the taker's side on `side`'s
+    /// token (None if the feed didn't say).
+    pub fn record(&mut self, now: f64, side: Side, taker_buy: Option<bool>) {
+        let dir = match (side, taker_buy) {
+            (_, None) => 0.0,
+            (Side::Yes, Some(true)) | (Side::No, Some(false)) => 1.0,
+            (Side::Yes, Some(false)) | (Side::No, Some(true)) => -1.0,
+        };
+        self.trades.push_back((now, dir));
+        self.evict(now);
+    }
+
+    /// Current rate and pressure.
+    pub fn signal(&mut self, now: f64) -> TapeSignal {
+        self.evict(now);
+        if self.trades.is_empty() {
+            return TapeSignal::default();
+        }
+        let n = self.trades.len() as f64;
+        TapeSignal {
+            rate: n / self.config.window_secs,
+            pressure: self.trades.iter().map(|(_, d)| d).sum::<f64>() / n,
+        }
+    }
+
+    /// Reset for new market window.
+    pub fn reset(&mut self) {
+        self.trades.clear();
+    }
+
+    fn evict(&mut self, now: f64) {
+        while let Some(&(t, _)) = self.trades.front() {
+            if now - t > self.config.window_secs {
+                self.trades.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction_in_yes_terms() {
+        let mut tape = TapeVelocity::new(TapeConfig::default());
+        tape.record(0.0, Side::Yes, Some(true));
+        tape.record(0.1, Side::No, Some(false));
+        tape.record(0.2, Side::No, Some(true));
+        tape.record(0.3, Side::Yes, None);
+
+        let signal = tape.signal(0.3);
+        assert!((signal.rate - 0.8).abs() < 1e-9);
+        assert!((signal.pressure - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_window_eviction() {
+        let mut tape = TapeVelocity::new(TapeConfig::default());
+        tape.record(0.0, Side::Yes, Some(true));
+        tape.record(4.0, Side::Yes, Some(false));
+
+        let signal = tape.signal(6.0);
+        assert!((signal.pressure + 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_widen_then_pull_pressured_side() {
+        let config = TapeConfig::default();
+        let mut tape = TapeVelocity::new(config.clone());
+        for i in 0..8 {
+            tape.record(i as f64 * 0.5, Side::Yes, Some(true));
+        }
+        tape.record(4.0, Side::Yes, Some(false));
+
+        // 8 up, 1 down: pressure 0.78 → widen NO, leave YES alone
+        let signal = tape.signal(4.0);
+        assert_eq!(signal.pressured_side(&config), Some(Side::No));
+        assert_eq!(signal.extra_margin(Side::No, &config), Some(10));
+        assert_eq!(signal.extra_margin(Side::Yes, &config), Some(0));
+
+        // One-way tape: pull NO
+        for i in 0..20 {
+            tape.record(4.0 + i as f64 * 0.05, Side::No, Some(false));
+        }
+        let signal = tape.signal(5.0);
+        assert_eq!(signal.extra_margin(Side::No, &config), None);
+    }
+
+    #[test]
+    fn test_slow_tape_ignored() {
+        let config = TapeConfig::default();
+        let mut tape = TapeVelocity::new(config.clone());
+        tape.record(0.0, Side::Yes, Some(true));
+        tape.record(1.0, Side::Yes, Some(true));
+
+        let signal = tape.signal(1.0);
+        assert_eq!(signal.pressured_side(&config), None);
+    }
+}
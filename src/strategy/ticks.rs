@@ -0,0 +1,77 @@
+//! Conversion from operator-facing cents to internal ticks.
+//!
+//! Internally prices are ticks of 0.1c (1000 = $1.00). The venue quotes on a
+//! coarser grid per market (usually $0.01, $0.001 near the extremes), so a
+//! configured margin or spacing must land on that grid or every price built
+//! from it would be rejected.
+
+use anyhow::{bail, Result};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+/// Internal ticks per dollar (1 tick = 0.1c).
+pub const TICKS_PER_DOLLAR: i64 = 1000;
+
+/// Convert an amount in cents to internal ticks.
+///
+/// # Arguments
+/// * `cents` - Amount in cents (e.g., 0.5 = half a cent)
+/// * `venue_tick` - Market's minimum price increment in dollars (e.g., 0.01)
+///
+/// # Errors
+/// If the amount is negative or isn't a multiple of the venue tick.
+pub fn cents_to_ticks(cents: Decimal, venue_tick: Decimal) -> Result<u16> {
+    if cents < Decimal::ZERO {
+        bail!("{}c is negative", cents);
+    }
+
+    let venue_ticks = venue_tick * Decimal::from(TICKS_PER_DOLLAR);
+    if venue_ticks <= Decimal::ZERO || !venue_ticks.fract().is_zero() {
+        bail!("tick size ${} is not a multiple of 0.1c", venue_tick);
+    }
+
+    let ticks = cents * Decimal::from(TICKS_PER_DOLLAR / 100);
+    if !(ticks % venue_ticks).is_zero() {
+        bail!("{}c is not representable on a ${} tick grid", cents, venue_tick);
+    }
+
+    match ticks.to_u16() {
+        Some(t) if t <= TICKS_PER_DOLLAR as u16 => Ok(t),
+        _ => bail!("{}c is more than a dollar", cents),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_whole_cents_on_cent_grid() {
+        assert_eq!(cents_to_ticks(dec!(1), dec!(0.01)).unwrap(), 10);
+        assert_eq!(cents_to_ticks(dec!(3), dec!(0.01)).unwrap(), 30);
+        assert_eq!(cents_to_ticks(dec!(0), dec!(0.01)).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_fractional_cents_on_fine_grid() {
+        assert_eq!(cents_to_ticks(dec!(0.5), dec!(0.001)).unwrap(), 5);
+        assert_eq!(cents_to_ticks(dec!(1.5), dec!(0.001)).unwrap(), 15);
+    }
+
+    #[test]
+    fn test_rejects_off_grid() {
+        // Half a cent can't be quoted on a 1c grid
+        assert!(cents_to_ticks(dec!(0.5), dec!(0.01)).is_err());
+        // Finer than 0.1c is never representable
+        assert!(cents_to_ticks(dec!(0.05), dec!(0.001)).is_err());
+    }
+
+    #[test]
+    fn test_rejects_bad_inputs() {
+        assert!(cents_to_ticks(dec!(-1), dec!(0.01)).is_err());
+        assert!(cents_to_ticks(dec!(1), dec!(0)).is_err());
+        assert!(cents_to_ticks(dec!(1), dec!(0.0005)).is_err());
+        assert!(cents_to_ticks(dec!(150), dec!(0.01)).is_err());
+    }
+}
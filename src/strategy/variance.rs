@@ -19,6 +19,9 @@ pub struct VarianceEstimator {
     last_timestamp: Option<f64>,
     /// Minimum variance (never let spread collapse)
     var_floor: f64,
+    /// Variance carried from the previous market, blended out as the
+    /// window fills (see `carry_over`)
+    prior: Option<f64>,
 }
 
 impl VarianceEstimator {
@@ -31,6 +34,7 @@ impl VarianceEstimator {
             last_logit: None,
             last_timestamp: None,
             var_floor,
+            prior: None,
         }
     }
 
@@ -58,11 +62,23 @@ impl VarianceEstimator {
     }
 
     /// Variance of logit increments per second. Floored at var_floor.
+    /// After a carry-over, the prior stands in for the first samples and
+    /// gives way to the estimate as the window fills.
     pub fn current_var(&self) -> f64 {
+        let prior = self.prior.unwrap_or(self.var_floor);
         if self.recent_dx.len() < 5 {
-            return self.var_floor;
+            return prior;
         }
+        let estimate = self.estimate();
+        if self.prior.is_none() {
+            return estimate;
+        }
+        let filled = self.recent_dx.len() as f64 / self.window_size as f64;
+        prior * (1.0 - filled) + estimate * filled
+    }
 
+    /// Variance from the window alone.
+    fn estimate(&self) -> f64 {
         let n = self.recent_dx.len() as f64;
         let mean = self.recent_dx.iter().sum::<f64>() / n;
         let var_per_tick = self.recent_dx
@@ -79,12 +95,21 @@ impl VarianceEstimator {
         }
     }
 
-    /// Reset for new market window. Optionally carry forward last var.
+    /// Reset for new market window.
     pub fn reset(&mut self) {
         self.recent_dx.clear();
         self.recent_timestamps.clear();
         self.last_logit = None;
         self.last_timestamp = None;
+        self.prior = None;
+    }
+
+    /// Reset for a new market window, keeping the current variance as a
+    /// prior. `decay` (0-1) is how much of its excess over the floor is kept.
+    pub fn carry_over(&mut self, decay: f64) {
+        let var = self.current_var();
+        self.reset();
+        self.prior = Some(self.var_floor + (var - self.var_floor) * decay.clamp(0.0, 1.0));
     }
 
     /// Get current sample count.
@@ -153,4 +178,27 @@ mod tests {
         // Window size is 3, should only have 3 samples
         assert_eq!(est.sample_count(), 3);
     }
+
+    #[test]
+    fn test_carry_over() {
+        let mut est = VarianceEstimator::new(10, 0.001);
+        for i in 0..10 {
+            est.update_poly(if i % 2 == 0 { 0.45 } else { 0.55 }, i as f64);
+        }
+        let var = est.current_var();
+        assert!(var > 0.01);
+
+        // Half the excess over the floor, until the new window fills
+        est.carry_over(0.5);
+        assert_eq!(est.sample_count(), 0);
+        let prior = 0.001 + (var - 0.001) * 0.5;
+        assert!((est.current_var() - prior).abs() < 1e-12);
+        for i in 0..11 {
+            est.update_poly(0.5, 100.0 + i as f64);
+        }
+        assert!((est.current_var() - 0.001).abs() < 1e-12);
+
+        est.reset();
+        assert!((est.current_var() - 0.001).abs() < 1e-12);
+    }
 }
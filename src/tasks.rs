@@ -0,0 +1,153 @@
+//! Registry of background tasks, for an ordered shutdown.
+//!
+//! Shutdown runs strategy → executor cancel-all → feeds → sinks. The first
+//! two happen in the trading loop (it stops ticking, then cancels our
+//! orders); the registry handles the rest. Each stage has a cancellation
+//! token: stopping a stage cancels it, gives its tasks `grace` to finish
+//! (sinks flush what they hold), then aborts whatever is still running.
+
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// Teardown stage, in shutdown order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Everything that feeds events in: WebSockets, pollers, servers
+    Feeds,
+    /// Everything that writes out: event sink, recorder, metrics exporters
+    Sinks,
+}
+
+struct Entry {
+    stage: Stage,
+    name: &'static str,
+    handle: JoinHandle<()>,
+    /// Watches the stage token; otherwise aborted straight away
+    cooperative: bool,
+}
+
+/// Spawned tasks, by stage.
+pub struct TaskRegistry {
+    feeds: CancellationToken,
+    sinks: CancellationToken,
+    entries: Vec<Entry>,
+}
+
+impl Default for TaskRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TaskRegistry {
+    pub fn new() -> Self {
+        Self { feeds: CancellationToken::new(), sinks: CancellationToken::new(), entries: Vec::new() }
+    }
+
+    /// Token cancelled when `stage` is stopped.
+    pub fn token(&self, stage: Stage) -> CancellationToken {
+        match stage {
+            Stage::Feeds => self.feeds.clone(),
+            Stage::Sinks => self.sinks.clone(),
+        }
+    }
+
+    /// Spawn `task`, dropped at its next await once `stage` stops.
+    pub fn spawn(&mut self, stage: Stage, name: &'static str, task: impl Future<Output = ()> + Send + 'static) {
+        let token = self.token(stage);
+        self.spawn_with(stage, name, move |_| async move {
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = task => {}
+            }
+        });
+    }
+
+    /// Spawn a task that watches the stage token itself, to finish up
+    /// (flush, final export) before returning.
+    pub fn spawn_with<F, Fut>(&mut self, stage: Stage, name: &'static str, task: F)
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handle = tokio::spawn(task(self.token(stage)));
+        self.push(Entry { stage, name, handle, cooperative: true });
+    }
+
+    /// Register a task spawned elsewhere. It's aborted when `stage` stops.
+    pub fn track(&mut self, stage: Stage, name: &'static str, handle: JoinHandle<()>) {
+        self.push(Entry { stage, name, handle, cooperative: false });
+    }
+
+    fn push(&mut self, entry: Entry) {
+        // Feeds restarted on a market switch leave finished handles behind
+        self.entries.retain(|e| !e.handle.is_finished());
+        self.entries.push(entry);
+    }
+
+    /// Stop `stage`: cancel its token, wait up to `grace` for its tasks,
+    /// abort the rest. Returns the names of tasks that had to be aborted.
+    pub async fn stop(&mut self, stage: Stage, grace: Duration) -> Vec<&'static str> {
+        self.token(stage).cancel();
+        let (entries, rest) = std::mem::take(&mut self.entries).into_iter().partition(|e| e.stage == stage);
+        self.entries = rest;
+
+        let deadline = tokio::time::Instant::now() + grace;
+        let mut aborted = Vec::new();
+        for mut entry in entries {
+            if !entry.cooperative {
+                entry.handle.abort();
+                let _ = entry.handle.await;
+                continue;
+            }
+            if tokio::time::timeout_at(deadline, &mut entry.handle).await.is_err() {
+                entry.handle.abort();
+                let _ = entry.handle.await;
+                aborted.push(entry.name);
+            }
+        }
+        aborted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_stops_stages_in_order() {
+        let mut tasks = TaskRegistry::new();
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        // A feed that never returns on its own
+        tasks.spawn(Stage::Feeds, "feed", std::future::pending());
+        // A sink that flushes on cancel, once feeds are gone
+        let flushed = log.clone();
+        let feeds = tasks.token(Stage::Feeds);
+        tasks.spawn_with(Stage::Sinks, "sink", move |token| async move {
+            token.cancelled().await;
+            flushed.lock().unwrap().push(("sink flushed", feeds.is_cancelled()));
+        });
+        // A sink that ignores the token
+        tasks.spawn_with(Stage::Sinks, "stuck", |_| std::future::pending());
+
+        assert!(tasks.stop(Stage::Feeds, Duration::from_millis(50)).await.is_empty());
+        assert!(log.lock().unwrap().is_empty());
+        assert_eq!(tasks.stop(Stage::Sinks, Duration::from_millis(50)).await, vec!["stuck"]);
+        assert_eq!(*log.lock().unwrap(), vec![("sink flushed", true)]);
+        assert!(tasks.entries.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tracked_tasks_are_aborted() {
+        let mut tasks = TaskRegistry::new();
+        tasks.track(Stage::Feeds, "ws", tokio::spawn(std::future::pending()));
+        let started = std::time::Instant::now();
+        assert!(tasks.stop(Stage::Feeds, Duration::from_secs(5)).await.is_empty());
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,93 @@
+//! Tracing subscriber setup: log sinks and OpenTelemetry trace export.
+//!
+//! Sinks are combinable and configured in `[logging]`: stdout (text or
+//! JSON) for dev, a size-rotated text file for servers, and a size-rotated
+//! JSON-lines file for ingestion.
+//!
+//! When `[tracing] otlp_endpoint` is set, spans are also exported over
+//! OTLP/HTTP so a market session → reconcile cycle → CLOB call waterfall can
+//! be viewed in Jaeger/Tempo. Order placement spans carry the exchange
+//! `order_id` for correlation with CLOB logs.
+
+mod rotating;
+
+pub use rotating::RotatingFile;
+
+use std::sync::Mutex;
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing_subscriber::layer::{Layered, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer, Registry};
+
+use crate::config;
+
+type Stack = Layered<EnvFilter, Registry>;
+type BoxedLayer = Box<dyn Layer<Stack> + Send + Sync>;
+
+/// Keeps exporters alive; call `shutdown` on exit to flush pending spans.
+pub struct Telemetry {
+    provider: Option<SdkTracerProvider>,
+}
+
+impl Telemetry {
+    /// Flush and stop the span exporter, if any.
+    pub fn shutdown(self) {
+        if let Some(provider) = self.provider {
+            if let Err(e) = provider.shutdown() {
+                println!("[tracing] Shutdown failed: {}", e);
+            }
+        }
+    }
+}
+
+/// Install the global subscriber with the configured sinks.
+///
+/// # Arguments
+/// * `level` - Default filter (e.g. "info"); `RUST_LOG` overrides it
+/// * `logging` - Which sinks to enable
+/// * `tracing` - Optional OTLP span export
+pub fn init(level: &str, logging: &config::Logging, tracing: &config::Tracing) -> anyhow::Result<Telemetry> {
+    let mut layers: Vec<BoxedLayer> = Vec::new();
+
+    if logging.stdout {
+        let layer = fmt::layer();
+        layers.push(if logging.stdout_json { layer.json().boxed() } else { layer.boxed() });
+    }
+
+    if let Some(path) = &logging.file {
+        let file = RotatingFile::open(path, logging.max_file_bytes, logging.max_files)?;
+        layers.push(fmt::layer().with_ansi(false).with_writer(Mutex::new(file)).boxed());
+    }
+
+    if let Some(path) = &logging.json_file {
+        let file = RotatingFile::open(path, logging.max_file_bytes, logging.max_files)?;
+        layers.push(fmt::layer().json().with_writer(Mutex::new(file)).boxed());
+    }
+
+    let mut provider = None;
+    if let Some(endpoint) = &tracing.otlp_endpoint {
+        let exporter = SpanExporter::builder()
+            .with_http()
+            .with_endpoint(endpoint)
+            .build()?;
+        let otlp = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(tracing.service_name.clone()).build())
+            .build();
+        layers.push(tracing_opentelemetry::layer().with_tracer(otlp.tracer("polybot-rs")).boxed());
+        println!("[tracing] Exporting spans to {}", endpoint);
+        provider = Some(otlp);
+    }
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(layers)
+        .try_init()?;
+
+    Ok(Telemetry { provider })
+}
@@ -0,0 +1,116 @@
+//! Size-rotated log file.
+//!
+//! When the active file would exceed `max_bytes`, it is renamed to
+//! `<path>.1` (shifting older files up to `<path>.<max_files>`, the oldest
+//! being dropped) and a fresh file is opened.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    written: u64,
+}
+
+impl RotatingFile {
+    /// Open (or append to) `path`, creating parent directories.
+    pub fn open(path: impl AsRef<Path>, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            max_files,
+            file,
+            written,
+        })
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_files == 0 {
+            // No backups kept: just truncate
+            self.file = File::create(&self.path)?;
+        } else {
+            let _ = fs::remove_file(self.rotated_path(self.max_files));
+            for n in (1..self.max_files).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+            self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        }
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotates_by_size() {
+        let dir = std::env::temp_dir().join(format!("polybot_rotate_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("bot.log");
+
+        let mut f = RotatingFile::open(&path, 10, 2).unwrap();
+        f.write_all(b"aaaaaaaa\n").unwrap(); // 9 bytes
+        f.write_all(b"bbbbbbbb\n").unwrap(); // Would exceed 10: rotate
+        f.write_all(b"cccccccc\n").unwrap(); // Rotate again
+        f.write_all(b"dddddddd\n").unwrap(); // Oldest (a) dropped
+        f.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "dddddddd\n");
+        assert_eq!(fs::read_to_string(dir.join("bot.log.1")).unwrap(), "cccccccc\n");
+        assert_eq!(fs::read_to_string(dir.join("bot.log.2")).unwrap(), "bbbbbbbb\n");
+        assert!(!dir.join("bot.log.3").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_appends_to_existing() {
+        let dir = std::env::temp_dir().join(format!("polybot_append_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("bot.log");
+
+        RotatingFile::open(&path, 100, 1).unwrap().write_all(b"one\n").unwrap();
+        RotatingFile::open(&path, 100, 1).unwrap().write_all(b"two\n").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\ntwo\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
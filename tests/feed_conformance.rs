@@ -0,0 +1,144 @@
+//! Conformance tests for the feed parsers.
+//!
+//! `tests/fixtures/feeds/<feed>.jsonl` holds one frame per line, in the
+//! recorder's `{"source", "raw"}` shape plus the number of events it should
+//! give. The frames cover every message type each channel sends us
+//! (including batched arrays, heartbeats and types we ignore) in the shapes
+//! the APIs send them, so a format change on their side fails here rather
+//! than going quiet in production.
+//!
+//! To grow the corpus, copy lines out of a `[recorder]` file
+//! (`zstd -dc logs/feeds/FILE`) and add the expected event count.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use polybot_rs::events::{Event, Side};
+use polybot_rs::feeds::binance;
+use polybot_rs::feeds::polymarket::PolymarketFeed;
+use polybot_rs::feeds::user_ws::{UserFeed, UserFeedConfig};
+
+const YES_TOKEN: &str = "71321045679252212594626385532706912750332728571942532289631379312455583992563";
+const NO_TOKEN: &str = "52114319501245915516055106046884209969926127482827954674443846427813813222426";
+const MAKER_ADDRESS: &str = "0x1d0034134e339a309700ff2d34e99fa2d48b0313";
+
+#[derive(Deserialize)]
+struct Frame {
+    source: String,
+    expect: usize,
+    raw: String,
+}
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/feeds")
+}
+
+fn load(feed: &str) -> Vec<Frame> {
+    let path = fixtures_dir().join(format!("{}.jsonl", feed));
+    let text = fs::read_to_string(&path).unwrap_or_else(|e| panic!("read {}: {}", path.display(), e));
+    let frames: Vec<Frame> = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| serde_json::from_str(line).unwrap_or_else(|e| panic!("{} line {}: {}", feed, i + 1, e)))
+        .collect();
+    assert!(!frames.is_empty(), "no frames in {}", path.display());
+    frames
+}
+
+fn polymarket() -> PolymarketFeed {
+    PolymarketFeed::new(YES_TOKEN.to_string(), NO_TOKEN.to_string())
+}
+
+fn user() -> UserFeed {
+    UserFeed::new(UserFeedConfig {
+        api_key: String::new(),
+        api_secret: String::new(),
+        api_passphrase: String::new(),
+        maker_address: MAKER_ADDRESS.to_string(),
+        yes_token: YES_TOKEN.to_string(),
+        no_token: NO_TOKEN.to_string(),
+    })
+}
+
+/// Parse every frame with `parse` and check the event counts, collecting
+/// all mismatches so one run shows every drifted message type.
+fn check<T>(feed: &str, parse: impl Fn(&str) -> anyhow::Result<Vec<T>>) -> Vec<Vec<T>> {
+    let mut failures = Vec::new();
+    let mut parsed = Vec::new();
+    for (i, frame) in load(feed).into_iter().enumerate() {
+        match parse(&frame.raw) {
+            Ok(events) if events.len() == frame.expect => parsed.push(events),
+            Ok(events) => failures.push(format!(
+                "{} line {} ({}): {} events, expected {}",
+                feed,
+                i + 1,
+                frame.source,
+                events.len(),
+                frame.expect
+            )),
+            Err(e) => failures.push(format!("{} line {} ({}): {:#}", feed, i + 1, frame.source, e)),
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    parsed
+}
+
+#[test]
+fn polymarket_frames_parse() {
+    let feed = polymarket();
+    let events: Vec<Event> = check("polymarket", |raw| feed.parse(raw)).into_iter().flatten().collect();
+
+    // Leading-dot prices in the batched snapshot come through as ticks
+    match &events[0] {
+        Event::BookSnapshot { side, bids, asks } => {
+            assert_eq!(*side, Side::Yes);
+            assert!(bids.contains(&(480, 40.0)));
+            assert!(asks.contains(&(500, 310.12)));
+        }
+        _ => panic!("expected the YES snapshot first"),
+    }
+    assert!(events.iter().any(|e| matches!(e, Event::BookDelta { side: Side::No, is_bid: false, price: 500, .. })));
+    assert!(events.iter().any(|e| matches!(e, Event::Trade { side: Side::No, price: 456, taker_buy: Some(true) })));
+    assert!(events.iter().any(|e| matches!(e, Event::BookUpdate { side: Side::Yes, bid: 730, ask: 770 })));
+}
+
+#[test]
+fn binance_frames_parse() {
+    let prices: Vec<f64> = check("binance", |raw| Ok(binance::parse_price(raw)?.into_iter().collect()))
+        .into_iter()
+        .flatten()
+        .collect();
+    assert_eq!(prices, vec![67234.12, 67234.13, 67251.3]);
+}
+
+#[test]
+fn user_frames_parse() {
+    let feed = user();
+    let events: Vec<Event> = check("user", |raw| feed.parse(raw)).into_iter().flatten().collect();
+
+    let fills: Vec<(bool, u16)> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::OrderFill { is_maker, price, .. } => Some((*is_maker, *price)),
+            _ => None,
+        })
+        .collect();
+    // Taker fill, then only our leg of the maker match
+    assert_eq!(fills, vec![(false, 570), (true, 430)]);
+}
+
+#[test]
+fn drifted_frames_are_errors() {
+    let poly = polymarket();
+    assert!(poly.parse("not json").is_err());
+    assert!(poly.parse(&format!(r#"{{"event_type":"book","asset_id":"{}","buys":[],"sells":[]}}"#, YES_TOKEN)).is_err());
+    assert!(poly.parse(&format!(r#"{{"event_type":"best_bid_ask","asset_id":"{}","bid":"0.5"}}"#, YES_TOKEN)).is_err());
+    assert!(poly.parse(&format!(r#"{{"event_type":"price_change","price_changes":[{{"asset_id":"{}","price":"x","size":"1","side":"BUY"}}]}}"#, YES_TOKEN)).is_err());
+
+    assert!(binance::parse_price(r#"{"e":"trade","price":"67000.1"}"#).is_err());
+    assert!(binance::parse_price(r#"{"e":"trade","p":"n/a"}"#).is_err());
+
+    assert!(user().parse(r#"{"event_type":"order","type":"PLACEMENT","order_id":"0x1"}"#).is_err());
+}
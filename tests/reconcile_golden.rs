@@ -0,0 +1,176 @@
+//! Golden-file regression tests for `strategy::tick_actions`, the ladder
+//! and rebalance decision the live loop executes every tick.
+//!
+//! Each `tests/fixtures/reconcile/<name>.json` is a scenario snapshot: what
+//! the loop decided for each side (a top price, "pull" or "hold"), shares
+//! per rung, the YES fair probability, time left, the book, position and
+//! resting orders. The inventory tolerance is derived from the time left as
+//! the loop does. The actions are compared line-by-line against
+//! `<name>.golden`, so a ladder or rebalancing tweak shows exactly which
+//! scenarios changed behavior.
+//!
+//! Regenerate after an intentional change:
+//!     UPDATE_GOLDEN=1 cargo test --test reconcile_golden
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use polybot_rs::events::Side;
+use polybot_rs::risk::Variant;
+use polybot_rs::state::{Book, OrderTracker, Position};
+use polybot_rs::strategy::{tick_actions, SideQuote, StrategyConfig, TickQuotes};
+
+#[derive(Deserialize)]
+struct Scenario {
+    yes: Quote,
+    no: Quote,
+    size: f64,
+    p_mid: f64,
+    time_left: f64,
+    #[serde(default)]
+    book: BookSides,
+    #[serde(default)]
+    fills: Vec<Fill>,
+    #[serde(default)]
+    orders: Vec<Order>,
+}
+
+/// A side's decision: a top price, or "pull" / "hold".
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Quote {
+    Ladder(u16),
+    Named(String),
+}
+
+impl Quote {
+    fn side_quote(&self) -> SideQuote {
+        match self {
+            Quote::Ladder(top) => SideQuote::Ladder(*top),
+            Quote::Named(name) if name == "pull" => SideQuote::Pull,
+            Quote::Named(name) if name == "hold" => SideQuote::Hold,
+            Quote::Named(other) => panic!("bad quote in fixture: {}", other),
+        }
+    }
+}
+
+/// Best bid and ask per side (ticks).
+#[derive(Deserialize, Default)]
+struct BookSides {
+    yes: Option<(u16, u16)>,
+    no: Option<(u16, u16)>,
+}
+
+#[derive(Deserialize)]
+struct Fill {
+    side: String,
+    price: u16,
+    size: f64,
+}
+
+#[derive(Deserialize)]
+struct Order {
+    side: String,
+    order_id: String,
+    price: u16,
+    size: f64,
+}
+
+fn parse_side(s: &str) -> Side {
+    match s {
+        "YES" => Side::Yes,
+        "NO" => Side::No,
+        other => panic!("bad side in fixture: {}", other),
+    }
+}
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/reconcile")
+}
+
+/// Run a scenario's tick and render actions one per line, sorted
+/// (reconciling walks HashMaps, so emission order isn't stable).
+fn run_scenario(scenario: &Scenario) -> String {
+    let config = StrategyConfig::default();
+
+    let mut position = Position::default();
+    for fill in &scenario.fills {
+        let size = Decimal::try_from(fill.size).unwrap();
+        position.apply_fill(parse_side(&fill.side), fill.price, size);
+    }
+
+    let mut orders = OrderTracker::new();
+    for order in &scenario.orders {
+        let size = Decimal::try_from(order.size).unwrap();
+        orders.add(parse_side(&order.side), order.order_id.clone(), order.price, size);
+    }
+
+    let mut book = Book::default();
+    for (side, quote) in [(Side::Yes, scenario.book.yes), (Side::No, scenario.book.no)] {
+        if let Some((bid, ask)) = quote {
+            book.update(side, bid, ask, 0);
+        }
+    }
+
+    let quotes = TickQuotes {
+        yes: scenario.yes.side_quote(),
+        no: scenario.no.side_quote(),
+        size: Decimal::try_from(scenario.size).unwrap(),
+        tolerance: config.inventory.tolerance(
+            config.rebalance_threshold,
+            scenario.time_left,
+            config.duration.total_secs() as f64,
+        ),
+        p_mid: scenario.p_mid,
+        volatility: 0.0,
+        time_left: scenario.time_left,
+    };
+    let actions = tick_actions(&quotes, &position, &book, orders.of(Variant::Ladder), &config);
+
+    let mut lines: Vec<String> = actions.iter().map(|a| format!("{:?}", a)).collect();
+    lines.sort();
+    lines.iter().map(|l| format!("{}\n", l)).collect()
+}
+
+#[test]
+fn test_tick_golden_files() {
+    let update = std::env::var("UPDATE_GOLDEN").is_ok();
+    let mut checked = 0;
+    let mut failures = Vec::new();
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(fixtures_dir())
+        .expect("fixtures dir")
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let scenario: Scenario = serde_json::from_str(&fs::read_to_string(&path).unwrap())
+            .unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        let actual = run_scenario(&scenario);
+        let golden_path = path.with_extension("golden");
+
+        if update {
+            fs::write(&golden_path, &actual).unwrap();
+        } else {
+            let expected = fs::read_to_string(&golden_path)
+                .unwrap_or_else(|_| panic!("missing {}", golden_path.display()));
+            if actual != expected {
+                failures.push(format!(
+                    "{}\n--- expected\n{}--- actual\n{}",
+                    path.display(),
+                    expected,
+                    actual
+                ));
+            }
+        }
+        checked += 1;
+    }
+
+    assert!(checked > 0, "no tick fixtures found");
+    assert!(failures.is_empty(), "golden mismatches:\n{}", failures.join("\n"));
+}